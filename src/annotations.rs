@@ -0,0 +1,86 @@
+//! Annotationen für CI-Systeme (`--annotations github`).
+//!
+//! Warnungen erscheinen als Workflow-Befehle `::warning file=…,line=…::…`,
+//! die GitHub Actions direkt an den betroffenen Dateien im Pull Request
+//! anzeigt. Pfade werden dafür auf das Repository bezogen.
+
+use std::path::Path;
+
+use clap::ValueEnum;
+
+use crate::permalink::git;
+
+/// Format der Annotationen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum AnnotationFormat {
+    /// Workflow-Befehle für GitHub Actions
+    Github,
+}
+
+/// Erzeugt Annotationen zu Pfaden relativ zum Projektverzeichnis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotator {
+    pub format: AnnotationFormat,
+    /// Pfad des Projektverzeichnisses im Repository (leer oder mit `/` am Ende)
+    pub prefix: String,
+}
+
+impl Annotator {
+    /// Ermittelt die Lage des Projektverzeichnisses im Repository per git;
+    /// außerhalb eines Repositorys bleiben die Pfade unverändert.
+    pub fn detect(format: AnnotationFormat, base_path: &Path) -> Self {
+        Self {
+            format,
+            prefix: git(base_path, &["rev-parse", "--show-prefix"]).unwrap_or_default(),
+        }
+    }
+
+    /// Annotation für eine Warnung, optional zu einer Datei und Zeile.
+    pub fn warning(&self, path: Option<&str>, line: Option<usize>, message: &str) -> String {
+        match self.format {
+            AnnotationFormat::Github => {
+                let mut properties = Vec::new();
+                if let Some(path) = path {
+                    properties.push(format!("file={}", escape_property(&format!("{}{}", self.prefix, path))));
+                    if let Some(line) = line {
+                        properties.push(format!("line={}", line));
+                    }
+                }
+                let properties = if properties.is_empty() { String::new() } else { format!(" {}", properties.join(",")) };
+                format!("::warning{}::{}", properties, escape_data(message))
+            }
+        }
+    }
+}
+
+/// Maskiert die Meldung eines Workflow-Befehls.
+fn escape_data(value: &str) -> String {
+    value.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Maskiert den Wert einer Eigenschaft (zusätzlich `:` und `,`).
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_github_warning() {
+        let annotator = Annotator {
+            format: AnnotationFormat::Github,
+            prefix: "app/".to_string(),
+        };
+        assert_eq!(
+            annotator.warning(Some("src/a,b.rs"), Some(1), "Header fehlt"),
+            "::warning file=app/src/a%2Cb.rs,line=1::Header fehlt"
+        );
+        assert_eq!(
+            annotator.warning(Some("x.rs"), None, "übersprungen: 100%\nkaputt"),
+            "::warning file=app/x.rs::übersprungen: 100%25%0Akaputt"
+        );
+        assert_eq!(annotator.warning(None, Some(3), "zu groß"), "::warning::zu groß");
+    }
+}
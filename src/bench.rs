@@ -0,0 +1,166 @@
+//! Zeitmessung der Export-Pipeline je Phase (`code2md bench`).
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::cli::SelectionArgs;
+use crate::collector::{apply_read_policy, collect_files, ReadErrorPolicy};
+use crate::error::{Code2MdError, Result};
+use crate::events::EventSink;
+use crate::handlers::HandlerConfig;
+use crate::markdown::{format_size, generate_markdown, MarkdownConfig};
+use crate::paths;
+use crate::plugins::load_project_plugins;
+use crate::processor::retain_files;
+
+/// Gemessene Phasen in Pipeline-Reihenfolge.
+pub const PHASES: [&str; 5] = ["walk", "filter", "read", "render", "write"];
+
+/// Messergebnis aller Durchläufe.
+#[derive(Debug, Clone, Default)]
+pub struct BenchReport {
+    /// Dauer je Phase für jeden Durchlauf
+    pub runs: Vec<[Duration; 5]>,
+    pub files: usize,
+    pub bytes: u64,
+}
+
+impl BenchReport {
+    /// Durchschnittliche Dauer einer Phase.
+    pub fn mean(&self, phase: usize) -> Duration {
+        let total: Duration = self.runs.iter().map(|run| run[phase]).sum();
+        total / self.runs.len().max(1) as u32
+    }
+
+    /// Kürzeste Dauer einer Phase.
+    pub fn min(&self, phase: usize) -> Duration {
+        self.runs.iter().map(|run| run[phase]).min().unwrap_or_default()
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total: Duration = (0..PHASES.len()).map(|phase| self.mean(phase)).sum();
+        writeln!(
+            f,
+            "{} Dateien, {}, {} Durchläufe",
+            self.files,
+            format_size(self.bytes),
+            self.runs.len()
+        )?;
+        writeln!(f)?;
+        writeln!(f, "  {:<8} {:>12} {:>12} {:>7}", "Phase", "Ø", "min", "Anteil")?;
+        for (phase, name) in PHASES.iter().enumerate() {
+            let mean = self.mean(phase);
+            let share = if total.is_zero() {
+                0.0
+            } else {
+                mean.as_secs_f64() / total.as_secs_f64() * 100.0
+            };
+            writeln!(
+                f,
+                "  {:<8} {:>12} {:>12} {:>6.1}%",
+                name,
+                format_duration(mean),
+                format_duration(self.min(phase)),
+                share
+            )?;
+        }
+        write!(f, "  {:<8} {:>12}", "gesamt", format_duration(total))
+    }
+}
+
+/// Führt die Pipeline `runs`-mal aus und misst jede Phase.
+///
+/// Die Ausgabe wird in eine temporäre Datei geschrieben und anschließend
+/// wieder entfernt.
+pub fn run_bench(selection: &SelectionArgs, runs: usize) -> Result<BenchReport> {
+    let base_path =
+        paths::canonicalize(&selection.directory).map_err(|e| Code2MdError::io(&selection.directory, e))?;
+    let collector = selection.collector_config()?;
+    let processors = load_project_plugins(&base_path, false)?;
+    let output_path = std::env::temp_dir().join(format!("code2md-bench-{}.md", std::process::id()));
+
+    let mut report = BenchReport::default();
+    for _ in 0..runs.max(1) {
+        let mut timings = [Duration::ZERO; 5];
+
+        let start = Instant::now();
+        let mut collected = collect_files(&base_path, &collector)?;
+        timings[0] = start.elapsed();
+
+        let start = Instant::now();
+        retain_files(&mut collected.files, &collected.base_path, &processors);
+        apply_read_policy(
+            &mut collected.files,
+            &collected.base_path,
+            ReadErrorPolicy::Skip,
+            &EventSink::default(),
+        )?;
+        timings[1] = start.elapsed();
+
+        let start = Instant::now();
+        let bytes: u64 = collected
+            .files
+            .iter()
+            .filter_map(|file| file.bytes().ok().map(|b| b.len() as u64))
+            .sum();
+        timings[2] = start.elapsed();
+
+        let start = Instant::now();
+        let config = MarkdownConfig {
+            project_name: selection.project_name(),
+            base_path: collected.base_path.clone(),
+            include_tree: true,
+            handlers: HandlerConfig {
+                output_dir: collected.base_path.clone(),
+                ..Default::default()
+            },
+            processors: processors.clone(),
+            ..Default::default()
+        };
+        let markdown = generate_markdown(&collected.files, &config);
+        timings[3] = start.elapsed();
+
+        let start = Instant::now();
+        std::fs::write(&output_path, &markdown).map_err(|e| Code2MdError::io(&output_path, e))?;
+        timings[4] = start.elapsed();
+        let _ = std::fs::remove_file(&output_path);
+
+        report.files = collected.files.len();
+        report.bytes = bytes;
+        report.runs.push(timings);
+    }
+    Ok(report)
+}
+
+/// Formatiert eine Dauer in Millisekunden.
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2} ms", duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, Commands};
+    use clap::Parser;
+
+    #[test]
+    fn test_run_bench() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join("main.rs"), "fn main() {}")?;
+        std::fs::write(dir.path().join("lib.rs"), "pub fn lib() {}")?;
+        let dir_arg = dir.path().to_string_lossy().to_string();
+        let cli = Cli::try_parse_from(["code2md", "bench", &dir_arg, "-t", "rust", "--runs", "2"])?;
+        let Some(Commands::Bench { selection, runs }) = cli.command else {
+            anyhow::bail!("bench-Subcommand erwartet");
+        };
+
+        let report = run_bench(&selection, runs)?;
+        assert_eq!(report.runs.len(), 2);
+        assert_eq!(report.files, 2);
+        assert_eq!(report.bytes, 27);
+        assert!(report.to_string().contains("render"));
+        Ok(())
+    }
+}
@@ -0,0 +1,451 @@
+//! Caches im Verzeichnis `.code2md/`.
+//!
+//! - Run-Cache: aufgelöste Konfiguration und Dateistand des letzten Laufs, damit
+//!   unveränderte Läufe ohne Rendern mit „Ausgabe ist aktuell“ enden.
+//! - Inkrementeller Cache für `--update`: Stand aller exportierten Dateien,
+//!   damit nur geänderte Abschnitte neu erzeugt werden.
+//! - Zusammenfassungen für `--summarize-cmd`, damit unveränderte Dateien nicht
+//!   erneut zusammengefasst werden.
+//! - Fortschritt aufgeteilter Ausgaben (`--split`), damit `--resume` nach einem
+//!   Abbruch fertige Teile überspringt.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::collector::CollectedFile;
+use crate::error::{Code2MdError, Result};
+use crate::processor::relative_path;
+
+/// Verzeichnis für Cache-Dateien im Basisverzeichnis.
+pub const CACHE_DIR: &str = ".code2md";
+
+/// Aufgelöste Konfiguration und Eingabestand eines Laufs.
+///
+/// Stimmt der gespeicherte Stand mit dem aktuellen überein, ist die Ausgabe
+/// aktuell und muss nicht neu erzeugt werden.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunCache {
+    pub version: String,
+    pub base_path: String,
+    pub extensions: Vec<String>,
+    pub excludes: Vec<String>,
+    /// Prüfsumme der übrigen Optionen und der Konfigurationsdatei
+    pub options: String,
+    /// Prüfsumme über Pfade, Größen und Änderungszeiten der gesammelten Dateien
+    pub files: String,
+    /// Prüfsumme weiterer Eingaben des Dokuments außerhalb der Dateien
+    /// (git-HEAD für Permalinks, `SOURCE_DATE_EPOCH`, Projektangaben)
+    #[serde(default)]
+    pub inputs: String,
+    /// Größe und Änderungszeit der geschriebenen Ausgabe
+    pub output: Option<(u64, Option<u64>)>,
+    /// Alle geschriebenen Dateien (Ausgabe, Teile, Vault-Notizen)
+    #[serde(default)]
+    pub written: Vec<PathBuf>,
+}
+
+impl RunCache {
+    /// Gibt den Pfad der Cache-Datei zur Ausgabedatei zurück.
+    pub fn path(base_path: &Path, output_path: &Path) -> PathBuf {
+        cache_file(base_path, output_path, "run.json")
+    }
+
+    /// Lädt den Cache des letzten Laufs, falls vorhanden und lesbar.
+    pub fn load(path: &Path) -> Option<Self> {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+    }
+
+    /// Speichert den Cache und legt das Verzeichnis bei Bedarf an.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        save_json(self, path)
+    }
+
+    /// Prüft, ob dieser gespeicherte Stand zum aktuellen Lauf passt und alle
+    /// damals geschriebenen Dateien noch vorhanden sind.
+    pub fn is_current(&self, current: &RunCache) -> bool {
+        let comparable = RunCache {
+            written: current.written.clone(),
+            ..self.clone()
+        };
+        comparable == *current && self.written.iter().all(|path| path.exists())
+    }
+
+    /// Übernimmt die Liste der geschriebenen Dateien.
+    pub fn with_written(mut self, written: &[PathBuf]) -> Self {
+        self.written = written.to_vec();
+        self
+    }
+
+    /// Übernimmt Größe und Änderungszeit der Ausgabedatei.
+    pub fn with_output(mut self, output_path: &Path) -> Self {
+        self.output = std::fs::metadata(output_path)
+            .ok()
+            .map(|meta| (meta.len(), modified_nanos(&meta)));
+        self
+    }
+}
+
+/// Prüfsumme über die übrigen Optionen (Kommandozeile ohne Cache-Schalter) und
+/// den Inhalt der Konfigurationsdatei.
+pub fn options_hash(args: &[String], config_file: Option<&str>) -> String {
+    let mut hasher = Sha256::new();
+    for arg in args {
+        hasher.update(arg.as_bytes());
+        hasher.update([0]);
+    }
+    hasher.update(config_file.unwrap_or_default().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Prüfsumme über benannte Eingaben; fehlende Werte zählen als eigener Zustand.
+pub fn inputs_hash(inputs: &[(&str, Option<String>)]) -> String {
+    let mut hasher = Sha256::new();
+    for (name, value) in inputs {
+        hasher.update(name.as_bytes());
+        match value {
+            Some(value) => {
+                hasher.update([1]);
+                hasher.update(value.as_bytes());
+            }
+            None => hasher.update([0]),
+        }
+        hasher.update([0]);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Prüfsumme über Pfade, Größen und Änderungszeiten der gesammelten Dateien.
+pub fn files_hash(files: &[CollectedFile], base_path: &Path) -> String {
+    let mut hasher = Sha256::new();
+    for file in files {
+        hasher.update(relative_path(&file.path, base_path).as_bytes());
+        hasher.update([0]);
+        hasher.update(file.size().to_le_bytes());
+        hasher.update(modified_nanos(&file.metadata).unwrap_or_default().to_le_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Zwischengespeicherter Stand einer Datei.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedFile {
+    pub size: u64,
+    /// Änderungszeit in Nanosekunden seit 1970
+    pub modified: Option<u64>,
+    pub sha256: String,
+}
+
+/// Stand aller Dateien eines Exports, nach relativem Pfad.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateCache {
+    pub files: BTreeMap<String, CachedFile>,
+}
+
+/// Ergebnis des Abgleichs mit dem Cache.
+#[derive(Debug)]
+pub struct UpdatePlan {
+    /// Neuer Cache-Stand aller aktuellen Dateien
+    pub cache: UpdateCache,
+    /// Indizes der neuen oder geänderten Dateien
+    pub changed: Vec<usize>,
+    /// Relative Pfade nicht mehr vorhandener Dateien
+    pub removed: Vec<String>,
+}
+
+impl UpdatePlan {
+    /// Prüft, ob sich seit dem letzten Lauf nichts geändert hat.
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl UpdateCache {
+    /// Gibt den Pfad der Cache-Datei zur Ausgabedatei zurück.
+    pub fn path(base_path: &Path, output_path: &Path) -> PathBuf {
+        cache_file(base_path, output_path, "json")
+    }
+
+    /// Lädt den Cache; ein fehlender oder unlesbarer Cache gilt als leer.
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Speichert den Cache und legt das Verzeichnis bei Bedarf an.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        save_json(self, path)
+    }
+}
+
+/// Gleicht die gesammelten Dateien mit dem Cache ab.
+///
+/// Stimmen Größe und Änderungszeit überein, gilt eine Datei ohne Lesen als
+/// unverändert; sonst entscheidet die SHA-256-Prüfsumme. Nicht lesbare Dateien
+/// gelten immer als geändert.
+pub fn plan_update(files: &[CollectedFile], base_path: &Path, previous: &UpdateCache) -> UpdatePlan {
+    let mut cache = UpdateCache::default();
+    let mut changed = Vec::new();
+    let mut current = HashSet::new();
+
+    for (index, file) in files.iter().enumerate() {
+        let key = relative_path(&file.path, base_path);
+        let size = file.size();
+        let modified = modified_nanos(&file.metadata);
+        let cached = previous.files.get(&key);
+        current.insert(key.clone());
+
+        if let Some(cached) = cached.filter(|c| c.size == size && c.modified.is_some() && c.modified == modified) {
+            cache.files.insert(key, cached.clone());
+            continue;
+        }
+
+        match file.sha256() {
+            Ok(sha256) => {
+                if cached.is_none_or(|c| c.sha256 != sha256) {
+                    changed.push(index);
+                }
+                cache.files.insert(key, CachedFile { size, modified, sha256 });
+            }
+            Err(_) => changed.push(index),
+        }
+    }
+
+    let removed = previous
+        .files
+        .keys()
+        .filter(|key| !current.contains(*key))
+        .cloned()
+        .collect();
+
+    UpdatePlan { cache, changed, removed }
+}
+
+/// Zusammenfassungen nach Prüfsumme von Befehl und Dateiinhalt.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SummaryCache {
+    pub summaries: BTreeMap<String, String>,
+}
+
+impl SummaryCache {
+    /// Gibt den Pfad der Cache-Datei im Basisverzeichnis zurück.
+    pub fn path(base_path: &Path) -> PathBuf {
+        base_path.join(CACHE_DIR).join("summaries.json")
+    }
+
+    /// Lädt den Cache; ein fehlender oder unlesbarer Cache gilt als leer.
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Speichert den Cache und legt das Verzeichnis bei Bedarf an.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        save_json(self, path)
+    }
+
+    /// Schlüssel einer Zusammenfassung; ein anderer Befehl erzeugt neue Einträge.
+    pub fn key(command: &str, content: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(command.as_bytes());
+        hasher.update([0]);
+        hasher.update(content.as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+/// Fortschritt einer aufgeteilten Ausgabe: je fertigem Teil der Eingabestand
+/// und die Prüfsumme der geschriebenen Datei.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartJournal {
+    /// Prüfsumme der Optionen (siehe [`options_hash`]); bei Abweichung gilt kein Teil als fertig
+    pub options: String,
+    /// Fertige Teile nach Nummer
+    pub parts: BTreeMap<usize, CompletedPart>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+/// Ein vollständig geschriebener Teil.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompletedPart {
+    /// Prüfsumme über Optionen, Teilnummer, Anzahl Teile und Dateistand
+    pub input: String,
+    /// SHA-256 der geschriebenen Teildatei
+    pub sha256: String,
+}
+
+impl PartJournal {
+    /// Gibt den Pfad des Protokolls zur Ausgabedatei zurück.
+    pub fn path(base_path: &Path, output_path: &Path) -> PathBuf {
+        cache_file(base_path, output_path, "parts.json")
+    }
+
+    /// Beginnt ein leeres Protokoll; fertige Teile werden unter `path` festgehalten.
+    pub fn new(path: &Path, options: &str) -> Self {
+        Self {
+            options: options.to_string(),
+            parts: BTreeMap::new(),
+            path: path.to_path_buf(),
+        }
+    }
+
+    /// Lädt das Protokoll eines früheren Laufs mit denselben Optionen; sonst leer.
+    pub fn resume(path: &Path, options: &str) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<Self>(&json).ok())
+            .filter(|journal| journal.options == options)
+            .map(|journal| Self { path: path.to_path_buf(), ..journal })
+            .unwrap_or_else(|| Self::new(path, options))
+    }
+
+    /// Eingabestand eines Teils aus seinen Dateien.
+    pub fn input(&self, number: usize, total: usize, files: &[CollectedFile], base_path: &Path) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.options.as_bytes());
+        hasher.update(format!("\0{}/{}\0", number, total).as_bytes());
+        hasher.update(files_hash(files, base_path).as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Prüft, ob der Teil mit diesem Eingabestand fertig ist und die Datei
+    /// unverändert vorliegt.
+    pub fn is_complete(&self, number: usize, input: &str, part_path: &Path) -> bool {
+        self.parts
+            .get(&number)
+            .is_some_and(|part| part.input == input && file_sha256(part_path).is_ok_and(|hash| hash == part.sha256))
+    }
+
+    /// Hält einen fertig geschriebenen Teil fest und speichert das Protokoll.
+    pub fn complete(&mut self, number: usize, input: String, part_path: &Path) -> std::io::Result<()> {
+        let sha256 = file_sha256(part_path)?;
+        self.parts.insert(number, CompletedPart { input, sha256 });
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(&self.path, json)
+    }
+}
+
+/// SHA-256 einer Datei, ohne sie vollständig in den Speicher zu laden.
+fn file_sha256(path: &Path) -> std::io::Result<String> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut std::fs::File::open(path)?, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Pfad einer Cache-Datei zur Ausgabedatei mit der angegebenen Endung.
+fn cache_file(base_path: &Path, output_path: &Path, suffix: &str) -> PathBuf {
+    let name = output_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    base_path.join(CACHE_DIR).join(format!("{}.{}", name, suffix))
+}
+
+/// Schreibt einen Cache als JSON und legt das Verzeichnis bei Bedarf an.
+fn save_json(value: &impl Serialize, path: &Path) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| Code2MdError::io(dir, e))?;
+    }
+    let json = serde_json::to_string_pretty(value).map_err(|e| Code2MdError::Render(e.to_string()))?;
+    std::fs::write(path, json).map_err(|e| Code2MdError::io(path, e))
+}
+
+/// Änderungszeit in Nanosekunden seit 1970.
+fn modified_nanos(metadata: &Metadata) -> Option<u64> {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inputs_hash() {
+        let hash = inputs_hash(&[("head", Some("abc".to_string())), ("epoch", None)]);
+        assert_eq!(hash, inputs_hash(&[("head", Some("abc".to_string())), ("epoch", None)]));
+        assert_ne!(hash, inputs_hash(&[("head", Some("abd".to_string())), ("epoch", None)]));
+        assert_ne!(hash, inputs_hash(&[("head", Some("abc".to_string())), ("epoch", Some(String::new()))]));
+    }
+
+    #[test]
+    fn test_plan_update() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path();
+        std::fs::write(base.join("a.rs"), "fn a() {}")?;
+        std::fs::write(base.join("b.rs"), "fn b() {}")?;
+        let collect = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| CollectedFile::from_path(base.join(name)))
+                .collect::<std::io::Result<Vec<_>>>()
+        };
+
+        let first = plan_update(&collect(&["a.rs", "b.rs"])?, base, &UpdateCache::default());
+        assert_eq!(first.changed, vec![0, 1]);
+        assert!(first.removed.is_empty());
+
+        let unchanged = plan_update(&collect(&["a.rs", "b.rs"])?, base, &first.cache);
+        assert!(unchanged.is_empty());
+        assert_eq!(unchanged.cache, first.cache);
+
+        std::fs::write(base.join("a.rs"), "fn a() { neu(); }")?;
+        std::fs::write(base.join("c.rs"), "fn c() {}")?;
+        let update = plan_update(&collect(&["a.rs", "c.rs"])?, base, &first.cache);
+        assert_eq!(update.changed, vec![0, 1]);
+        assert_eq!(update.removed, vec!["b.rs".to_string()]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_files_hash() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("a.rs");
+        std::fs::write(&file, "fn a() {}")?;
+        let before = files_hash(&[CollectedFile::from_path(&file)?], dir.path());
+        assert_eq!(before, files_hash(&[CollectedFile::from_path(&file)?], dir.path()));
+
+        std::fs::write(&file, "fn a() { neu(); }")?;
+        assert_ne!(before, files_hash(&[CollectedFile::from_path(&file)?], dir.path()));
+        assert_ne!(options_hash(&["-t".into(), "rust".into()], None), options_hash(&["-t".into()], None));
+        Ok(())
+    }
+
+    #[test]
+    fn test_cache_roundtrip() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = UpdateCache::path(dir.path(), Path::new("out/demo_code.md"));
+        assert_eq!(path, dir.path().join(".code2md/demo_code.md.json"));
+        assert_eq!(UpdateCache::load(&path), UpdateCache::default());
+
+        let mut cache = UpdateCache::default();
+        cache.files.insert(
+            "a.rs".to_string(),
+            CachedFile {
+                size: 9,
+                modified: Some(1),
+                sha256: "abc".to_string(),
+            },
+        );
+        cache.save(&path)?;
+        assert_eq!(UpdateCache::load(&path), cache);
+        Ok(())
+    }
+}
@@ -1,8 +1,23 @@
 //! CLI-Definitionen mit clap.
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::collections::HashSet;
 use std::path::PathBuf;
 
+use crate::annotations::AnnotationFormat;
+use crate::collector::{CollectorConfig, ReadErrorPolicy, WalkErrorPolicy};
+use crate::error::{Code2MdError, Result};
+use crate::handlers::SvgMode;
+use crate::markdown::Split;
+use crate::metrics::MetricsSort;
+use crate::output::{Bundle, Compression, OversizePolicy};
+use crate::paths;
+use crate::permalink::git;
+use crate::tokens::{find_model, Tokenizer};
+use crate::transform::LineEnding;
+use crate::gitattributes::GitAttributes;
+use crate::types::{collect_extensions, default_excludes, ExcludeCategories, TypeAliases};
+
 /// code2md - Exportiert Projektcode in eine strukturierte Markdown-Datei.
 #[derive(Parser, Debug)]
 #[command(
@@ -24,10 +39,377 @@ pub struct Cli {
     #[command(subcommand)]
     pub command: Option<Commands>,
 
+    #[command(flatten)]
+    pub selection: SelectionArgs,
+
+    /// Ausgabedatei (Standard: <projektname>_code.md)
+    #[arg(short = 'o', long = "output")]
+    pub output: Option<PathBuf>,
+
+    /// Verzeichnis für alle erzeugten Dateien (Dokument, Teile, Manifest, Archiv);
+    /// wird bei Bedarf angelegt
+    #[arg(long = "output-dir", value_name = "VERZEICHNIS")]
+    pub output_dir: Option<PathBuf>,
+
+    /// Bestehende Ausgabedatei überschreiben, auch wenn sie nicht von code2md stammt
+    #[arg(long = "force")]
+    pub force: bool,
+
+    /// Bestehende Ausgabedatei vor dem Schreiben nach <datei>.bak verschieben
+    #[arg(long = "backup", conflicts_with_all = ["append", "update"])]
+    pub backup: bool,
+
+    /// Ausgabeformat
+    #[arg(short = 'f', long = "format", value_enum, default_value_t = OutputFormat::Markdown)]
+    pub format: OutputFormat,
+
+    /// Markdown-Variante (obsidian: Übersichtsnotiz und eine Notiz je Datei in einem Vault-Ordner)
+    #[arg(long = "flavor", value_enum, default_value_t = Flavor::Standard, conflicts_with_all = ["compress", "bundle", "append", "update", "prompt", "split"])]
+    pub flavor: Flavor,
+
+    /// Export veröffentlichen: confluence (Seite über die REST-API, Standard) oder gist (geheimer GitHub Gist)
+    #[arg(long = "publish", value_name = "ZIEL", value_enum, num_args = 0..=1, default_missing_value = "confluence")]
+    pub publish: Option<PublishTarget>,
+
+    /// Erzeugte Dateien per HTTP PUT oder nach S3 hochladen (s3://bucket/pfad/, https://…; Zugangsdaten aus der Umgebung)
+    #[arg(long = "upload", value_name = "URL")]
+    pub upload: Option<String>,
+
+    /// Ausgabedatei komprimieren (Standardname erhält .gz bzw. .zst)
+    #[arg(long = "compress", value_name = "VERFAHREN", value_enum)]
+    pub compress: Option<Compression>,
+
+    /// Alle erzeugten Dateien (inkl. Manifest) zusätzlich als Archiv bündeln
+    #[arg(long = "bundle", value_name = "FORMAT", value_enum)]
+    pub bundle: Option<Bundle>,
+
+    /// Dateiabschnitte in eine bestehende Ausgabedatei einfügen bzw. dort ersetzen
+    #[arg(long = "append", conflicts_with_all = ["compress", "prompt"])]
+    pub append: bool,
+
+    /// Nur geänderte Dateiabschnitte der bestehenden Ausgabedatei erneuern (Cache in .code2md/)
+    #[arg(long = "update", conflicts_with_all = ["compress", "prompt", "append"])]
+    pub update: bool,
+
+    /// Nur prüfen, ob die vorhandene Ausgabe aktuell ist: das Dokument wird immer
+    /// (ohne Run-Cache) im Speicher reproduzierbar (wie mit --reproducible) neu
+    /// erzeugt und verglichen (Exit-Code 6 mit Zusammenfassung der Unterschiede, falls veraltet)
+    #[arg(long = "check", conflicts_with_all = ["append", "update", "compress", "split", "interactive"])]
+    pub check: bool,
+
+    /// Reproduzierbare Ausgabe: Zeitstempel aus SOURCE_DATE_EPOCH (sonst ohne
+    /// Zeitstempel) und `.` als Basisverzeichnis, z.B. für eingecheckte Exporte
+    #[arg(long = "reproducible")]
+    pub reproducible: bool,
+
+    /// Run-Cache in .code2md/ weder lesen noch schreiben
+    #[arg(long = "no-cache")]
+    pub no_cache: bool,
+
+    /// JSONL: Inhalte zusätzlich in Abschnitte von N Zeilen zerlegen
+    #[arg(long = "chunk-lines", value_name = "N")]
+    pub chunk_lines: Option<usize>,
+
+    /// Sprachmodell-Preset: setzt Tokenizer und Token-Budget (z.B. gpt-4o, claude-sonnet-4)
+    #[arg(short = 'm', long = "model")]
+    pub model: Option<String>,
+
+    /// Token-Budget des Exports (Standard bei --model: 80 % des Kontextfensters)
+    #[arg(long = "max-tokens", value_name = "N")]
+    pub max_tokens: Option<usize>,
+
+    /// Ausgabe in mehrere verlinkte Teile aufteilen
+    #[arg(long = "split", value_name = "MODUS", value_enum, conflicts_with_all = ["compress", "append", "update", "prompt"])]
+    pub split: Option<SplitMode>,
+
+    /// Aufgeteilte Ausgabe nach einem Abbruch fortsetzen: fertige Teile mit
+    /// unverändertem Eingabestand und passender Prüfsumme werden übersprungen
+    #[arg(long = "resume", requires = "split", conflicts_with = "no_cache")]
+    pub resume: bool,
+
+    /// Maximale Größe des Dateiinhalts je Teil bei --split size (z.B. 512KB, 2MB)
+    #[arg(long = "split-size", value_name = "SIZE", value_parser = parse_size)]
+    pub split_size: Option<u64>,
+
+    /// Höchstgröße der Ausgabe (z.B. 50MB); vorab geschätzt und beim Schreiben geprüft
+    #[arg(long = "max-output-size", value_name = "SIZE", value_parser = parse_size, conflicts_with = "split")]
+    pub max_output_size: Option<u64>,
+
+    /// Verhalten bei zu großer Ausgabe: abbrechen, in Teile aufteilen oder letzte Dateien weglassen
+    #[arg(long = "on-oversize", value_name = "VERHALTEN", value_enum, default_value_t = OversizePolicy::Abort, requires = "max_output_size")]
+    pub on_oversize: OversizePolicy,
+
+    /// Projektname für den Header (Standard: Ordnername)
+    #[arg(short = 'n', long = "name")]
+    pub name: Option<String>,
+
+    /// Konfigurationsdatei (Standard: nächste code2md.toml im Projektverzeichnis oder darüber)
+    #[arg(short = 'c', long = "config", value_name = "DATEI")]
+    pub config: Option<PathBuf>,
+
+    /// Hooks, [pipe_through] und [confluence]-Ziel aus einer automatisch gefundenen
+    /// code2md.toml sowie Plugins aus .code2md/plugins im Projekt verwenden
+    #[arg(long = "allow-hooks")]
+    pub allow_hooks: bool,
+
+    /// Ordnerstruktur-Baum nicht ausgeben
+    #[arg(long = "no-tree")]
+    pub no_tree: bool,
+
+    /// Ordnerstruktur mit ASCII-Zeichen (`|--`, `` `-- ``) statt Rahmenzeichen zeichnen
+    #[arg(long = "ascii-tree", conflicts_with = "no_tree")]
+    pub ascii_tree: bool,
+
+    /// Größe und Zeilen im Baum anzeigen, bei Verzeichnissen als Summe
+    #[arg(long = "tree-stats", conflicts_with = "no_tree")]
+    pub tree_stats: bool,
+
+    /// Ordnerstruktur nur bis zu dieser Tiefe; tiefere Inhalte als "… (N Dateien)"
+    #[arg(long = "tree-depth", value_name = "N", conflicts_with = "no_tree")]
+    pub tree_depth: Option<usize>,
+
+    /// Verzeichnisse mit mehr Einträgen im Baum kürzen; der Rest erscheint als "… (N weitere Dateien)"
+    #[arg(long = "tree-max-entries", value_name = "M", conflicts_with = "no_tree")]
+    pub tree_max_entries: Option<usize>,
+
+    /// Ausgeschlossene Verzeichnisse im Baum als "[ausgeschlossen]" zeigen
+    #[arg(long = "tree-show-excluded", conflicts_with = "no_tree")]
+    pub tree_show_excluded: bool,
+
+    /// Zeilenenden im Dokument vereinheitlichen
+    #[arg(long = "normalize-eol", value_enum, default_value_t = LineEnding::Keep)]
+    pub normalize_eol: LineEnding,
+
+    /// UTF-8 BOM am Dateianfang beibehalten (Standard: entfernen)
+    #[arg(long = "keep-bom")]
+    pub keep_bom: bool,
+
+    /// Tabs durch Leerzeichen ersetzen (Tabstopp-Breite N)
+    #[arg(long = "expand-tabs", value_name = "N")]
+    pub expand_tabs: Option<usize>,
+
+    /// Leerzeichen am Zeilenende entfernen
+    #[arg(long = "trim-trailing-whitespace")]
+    pub trim_trailing_whitespace: bool,
+
+    /// Zeilen mit mehr als N Zeichen kürzen
+    #[arg(long = "max-line-length", value_name = "N")]
+    pub max_line_length: Option<usize>,
+
+    /// Nur diese Pakete eines Monorepos exportieren, kommasepariert (Name oder Pfad);
+    /// erkannt über package.json-Workspaces, pnpm-workspace.yaml, go.work und Cargo-Workspaces
+    #[arg(long = "packages", value_name = "PAKETE", value_delimiter = ',')]
+    pub packages: Option<Vec<String>>,
+
+    /// Linguist-Attribute aus .gitattributes ignorieren: ohne diese Option werden
+    /// Dateien mit linguist-vendored oder linguist-generated übersprungen und
+    /// linguist-language bestimmt die Sprache
+    #[arg(long = "no-linguist")]
+    pub no_linguist: bool,
+
+    /// Generierte Dateien (Markierungen wie @generated, DO NOT EDIT oder
+    /// "Code generated by" am Dateianfang) mit exportieren
+    #[arg(long = "include-generated")]
+    pub include_generated: bool,
+
+    /// Dateien dieser Sprachen auslassen, kommasepariert (z.B. json,yaml); es
+    /// zählt die erkannte Sprache der Codeblöcke, nicht die Endung
+    #[arg(long = "exclude-lang", value_name = "SPRACHE", value_delimiter = ',')]
+    pub exclude_langs: Vec<String>,
+
+    /// Nach dem Sammeln die größten Dateien und Verzeichnisse einzeln abfragen
+    /// (Standard: 10) und die Auswahl verfeinern; Ausschlüsse lassen sich in
+    /// code2md.toml speichern
+    #[arg(long = "review", value_name = "ANZAHL", num_args = 0..=1, default_missing_value = "10")]
+    pub review: Option<usize>,
+
+    /// Dateien im Terminal auswählen, mit Vorschau des Abschnitts, wie er mit
+    /// den aktuellen Einstellungen exportiert wird
+    #[arg(short = 'i', long = "interactive")]
+    pub interactive: bool,
+
+    /// Nur die Dateien einer gespeicherten Auswahl exportieren
+    /// (.code2md/selections/<NAME>.list)
+    #[arg(long = "selection", value_name = "NAME")]
+    pub selection_name: Option<String>,
+
+    /// Die exportierten Dateien als Auswahl speichern (z.B. nach --interactive)
+    #[arg(long = "save-selection", value_name = "NAME")]
+    pub save_selection: Option<String>,
+
+    /// Testcode (tests/, *_test.go, *.spec.ts, #[cfg(test)]-Module) in einen
+    /// eigenen Abschnitt „Tests“ nach dem übrigen Code verschieben
+    #[arg(long = "separate-tests", conflicts_with_all = ["no_tests", "append", "update", "split"])]
+    pub separate_tests: bool,
+
+    /// Testcode nicht exportieren
+    #[arg(long = "no-tests")]
+    pub no_tests: bool,
+
+    /// Nur Dokumentationskommentare und öffentliche Signaturen ausgeben
+    /// (Rust, Go, JavaScript/TypeScript, Java/Kotlin/C#, Python)
+    #[arg(long = "docs-only")]
+    pub docs_only: bool,
+
+    /// Dateiinhalte vor dem Einbetten durch einen externen Befehl leiten (stdin → stdout)
+    #[arg(long = "pipe-through", value_name = "BEFEHL")]
+    pub pipe_through: Option<String>,
+
+    /// Jede Datei durch einen externen Befehl zusammenfassen lassen (stdin → stdout);
+    /// die Zusammenfassung erscheint unter der Dateiüberschrift
+    #[arg(long = "summarize-cmd", value_name = "BEFEHL")]
+    pub summarize_cmd: Option<String>,
+
+    /// Verzeichnis mit WASM-Plugins (Standard: .code2md/plugins im Projekt, nur mit
+    /// --allow-hooks oder --config)
+    #[arg(long = "plugins-dir", value_name = "DIR")]
+    pub plugins_dir: Option<PathBuf>,
+
+    /// Markdown-Zellen aus Jupyter-Notebooks mit ausgeben
+    #[arg(long = "notebook-markdown")]
+    pub notebook_markdown: bool,
+
+    /// CSV/TSV-Dateien als Tabelle mit den ersten N Zeilen darstellen
+    #[arg(long = "csv-preview", value_name = "N")]
+    pub csv_preview: Option<usize>,
+
+    /// Bilder als Base64-Data-URI einbetten statt zu verlinken
+    #[arg(long = "embed-images")]
+    pub embed_images: bool,
+
+    /// Maximale Größe eingebetteter Bilder (z.B. 512KB, 2MB)
+    #[arg(long = "max-embed-size", value_name = "SIZE", default_value = "256KB", value_parser = parse_size)]
+    pub max_embed_size: u64,
+
+    /// Darstellung von SVG-Dateien: Quelltext oder eingebettete Grafik
+    #[arg(long = "svg", value_name = "MODUS", value_enum, default_value_t = SvgMode::Source)]
+    pub svg_mode: SvgMode,
+
+    /// SHA-256-Prüfsumme je Datei ausgeben und Prüfsummen-Abschnitt anhängen
+    #[arg(long = "hashes")]
+    pub hashes: bool,
+
+    /// Verhalten bei nicht lesbaren Dateien
+    #[arg(long = "on-read-error", value_name = "MODUS", value_enum, default_value_t = ReadErrorPolicy::Placeholder)]
+    pub on_read_error: ReadErrorPolicy,
+
+    /// Abschnitt mit allen Warnungen (nicht lesbare/übersprungene Dateien) anhängen
+    #[arg(long = "warnings-appendix")]
+    pub warnings_appendix: bool,
+
+    /// Warnungen als Fehler behandeln (Exit-Code 6)
+    #[arg(long = "strict")]
+    pub strict: bool,
+
+    /// JSON-Manifest aller Dateien als <ausgabe>.manifest.json schreiben
+    #[arg(long = "manifest")]
+    pub manifest: bool,
+
+    /// Bericht über den Lauf als JSON schreiben (Zählwerte, übersprungene
+    /// Dateien mit Grund, Warnungen, Laufzeit, erzeugte Dateien), z.B. für CI
+    #[arg(long = "report", value_name = "DATEI")]
+    pub report: Option<PathBuf>,
+
+    /// Warnungen als Annotationen für CI ausgeben: github (Workflow-Befehle
+    /// `::warning file=…::`, erscheinen direkt im Pull Request)
+    #[arg(long = "annotations", value_name = "FORMAT", value_enum)]
+    pub annotations: Option<AnnotationFormat>,
+
+    /// Lizenzübersicht (SPDX-Header und LICENSE-Dateien) anhängen
+    #[arg(long = "licenses")]
+    pub licenses: bool,
+
+    /// Abschnitt „Offene Punkte“ mit TODO-, FIXME- und HACK-Kommentaren anhängen
+    #[arg(long = "todos")]
+    pub todos: bool,
+
+    /// Zeilen markieren, auf die der reguläre Ausdruck passt, und alle Treffer auflisten
+    #[arg(long = "annotate", value_name = "REGEX")]
+    pub annotate: Option<String>,
+
+    /// Projektinterne Imports je Datei als Links auf die importierten Dateien ausgeben
+    #[arg(long = "import-links")]
+    pub import_links: bool,
+
+    /// Link auf jede Datei im Repository, fest auf den aktuellen Commit (ohne URL: aus dem Remote `origin`)
+    #[arg(long = "repo-url", value_name = "URL", num_args = 0..=1)]
+    pub repo_url: Option<Option<String>>,
+
+    /// Dateiüberschriften als relative Links auf die Quelldateien (für Exporte im selben Repository)
+    #[arg(long = "link-sources")]
+    pub link_sources: bool,
+
+    /// YAML-Front-Matter (Titel, Datum, Projekt, Dateianzahl, Tags aus [front_matter]) vor dem Titel ausgeben
+    #[arg(long = "front-matter")]
+    pub front_matter: bool,
+
+    /// Sprache für Codeblöcke von Dateien mit unbekannter Endung, z.B. text
+    #[arg(long = "fallback-syntax", value_name = "SPRACHE")]
+    pub fallback_syntax: Option<String>,
+
+    /// Zusätzliche Angabe für Kopfbereich, Front Matter und Manifest, z.B. --meta build=1234 (mehrfach möglich)
+    #[arg(long = "meta", value_name = "KEY=WERT", value_parser = parse_meta)]
+    pub meta: Vec<(String, String)>,
+
+    /// Statistik-Abschnitt (Zeilen je Sprache, Dateien je Verzeichnis, größte Dateien) einfügen
+    #[arg(long = "stats")]
+    pub stats: bool,
+
+    /// Metriken je Datei (LOC, Kommentaranteil, Verschachtelung, Funktionen) und Metrik-Tabelle ausgeben
+    #[arg(long = "metrics")]
+    pub metrics: bool,
+
+    /// Sortierung der Metrik-Tabelle (absteigend)
+    #[arg(long = "metrics-sort", value_name = "WERT", value_enum, default_value_t = MetricsSort::Loc, requires = "metrics")]
+    pub metrics_sort: MetricsSort,
+
+    /// Prüfen, ob jede Datei mit dem Header beginnt (regulärer Ausdruck)
+    #[arg(long = "check-headers", value_name = "REGEX", conflicts_with = "header_template")]
+    pub check_headers: Option<String>,
+
+    /// Header-Vorlage aus Datei prüfen ({year} steht für eine Jahreszahl)
+    #[arg(long = "header-template", value_name = "DATEI")]
+    pub header_template: Option<PathBuf>,
+
+    /// Mit Fehler beenden, wenn Dateien ohne gültigen Header gefunden werden
+    #[arg(long = "require-headers")]
+    pub require_headers: bool,
+
+    /// Ergebnis nach dem Export im Standardprogramm öffnen
+    #[arg(long = "open")]
+    pub open: bool,
+
+    /// Ergebnis mit einem Programm öffnen (ohne Angabe: $EDITOR)
+    #[arg(long = "open-with", value_name = "PROGRAMM", num_args = 0..=1)]
+    pub open_with: Option<Option<String>>,
+
+    /// Export in eine Prompt-Vorlage einbetten ({project}, {file_count}, {date}, {document})
+    #[arg(long = "prompt", value_name = "VORLAGE")]
+    pub prompt: Option<PathBuf>,
+
+    /// Ausführliche Ausgabe (-v: Details, -vv: Ablaufverfolgung; RUST_LOG hat Vorrang)
+    #[arg(short = 'v', long = "verbose", action = clap::ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    /// Format der Statusausgabe auf stderr
+    #[arg(long = "log-format", value_enum, default_value_t = LogFormat::Text, global = true)]
+    pub log_format: LogFormat,
+}
+
+/// Auswahl des Projekts und der zu exportierenden Dateien.
+///
+/// Wird vom Export und von allen Subcommands geteilt, die Dateien sammeln.
+#[derive(Args, Debug, Clone)]
+pub struct SelectionArgs {
     /// Projektverzeichnis (Standard: aktuelles Verzeichnis)
     #[arg(default_value = ".")]
     pub directory: PathBuf,
 
+    /// Nur diese Teilpfade exportieren (relativ zum Projektverzeichnis); Baum
+    /// und Pfade bleiben auf das Projektverzeichnis bezogen
+    #[arg(value_name = "TEILPFAD")]
+    pub paths: Vec<PathBuf>,
+
     /// Projekttyp(en), kommasepariert (z.B. python,vue,config)
     #[arg(short = 't', long = "type", value_delimiter = ',')]
     pub types: Option<Vec<String>>,
@@ -36,91 +418,656 @@ pub struct Cli {
     #[arg(short = 'e', long = "ext", num_args = 1..)]
     pub extensions: Option<Vec<String>>,
 
+    /// Dateiendungen aus den Projekttypen wieder entfernen (z.B. .json .css)
+    #[arg(long = "without-ext", value_name = "EXT", num_args = 1.., requires = "types")]
+    pub without_extensions: Option<Vec<String>>,
+
+    /// Alle Textdateien unabhängig von der Endung exportieren (Binärdateien
+    /// werden am Inhalt erkannt und übersprungen)
+    #[arg(long = "all-text", conflicts_with_all = ["types", "extensions"])]
+    pub all_text: bool,
+
     /// Zusätzliche Ausschlüsse (Ordner/Dateien/Patterns)
     #[arg(short = 'x', long = "exclude", num_args = 1..)]
     pub excludes: Option<Vec<String>>,
 
-    /// Ausgabedatei (Standard: <projektname>_code.md)
-    #[arg(short = 'o', long = "output")]
-    pub output: Option<PathBuf>,
+    /// Nur Dateien, deren relativer Pfad mit einem dieser Präfixe beginnt
+    /// (z.B. backend/ shared/)
+    #[arg(long = "only", value_name = "PRÄFIX", num_args = 1..)]
+    pub only: Vec<String>,
 
-    /// Projektname für den Header (Standard: Ordnername)
-    #[arg(short = 'n', long = "name")]
-    pub name: Option<String>,
-
-    /// Ordnerstruktur-Baum nicht ausgeben
-    #[arg(long = "no-tree")]
-    pub no_tree: bool,
+    /// Einträge mit export-ignore in .gitattributes nicht ausschließen
+    #[arg(long = "no-export-ignore")]
+    pub no_export_ignore: bool,
 
     /// Standard-Ausschlüsse deaktivieren
     #[arg(long = "no-default-excludes")]
     pub no_default_excludes: bool,
 
-    /// Ausführliche Ausgabe
-    #[arg(short = 'v', long = "verbose")]
-    pub verbose: bool,
+    /// Einzelne Kategorien der Standard-Ausschlüsse deaktivieren, kommasepariert
+    /// (deps, build, ide, os, vcs, logs, coverage oder aus [excludes])
+    #[arg(long = "no-exclude-category", value_name = "KATEGORIE", value_delimiter = ',')]
+    pub no_exclude_categories: Vec<String>,
+
+    /// Verhalten bei nicht lesbaren Verzeichnissen während der Suche
+    #[arg(long = "on-walk-error", value_name = "MODUS", value_enum, default_value_t = WalkErrorPolicy::Skip)]
+    pub on_walk_error: WalkErrorPolicy,
+
+    /// Verzeichnisse mit eigenem .git (Klone, Submodule) überspringen; mit
+    /// `--skip-nested-repos=false` werden sie mit exportiert
+    #[arg(
+        long = "skip-nested-repos",
+        value_name = "BOOL",
+        default_value_t = true,
+        num_args = 0..=1,
+        default_missing_value = "true",
+        action = clap::ArgAction::Set
+    )]
+    pub skip_nested_repos: bool,
+
+    /// Symlinks auf Verzeichnisse folgen; mehrfach erreichbare Dateien werden
+    /// nur einmal exportiert, weitere Pfade als Alias aufgeführt
+    #[arg(long = "follow-symlinks")]
+    pub follow_symlinks: bool,
+}
+
+/// Verfügbare Ausgabeformate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Markdown-Dokument mit Codeblöcken
+    Markdown,
+    /// XML-Dokumentstruktur für Sprachmodelle (<documents><document>…)
+    Xml,
+    /// Ein JSON-Objekt pro Datei (JSON Lines)
+    Jsonl,
+    /// Confluence-Speicherformat (XHTML mit code-Makros)
+    Confluence,
+}
+
+impl OutputFormat {
+    /// Gibt die Dateiendung des Formats zurück.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Markdown => "md",
+            OutputFormat::Xml => "xml",
+            OutputFormat::Jsonl => "jsonl",
+            OutputFormat::Confluence => "xml",
+        }
+    }
+}
+
+/// Varianten der Markdown-Ausgabe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Flavor {
+    /// Ein Dokument mit Ankerlinks
+    Standard,
+    /// Obsidian-Vault mit Wikilinks, Front Matter und Tags
+    Obsidian,
+}
+
+/// Ziele für `--publish`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PublishTarget {
+    /// Confluence-Seite (nur mit --format confluence, Tabelle [confluence])
+    Confluence,
+    /// Geheimer GitHub Gist mit allen Ausgabedateien (Token aus GITHUB_TOKEN)
+    Gist,
+}
+
+/// Kriterien für die Aufteilung der Ausgabe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SplitMode {
+    /// Nach Token-Budget (--model oder --max-tokens)
+    Tokens,
+    /// Nach Größe des Dateiinhalts (--split-size)
+    Size,
+    /// Ein Teil je Verzeichnis der obersten Ebene
+    Dir,
+}
+
+/// Formate der Statusausgabe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Lesbarer Text
+    Text,
+    /// Ein JSON-Objekt pro Zeile (z.B. für CI-Systeme)
+    Json,
 }
 
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Zeigt alle verfügbaren Projekttypen an
     ListTypes,
+    /// Startet einen MCP-Server (stdio), über den KI-Agenten das Projekt abfragen
+    ServeMcp(SelectionArgs),
+    /// Startet eine lokale HTML-Vorschau mit Live-Reload
+    Serve {
+        #[command(flatten)]
+        selection: SelectionArgs,
+
+        /// Port des Vorschauservers
+        #[arg(short = 'p', long = "port", default_value_t = 8000)]
+        port: u16,
+    },
+    /// Misst die Laufzeit der Pipeline je Phase (walk, filter, read, render, write)
+    Bench {
+        #[command(flatten)]
+        selection: SelectionArgs,
+
+        /// Anzahl der Durchläufe
+        #[arg(short = 'r', long = "runs", default_value_t = 3)]
+        runs: usize,
+    },
+    /// Schätzt die Token-Zahl je Datei und insgesamt für ein Sprachmodell
+    Tokens {
+        #[command(flatten)]
+        selection: SelectionArgs,
+
+        /// Sprachmodell, dessen Tokenizer geschätzt wird (z.B. gpt-4o, claude-sonnet-4)
+        #[arg(short = 'm', long = "model", default_value = "gpt-4o")]
+        model: String,
+
+        /// Nur die N größten Dateien anzeigen
+        #[arg(long = "top", value_name = "N")]
+        top: Option<usize>,
+    },
+    /// Prüft Konfiguration, Ausschlüsse, gefundene Endungen und Ausgabepfad
+    Doctor {
+        #[command(flatten)]
+        selection: SelectionArgs,
+
+        /// Konfigurationsdatei (Standard: nächste code2md.toml im Projektverzeichnis oder darüber)
+        #[arg(short = 'c', long = "config", value_name = "DATEI")]
+        config: Option<PathBuf>,
+
+        /// Geplante Ausgabedatei, deren Lage geprüft werden soll
+        #[arg(short = 'o', long = "output")]
+        output: Option<PathBuf>,
+    },
+    /// Wählt Dateien per unscharfer Suche aus und exportiert nur diese
+    /// (z.B. für minimale Fehlerberichte)
+    Pick {
+        #[command(flatten)]
+        selection: SelectionArgs,
+
+        /// Ausgabedatei (Standard: <projektname>_pick.md)
+        #[arg(short = 'o', long = "output")]
+        output: Option<PathBuf>,
+
+        /// Die gewählten Dateien als Auswahl speichern (wieder exportierbar mit --selection)
+        #[arg(long = "save-selection", value_name = "NAME")]
+        save_selection: Option<String>,
+    },
+    /// Zeigt den Verlauf der Exporte (Dateien, Größe, Dauer je Lauf)
+    History {
+        /// Projektverzeichnis (Standard: aktuelles Verzeichnis)
+        #[arg(default_value = ".")]
+        directory: PathBuf,
+
+        /// Nur die letzten N Läufe anzeigen
+        #[arg(short = 'n', long = "last", value_name = "N")]
+        last: Option<usize>,
+    },
 }
 
 impl Cli {
     /// Prüft, ob die CLI-Argumente valide sind.
-    pub fn validate(&self) -> anyhow::Result<()> {
+    pub fn validate(&self) -> Result<()> {
         // Wenn ein Subcommand aktiv ist, keine weitere Validierung nötig
         if self.command.is_some() {
             return Ok(());
         }
 
-        // Mindestens --type oder --ext muss angegeben sein
-        if self.types.is_none() && self.extensions.is_none() {
-            anyhow::bail!(
-                "Bitte mindestens --type oder --ext angeben.\n\
+        if (self.append || self.update) && self.format != OutputFormat::Markdown {
+            return Err(Code2MdError::Config(format!(
+                "{} wird nur für das Markdown-Format unterstützt.",
+                if self.append { "--append" } else { "--update" }
+            )));
+        }
+
+        if self.separate_tests && self.format != OutputFormat::Markdown {
+            return Err(Code2MdError::Config(
+                "--separate-tests wird nur für das Markdown-Format unterstützt.".to_string(),
+            ));
+        }
+
+        let markdown_only = [
+            ("--stats", self.stats),
+            ("--metrics", self.metrics),
+            ("--todos", self.todos),
+            ("--annotate", self.annotate.is_some()),
+            ("--import-links", self.import_links),
+            ("--repo-url", self.repo_url.is_some()),
+            ("--link-sources", self.link_sources),
+            ("--front-matter", self.front_matter),
+            ("--flavor", self.flavor != Flavor::Standard),
+        ];
+        if let Some((flag, _)) = markdown_only.iter().find(|(_, set)| *set) {
+            if self.format != OutputFormat::Markdown {
+                return Err(Code2MdError::Config(format!(
+                    "{} wird nur für das Markdown-Format unterstützt.",
+                    flag
+                )));
+            }
+        }
+
+        if self.check && self.flavor == Flavor::Obsidian {
+            return Err(Code2MdError::Config(
+                "--check wird für --flavor obsidian nicht unterstützt.".to_string(),
+            ));
+        }
+
+        match self.publish {
+            Some(PublishTarget::Confluence) if self.format != OutputFormat::Confluence => {
+                return Err(Code2MdError::Config(
+                    "--publish confluence wird nur für das Confluence-Format unterstützt.".to_string(),
+                ));
+            }
+            Some(PublishTarget::Gist) if self.compress.is_some() || self.flavor != Flavor::Standard => {
+                return Err(Code2MdError::Config(
+                    "--publish gist unterstützt weder --compress noch --flavor.".to_string(),
+                ));
+            }
+            _ => {}
+        }
+
+        if self.summarize_cmd.is_some() && self.format != OutputFormat::Markdown {
+            return Err(Code2MdError::Config(
+                "--summarize-cmd wird nur für das Markdown-Format unterstützt.".to_string(),
+            ));
+        }
+
+        self.token_budget()?;
+        self.split()?;
+        self.selection.validate()
+    }
+
+    /// Gibt die Aufteilung aus --split und den zugehörigen Limits zurück.
+    pub fn split(&self) -> Result<Option<Split>> {
+        let Some(mode) = self.split else {
+            return Ok(None);
+        };
+        if self.format != OutputFormat::Markdown {
+            return Err(Code2MdError::Config(
+                "--split wird nur für das Markdown-Format unterstützt.".to_string(),
+            ));
+        }
+        let split = match mode {
+            SplitMode::Tokens => {
+                let (budget, tokenizer) = self.token_budget()?.ok_or_else(|| {
+                    Code2MdError::Config("--split tokens benötigt --model oder --max-tokens.".to_string())
+                })?;
+                Split::Tokens { budget, tokenizer }
+            }
+            SplitMode::Size => Split::Size(self.split_size.ok_or_else(|| {
+                Code2MdError::Config("--split size benötigt --split-size.".to_string())
+            })?),
+            SplitMode::Dir => Split::Dir,
+        };
+        Ok(Some(split))
+    }
+
+    /// Gibt Token-Budget und Tokenizer aus --max-tokens bzw. --model zurück.
+    ///
+    /// Ohne Modell wird nach o200k geschätzt.
+    pub fn token_budget(&self) -> Result<Option<(usize, Tokenizer)>> {
+        let model = self.model.as_deref().map(find_model).transpose()?;
+        let tokenizer = model.map(|m| m.tokenizer).unwrap_or(Tokenizer::O200k);
+        Ok(self
+            .max_tokens
+            .or(model.map(|m| m.token_budget()))
+            .map(|budget| (budget, tokenizer)))
+    }
+
+    /// Gibt den Projektnamen zurück (aus --name oder Ordnername).
+    pub fn project_name(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| self.selection.project_name())
+    }
+
+    /// Gibt den Ausgabepfad zurück; Platzhalter in `--output` und
+    /// `--output-dir` (siehe [`OUTPUT_PLACEHOLDERS`]) werden aufgelöst.
+    /// Relative Pfade liegen in `--output-dir`, sofern angegeben.
+    pub fn output_path(&self) -> Result<PathBuf> {
+        let safe_name = safe_file_name(&self.project_name());
+        let dir = match &self.output_dir {
+            Some(dir) => Some(PathBuf::from(self.expand_output(&dir.to_string_lossy(), &safe_name)?)),
+            None => None,
+        };
+        let Some(output) = &self.output else {
+            return Ok(dir.as_ref().unwrap_or(&self.selection.directory).join(match self.compress {
+                Some(compression) => format!(
+                    "{}_code.{}.{}",
+                    safe_name,
+                    self.format.extension(),
+                    compression.extension()
+                ),
+                None => format!("{}_code.{}", safe_name, self.format.extension()),
+            }));
+        };
+        let output = PathBuf::from(self.expand_output(&output.to_string_lossy(), &safe_name)?);
+        Ok(match dir {
+            Some(dir) => dir.join(output),
+            None => output,
+        })
+    }
+
+    fn expand_output(&self, template: &str, safe_name: &str) -> Result<String> {
+        let now = chrono::Local::now();
+        let git = |args: &[&str]| git(&self.selection.directory, args).map(|value| safe_file_name(&value));
+        expand_placeholders(template, |key| match key {
+            "name" => Ok(safe_name.to_string()),
+            "date" => Ok(now.format("%Y-%m-%d").to_string()),
+            "time" => Ok(now.format("%H%M%S").to_string()),
+            "git_short" => git(&["rev-parse", "--short", "HEAD"]),
+            "git_branch" => git(&["rev-parse", "--abbrev-ref", "HEAD"]),
+            _ => Err(Code2MdError::Config(format!(
+                "Unbekannter Platzhalter {{{}}} in --output (verfügbar: {}).",
+                key,
+                OUTPUT_PLACEHOLDERS.map(|p| format!("{{{}}}", p)).join(", ")
+            ))),
+        })
+    }
+}
+
+/// Platzhalter für Dateinamen in `--output`.
+pub const OUTPUT_PLACEHOLDERS: [&str; 5] = ["name", "date", "time", "git_short", "git_branch"];
+
+/// Ersetzt `{schlüssel}` durch den Wert aus `value`; Text ohne Platzhalter
+/// bleibt unverändert, `{{` und `}}` stehen für einzelne Klammern.
+pub fn expand_placeholders(template: &str, mut value: impl FnMut(&str) -> Result<String>) -> Result<String> {
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(pos) = rest.find(['{', '}']) {
+        expanded.push_str(&rest[..pos]);
+        let tail = &rest[pos..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            expanded.push_str(&tail[..1]);
+            rest = &tail[2..];
+            continue;
+        }
+        match tail.strip_prefix('{').and_then(|inner| inner.split_once('}')) {
+            Some((key, after)) => {
+                expanded.push_str(&value(key)?);
+                rest = after;
+            }
+            None => {
+                expanded.push_str(&tail[..1]);
+                rest = &tail[1..];
+            }
+        }
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Ersetzt Zeichen, die in Dateinamen stören, durch `_`.
+fn safe_file_name(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+impl SelectionArgs {
+    /// Prüft, ob Auswahl und Verzeichnis valide sind.
+    pub fn validate(&self) -> Result<()> {
+        // Mindestens --type, --ext oder --all-text muss angegeben sein
+        if self.types.is_none() && self.extensions.is_none() && !self.all_text {
+            return Err(Code2MdError::Config(
+                "Bitte mindestens --type, --ext oder --all-text angeben.\n\
                  Nutze 'code2md list-types' für verfügbare Typen."
-            );
+                    .to_string(),
+            ));
         }
 
         // Verzeichnis muss existieren
         if !self.directory.exists() {
-            anyhow::bail!(
+            return Err(Code2MdError::Config(format!(
                 "Verzeichnis '{}' existiert nicht.",
                 self.directory.display()
-            );
+            )));
         }
 
         if !self.directory.is_dir() {
-            anyhow::bail!(
+            return Err(Code2MdError::Config(format!(
                 "'{}' ist kein Verzeichnis.",
                 self.directory.display()
-            );
+            )));
         }
 
+        self.sub_paths()?;
         Ok(())
     }
 
-    /// Gibt den Projektnamen zurück (aus --name oder Ordnername).
+    /// Teilpfade relativ zum Projektverzeichnis, mit `/` als Trenner. Relative
+    /// Angaben werden zuerst im Projektverzeichnis, dann im Arbeitsverzeichnis
+    /// gesucht. Leer, wenn das ganze Projekt exportiert wird.
+    pub fn sub_paths(&self) -> Result<Vec<String>> {
+        if self.paths.is_empty() {
+            return Ok(Vec::new());
+        }
+        let base = paths::canonicalize(&self.directory).map_err(|e| Code2MdError::io(&self.directory, e))?;
+        let mut sub_paths = Vec::new();
+        for path in &self.paths {
+            let in_project = self.directory.join(path);
+            let candidate = if in_project.exists() { in_project } else { path.clone() };
+            let resolved = paths::canonicalize(&candidate).map_err(|_| {
+                Code2MdError::Config(format!("Teilpfad '{}' existiert nicht.", path.display()))
+            })?;
+            match paths::relative_to(&resolved, &base) {
+                Some(rel) if rel.is_empty() => return Ok(Vec::new()),
+                Some(rel) => sub_paths.push(rel),
+                None => {
+                    return Err(Code2MdError::Config(format!(
+                        "Teilpfad '{}' liegt nicht im Projektverzeichnis.",
+                        path.display()
+                    )))
+                }
+            }
+        }
+        Ok(sub_paths)
+    }
+
+    /// Gibt den Ordnernamen des Projekts zurück.
     pub fn project_name(&self) -> String {
-        self.name.clone().unwrap_or_else(|| {
-            self.directory
-                .canonicalize()
-                .ok()
-                .and_then(|p| p.file_name().map(|s| s.to_string_lossy().to_string()))
-                .unwrap_or_else(|| "project".to_string())
-        })
+        paths::canonicalize(&self.directory)
+            .ok()
+            .and_then(|p| p.file_name().map(|s| s.to_string_lossy().to_string()))
+            .unwrap_or_else(|| "project".to_string())
     }
 
-    /// Gibt den Ausgabepfad zurück.
-    pub fn output_path(&self) -> PathBuf {
-        self.output.clone().unwrap_or_else(|| {
-            let safe_name: String = self
-                .project_name()
-                .chars()
-                .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
-                .collect();
-            self.directory.join(format!("{}_code.md", safe_name))
-        })
+    /// Sammelt alle Dateiendungen aus Projekttypen und --ext.
+    pub fn collect_extensions(&self) -> Result<HashSet<String>> {
+        self.collect_extensions_with(&TypeAliases::new())
+    }
+
+    /// Wie [`SelectionArgs::collect_extensions`], mit Typ-Aliasen aus der Konfiguration.
+    pub fn collect_extensions_with(&self, aliases: &TypeAliases) -> Result<HashSet<String>> {
+        let mut extensions: HashSet<String> = HashSet::new();
+
+        if let Some(ref type_names) = self.types {
+            extensions.extend(collect_extensions(type_names, aliases)?);
+        }
+
+        // Von den Typen beigesteuerte Extensions abziehen
+        if let Some(ref exts) = self.without_extensions {
+            for ext in exts {
+                extensions.remove(&normalize_extension(ext));
+            }
+        }
+
+        // Zusätzliche Extensions hinzufügen
+        if let Some(ref exts) = self.extensions {
+            extensions.extend(exts.iter().map(|ext| normalize_extension(ext)));
+        }
+
+        Ok(extensions)
+    }
+
+    /// Stellt Standard- und zusätzliche Ausschlüsse zusammen.
+    pub fn exclude_patterns(&self) -> Result<Vec<String>> {
+        self.exclude_patterns_with(&ExcludeCategories::new())
+    }
+
+    /// Wie [`SelectionArgs::exclude_patterns`], mit Kategorien aus der Konfiguration.
+    pub fn exclude_patterns_with(&self, categories: &ExcludeCategories) -> Result<Vec<String>> {
+        let mut exclude_patterns: Vec<String> = if self.no_default_excludes {
+            Vec::new()
+        } else {
+            default_excludes(&self.no_exclude_categories, categories)?
+        };
+
+        if let Some(ref excludes) = self.excludes {
+            exclude_patterns.extend(excludes.iter().cloned());
+        }
+
+        Ok(exclude_patterns)
+    }
+
+    /// Erstellt die Collector-Konfiguration für diese Auswahl.
+    pub fn collector_config(&self) -> Result<CollectorConfig> {
+        let mut config = CollectorConfig::new(self.collect_extensions()?, &self.exclude_patterns()?)?;
+        config.on_walk_error = self.on_walk_error;
+        config.skip_nested_repos = self.skip_nested_repos;
+        config.all_text = self.all_text;
+        config.attributes = self.export_ignore_attributes()?;
+        config.follow_links = self.follow_symlinks;
+        config.roots = self.sub_paths()?;
+        config.set_only(&self.only);
+        Ok(config)
+    }
+
+    /// Attribute für `export-ignore` aus den `.gitattributes` des Projekts und
+    /// der übergeordneten Verzeichnisse, sofern nicht abgeschaltet.
+    pub fn export_ignore_attributes(&self) -> Result<Option<GitAttributes>> {
+        if self.no_export_ignore {
+            return Ok(None);
+        }
+        let attributes = GitAttributes::load(&self.directory, [])?;
+        Ok((!attributes.is_empty()).then_some(attributes))
+    }
+}
+
+/// Vereinheitlicht eine Endung auf Kleinbuchstaben mit führendem Punkt.
+fn normalize_extension(ext: &str) -> String {
+    if ext.starts_with('.') {
+        ext.to_lowercase()
+    } else {
+        format!(".{}", ext.to_lowercase())
+    }
+}
+
+/// Parst eine Größenangabe wie `512`, `256KB` oder `2MB` in Bytes.
+pub fn parse_size(value: &str) -> std::result::Result<u64, String> {
+    let upper = value.trim().to_uppercase();
+    let (number, factor) = if let Some(n) = upper.strip_suffix("GB") {
+        (n, 1024 * 1024 * 1024)
+    } else if let Some(n) = upper.strip_suffix("MB") {
+        (n, 1024 * 1024)
+    } else if let Some(n) = upper.strip_suffix("KB") {
+        (n, 1024)
+    } else {
+        (upper.strip_suffix('B').unwrap_or(&upper), 1)
+    };
+
+    number
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(factor))
+        .ok_or_else(|| format!("Ungültige Größenangabe: '{}'", value))
+}
+
+/// Parst eine Angabe `key=wert` für `--meta`. Schlüssel bestehen aus
+/// Buchstaben, Ziffern, `_`, `-` und `.`.
+pub fn parse_meta(value: &str) -> std::result::Result<(String, String), String> {
+    let (key, text) = value
+        .split_once('=')
+        .ok_or_else(|| format!("Erwartet KEY=WERT: '{}'", value))?;
+    let key = key.trim();
+    if key.is_empty() || !key.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.')) {
+        return Err(format!("Ungültiger Schlüssel: '{}'", key));
+    }
+    Ok((key.to_string(), text.trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("512"), Ok(512));
+        assert_eq!(parse_size("256KB"), Ok(256 * 1024));
+        assert_eq!(parse_size("2mb"), Ok(2 * 1024 * 1024));
+        assert!(parse_size("viel").is_err());
+        assert!(parse_size("20000000000GB").is_err());
+    }
+
+    #[test]
+    fn test_expand_placeholders() -> anyhow::Result<()> {
+        let value = |key: &str| match key {
+            "name" => Ok("demo".to_string()),
+            "date" => Ok("2024-05-01".to_string()),
+            _ => Err(Code2MdError::Config(key.to_string())),
+        };
+        assert_eq!(expand_placeholders("exports/{name}_{date}.md", value)?, "exports/demo_2024-05-01.md");
+        assert_eq!(expand_placeholders("plain.md", value)?, "plain.md");
+        assert_eq!(expand_placeholders("{{name}}_{name}", value)?, "{name}_demo");
+        assert_eq!(expand_placeholders("offen{", value)?, "offen{");
+        assert!(expand_placeholders("{unbekannt}.md", value).is_err());
+
+        let cli = Cli::try_parse_from(["code2md", ".", "-t", "rust", "-n", "mein projekt", "-o", "{name}_{time}.md"])?;
+        let output = cli.output_path()?.to_string_lossy().to_string();
+        assert!(output.starts_with("mein_projekt_") && output.len() == "mein_projekt_000000.md".len(), "{}", output);
+
+        let cli = Cli::try_parse_from(["code2md", ".", "-t", "rust", "-n", "demo", "--output-dir", "exporte/{name}"])?;
+        assert_eq!(cli.output_path()?, PathBuf::from("exporte/demo/demo_code.md"));
+        let cli = Cli::try_parse_from(["code2md", ".", "-t", "rust", "--output-dir", "exporte", "-o", "x.md"])?;
+        assert_eq!(cli.output_path()?, PathBuf::from("exporte/x.md"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_meta() {
+        assert_eq!(parse_meta("build=1234"), Ok(("build".to_string(), "1234".to_string())));
+        assert_eq!(parse_meta("ticket = ABC-1=2"), Ok(("ticket".to_string(), "ABC-1=2".to_string())));
+        assert!(parse_meta("ohne-wert").is_err());
+        assert!(parse_meta("mit leerzeichen=x").is_err());
+    }
+
+    #[test]
+    fn test_without_extensions() -> anyhow::Result<()> {
+        let cli = Cli::try_parse_from(["code2md", "-t", "vue", "--without-ext", ".json", "CSS", "-e", ".css"])?;
+        let extensions = cli.selection.collect_extensions()?;
+        assert!(extensions.contains(".vue"));
+        assert!(!extensions.contains(".json"));
+        assert!(extensions.contains(".css"));
+
+        assert!(Cli::try_parse_from(["code2md", "-e", ".py", "--without-ext", ".json"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sub_paths() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::create_dir_all(dir.path().join("src/api"))?;
+        std::fs::create_dir_all(dir.path().join("src/models"))?;
+        let base = dir.path().to_string_lossy().to_string();
+
+        let cli = Cli::try_parse_from(["code2md", &base, "src/api", "src/models/", "--type", "python"])?;
+        assert_eq!(cli.selection.sub_paths()?, vec!["src/api", "src/models"]);
+        assert!(cli.command.is_none());
+
+        let cli = Cli::try_parse_from(["code2md", &base, "src/fehlt", "-t", "python"])?;
+        assert!(cli.selection.validate().is_err());
+
+        let cli = Cli::try_parse_from(["code2md", &base, ".", "-t", "python"])?;
+        assert!(cli.selection.sub_paths()?.is_empty());
+
+        assert!(matches!(Cli::try_parse_from(["code2md", "list-types"])?.command, Some(Commands::ListTypes)));
+        Ok(())
     }
 }
@@ -1,16 +1,166 @@
 //! Datei-Sammlung und Filterung.
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::fs::Metadata;
+use std::io;
+use std::ops::Deref;
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
 
+use clap::ValueEnum;
 use globset::{Glob, GlobSet, GlobSetBuilder};
-use walkdir::{DirEntry, WalkDir};
+use sha2::{Digest, Sha256};
+use ignore::{WalkBuilder, WalkState};
+use memmap2::Mmap;
+
+use crate::error::{Code2MdError, Result};
+use crate::events::EventSink;
+use crate::gitattributes::GitAttributes;
+use crate::paths;
+use crate::processor::{relative_path, slash_path};
+
+/// Eine gesammelte Datei mit den Metadaten aus der Verzeichnissuche.
+///
+/// Der Inhalt wird beim ersten Zugriff einmal gelesen und von allen Stufen
+/// (Header-Prüfung, Lizenzen, Prüfsummen, Rendern) gemeinsam genutzt.
+/// Dateien ab [`MMAP_THRESHOLD`] werden nicht zwischengespeichert, sondern
+/// bei jedem Zugriff in den Speicher abgebildet.
+pub struct CollectedFile {
+    pub path: PathBuf,
+    pub metadata: Metadata,
+    content: OnceLock<std::result::Result<Vec<u8>, (io::ErrorKind, String)>>,
+}
+
+/// Inhalt einer [`CollectedFile`].
+pub enum FileBytes<'a> {
+    /// Zwischengespeicherter Inhalt
+    Cached(&'a [u8]),
+    /// In den Speicher abgebildete große Datei
+    Mapped(Mmap),
+}
+
+impl Deref for FileBytes<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            FileBytes::Cached(bytes) => bytes,
+            FileBytes::Mapped(map) => map,
+        }
+    }
+}
+
+impl CollectedFile {
+    pub fn new(path: PathBuf, metadata: Metadata) -> Self {
+        Self {
+            path,
+            metadata,
+            content: OnceLock::new(),
+        }
+    }
+
+    /// Erstellt einen Eintrag für einen Pfad außerhalb der Verzeichnissuche.
+    pub fn from_path(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let metadata = std::fs::metadata(&path)?;
+        Ok(Self::new(path, metadata))
+    }
+
+    /// Dateigröße laut Verzeichnissuche.
+    pub fn size(&self) -> u64 {
+        self.metadata.len()
+    }
+
+    /// Rohinhalt der Datei.
+    pub fn bytes(&self) -> io::Result<FileBytes<'_>> {
+        if self.size() >= MMAP_THRESHOLD {
+            let file = std::fs::File::open(&self.path)?;
+            // SAFETY: Die Abbildung wird nur gelesen und lebt nur für einen
+            // Verarbeitungsschritt. Wird die Datei währenddessen von außen
+            // gekürzt, kann der Zugriff fehlschlagen; Quelldateien ändern sich
+            // während eines Exports üblicherweise nicht.
+            return Ok(FileBytes::Mapped(unsafe { Mmap::map(&file)? }));
+        }
+
+        match self
+            .content
+            .get_or_init(|| std::fs::read(&self.path).map_err(|e| (e.kind(), e.to_string())))
+        {
+            Ok(bytes) => Ok(FileBytes::Cached(bytes)),
+            Err((kind, message)) => Err(io::Error::new(*kind, message.clone())),
+        }
+    }
+
+    /// Inhalt als Text; ungültiges UTF-8 wird verlustbehaftet konvertiert.
+    pub fn text(&self) -> io::Result<String> {
+        Ok(String::from_utf8_lossy(&self.bytes()?).into_owned())
+    }
+
+    /// SHA-256-Prüfsumme des Inhalts als Hex-String.
+    pub fn sha256(&self) -> io::Result<String> {
+        Ok(format!("{:x}", Sha256::digest(&*self.bytes()?)))
+    }
+}
+
+impl CollectedFiles {
+    /// Aliase je relativem Pfad der exportierten Datei, in Sortierreihenfolge.
+    pub fn alias_map(&self) -> BTreeMap<String, Vec<String>> {
+        let mut map: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (alias, original) in &self.aliases {
+            map.entry(relative_path(original, &self.base_path))
+                .or_default()
+                .push(relative_path(alias, &self.base_path));
+        }
+        map
+    }
+}
+
+impl fmt::Debug for CollectedFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CollectedFile")
+            .field("path", &self.path)
+            .field("size", &self.size())
+            .finish()
+    }
+}
 
 /// Ergebnis der Dateisammlung.
 #[derive(Debug)]
 pub struct CollectedFiles {
-    pub files: Vec<PathBuf>,
+    pub files: Vec<CollectedFile>,
     pub base_path: PathBuf,
+    /// Übersprungene Einträge (z.B. nicht lesbare Verzeichnisse) mit Fehlermeldung
+    pub skipped: Vec<(PathBuf, String)>,
+    /// Übersprungene verschachtelte Repositories (eigenes `.git`)
+    pub nested_repos: Vec<PathBuf>,
+    /// Durch Ausschlussregeln übersprungene Verzeichnisse (ohne Unterverzeichnisse)
+    pub excluded_dirs: Vec<PathBuf>,
+    /// Weitere Pfade bereits gesammelter Dateien (Symlinks, Hardlinks) mit dem
+    /// Pfad, unter dem der Inhalt exportiert wird
+    pub aliases: Vec<(PathBuf, PathBuf)>,
+}
+
+/// Verhalten bei nicht lesbaren Dateien.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ReadErrorPolicy {
+    /// Datei weglassen und Warnung ausgeben
+    Skip,
+    /// Hinweis statt Inhalt einfügen und Warnung ausgeben
+    #[default]
+    Placeholder,
+    /// Export abbrechen
+    Fail,
+}
+
+/// Verhalten bei nicht lesbaren Verzeichnissen während der Suche.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum WalkErrorPolicy {
+    /// Eintrag überspringen und Warnung ausgeben
+    #[default]
+    Skip,
+    /// Export abbrechen
+    Fail,
 }
 
 /// Konfiguration für die Dateisammlung.
@@ -18,29 +168,105 @@ pub struct CollectedFiles {
 pub struct CollectorConfig {
     pub extensions: HashSet<String>,
     pub excludes: GlobSet,
+    pub on_walk_error: WalkErrorPolicy,
+    /// Verzeichnisse mit eigenem `.git` (Klone, Submodule) nicht betreten
+    pub skip_nested_repos: bool,
+    /// Abweichende Auswahl für Teilbäume, nach Tiefe aufsteigend sortiert
+    pub overrides: Vec<DirOverride>,
+    /// Alle Textdateien unabhängig von der Endung aufnehmen (`--all-text`)
+    pub all_text: bool,
+    /// Attribute aus `.gitattributes`; Pfade mit `export-ignore` entfallen
+    pub attributes: Option<GitAttributes>,
+    /// Symlinks auf Verzeichnisse folgen (`--follow-symlinks`)
+    pub follow_links: bool,
+    /// Nur diese Teilpfade (relativ, mit `/`) sammeln; leer für das ganze Projekt
+    pub roots: Vec<String>,
+    /// Erlaubte Präfixe relativer Pfade (`--only`); leer für alle
+    pub only: Vec<String>,
+}
+
+/// Abweichende Auswahl für einen Teilbaum (`[dir."…"]` in der Konfiguration).
+#[derive(Debug)]
+pub struct DirOverride {
+    /// Verzeichnis relativ zum Basisverzeichnis, leer für das ganze Projekt
+    pub dir: String,
+    /// Dateiendungen, die im Teilbaum statt der Auswahl gelten
+    pub extensions: Option<HashSet<String>>,
+    /// Zusätzliche Ausschlüsse, relativ zum Verzeichnis
+    pub excludes: GlobSet,
+}
+
+impl DirOverride {
+    pub fn new(dir: &str, extensions: Option<HashSet<String>>, exclude_patterns: &[String]) -> Result<Self> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in exclude_patterns {
+            // `dist/` meint das Verzeichnis selbst
+            builder.add(exclude_glob(pattern.trim_end_matches('/'))?);
+        }
+        Ok(Self {
+            dir: dir.trim_matches('/').to_string(),
+            extensions,
+            excludes: builder.build().map_err(|e| Code2MdError::Config(e.to_string()))?,
+        })
+    }
+
+    /// Pfad relativ zum Verzeichnis, falls er im Teilbaum liegt.
+    fn inner<'a>(&self, rel_str: &'a str) -> Option<&'a str> {
+        if self.dir.is_empty() {
+            return Some(rel_str);
+        }
+        rel_str.strip_prefix(self.dir.as_str())?.strip_prefix('/')
+    }
+
+    /// Prüft, ob der Pfad oder eine seiner Komponenten unterhalb des
+    /// Verzeichnisses ausgeschlossen ist.
+    fn excludes(&self, rel_str: &str) -> bool {
+        self.inner(rel_str).is_some_and(|inner| {
+            self.excludes.is_match(inner) || inner.split('/').any(|component| self.excludes.is_match(component))
+        })
+    }
 }
 
 impl CollectorConfig {
     /// Erstellt eine neue Collector-Konfiguration.
-    pub fn new(extensions: HashSet<String>, exclude_patterns: &[String]) -> anyhow::Result<Self> {
+    pub fn new(extensions: HashSet<String>, exclude_patterns: &[String]) -> Result<Self> {
         let mut builder = GlobSetBuilder::new();
         
         for pattern in exclude_patterns {
-            // Pattern normalisieren
-            let normalized = if pattern.contains('/') || pattern.contains('\\') {
-                pattern.replace('\\', "/")
-            } else {
-                format!("**/{}", pattern)
-            };
-            
-            let glob = Glob::new(&normalized)
-                .or_else(|_| Glob::new(&format!("**/{}", pattern)))?;
-            builder.add(glob);
+            builder.add(exclude_glob(pattern)?);
         }
         
-        let excludes = builder.build()?;
+        let excludes = builder
+            .build()
+            .map_err(|e| Code2MdError::Config(e.to_string()))?;
         
-        Ok(Self { extensions, excludes })
+        Ok(Self {
+            extensions,
+            excludes,
+            on_walk_error: WalkErrorPolicy::default(),
+            skip_nested_repos: true,
+            overrides: Vec::new(),
+            all_text: false,
+            attributes: None,
+            follow_links: false,
+            roots: Vec::new(),
+            only: Vec::new(),
+        })
+    }
+
+    /// Setzt die Teilbaum-Abweichungen; tiefere Verzeichnisse haben Vorrang.
+    pub fn set_overrides(&mut self, mut overrides: Vec<DirOverride>) {
+        overrides.sort_by_key(|o| if o.dir.is_empty() { 0 } else { o.dir.matches('/').count() + 1 });
+        self.overrides = overrides;
+    }
+
+    /// Setzt die erlaubten Pfadpräfixe; `\\` und ein führendes `./` werden vereinheitlicht.
+    pub fn set_only(&mut self, prefixes: &[String]) {
+        self.only = prefixes
+            .iter()
+            .map(|prefix| prefix.replace('\\', "/").trim_start_matches("./").to_string())
+            .filter(|prefix| !prefix.is_empty())
+            .collect();
     }
 
     /// Prüft, ob eine Datei eingeschlossen werden soll.
@@ -62,84 +288,304 @@ impl CollectorConfig {
             }
         }
         
+        // Abweichungen für Teilbäume und export-ignore
+        let rel_str = rel_str.replace('\\', "/");
+        if !self.roots.is_empty() && !self.roots.iter().any(|root| within(&rel_str, root)) {
+            return false;
+        }
+        if !self.only.is_empty() && !self.only.iter().any(|prefix| rel_str.starts_with(prefix.as_str())) {
+            return false;
+        }
+        if self.overrides.iter().any(|o| o.excludes(&rel_str)) || self.export_ignored(&rel_str) {
+            return false;
+        }
+        let extensions = match self
+            .overrides
+            .iter()
+            .rev()
+            .filter(|o| o.inner(&rel_str).is_some())
+            .find_map(|o| o.extensions.as_ref())
+        {
+            Some(extensions) => extensions,
+            None if self.all_text => return is_text_file(path),
+            None => &self.extensions,
+        };
+
         // Extension prüfen
         if let Some(ext) = path.extension() {
             let ext_with_dot = format!(".{}", ext.to_string_lossy().to_lowercase());
-            return self.extensions.contains(&ext_with_dot);
+            return extensions.contains(&ext_with_dot);
         }
         
         false
     }
 
     /// Prüft, ob ein Verzeichnis betreten werden soll.
-    fn should_enter_dir(&self, entry: &DirEntry, base_path: &Path) -> bool {
-        let path = entry.path();
+    /// Prüft, ob ein Verzeichnis durch Ausschlussregeln (Patterns, `[dir."…"]`,
+    /// `export-ignore`) übersprungen wird.
+    fn dir_excluded(&self, path: &Path, base_path: &Path) -> bool {
         let rel_path = path.strip_prefix(base_path).unwrap_or(path);
         let rel_str = rel_path.to_string_lossy();
         
         // Ausschluss-Patterns prüfen
         if self.excludes.is_match(rel_str.as_ref()) {
-            return false;
+            return true;
         }
         
         // Auch den Ordnernamen selbst prüfen
         if let Some(name) = path.file_name() {
             let name_str = name.to_string_lossy();
             if self.excludes.is_match(name_str.as_ref()) {
-                return false;
+                return true;
             }
         }
-        
-        true
+
+        let rel_str = rel_str.replace('\\', "/");
+        self.overrides.iter().any(|o| o.excludes(&rel_str)) || self.export_ignored(&rel_str)
     }
+
+    /// Prüft, ob ein Verzeichnis innerhalb der Teilpfade und `--only`-Präfixe liegt.
+    fn dir_selected(&self, path: &Path, base_path: &Path) -> bool {
+        let rel_str = slash_path(path.strip_prefix(base_path).unwrap_or(path));
+        // Nur Teilpfade und die Verzeichnisse auf dem Weg dorthin
+        if !self.roots.is_empty() && !self.roots.iter().any(|root| within(&rel_str, root) || within(root, &rel_str)) {
+            return false;
+        }
+        // Verzeichnisse, in denen kein Pfad mit erlaubtem Präfix liegen kann
+        let dir_prefix = format!("{}/", rel_str);
+        self.only.is_empty()
+            || self.only.iter().any(|prefix| dir_prefix.starts_with(prefix.as_str()) || prefix.starts_with(&dir_prefix))
+    }
+
+    fn export_ignored(&self, rel_str: &str) -> bool {
+        self.attributes.as_ref().is_some_and(|attributes| attributes.export_ignored(rel_str))
+    }
+}
+
+/// Prüft, ob ein relativer Pfad gleich `dir` ist oder darunter liegt.
+fn within(path: &str, dir: &str) -> bool {
+    path.strip_prefix(dir).is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Anzahl Bytes, die [`is_text_file`] prüft.
+const SNIFF_LEN: u64 = 8192;
+
+/// Prüft anhand des Dateianfangs, ob eine Datei Text enthält: Binärdateien
+/// enthalten in den ersten 8 KiB fast immer ein Nullbyte.
+pub fn is_text_file(path: &Path) -> bool {
+    use std::io::Read;
+
+    let Ok(file) = std::fs::File::open(path) else {
+        return false;
+    };
+    let mut head = Vec::with_capacity(SNIFF_LEN as usize);
+    file.take(SNIFF_LEN).read_to_end(&mut head).is_ok() && !head.contains(&0)
+}
+
+/// Übersetzt ein Ausschluss-Pattern in einen Glob.
+///
+/// Patterns ohne Pfadtrenner gelten in jeder Verzeichnistiefe.
+pub fn exclude_glob(pattern: &str) -> Result<Glob> {
+    // Pattern normalisieren
+    let normalized = if pattern.contains('/') || pattern.contains('\\') {
+        pattern.replace('\\', "/")
+    } else {
+        format!("**/{}", pattern)
+    };
+
+    Glob::new(&normalized)
+        .or_else(|_| Glob::new(&format!("**/{}", pattern)))
+        .map_err(|e| Code2MdError::Config(format!("Ungültiges Muster '{}': {}", pattern, e)))
 }
 
 /// Sammelt alle relevanten Dateien aus einem Verzeichnis.
-pub fn collect_files(base_path: &Path, config: &CollectorConfig) -> anyhow::Result<CollectedFiles> {
-    let base_path = base_path.canonicalize()?;
-    let mut files = Vec::new();
+///
+/// Verzeichnisse werden parallel durchsucht; das Ergebnis ist unabhängig von
+/// der Reihenfolge der Threads sortiert.
+pub fn collect_files(base_path: &Path, config: &CollectorConfig) -> Result<CollectedFiles> {
+    let base_path = paths::canonicalize(base_path).map_err(|e| Code2MdError::io(base_path, e))?;
 
-    let walker = WalkDir::new(&base_path)
-        .follow_links(false)
-        .into_iter()
-        .filter_entry(|e| {
-            if e.file_type().is_dir() {
-                config.should_enter_dir(e, &base_path)
-            } else {
-                true
-            }
+    // Ein nicht lesbares Basisverzeichnis ist immer fatal
+    std::fs::read_dir(&base_path).map_err(|e| Code2MdError::io(&base_path, e))?;
+
+    let found = Mutex::new(Vec::new());
+    let errors = Mutex::new(Vec::new());
+    let nested = Mutex::new(Vec::new());
+    let excluded = Mutex::new(Vec::new());
+
+    WalkBuilder::new(&base_path)
+        .standard_filters(false)
+        .follow_links(config.follow_links)
+        .build_parallel()
+        .run(|| {
+            let (found, errors, nested, excluded, base_path) = (&found, &errors, &nested, &excluded, &base_path);
+            Box::new(move |entry| {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(e) => {
+                        lock(errors).push(e);
+                        return match config.on_walk_error {
+                            WalkErrorPolicy::Skip => WalkState::Continue,
+                            WalkErrorPolicy::Fail => WalkState::Quit,
+                        };
+                    }
+                };
+                let path = entry.path();
+
+                if entry.file_type().is_some_and(|t| t.is_dir()) {
+                    if entry.depth() > 0 && !config.dir_selected(path, base_path) {
+                        return WalkState::Skip;
+                    }
+                    if entry.depth() > 0 && config.dir_excluded(path, base_path) {
+                        lock(excluded).push(path.to_path_buf());
+                        return WalkState::Skip;
+                    }
+                    // `.git` ist bei Submodulen eine Datei, bei Klonen ein Verzeichnis
+                    if entry.depth() > 0 && config.skip_nested_repos && path.join(".git").exists() {
+                        lock(nested).push(path.to_path_buf());
+                        return WalkState::Skip;
+                    }
+                } else if config.should_include(path, base_path) {
+                    // Metadaten folgen Symlinks, wie zuvor `Path::is_file`
+                    match std::fs::metadata(path) {
+                        Ok(metadata) if metadata.is_file() => {
+                            lock(found).push(CollectedFile::new(path.to_path_buf(), metadata));
+                        }
+                        _ => {}
+                    }
+                }
+                WalkState::Continue
+            })
         });
 
-    for entry in walker.filter_map(|e| e.ok()) {
-        let path = entry.path();
-        
-        if path.is_file() && config.should_include(path, &base_path) {
-            files.push(path.to_path_buf());
+    let mut files = found.into_inner().unwrap_or_else(|e| e.into_inner());
+    let mut errors: Vec<(PathBuf, ignore::Error)> = errors
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner())
+        .into_iter()
+        .map(|e| (error_path(&e).unwrap_or(&base_path).to_path_buf(), e))
+        .collect();
+    errors.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut skipped = Vec::new();
+    for (path, error) in errors {
+        if config.on_walk_error == WalkErrorPolicy::Fail {
+            return Err(Code2MdError::Walk { path, source: error });
         }
+        skipped.push((path, error.to_string()));
     }
 
     // Sortieren für konsistente Ausgabe
     files.sort_by(|a, b| {
-        let rel_a = a.strip_prefix(&base_path).unwrap_or(a);
-        let rel_b = b.strip_prefix(&base_path).unwrap_or(b);
+        let rel_a = a.path.strip_prefix(&base_path).unwrap_or(&a.path);
+        let rel_b = b.path.strip_prefix(&base_path).unwrap_or(&b.path);
         rel_a.to_string_lossy().to_lowercase().cmp(&rel_b.to_string_lossy().to_lowercase())
     });
 
-    Ok(CollectedFiles { files, base_path })
+    let (files, aliases) = dedupe_links(files);
+
+    let mut nested_repos = nested.into_inner().unwrap_or_else(|e| e.into_inner());
+    nested_repos.sort();
+    let mut excluded_dirs = excluded.into_inner().unwrap_or_else(|e| e.into_inner());
+    excluded_dirs.sort();
+
+    Ok(CollectedFiles {
+        files,
+        base_path,
+        skipped,
+        nested_repos,
+        excluded_dirs,
+        aliases,
+    })
+}
+
+/// Sperrt einen Mutex auch dann, wenn ein anderer Thread dabei abgestürzt ist.
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
 }
 
-/// Liest den Inhalt einer Datei sicher aus.
-pub fn read_file_content(path: &Path) -> String {
-    // Versuche verschiedene Encodings
-    if let Ok(content) = std::fs::read_to_string(path) {
-        return content;
+/// Gibt den Pfad zurück, auf den sich ein Fehler der Verzeichnissuche bezieht.
+fn error_path(error: &ignore::Error) -> Option<&Path> {
+    match error {
+        ignore::Error::WithPath { path, .. } => Some(path),
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => error_path(err),
+        ignore::Error::Loop { child, .. } => Some(child),
+        _ => None,
     }
-    
-    // Fallback: Als Bytes lesen und lossy konvertieren
-    match std::fs::read(path) {
-        Ok(bytes) => String::from_utf8_lossy(&bytes).to_string(),
-        Err(e) => format!("[Fehler: Datei konnte nicht gelesen werden - {}]", e),
+}
+
+/// Ab dieser Größe werden Dateien per Speicherabbildung gelesen.
+pub const MMAP_THRESHOLD: u64 = 4 * 1024 * 1024;
+
+/// Liest den Inhalt einer Datei als Text.
+///
+/// Ungültiges UTF-8 wird verlustbehaftet konvertiert; nur Lesefehler
+/// werden zurückgegeben.
+pub fn read_file(path: &Path) -> std::io::Result<String> {
+    let bytes = std::fs::read(path)?;
+    Ok(match String::from_utf8(bytes) {
+        Ok(content) => content,
+        Err(e) => String::from_utf8_lossy(e.as_bytes()).into_owned(),
+    })
+}
+
+/// Wendet die Richtlinie für nicht lesbare Dateien vor dem Rendern an.
+///
+/// Bei [`ReadErrorPolicy::Placeholder`] bleibt die Auswahl unverändert; der
+/// Hinweis wird erst beim Rendern eingefügt.
+pub fn apply_read_policy(
+    files: &mut Vec<CollectedFile>,
+    base_path: &Path,
+    policy: ReadErrorPolicy,
+    events: &EventSink,
+) -> Result<()> {
+    if policy == ReadErrorPolicy::Placeholder {
+        return Ok(());
     }
+
+    let mut readable = Vec::with_capacity(files.len());
+    for file in files.drain(..) {
+        match std::fs::File::open(&file.path) {
+            Ok(_) => readable.push(file),
+            Err(e) if policy == ReadErrorPolicy::Fail => return Err(Code2MdError::io(&file.path, e)),
+            Err(e) => events.warn(&relative_path(&file.path, base_path), format!("übersprungen: {}", e)),
+        }
+    }
+    *files = readable;
+    Ok(())
+}
+
+/// Behält je physischer Datei (Gerät und Inode) nur den ersten Pfad der
+/// sortierten Liste; weitere Pfade werden als Alias auf diesen zurückgegeben.
+fn dedupe_links(files: Vec<CollectedFile>) -> (Vec<CollectedFile>, Vec<(PathBuf, PathBuf)>) {
+    let mut seen: HashMap<(u64, u64), PathBuf> = HashMap::new();
+    let mut aliases = Vec::new();
+    let mut unique = Vec::with_capacity(files.len());
+    for file in files {
+        match file_id(&file.metadata) {
+            Some(id) => match seen.get(&id) {
+                Some(original) => aliases.push((file.path, original.clone())),
+                None => {
+                    seen.insert(id, file.path.clone());
+                    unique.push(file);
+                }
+            },
+            None => unique.push(file),
+        }
+    }
+    (unique, aliases)
+}
+
+/// Gerät und Inode einer Datei; auf anderen Plattformen keine Erkennung.
+#[cfg(unix)]
+fn file_id(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
 }
 
 #[cfg(test)]
@@ -168,4 +614,271 @@ mod tests {
         assert_eq!(result.files.len(), 2);
         Ok(())
     }
+
+    #[test]
+    fn test_dir_overrides() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let base = dir.path();
+        for file in ["api/app.py", "api/gen/schema.py", "web/app.py", "web/main.ts", "web/src/gen/x.ts"] {
+            fs::create_dir_all(base.join(file).parent().unwrap_or(base))?;
+            fs::write(base.join(file), "")?;
+        }
+
+        let mut config = CollectorConfig::new(HashSet::from([".py".to_string()]), &[])?;
+        config.set_overrides(vec![
+            DirOverride::new("web/src", None, &["gen".to_string()])?,
+            DirOverride::new("web/", Some(HashSet::from([".ts".to_string()])), &[])?,
+            DirOverride::new("api", None, &["gen/".to_string()])?,
+        ]);
+        let result = collect_files(base, &config)?;
+        let paths: Vec<String> = result
+            .files
+            .iter()
+            .map(|f| f.path.strip_prefix(&result.base_path).unwrap_or(&f.path).to_string_lossy().replace('\\', "/"))
+            .collect();
+        assert_eq!(paths, vec!["api/app.py", "web/main.ts"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_roots() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let base = dir.path();
+        for sub in ["src/api", "src/models", "src/apiary", "tests"] {
+            fs::create_dir_all(base.join(sub))?;
+            fs::write(base.join(sub).join("x.py"), "")?;
+        }
+        fs::write(base.join("main.py"), "")?;
+
+        let mut config = CollectorConfig::new(HashSet::from([".py".to_string()]), &[])?;
+        config.roots = vec!["src/api".to_string(), "main.py".to_string()];
+        let result = collect_files(base, &config)?;
+        let paths: Vec<String> = result.files.iter().map(|f| relative_path(&f.path, &result.base_path)).collect();
+        assert_eq!(paths, vec!["main.py", "src/api/x.py"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_only_prefixes() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let base = dir.path();
+        for sub in ["backend/api", "backend_old", "shared", "frontend"] {
+            fs::create_dir_all(base.join(sub))?;
+            fs::write(base.join(sub).join("x.py"), "")?;
+        }
+        fs::write(base.join("setup.py"), "")?;
+
+        let mut config = CollectorConfig::new(HashSet::from([".py".to_string()]), &["api".to_string()])?;
+        config.set_only(&["./backend/".to_string(), "shared".to_string(), "set".to_string()]);
+        let result = collect_files(base, &config)?;
+        let paths: Vec<String> = result.files.iter().map(|f| relative_path(&f.path, &result.base_path)).collect();
+        assert_eq!(paths, vec!["setup.py", "shared/x.py"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_all_text() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let base = dir.path();
+        fs::create_dir_all(base.join("node_modules"))?;
+        fs::write(base.join("Justfile"), "build:\n    cargo build\n")?;
+        fs::write(base.join("rules.dsl"), "a -> b\n")?;
+        fs::write(base.join("logo.png"), b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR")?;
+        fs::write(base.join("node_modules/x.js"), "")?;
+
+        let mut config = CollectorConfig::new(HashSet::new(), &["node_modules".to_string()])?;
+        config.all_text = true;
+        let result = collect_files(base, &config)?;
+        let names: Vec<String> = result
+            .files
+            .iter()
+            .map(|f| f.path.file_name().unwrap_or_default().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["Justfile", "rules.dsl"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_ignore() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let base = dir.path();
+        fs::create_dir_all(base.join("tests/fixtures"))?;
+        fs::write(base.join(".gitattributes"), "/tests/fixtures export-ignore\nlegacy.py export-ignore\n")?;
+        for file in ["main.py", "legacy.py", "tests/test_main.py", "tests/fixtures/data.py"] {
+            fs::write(base.join(file), "")?;
+        }
+
+        let mut config = CollectorConfig::new(HashSet::from([".py".to_string()]), &[])?;
+        config.attributes = Some(GitAttributes::load(base, [])?);
+        let result = collect_files(base, &config)?;
+        let paths: Vec<String> = result.files.iter().map(|f| relative_path(&f.path, &result.base_path)).collect();
+        assert_eq!(paths, vec!["main.py", "tests/test_main.py"]);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_links_collected_once() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let base = dir.path();
+        fs::create_dir_all(base.join("src"))?;
+        fs::write(base.join("src/main.py"), "print(1)")?;
+        fs::hard_link(base.join("src/main.py"), base.join("hardlink.py"))?;
+        std::os::unix::fs::symlink("src", base.join("lib"))?;
+
+        let rel = |result: &CollectedFiles, path: &Path| relative_path(path, &result.base_path);
+        let mut config = CollectorConfig::new(HashSet::from([".py".to_string()]), &[])?;
+        let result = collect_files(base, &config)?;
+        let paths: Vec<String> = result.files.iter().map(|f| rel(&result, &f.path)).collect();
+        assert_eq!(paths, vec!["hardlink.py"]);
+        let aliases: Vec<(String, String)> =
+            result.aliases.iter().map(|(alias, original)| (rel(&result, alias), rel(&result, original))).collect();
+        assert_eq!(aliases, vec![("src/main.py".to_string(), "hardlink.py".to_string())]);
+
+        // Mit --follow-symlinks wird lib/ betreten, der Inhalt aber nicht verdoppelt
+        config.follow_links = true;
+        let result = collect_files(base, &config)?;
+        assert_eq!(result.files.len(), 1);
+        let aliases: Vec<String> = result.aliases.iter().map(|(alias, _)| rel(&result, alias)).collect();
+        assert_eq!(aliases, vec!["lib/main.py", "src/main.py"]);
+        assert_eq!(result.alias_map()["hardlink.py"], vec!["lib/main.py", "src/main.py"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_collect_files_sorted_and_filtered() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let base = dir.path();
+        for sub in ["b", "a/tief", "node_modules/pkg", ".versteckt"] {
+            fs::create_dir_all(base.join(sub))?;
+            fs::write(base.join(sub).join("x.py"), "X = 1")?;
+        }
+        // .gitignore wird bewusst nicht ausgewertet
+        fs::write(base.join(".gitignore"), "b/\n")?;
+
+        let extensions = HashSet::from([".py".to_string()]);
+        let config = CollectorConfig::new(extensions, &["node_modules".to_string()])?;
+        let result = collect_files(base, &config)?;
+
+        let rel: Vec<String> = result
+            .files
+            .iter()
+            .map(|f| relative_path(&f.path, &result.base_path))
+            .collect();
+        assert_eq!(rel, [".versteckt/x.py", "a/tief/x.py", "b/x.py"]);
+        assert!(result.skipped.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_skip_nested_repos() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let base = dir.path();
+        fs::create_dir_all(base.join(".git"))?;
+        fs::create_dir_all(base.join("vendor/lib/.git"))?;
+        fs::create_dir_all(base.join("modules/sub"))?;
+        fs::write(base.join("modules/sub/.git"), "gitdir: ../../.git/modules/sub\n")?;
+        for file in ["main.py", "vendor/lib/a.py", "modules/sub/b.py"] {
+            fs::write(base.join(file), "X = 1")?;
+        }
+
+        let extensions = HashSet::from([".py".to_string()]);
+        let mut config = CollectorConfig::new(extensions, &[".git".to_string()])?;
+        let result = collect_files(base, &config)?;
+        assert_eq!(result.files.len(), 1);
+        let nested: Vec<String> = result
+            .nested_repos
+            .iter()
+            .map(|p| relative_path(p, &result.base_path))
+            .collect();
+        assert_eq!(nested, ["modules/sub", "vendor/lib"]);
+
+        config.skip_nested_repos = false;
+        let result = collect_files(base, &config)?;
+        assert_eq!(result.files.len(), 3);
+        assert!(result.nested_repos.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_excluded_dirs() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let base = dir.path();
+        fs::create_dir_all(base.join("web/node_modules/react"))?;
+        fs::create_dir_all(base.join("other/node_modules"))?;
+        fs::write(base.join("web/app.js"), "x")?;
+        fs::write(base.join("web/node_modules/react/index.js"), "x")?;
+
+        let extensions = HashSet::from([".js".to_string()]);
+        let mut config = CollectorConfig::new(extensions, &["node_modules".to_string()])?;
+        config.roots = vec!["web".to_string()];
+        let result = collect_files(base, &config)?;
+        assert_eq!(result.files.len(), 1);
+        // Nur das oberste ausgeschlossene Verzeichnis, nur innerhalb der Teilpfade
+        let excluded: Vec<String> = result
+            .excluded_dirs
+            .iter()
+            .map(|p| relative_path(p, &result.base_path))
+            .collect();
+        assert_eq!(excluded, ["web/node_modules"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_collected_file_content_is_cached() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("a.txt");
+        fs::write(&path, "alt")?;
+        let file = CollectedFile::from_path(&path)?;
+        assert_eq!(file.text()?, "alt");
+
+        // Spätere Stufen sehen denselben Inhalt, ohne erneut zu lesen
+        fs::write(&path, "neu")?;
+        assert_eq!(file.text()?, "alt");
+        assert!(matches!(file.bytes()?, FileBytes::Cached(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_collected_file_sha256() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let file = dir.path().join("a.txt");
+        fs::write(&file, "abc")?;
+
+        assert_eq!(
+            CollectedFile::from_path(&file)?.sha256()?,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_read_policy() -> anyhow::Result<()> {
+        let dir = tempdir()?;
+        let base = dir.path();
+        fs::write(base.join("a.py"), "A = 1")?;
+        fs::write(base.join("entfernt.py"), "B = 2")?;
+        let collect = || -> std::io::Result<Vec<CollectedFile>> {
+            ["a.py", "entfernt.py"]
+                .iter()
+                .map(|name| CollectedFile::from_path(base.join(name)))
+                .collect()
+        };
+        let paths = |files: &[CollectedFile]| -> Vec<PathBuf> { files.iter().map(|f| f.path.clone()).collect() };
+        let mut kept = collect()?;
+        let mut failing = collect()?;
+        fs::remove_file(base.join("entfernt.py"))?;
+
+        let events = EventSink::default();
+        apply_read_policy(&mut kept, base, ReadErrorPolicy::Placeholder, &events)?;
+        assert_eq!(kept.len(), 2);
+
+        apply_read_policy(&mut kept, base, ReadErrorPolicy::Skip, &events)?;
+        assert_eq!(paths(&kept), vec![base.join("a.py")]);
+        assert_eq!(events.warnings()[0].path.as_deref(), Some("entfernt.py"));
+
+        let result = apply_read_policy(&mut failing, base, ReadErrorPolicy::Fail, &events);
+        assert!(matches!(result, Err(Code2MdError::Io { .. })));
+        Ok(())
+    }
 }
@@ -0,0 +1,457 @@
+//! Projektkonfiguration aus `code2md.toml`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use globset::GlobMatcher;
+use serde::Deserialize;
+
+use crate::collector::{exclude_glob, DirOverride};
+use crate::error::{Code2MdError, Result};
+use crate::types::{collect_extensions, ExcludeCategories, TypeAliases};
+
+/// Dateiname der Projektkonfiguration.
+pub const CONFIG_FILE_NAME: &str = "code2md.toml";
+
+/// Inhalt einer `code2md.toml`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub hooks: HooksConfig,
+    /// Externe Befehle je Sprache, durch die Dateiinhalte geleitet werden
+    pub pipe_through: HashMap<String, String>,
+    /// Beschreibungen je Pfad oder Glob, die unter der Dateiüberschrift erscheinen
+    pub annotations: BTreeMap<String, String>,
+    /// Markdown-Zeilen (z.B. Shields), die unter dem Titel erscheinen
+    pub badges: Vec<String>,
+    pub repository: RepositoryConfig,
+    pub confluence: ConfluenceConfig,
+    pub front_matter: FrontMatterConfig,
+    /// Abweichende Auswahl je Teilbaum, z.B. `[dir."frontend/"]`
+    pub dir: BTreeMap<String, DirConfig>,
+    /// Eigene Typ-Bündel, z.B. `fullstack = ["rust", "vue", "config"]`
+    pub aliases: TypeAliases,
+    /// Zusätzliche Standard-Ausschlüsse je Kategorie, z.B. `ide = [".fleet"]`
+    pub excludes: ExcludeCategories,
+    /// Sprache für Codeblöcke von Dateien mit unbekannter Endung
+    pub fallback_syntax: Option<String>,
+}
+
+/// Abweichende Auswahl für einen Teilbaum (`[dir."pfad/"]`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DirConfig {
+    /// Projekttypen, deren Endungen im Teilbaum statt der Auswahl gelten
+    pub types: Vec<String>,
+    /// Dateiendungen, die im Teilbaum statt der Auswahl gelten
+    pub extensions: Vec<String>,
+    /// Zusätzliche Ausschlüsse, relativ zum Verzeichnis
+    pub exclude: Vec<String>,
+}
+
+/// Befehle, die vor bzw. nach dem Export ausgeführt werden.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    pub pre_export: Vec<String>,
+    pub post_export: Vec<String>,
+}
+
+/// Verlinkung der Dateien ins Repository (`[repository]`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct RepositoryConfig {
+    /// Web-URL des Repositorys; aktiviert die Links auch ohne `--repo-url`
+    pub url: Option<String>,
+    /// URL-Vorlage mit `{base}`, `{rev}`, `{path}` und `{line}`
+    pub template: Option<String>,
+    /// Beschriftung der Links
+    pub label: Option<String>,
+}
+
+/// Angaben für `--front-matter` (`[front_matter]`).
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct FrontMatterConfig {
+    /// Tags des Dokuments
+    pub tags: Vec<String>,
+}
+
+/// Zielseite für `--publish` (`[confluence]`). Das Token wird aus der
+/// Umgebungsvariable `CONFLUENCE_TOKEN` gelesen.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ConfluenceConfig {
+    /// Basis-URL, z.B. `https://firma.atlassian.net/wiki`
+    pub url: Option<String>,
+    /// Schlüssel des Bereichs
+    pub space: Option<String>,
+    /// Seitentitel (Standard: Projektname)
+    pub title: Option<String>,
+    /// ID einer bestehenden Seite, die aktualisiert wird
+    pub page_id: Option<String>,
+    /// Übergeordnete Seite für neu angelegte Seiten
+    pub parent_id: Option<String>,
+    /// Benutzer für Confluence Cloud (alternativ `CONFLUENCE_USER`)
+    pub user: Option<String>,
+}
+
+impl Config {
+    /// Lädt die Konfiguration aus einer expliziten Datei oder der nächsten
+    /// `code2md.toml` (siehe [`Config::find`]). Fehlt die Datei, gilt die
+    /// Standardkonfiguration.
+    pub fn load(explicit: Option<&Path>, project_dir: &Path) -> Result<(Self, Option<PathBuf>)> {
+        let path = match explicit {
+            Some(path) => path.to_path_buf(),
+            None => match Self::find(project_dir) {
+                Some(path) => path,
+                None => return Ok((Self::default(), None)),
+            },
+        };
+
+        let content = std::fs::read_to_string(&path).map_err(|e| Code2MdError::io(&path, e))?;
+        let config = Self::parse(&content).map_err(|e| {
+            Code2MdError::Config(format!("Ungültige Konfiguration '{}': {}", path.display(), e))
+        })?;
+        Ok((config, Some(path)))
+    }
+
+    /// Sucht `code2md.toml` im Projektverzeichnis und in den übergeordneten
+    /// Verzeichnissen bis zur Wurzel des git-Repositorys. Außerhalb eines
+    /// Repositorys zählt nur das Projektverzeichnis selbst.
+    pub fn find(project_dir: &Path) -> Option<PathBuf> {
+        let Some(root) = project_dir.ancestors().find(|dir| dir.join(".git").exists()) else {
+            let candidate = project_dir.join(CONFIG_FILE_NAME);
+            return candidate.is_file().then_some(candidate);
+        };
+        for dir in project_dir.ancestors() {
+            let candidate = dir.join(CONFIG_FILE_NAME);
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+            if dir == root {
+                break;
+            }
+        }
+        None
+    }
+
+    /// Parst den Inhalt einer Konfigurationsdatei.
+    pub fn parse(content: &str) -> Result<Self> {
+        toml::from_str(content).map_err(|e| Code2MdError::Config(e.to_string()))
+    }
+
+    /// Entfernt alle Shell-Befehle (Hooks und `[pipe_through]`) und gibt
+    /// zurück, ob welche eingetragen waren. Gilt für automatisch gefundene
+    /// Konfigurationen ohne `--allow-hooks`: Ein ausgechecktes Repository
+    /// soll beim Export keine Befehle ausführen können.
+    pub fn strip_commands(&mut self) -> bool {
+        let had_commands =
+            !self.hooks.pre_export.is_empty() || !self.hooks.post_export.is_empty() || !self.pipe_through.is_empty();
+        self.hooks = HooksConfig::default();
+        self.pipe_through.clear();
+        had_commands
+    }
+
+    /// Entfernt Ziel-URL und Benutzer aus `[confluence]` und gibt zurück, ob
+    /// welche eingetragen waren. Wie bei [`Config::strip_commands`] soll ein
+    /// ausgechecktes Repository nicht bestimmen, wohin `CONFLUENCE_TOKEN` geht.
+    pub fn strip_publish_target(&mut self) -> bool {
+        let had_target = self.confluence.url.is_some() || self.confluence.user.is_some();
+        self.confluence.url = None;
+        self.confluence.user = None;
+        had_target
+    }
+
+    /// Übersetzt die `[dir."…"]`-Tabellen in Abweichungen für den Collector.
+    /// `prefix` ist das Projektverzeichnis relativ zum Verzeichnis der
+    /// Konfiguration; Tabellen außerhalb davon entfallen.
+    pub fn dir_overrides(&self, prefix: &Path) -> Result<Vec<DirOverride>> {
+        let prefix = prefix.to_string_lossy().replace('\\', "/");
+        let prefix = prefix.trim_matches('/');
+        let mut overrides = Vec::new();
+        for (key, dir_config) in &self.dir {
+            let key = key.replace('\\', "/");
+            let key = key.trim_start_matches("./").trim_matches('/');
+            let dir = if prefix.is_empty() {
+                key
+            } else if key.is_empty() || prefix == key || prefix.starts_with(&format!("{}/", key)) {
+                ""
+            } else {
+                match key.strip_prefix(prefix).and_then(|rest| rest.strip_prefix('/')) {
+                    Some(rest) => rest,
+                    None => continue,
+                }
+            };
+
+            let mut extensions = collect_extensions(&dir_config.types, &self.aliases)?;
+            extensions.extend(dir_config.extensions.iter().map(|ext| {
+                let ext = ext.to_lowercase();
+                if ext.starts_with('.') {
+                    ext
+                } else {
+                    format!(".{}", ext)
+                }
+            }));
+            overrides.push(DirOverride::new(
+                dir,
+                (!extensions.is_empty()).then_some(extensions),
+                &dir_config.exclude,
+            )?);
+        }
+        Ok(overrides)
+    }
+
+    /// Übersetzt die `[annotations]`-Tabelle in Dateibeschreibungen.
+    pub fn descriptions(&self) -> Result<FileDescriptions> {
+        let mut descriptions = FileDescriptions::default();
+        for (pattern, text) in &self.annotations {
+            let pattern = pattern.replace('\\', "/");
+            if pattern.contains(['*', '?', '[', '{']) {
+                let matcher = exclude_glob(&pattern)?.compile_matcher();
+                descriptions.globs.push((pattern, matcher, text.clone()));
+            } else {
+                descriptions.paths.insert(pattern, text.clone());
+            }
+        }
+        // Spezifischere (längere) Muster haben Vorrang
+        descriptions
+            .globs
+            .sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+        Ok(descriptions)
+    }
+}
+
+/// Beschreibungen aus der `[annotations]`-Tabelle.
+///
+/// Ein exakter Pfad hat Vorrang vor Globs; unter den Globs gewinnt das
+/// längste passende Muster.
+#[derive(Debug, Clone, Default)]
+pub struct FileDescriptions {
+    paths: HashMap<String, String>,
+    globs: Vec<(String, GlobMatcher, String)>,
+    /// Pfad des Projektverzeichnisses relativ zur Konfigurationsdatei
+    prefix: String,
+}
+
+impl FileDescriptions {
+    /// Setzt den Pfad des Projektverzeichnisses relativ zum Verzeichnis der
+    /// Konfiguration, wenn diese weiter oben gefunden wurde.
+    pub fn set_prefix(&mut self, prefix: &Path) {
+        let prefix = prefix.to_string_lossy().replace('\\', "/");
+        self.prefix = match prefix.trim_end_matches('/') {
+            "" => String::new(),
+            prefix => format!("{}/", prefix),
+        };
+    }
+
+    /// Gibt die Beschreibung zu einem relativen Pfad zurück.
+    pub fn describe(&self, rel_path: &str) -> Option<&str> {
+        let rel_path = format!("{}{}", self.prefix, rel_path.replace('\\', "/"));
+        self.paths
+            .get(&rel_path)
+            .or_else(|| {
+                self.globs
+                    .iter()
+                    .find(|(_, matcher, _)| matcher.is_match(&rel_path))
+                    .map(|(_, _, text)| text)
+            })
+            .map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_hooks() -> anyhow::Result<()> {
+        let config = Config::parse(
+            r#"
+            [hooks]
+            pre_export = ["cargo fmt"]
+            post_export = ["gh gist create $CODE2MD_OUTPUT"]
+            "#,
+        )?;
+        assert_eq!(config.hooks.pre_export, vec!["cargo fmt"]);
+        assert_eq!(config.hooks.post_export.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_strip_commands() -> anyhow::Result<()> {
+        let mut config = Config::parse("badges = [\"x\"]\n[hooks]\npost_export = [\"rm -rf x\"]\n[pipe_through]\nrust = \"cat\"")?;
+        assert!(config.strip_commands());
+        assert!(config.hooks.post_export.is_empty());
+        assert!(config.pipe_through.is_empty());
+        assert_eq!(config.badges, vec!["x"]);
+        assert!(!config.strip_commands());
+        Ok(())
+    }
+
+    #[test]
+    fn test_strip_publish_target() -> anyhow::Result<()> {
+        let mut config = Config::parse("[confluence]\nurl = \"http://evil.example\"\nuser = \"x\"\nspace = \"DEV\"")?;
+        assert!(config.strip_publish_target());
+        assert!(config.confluence.url.is_none());
+        assert!(config.confluence.user.is_none());
+        assert_eq!(config.confluence.space.as_deref(), Some("DEV"));
+        assert!(!config.strip_publish_target());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_pipe_through() -> anyhow::Result<()> {
+        let config = Config::parse(
+            r#"
+            [pipe_through]
+            python = "black -q -"
+            "#,
+        )?;
+        assert_eq!(config.pipe_through["python"], "black -q -");
+        Ok(())
+    }
+
+    #[test]
+    fn test_annotations() -> anyhow::Result<()> {
+        let config = Config::parse(
+            r#"
+            [annotations]
+            "src/main.rs" = "Einstiegspunkt der CLI"
+            "src/*.rs" = "Bibliotheksmodul"
+            "*.toml" = "Konfiguration"
+            "#,
+        )?;
+        let descriptions = config.descriptions()?;
+        assert_eq!(descriptions.describe("src/main.rs"), Some("Einstiegspunkt der CLI"));
+        assert_eq!(descriptions.describe("src/lib.rs"), Some("Bibliotheksmodul"));
+        assert_eq!(descriptions.describe("crates/a/Cargo.toml"), Some("Konfiguration"));
+        assert_eq!(descriptions.describe("README.md"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_badges() -> anyhow::Result<()> {
+        let config = Config::parse(
+            r#"
+            badges = [
+                "![Build](https://ci.example.com/badge.svg)",
+                "![Version](https://img.shields.io/badge/version-{version}-blue)",
+            ]
+            "#,
+        )?;
+        assert_eq!(config.badges.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_front_matter() -> anyhow::Result<()> {
+        let config = Config::parse("[front_matter]\ntags = [\"code\", \"rust\"]\n")?;
+        assert_eq!(config.front_matter.tags, vec!["code", "rust"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_fallback_syntax() -> anyhow::Result<()> {
+        let config = Config::parse("fallback_syntax = \"text\"\n")?;
+        assert_eq!(config.fallback_syntax.as_deref(), Some("text"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_excludes() -> anyhow::Result<()> {
+        let config = Config::parse("[excludes]\nide = [\".fleet\"]\ngenerated = [\"*.pb.go\"]\n")?;
+        assert_eq!(config.excludes["ide"], vec![".fleet"]);
+        assert_eq!(config.excludes["generated"], vec!["*.pb.go"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_repository() -> anyhow::Result<()> {
+        let config = Config::parse(
+            r#"
+            [repository]
+            url = "https://git.example.com/acme/app"
+            template = "{base}/src/commit/{rev}/{path}#L{line}"
+            "#,
+        )?;
+        assert_eq!(config.repository.url.as_deref(), Some("https://git.example.com/acme/app"));
+        assert!(config.repository.label.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_config_uses_defaults() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let (config, path) = Config::load(None, dir.path())?;
+        assert!(config.hooks.pre_export.is_empty());
+        assert!(path.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_config_upwards() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let repo = dir.path().join("repo");
+        let nested = repo.join("crates/app/src");
+        std::fs::create_dir_all(&nested)?;
+        std::fs::create_dir(repo.join(".git"))?;
+        std::fs::write(dir.path().join(CONFIG_FILE_NAME), "")?;
+        assert_eq!(Config::find(&nested), None);
+
+        std::fs::write(repo.join(CONFIG_FILE_NAME), "[annotations]\n\"crates/app/src/lib.rs\" = \"Wurzel\"\n")?;
+        let (config, path) = Config::load(None, &nested)?;
+        assert_eq!(path, Some(repo.join(CONFIG_FILE_NAME)));
+
+        let mut descriptions = config.descriptions()?;
+        descriptions.set_prefix(Path::new("crates/app/src"));
+        assert_eq!(descriptions.describe("lib.rs"), Some("Wurzel"));
+
+        // Ohne Repository kein Aufstieg in fremde Verzeichnisse
+        let plain = dir.path().join("plain/src");
+        std::fs::create_dir_all(&plain)?;
+        assert_eq!(Config::find(&plain), None);
+        std::fs::write(plain.join(CONFIG_FILE_NAME), "")?;
+        assert_eq!(Config::find(&plain), Some(plain.join(CONFIG_FILE_NAME)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_dir_overrides() -> anyhow::Result<()> {
+        let config = Config::parse(
+            r#"
+            [dir."frontend/"]
+            types = ["vue"]
+            exclude = ["dist/"]
+
+            [dir."services/api"]
+            extensions = ["PY"]
+            "#,
+        )?;
+
+        let overrides = config.dir_overrides(Path::new(""))?;
+        assert_eq!(overrides[0].dir, "frontend");
+        assert!(overrides[0].extensions.as_ref().is_some_and(|e| e.contains(".vue")));
+        assert_eq!(overrides[1].extensions, Some(HashSet::from([".py".to_string()])));
+
+        // Export aus einem Unterverzeichnis
+        let overrides = config.dir_overrides(Path::new("services"))?;
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(overrides[0].dir, "api");
+        assert_eq!(config.dir_overrides(Path::new("services/api/src"))?[0].dir, "");
+        Ok(())
+    }
+
+    #[test]
+    fn test_error_kinds() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        assert!(matches!(Config::parse("[hooks"), Err(Code2MdError::Config(_))));
+        assert!(matches!(
+            Config::load(Some(&dir.path().join("fehlt.toml")), dir.path()),
+            Err(Code2MdError::Io { .. })
+        ));
+        Ok(())
+    }
+}
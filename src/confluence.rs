@@ -0,0 +1,249 @@
+//! Confluence-Speicherformat (`--format confluence`) und Veröffentlichung
+//! über die REST-API (`--publish confluence`).
+//!
+//! Code erscheint in `code`-Makros statt in Markdown-Fences, die Confluence
+//! nicht darstellt; Textblöcke werden nach XHTML übersetzt.
+
+use pulldown_cmark::{html, Options, Parser};
+use serde_json::json;
+use tracing::info;
+
+use crate::collector::CollectedFile;
+use crate::config::ConfluenceConfig;
+use crate::error::{Code2MdError, Result};
+use crate::events::ExportEvent;
+use crate::handlers::Block;
+use crate::http::json_request;
+use crate::markdown::{file_blocks, generated_at, MarkdownConfig};
+use crate::paths;
+use crate::tree::generate_tree;
+
+/// Umgebungsvariable mit dem API-Token bzw. Personal Access Token.
+pub const TOKEN_ENV: &str = "CONFLUENCE_TOKEN";
+/// Umgebungsvariable mit dem Benutzer (Confluence Cloud).
+pub const USER_ENV: &str = "CONFLUENCE_USER";
+
+/// Generiert den Seiteninhalt im Confluence-Speicherformat.
+pub fn generate_confluence(files: &[CollectedFile], config: &MarkdownConfig) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    lines.push(match generated_at(config) {
+        Some(now) => format!("<p>Generiert am {} · Anzahl Dateien: {}</p>", now.format("%Y-%m-%d %H:%M:%S"), files.len()),
+        None => format!("<p>Anzahl Dateien: {}</p>", files.len()),
+    });
+    lines.push("<ac:structured-macro ac:name=\"toc\" />".to_string());
+
+    if config.include_tree {
+        lines.push("<h2>Ordnerstruktur</h2>".to_string());
+        let tree = generate_tree(files, &config.base_path, &config.project_name, &Default::default(), &config.tree);
+        lines.push(code_macro("none", None, &tree.join("\n")));
+    }
+
+    lines.push("<h2>Dateien</h2>".to_string());
+    let mut index = 1;
+    for file in files {
+        if let Some(rel_str) = paths::relative_to(&file.path, &config.base_path) {
+            lines.push(format!("<h3>{}</h3>", escape_xml(&rel_str)));
+            if let Some(description) = config.descriptions.describe(&rel_str) {
+                lines.push(format!("<blockquote>{}</blockquote>", markdown_to_xhtml(description.trim())));
+            }
+            for block in file_blocks(file, &rel_str, config) {
+                lines.push(match block {
+                    Block::Text(text) => markdown_to_xhtml(&text),
+                    Block::Code { syntax, content } => code_macro(language(&syntax), Some(&rel_str), &content),
+                });
+            }
+            config.events.emit(ExportEvent::SectionWritten {
+                path: rel_str,
+                index,
+                total: files.len(),
+            });
+            index += 1;
+        }
+    }
+
+    lines.push(String::new());
+    config.transform.line_ending.join(&lines)
+}
+
+/// `code`-Makro mit Sprache und optionalem Titel.
+fn code_macro(language: &str, title: Option<&str>, content: &str) -> String {
+    let title = title
+        .map(|title| format!("<ac:parameter ac:name=\"title\">{}</ac:parameter>", escape_xml(title)))
+        .unwrap_or_default();
+    format!(
+        "<ac:structured-macro ac:name=\"code\"><ac:parameter ac:name=\"language\">{}</ac:parameter>{}\
+         <ac:plain-text-body><![CDATA[{}]]></ac:plain-text-body></ac:structured-macro>",
+        language,
+        title,
+        content.trim_end().replace("]]>", "]]]]><![CDATA[>")
+    )
+}
+
+/// Sprache des Code-Makros; Confluence kennt nur eine feste Auswahl.
+fn language(syntax: &str) -> &'static str {
+    match syntax {
+        "bash" | "zsh" | "fish" | "dockerfile" | "makefile" => "bash",
+        "c" | "cpp" => "cpp",
+        "csharp" => "c#",
+        "css" => "css",
+        "scss" | "less" => "sass",
+        "diff" => "diff",
+        "go" => "go",
+        "groovy" | "gradle" => "groovy",
+        "java" | "kotlin" => "java",
+        "javascript" | "typescript" | "jsx" | "tsx" | "json" => "javascript",
+        "html" | "xml" | "svg" | "vue" => "xml",
+        "perl" => "perl",
+        "php" => "php",
+        "powershell" => "powershell",
+        "python" => "python",
+        "ruby" => "ruby",
+        "scala" => "scala",
+        "sql" => "sql",
+        "yaml" => "yaml",
+        _ => "none",
+    }
+}
+
+/// Übersetzt Markdown in XHTML (z.B. Bilder und Hinweise der Handler).
+fn markdown_to_xhtml(text: &str) -> String {
+    let mut out = String::new();
+    html::push_html(&mut out, Parser::new_ext(text, Options::ENABLE_TABLES));
+    out.trim_end().to_string()
+}
+
+/// Maskiert XML-Sonderzeichen.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Veröffentlicht den Inhalt als Seite: aktualisiert die konfigurierte bzw.
+/// gleichnamige Seite im Bereich oder legt sie an. Gibt die URL der Seite zurück.
+pub fn publish(content: &str, title: &str, config: &ConfluenceConfig) -> Result<String> {
+    let missing = |key: &str| Code2MdError::Config(format!("--publish confluence benötigt `{}` in der Tabelle [confluence].", key));
+    let base = config.url.as_deref().ok_or_else(|| missing("url"))?.trim_end_matches('/');
+    // Das Token geht nie unverschlüsselt über die Leitung
+    let host = base
+        .strip_prefix("https://")
+        .and_then(|rest| rest.split('/').next())
+        .filter(|host| !host.is_empty())
+        .ok_or_else(|| Code2MdError::Config(format!("--publish confluence benötigt eine https-URL, nicht '{}'.", base)))?;
+    let space = config.space.as_deref().ok_or_else(|| missing("space"))?;
+    let title = config.title.as_deref().unwrap_or(title);
+    let auth = authorization(config)?;
+    info!("Sende Zugangsdaten an {}", host);
+    let api = format!("{}/rest/api/content", base);
+
+    let existing = match &config.page_id {
+        Some(id) => Some(json_request(
+            ureq::get(&format!("{}/{}?expand=version", api, id)).set("Authorization", &auth),
+            None,
+        )?),
+        None => {
+            let found = json_request(
+                ureq::get(&api)
+                    .set("Authorization", &auth)
+                    .query("spaceKey", space)
+                    .query("title", title)
+                    .query("expand", "version"),
+                None,
+            )?;
+            found["results"].get(0).cloned()
+        }
+    };
+
+    let mut page = json!({
+        "type": "page",
+        "title": title,
+        "space": { "key": space },
+        "body": { "storage": { "value": content, "representation": "storage" } },
+    });
+    let response = match existing {
+        Some(existing) => {
+            let id = existing["id"].as_str().unwrap_or_default().to_string();
+            let version = existing["version"]["number"].as_u64().unwrap_or(0);
+            page["version"] = json!({ "number": version + 1 });
+            json_request(ureq::put(&format!("{}/{}", api, id)).set("Authorization", &auth), Some(page))?
+        }
+        None => {
+            if let Some(parent) = &config.parent_id {
+                page["ancestors"] = json!([{ "id": parent }]);
+            }
+            json_request(ureq::post(&api).set("Authorization", &auth), Some(page))?
+        }
+    };
+
+    Ok(match response["_links"]["webui"].as_str() {
+        Some(path) => format!("{}{}", base, path),
+        None => base.to_string(),
+    })
+}
+
+/// Authorization-Header: Basic mit Benutzer und API-Token (Cloud), sonst
+/// Bearer mit Personal Access Token (Server/Data Center).
+fn authorization(config: &ConfluenceConfig) -> Result<String> {
+    use base64::Engine;
+
+    let token = std::env::var(TOKEN_ENV)
+        .map_err(|_| Code2MdError::Config(format!("--publish confluence benötigt die Umgebungsvariable {}.", TOKEN_ENV)))?;
+    let user = std::env::var(USER_ENV).ok().or_else(|| config.user.clone());
+    Ok(match user {
+        Some(user) => format!(
+            "Basic {}",
+            base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", user, token))
+        ),
+        None => format!("Bearer {}", token),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_confluence() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().to_path_buf();
+        std::fs::write(base.join("a&b.py"), "x = '<]]>'\n")?;
+
+        let config = MarkdownConfig {
+            project_name: "demo".to_string(),
+            base_path: base.clone(),
+            ..Default::default()
+        };
+        let page = generate_confluence(&[CollectedFile::from_path(base.join("a&b.py"))?], &config);
+
+        assert!(page.starts_with("<p>Generiert am "));
+        if std::env::var(crate::markdown::SOURCE_DATE_EPOCH).is_err() {
+            let config = MarkdownConfig {
+                reproducible: true,
+                ..config
+            };
+            let page = generate_confluence(&[CollectedFile::from_path(base.join("a&b.py"))?], &config);
+            assert!(page.starts_with("<p>Anzahl Dateien: 1</p>\n"));
+        }
+        assert!(page.contains(
+            "<h3>a&amp;b.py</h3>\n<ac:structured-macro ac:name=\"code\">\
+             <ac:parameter ac:name=\"language\">python</ac:parameter>\
+             <ac:parameter ac:name=\"title\">a&amp;b.py</ac:parameter>\
+             <ac:plain-text-body><![CDATA[x = '<]]]]><![CDATA[>']]></ac:plain-text-body></ac:structured-macro>\n"
+        ));
+        assert_eq!(language("rust"), "none");
+        assert_eq!(markdown_to_xhtml("![Logo](logo.png)"), "<p><img src=\"logo.png\" alt=\"Logo\" /></p>");
+        Ok(())
+    }
+
+    #[test]
+    fn test_publish_requires_https() {
+        let config = ConfluenceConfig {
+            url: Some("http://wiki.example/".to_string()),
+            space: Some("DEV".to_string()),
+            ..Default::default()
+        };
+        let error = publish("<p/>", "demo", &config).unwrap_err();
+        assert!(error.to_string().contains("https"));
+    }
+}
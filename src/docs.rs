@@ -0,0 +1,605 @@
+//! API-Auszug aus Quelltexten (`--docs-only`).
+//!
+//! Behalten werden Dokumentationskommentare, Moduldokumentation und die
+//! Signaturen öffentlicher Elemente; Funktionsrümpfe werden durch `{ … }`
+//! bzw. `...` ersetzt. Die Erkennung arbeitet zeilenweise und ersetzt keinen
+//! Parser – ungewöhnlich formatierter Code kann unvollständig erscheinen.
+
+use std::path::Path;
+
+use crate::processor::FileProcessor;
+use crate::types::syntax_for_path;
+
+/// Sprachen mit API-Auszug.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    Go,
+    /// JavaScript und TypeScript
+    JavaScript,
+    /// Java, Kotlin und C#
+    Java,
+    Python,
+}
+
+impl Language {
+    /// Ordnet eine Syntax-Angabe einer unterstützten Sprache zu.
+    pub fn from_syntax(syntax: &str) -> Option<Self> {
+        match syntax {
+            "rust" => Some(Language::Rust),
+            "go" => Some(Language::Go),
+            "javascript" | "typescript" | "jsx" | "tsx" => Some(Language::JavaScript),
+            "java" | "kotlin" | "csharp" => Some(Language::Java),
+            "python" => Some(Language::Python),
+            _ => None,
+        }
+    }
+}
+
+/// Prozessor für `--docs-only`; Dateien anderer Sprachen bleiben unverändert.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DocsOnly;
+
+impl FileProcessor for DocsOnly {
+    fn name(&self) -> &str {
+        "docs-only"
+    }
+
+    fn transform(&self, rel_path: &str, content: String) -> String {
+        match Language::from_syntax(syntax_for_path(Path::new(rel_path))) {
+            Some(language) => extract_api(&content, language),
+            None => content,
+        }
+    }
+}
+
+/// Extrahiert Dokumentation und öffentliche Signaturen aus einem Quelltext.
+pub fn extract_api(content: &str, language: Language) -> String {
+    let lines = match language {
+        Language::Python => extract_python(content),
+        _ => extract_braced(content, language),
+    };
+    let mut result = lines.join("\n");
+    result.push('\n');
+    result
+}
+
+/// Offener Block eines ausgegebenen Containers (Struct, Klasse, Impl …).
+struct Container {
+    /// Klammertiefe vor dem Block
+    depth: usize,
+    /// Alle Mitglieder gelten als öffentlich (Traits, Interfaces, Enums)
+    all_public: bool,
+}
+
+/// Auszug für Sprachen mit geschweiften Klammern.
+fn extract_braced(content: &str, language: Language) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    let mut pending: Vec<&str> = Vec::new();
+    let mut signature: Vec<&str> = Vec::new();
+    let mut stack: Vec<Container> = Vec::new();
+    let mut depth = 0usize;
+    let mut skip_to: Option<usize> = None;
+    let mut in_doc_block = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        // Rumpf überspringen, bis die Klammertiefe wieder erreicht ist
+        if let Some(target) = skip_to {
+            depth = apply_braces(depth, line);
+            if depth <= target {
+                skip_to = None;
+            }
+            continue;
+        }
+
+        if in_doc_block {
+            pending.push(line);
+            in_doc_block = !trimmed.contains("*/");
+            continue;
+        }
+
+        if !signature.is_empty() {
+            signature.push(line);
+            if signature_complete(&signature) {
+                let lines = std::mem::take(&mut signature);
+                emit_item(&lines, &mut pending, &mut out, &mut stack, &mut depth, &mut skip_to);
+            }
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            pending.clear();
+            if out.last().is_some_and(|l| !l.is_empty()) {
+                out.push(String::new());
+            }
+            continue;
+        }
+
+        if is_module_doc(trimmed, language) {
+            out.push(line.to_string());
+            continue;
+        }
+        if is_doc_start(trimmed, language) {
+            pending.push(line);
+            in_doc_block = trimmed.starts_with("/*") && !trimmed.contains("*/");
+            continue;
+        }
+        if is_annotation(trimmed, language) {
+            pending.push(line);
+            continue;
+        }
+
+        let all_public = stack.last().is_some_and(|c| c.all_public);
+        if is_public_item(trimmed, language, all_public, !stack.is_empty()) {
+            signature.push(line);
+            if signature_complete(&signature) {
+                let lines = std::mem::take(&mut signature);
+                emit_item(&lines, &mut pending, &mut out, &mut stack, &mut depth, &mut skip_to);
+            }
+            continue;
+        }
+
+        // Nicht öffentliche Zeile: Blöcke überspringen, Container schließen
+        pending.clear();
+        let before = depth;
+        depth = apply_braces(depth, line);
+        if depth > before {
+            skip_to = Some(before);
+        } else if stack.last().is_some_and(|c| depth <= c.depth) {
+            stack.pop();
+            while out.last().is_some_and(|l| l.is_empty()) {
+                out.pop();
+            }
+            out.push(line.to_string());
+        }
+    }
+
+    while out.last().is_some_and(|l| l.is_empty()) {
+        out.pop();
+    }
+    out
+}
+
+/// Gibt ein öffentliches Element samt Dokumentation aus.
+fn emit_item(
+    lines: &[&str],
+    pending: &mut Vec<&str>,
+    out: &mut Vec<String>,
+    stack: &mut Vec<Container>,
+    depth: &mut usize,
+    skip_to: &mut Option<usize>,
+) {
+    out.extend(pending.drain(..).map(str::to_string));
+
+    let before = *depth;
+    for line in lines {
+        *depth = apply_braces(*depth, line);
+    }
+    let text = lines.join(" ");
+    let opens = *depth > before;
+
+    match container_kind(&text) {
+        Some(all_public) if text.contains('{') => {
+            out.extend(lines.iter().map(|l| l.to_string()));
+            if opens {
+                stack.push(Container {
+                    depth: before,
+                    all_public,
+                });
+            }
+        }
+        _ => {
+            // Rumpf ab der ersten öffnenden Klammer weglassen
+            let brace = lines.iter().position(|l| l.contains('{'));
+            match brace {
+                Some(index) => {
+                    out.extend(lines[..index].iter().map(|l| l.to_string()));
+                    let line = lines[index];
+                    let cut = line.find('{').unwrap_or(line.len());
+                    let head = line[..cut].trim_end();
+                    out.push(if head.trim().is_empty() {
+                        format!("{}{{ … }}", head)
+                    } else {
+                        format!("{} {{ … }}", head)
+                    });
+                    if opens {
+                        *skip_to = Some(before);
+                    }
+                }
+                None => out.extend(lines.iter().map(|l| l.to_string())),
+            }
+        }
+    }
+}
+
+/// Prüft, ob eine (mehrzeilige) Signatur vollständig ist.
+fn signature_complete(lines: &[&str]) -> bool {
+    let text = lines.join(" ");
+    let open = text.matches(['(', '[']).count();
+    if open > text.matches([')', ']']).count() {
+        return false;
+    }
+    let last = text.trim_end();
+    text.contains('{')
+        || last.ends_with(';')
+        || last.ends_with(',')
+        || !["(", ",", "=", "|", "&", "<", "+", "->", "=>", ":"]
+            .iter()
+            .any(|c| last.ends_with(c))
+}
+
+/// Gibt für Container-Deklarationen zurück, ob alle Mitglieder öffentlich sind.
+fn container_kind(text: &str) -> Option<bool> {
+    let head = text.split(['(', '{']).next().unwrap_or_default();
+    let words: Vec<&str> = head
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|w| !w.is_empty())
+        .collect();
+    // Bei Funktionen gehört ein Container-Wort höchstens zum Rückgabetyp
+    if words.iter().any(|w| ["fn", "func", "function", "fun", "def"].contains(w)) {
+        return None;
+    }
+    for word in &words {
+        match *word {
+            "trait" | "interface" | "enum" => return Some(true),
+            // Trait-Implementierungen haben keine eigenen Sichtbarkeiten
+            "impl" => return Some(words.contains(&"for")),
+            "struct" | "union" | "mod" | "class" | "record" | "namespace" | "object" => return Some(false),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Moduldokumentation, die unabhängig vom folgenden Element erhalten bleibt.
+fn is_module_doc(trimmed: &str, language: Language) -> bool {
+    match language {
+        Language::Rust => trimmed.starts_with("//!") || trimmed.starts_with("/*!"),
+        Language::Go => trimmed.starts_with("package "),
+        _ => false,
+    }
+}
+
+/// Beginn eines Dokumentationskommentars.
+fn is_doc_start(trimmed: &str, language: Language) -> bool {
+    match language {
+        Language::Rust => trimmed.starts_with("///") || trimmed.starts_with("/**"),
+        // Go dokumentiert mit normalen Kommentaren direkt vor der Deklaration
+        Language::Go => trimmed.starts_with("//"),
+        Language::JavaScript => trimmed.starts_with("/**"),
+        Language::Java => trimmed.starts_with("/**") || trimmed.starts_with("///"),
+        Language::Python => false,
+    }
+}
+
+/// Attribute und Annotationen, die zum folgenden Element gehören.
+fn is_annotation(trimmed: &str, language: Language) -> bool {
+    match language {
+        Language::Rust => trimmed.starts_with("#[") && !trimmed.starts_with("#[cfg(test)]"),
+        Language::Java => trimmed.starts_with('@') || (trimmed.starts_with('[') && trimmed.ends_with(']')),
+        Language::JavaScript => trimmed.starts_with('@'),
+        _ => false,
+    }
+}
+
+/// Prüft, ob eine Zeile ein öffentliches Element beginnt.
+fn is_public_item(trimmed: &str, language: Language, all_public: bool, nested: bool) -> bool {
+    if trimmed.starts_with('}') || trimmed.starts_with("//") {
+        return false;
+    }
+    let private = ["private ", "protected ", "#", "internal "]
+        .iter()
+        .any(|p| trimmed.starts_with(p));
+    if all_public {
+        return !private;
+    }
+    match language {
+        Language::Rust => {
+            trimmed.starts_with("pub ")
+                || trimmed.starts_with("pub(")
+                || trimmed.starts_with("impl")
+                || trimmed.starts_with("unsafe impl")
+        }
+        Language::Go => {
+            let rest = trimmed
+                .strip_prefix("func ")
+                .map(|rest| match rest.strip_prefix('(') {
+                    // Methode: Empfänger überspringen
+                    Some(receiver) => receiver.split_once(')').map(|(_, r)| r.trim_start()).unwrap_or(""),
+                    None => rest,
+                })
+                .or_else(|| trimmed.strip_prefix("type "))
+                .or_else(|| trimmed.strip_prefix("const "))
+                .or_else(|| trimmed.strip_prefix("var "));
+            match rest {
+                Some(rest) => rest.starts_with(|c: char| c.is_uppercase() || c == '('),
+                // Exportierte Felder in Structs und Interfaces
+                None => nested && trimmed.starts_with(|c: char| c.is_uppercase()),
+            }
+        }
+        Language::JavaScript => {
+            trimmed.starts_with("export ")
+                || (nested && !private && trimmed.starts_with(|c: char| c.is_alphabetic() || c == '_'))
+        }
+        Language::Java => {
+            trimmed.starts_with("public ")
+                || trimmed.starts_with("namespace ")
+                || (trimmed.starts_with("protected ") && nested)
+        }
+        Language::Python => false,
+    }
+}
+
+/// Wendet die Klammern einer Zeile auf die Tiefe an; Zeichenketten und
+/// Zeilenkommentare werden grob übersprungen.
+pub fn apply_braces(depth: usize, line: &str) -> usize {
+    let mut depth = depth as isize;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) => {
+                if c == '\\' {
+                    chars.next();
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None => match c {
+                '"' | '`' => quote = Some(c),
+                '/' if chars.peek() == Some(&'/') => break,
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                _ => {}
+            },
+        }
+    }
+    depth.max(0) as usize
+}
+
+/// Auszug für Python: öffentliche `def`/`class` mit Docstrings.
+fn extract_python(content: &str) -> Vec<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut out: Vec<String> = Vec::new();
+    let mut decorators: Vec<&str> = Vec::new();
+    let mut skip_indent: Option<usize> = None;
+    let mut seen_code = false;
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim();
+        let indent = line.len() - line.trim_start().len();
+
+        if let Some(limit) = skip_indent {
+            if !trimmed.is_empty() && indent > limit {
+                i += 1;
+                continue;
+            }
+            if !trimmed.is_empty() {
+                skip_indent = None;
+            }
+        }
+
+        if trimmed.is_empty() {
+            decorators.clear();
+            if out.last().is_some_and(|l| !l.is_empty()) {
+                out.push(String::new());
+            }
+            i += 1;
+            continue;
+        }
+
+        // Moduldocstring
+        if !seen_code && is_docstring_start(trimmed) {
+            let end = docstring_end(&lines, i);
+            out.extend(lines[i..end].iter().map(|l| l.to_string()));
+            seen_code = true;
+            i = end;
+            continue;
+        }
+        seen_code = seen_code || !trimmed.starts_with('#');
+
+        if trimmed.starts_with('@') {
+            decorators.push(line);
+            i += 1;
+            continue;
+        }
+
+        let definition = ["def ", "async def ", "class "]
+            .iter()
+            .find_map(|keyword| trimmed.strip_prefix(keyword).map(|rest| (*keyword, rest)));
+        let Some((keyword, rest)) = definition else {
+            decorators.clear();
+            i += 1;
+            continue;
+        };
+
+        // Signatur kann über mehrere Zeilen gehen
+        let mut end = i;
+        let mut parens = 0isize;
+        loop {
+            let l = lines[end];
+            parens += l.matches('(').count() as isize - l.matches(')').count() as isize;
+            if (parens <= 0 && l.split('#').next().unwrap_or(l).trim_end().ends_with(':')) || end + 1 >= lines.len() {
+                break;
+            }
+            end += 1;
+        }
+
+        let name: String = rest.chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+        let public = !name.starts_with('_') || (name.starts_with("__") && name.ends_with("__"));
+        if !public {
+            decorators.clear();
+            skip_indent = Some(indent);
+            i = end + 1;
+            continue;
+        }
+
+        out.extend(decorators.drain(..).map(str::to_string));
+        out.extend(lines[i..=end].iter().map(|l| l.to_string()));
+        i = end + 1;
+
+        // Docstring direkt nach der Signatur
+        let body = (i..lines.len()).find(|&j| !lines[j].trim().is_empty());
+        let docstring = body.filter(|&j| is_docstring_start(lines[j].trim()));
+        if let Some(start) = docstring {
+            let doc_end = docstring_end(&lines, start);
+            out.extend(lines[start..doc_end].iter().map(|l| l.to_string()));
+            i = doc_end;
+        }
+
+        if keyword != "class " {
+            if docstring.is_none() {
+                out.push(format!("{}    ...", &line[..indent]));
+            }
+            skip_indent = Some(indent);
+        }
+    }
+
+    while out.last().is_some_and(|l| l.is_empty()) {
+        out.pop();
+    }
+    out
+}
+
+/// Prüft, ob eine Zeile einen Docstring beginnt.
+fn is_docstring_start(trimmed: &str) -> bool {
+    let trimmed = trimmed.trim_start_matches(['r', 'R', 'u', 'U']);
+    trimmed.starts_with("\"\"\"") || trimmed.starts_with("'''")
+}
+
+/// Gibt den Index der ersten Zeile nach dem Docstring ab `start` zurück.
+fn docstring_end(lines: &[&str], start: usize) -> usize {
+    let first = lines[start].trim().trim_start_matches(['r', 'R', 'u', 'U']);
+    let quote = &first[..3];
+    if first[3..].contains(quote) {
+        return start + 1;
+    }
+    (start + 1..lines.len())
+        .find(|&j| lines[j].contains(quote))
+        .map_or(lines.len(), |j| j + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_rust() {
+        let source = r#"//! Modul-Doku.
+
+use std::fmt;
+
+/// Ein Punkt.
+#[derive(Debug)]
+pub struct Point {
+    /// X-Wert
+    pub x: i32,
+    y: i32,
+}
+
+impl Point {
+    /// Erstellt einen Punkt.
+    pub fn new(x: i32) -> Self {
+        let y = { x * 2 };
+        Self { x, y }
+    }
+
+    fn hidden(&self) {}
+}
+
+pub trait Shape {
+    fn area(&self) -> f64;
+}
+
+fn private() {
+    println!("{}", "}");
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    pub fn t() {}
+}
+"#;
+        let expected = r#"//! Modul-Doku.
+
+/// Ein Punkt.
+#[derive(Debug)]
+pub struct Point {
+    /// X-Wert
+    pub x: i32,
+}
+
+impl Point {
+    /// Erstellt einen Punkt.
+    pub fn new(x: i32) -> Self { … }
+}
+
+pub trait Shape {
+    fn area(&self) -> f64;
+}
+"#;
+        assert_eq!(extract_api(source, Language::Rust), expected);
+    }
+
+    #[test]
+    fn test_extract_python() {
+        let source = r#""""Modul-Doku."""
+
+import os
+
+
+@dataclass
+class Client:
+    """Ein Client."""
+
+    def get(self, path,
+            timeout=5):
+        """Lädt eine Ressource."""
+        return os.path.join(path)
+
+    def _intern(self):
+        pass
+
+    def close(self):
+        self.closed = True
+
+
+def _helper():
+    return 1
+"#;
+        let expected = r#""""Modul-Doku."""
+
+@dataclass
+class Client:
+    """Ein Client."""
+
+    def get(self, path,
+            timeout=5):
+        """Lädt eine Ressource."""
+
+    def close(self):
+        ...
+"#;
+        assert_eq!(extract_api(source, Language::Python), expected);
+    }
+
+    #[test]
+    fn test_extract_go_and_typescript() {
+        let go = "package api\n\n// Client ruft die API auf.\ntype Client struct {\n\tBase string\n\ttoken string\n}\n\n// Get lädt.\nfunc (c *Client) Get(p string) error {\n\treturn nil\n}\n\nfunc helper() {}\n";
+        assert_eq!(
+            extract_api(go, Language::Go),
+            "package api\n\n// Client ruft die API auf.\ntype Client struct {\n\tBase string\n}\n\n// Get lädt.\nfunc (c *Client) Get(p string) error { … }\n"
+        );
+
+        let ts = "/** Lädt Daten. */\nexport async function load(url: string): Promise<void> {\n  await fetch(url);\n}\n\nfunction intern() {}\n\nexport class Store {\n  private cache = {};\n  get(key: string) {\n    return key;\n  }\n}\n";
+        assert_eq!(
+            extract_api(ts, Language::JavaScript),
+            "/** Lädt Daten. */\nexport async function load(url: string): Promise<void> { … }\n\nexport class Store {\n  get(key: string) { … }\n}\n"
+        );
+    }
+}
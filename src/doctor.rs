@@ -0,0 +1,329 @@
+//! Diagnose von Konfiguration und Auswahl (`code2md doctor`).
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use globset::{GlobMatcher, GlobSet};
+
+use crate::cli::SelectionArgs;
+use crate::collector::{exclude_glob, is_text_file, CollectorConfig};
+use crate::config::{Config, CONFIG_FILE_NAME};
+use crate::error::{Code2MdError, Result};
+use crate::paths;
+use crate::types::{get_syntax_map, ExcludeCategories, TypeAliases};
+
+/// Schweregrad eines Befunds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// Einzelner Befund der Diagnose.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Finding {
+    fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self.severity {
+            Severity::Info => "✓",
+            Severity::Warning => "⚠",
+            Severity::Error => "✗",
+        };
+        write!(f, "{} {}", symbol, self.message)
+    }
+}
+
+/// Ausschluss-Pattern mit Trefferzahl.
+struct ExcludeCheck {
+    pattern: String,
+    default: bool,
+    matcher: GlobMatcher,
+    hits: usize,
+}
+
+/// Ergebnis des Verzeichnisdurchlaufs.
+#[derive(Default)]
+struct Scan {
+    selected: usize,
+    unknown_extensions: BTreeMap<String, usize>,
+}
+
+/// Prüft Konfigurationsdatei, Ausschlüsse, gefundene Endungen und Ausgabepfad.
+pub fn run_doctor(selection: &SelectionArgs, config: Option<&Path>, output: Option<&Path>) -> Result<Vec<Finding>> {
+    let base_path =
+        paths::canonicalize(&selection.directory).map_err(|e| Code2MdError::io(&selection.directory, e))?;
+    let mut findings = Vec::new();
+
+    // Konfigurationsdatei
+    let mut aliases = TypeAliases::new();
+    let mut categories = ExcludeCategories::new();
+    findings.push(match Config::load(config, &base_path) {
+        Ok((config, Some(path))) => {
+            aliases = config.aliases;
+            categories = config.excludes;
+            Finding::new(Severity::Info, format!("Konfiguration gültig: {}", path.display()))
+        }
+        Ok((_, None)) => Finding::new(Severity::Info, format!("Keine {} gefunden", CONFIG_FILE_NAME)),
+        Err(e) => Finding::new(Severity::Error, e.to_string()),
+    });
+
+    // Dateiendungen
+    let extensions = match selection.collect_extensions_with(&aliases) {
+        Ok(extensions) => extensions,
+        Err(e) => {
+            findings.push(Finding::new(Severity::Error, e.to_string()));
+            HashSet::new()
+        }
+    };
+    if extensions.is_empty() && !selection.all_text {
+        findings.push(Finding::new(
+            Severity::Warning,
+            "Keine Dateiendungen ausgewählt (--type, --ext oder --all-text angeben)",
+        ));
+    }
+
+    // Ausschluss-Patterns einzeln übersetzen
+    let user_excludes: Vec<String> = selection.excludes.clone().unwrap_or_default();
+    let exclude_patterns = match selection.exclude_patterns_with(&categories) {
+        Ok(patterns) => patterns,
+        Err(e) => {
+            findings.push(Finding::new(Severity::Error, e.to_string()));
+            user_excludes.clone()
+        }
+    };
+    let mut checks = Vec::new();
+    for pattern in exclude_patterns.iter().cloned() {
+        match exclude_glob(&pattern) {
+            Ok(glob) => checks.push(ExcludeCheck {
+                default: !user_excludes.contains(&pattern),
+                matcher: glob.compile_matcher(),
+                pattern,
+                hits: 0,
+            }),
+            Err(e) => findings.push(Finding::new(Severity::Error, e.to_string())),
+        }
+    }
+
+    let known: HashSet<&str> = get_syntax_map().keys().copied().collect();
+    let mut scan = Scan::default();
+    let selected = |path: &Path, ext: Option<&str>| {
+        ext.is_some_and(|ext| extensions.contains(ext)) || (selection.all_text && is_text_file(path))
+    };
+    scan_dir(&base_path, &base_path, &selected, &known, &mut checks, &mut scan);
+
+    for check in checks.iter().filter(|c| !c.default) {
+        findings.push(if check.hits == 0 {
+            Finding::new(Severity::Warning, format!("Ausschluss '{}' trifft nichts", check.pattern))
+        } else {
+            Finding::new(
+                Severity::Info,
+                format!("Ausschluss '{}': {} Treffer", check.pattern, check.hits),
+            )
+        });
+    }
+    let default_hits: Vec<String> = checks
+        .iter()
+        .filter(|c| c.default && c.hits > 0)
+        .map(|c| format!("{} ({})", c.pattern, c.hits))
+        .collect();
+    if !default_hits.is_empty() {
+        findings.push(Finding::new(
+            Severity::Info,
+            format!("Standard-Ausschlüsse mit Treffern: {}", default_hits.join(", ")),
+        ));
+    }
+
+    if !scan.unknown_extensions.is_empty() {
+        let list: Vec<String> = scan
+            .unknown_extensions
+            .iter()
+            .map(|(ext, count)| format!("{} ({})", ext, count))
+            .collect();
+        findings.push(Finding::new(
+            Severity::Info,
+            format!("Unbekannte Dateiendungen: {}", list.join(", ")),
+        ));
+    }
+
+    findings.push(if scan.selected == 0 {
+        Finding::new(Severity::Warning, "Keine Dateien würden exportiert")
+    } else {
+        Finding::new(Severity::Info, format!("{} Datei(en) würden exportiert", scan.selected))
+    });
+
+    // Ausgabepfad
+    if let Some(output) = output {
+        let collector = CollectorConfig::new(extensions.clone(), &exclude_patterns).ok();
+        findings.extend(check_output(output, &base_path, &extensions, collector.as_ref().map(|c| &c.excludes)));
+    }
+
+    Ok(findings)
+}
+
+/// Durchsucht das Verzeichnis ohne Ausschlüsse und zählt Treffer je Pattern.
+///
+/// Ausgeschlossene Verzeichnisse werden nicht betreten.
+fn scan_dir(
+    dir: &Path,
+    base_path: &Path,
+    selected: &dyn Fn(&Path, Option<&str>) -> bool,
+    known: &HashSet<&str>,
+    checks: &mut [ExcludeCheck],
+    scan: &mut Scan,
+) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<(PathBuf, bool)> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| (e.path(), e.file_type().map(|t| t.is_dir()).unwrap_or(false)))
+        .collect();
+    paths.sort();
+
+    for (path, is_dir) in paths {
+        let rel_path = path.strip_prefix(base_path).unwrap_or(&path);
+        let mut excluded = false;
+        for check in checks.iter_mut() {
+            if pattern_matches(&check.matcher, rel_path) {
+                check.hits += 1;
+                excluded = true;
+            }
+        }
+        if excluded {
+            continue;
+        }
+
+        if is_dir {
+            scan_dir(&path, base_path, selected, known, checks, scan);
+        } else {
+            let ext = path
+                .extension()
+                .map(|ext| format!(".{}", ext.to_string_lossy().to_lowercase()));
+            if selected(&path, ext.as_deref()) {
+                scan.selected += 1;
+            } else if let Some(ext) = ext.filter(|ext| !known.contains(ext.as_str())) {
+                *scan.unknown_extensions.entry(ext).or_insert(0) += 1;
+            }
+        }
+    }
+}
+
+/// Prüft einen Pfad wie der Collector: ganzer Pfad oder einzelne Komponente.
+fn pattern_matches(matcher: &GlobMatcher, rel_path: &Path) -> bool {
+    matcher.is_match(rel_path)
+        || rel_path
+            .components()
+            .any(|c| matcher.is_match(c.as_os_str().to_string_lossy().as_ref()))
+}
+
+/// Erkennt verdächtige Ausgabepfade.
+fn check_output(
+    output: &Path,
+    base_path: &Path,
+    extensions: &HashSet<String>,
+    excludes: Option<&GlobSet>,
+) -> Vec<Finding> {
+    let parent = match output.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let Ok(parent) = paths::canonicalize(&parent) else {
+        return vec![Finding::new(
+            Severity::Error,
+            format!("Ausgabeverzeichnis existiert nicht: {}", parent.display()),
+        )];
+    };
+    let Some(rel_dir) = paths::relative_to(&parent, base_path).map(PathBuf::from) else {
+        return Vec::new();
+    };
+
+    let excluded_dir = excludes.and_then(|excludes| {
+        rel_dir
+            .ancestors()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .find(|dir| {
+                excludes.is_match(dir)
+                    || dir
+                        .file_name()
+                        .is_some_and(|name| excludes.is_match(name.to_string_lossy().as_ref()))
+            })
+    });
+    if let Some(dir) = excluded_dir {
+        return vec![Finding::new(
+            Severity::Warning,
+            format!(
+                "Ausgabedatei liegt im ausgeschlossenen Verzeichnis '{}'",
+                dir.to_string_lossy().replace('\\', "/")
+            ),
+        )];
+    }
+
+    let ext = output
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy().to_lowercase()))
+        .unwrap_or_default();
+    if extensions.contains(&ext) {
+        return vec![Finding::new(
+            Severity::Info,
+            format!(
+                "Ausgabedatei liegt im Projekt und passt zur Auswahl ({}); sie wird beim Export automatisch ausgeschlossen",
+                ext
+            ),
+        )];
+    }
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, Commands};
+    use clap::Parser;
+
+    #[test]
+    fn test_run_doctor() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path();
+        std::fs::create_dir_all(base.join("src"))?;
+        std::fs::create_dir_all(base.join("target"))?;
+        std::fs::write(base.join("src/main.rs"), "fn main() {}")?;
+        std::fs::write(base.join("target/build.rs"), "")?;
+        std::fs::write(base.join("notes.xyz"), "")?;
+        std::fs::write(base.join(CONFIG_FILE_NAME), "[hooks\n")?;
+
+        let dir_arg = base.to_string_lossy().to_string();
+        let output_arg = base.join("target/out.md").to_string_lossy().to_string();
+        let cli = Cli::try_parse_from([
+            "code2md", "doctor", &dir_arg, "-t", "rust", "-x", "gibtsnicht", "-o", &output_arg,
+        ])?;
+        let Some(Commands::Doctor { selection, config, output }) = cli.command else {
+            anyhow::bail!("doctor-Subcommand erwartet");
+        };
+
+        let findings = run_doctor(&selection, config.as_deref(), output.as_deref())?;
+        let messages: Vec<String> = findings.iter().map(|f| f.message.clone()).collect();
+
+        assert_eq!(findings[0].severity, Severity::Error);
+        assert!(messages.contains(&"Ausschluss 'gibtsnicht' trifft nichts".to_string()));
+        assert!(messages.contains(&"Standard-Ausschlüsse mit Treffern: target (1)".to_string()));
+        assert!(messages.contains(&"Unbekannte Dateiendungen: .xyz (1)".to_string()));
+        assert!(messages.contains(&"2 Datei(en) würden exportiert".to_string()));
+        assert!(messages.contains(&"Ausgabedatei liegt im ausgeschlossenen Verzeichnis 'target'".to_string()));
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+//! Fehlertypen der Bibliothek.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Fehler beim Sammeln, Verarbeiten oder Schreiben eines Exports.
+#[derive(Debug, thiserror::Error)]
+pub enum Code2MdError {
+    /// Ungültige Optionen, Konfigurationsdateien oder Muster
+    #[error("{0}")]
+    Config(String),
+
+    /// Verzeichnis konnte nicht durchsucht werden
+    #[error("Verzeichnis '{}' konnte nicht durchsucht werden: {source}", path.display())]
+    Walk {
+        path: PathBuf,
+        #[source]
+        source: ignore::Error,
+    },
+
+    /// Ein-/Ausgabefehler bei einer bestimmten Datei
+    #[error("'{}': {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    /// Keine Datei entspricht der Auswahl
+    #[error("Keine passenden Dateien gefunden.")]
+    NoFiles,
+
+    /// Eine geforderte Prüfung (z.B. `--require-headers`) ist fehlgeschlagen
+    #[error("{0}")]
+    CheckFailed(String),
+
+    /// Dokument oder Teile davon konnten nicht erzeugt werden
+    #[error("{0}")]
+    Render(String),
+
+    /// Pre-/Post-Export-Hook ist fehlgeschlagen
+    #[error("Hook '{command}' ({stage}) fehlgeschlagen: {message}")]
+    Hook {
+        stage: String,
+        command: String,
+        message: String,
+    },
+
+    /// Plugin konnte nicht geladen werden
+    #[error("Plugin '{}' konnte nicht geladen werden: {message}", path.display())]
+    Plugin { path: PathBuf, message: String },
+
+    /// Aufruf eines Plugins ist fehlgeschlagen oder hat sein Budget überschritten
+    #[error("Plugin '{name}' fehlgeschlagen: {message}")]
+    PluginCall { name: String, message: String },
+
+    /// Fehler im Vorschau- oder MCP-Server
+    #[error("{0}")]
+    Server(String),
+
+    /// Veröffentlichung (z.B. in Confluence) ist fehlgeschlagen
+    #[error("Veröffentlichung fehlgeschlagen: {0}")]
+    Publish(String),
+}
+
+impl Code2MdError {
+    /// Erstellt einen I/O-Fehler für einen Pfad.
+    pub fn io(path: impl AsRef<Path>, source: io::Error) -> Self {
+        Self::Io {
+            path: path.as_ref().to_path_buf(),
+            source,
+        }
+    }
+}
+
+/// Ergebnistyp der Bibliothek.
+pub type Result<T> = std::result::Result<T, Code2MdError>;
@@ -0,0 +1,107 @@
+//! Fortschritts- und Diagnose-Ereignisse während eines Exports.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use tracing::warn;
+
+/// Ereignis im Verlauf eines Exports. Pfade sind relativ zum Projektverzeichnis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportEvent {
+    /// Datei wurde gefunden und wird exportiert
+    FileDiscovered { path: String },
+    /// Datei wurde gelesen
+    FileRead { path: String, bytes: u64 },
+    /// Abschnitt einer Datei wurde in das Dokument geschrieben (`index` ab 1)
+    SectionWritten { path: String, index: usize, total: usize },
+    /// Nicht-fataler Fehler; der Export läuft weiter
+    Warning { path: Option<String>, message: String },
+}
+
+/// Gesammelte Warnung für die Zusammenfassung am Ende eines Exports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Warning {
+    pub path: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{}: {}", path, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Callback für Export-Ereignisse.
+pub type EventCallback = Arc<dyn Fn(&ExportEvent) + Send + Sync>;
+
+/// Empfänger für Export-Ereignisse.
+///
+/// Ohne Callback werden nur Warnungen ausgegeben (über `tracing`).
+/// Warnungen werden in jedem Fall gesammelt.
+#[derive(Clone, Default)]
+pub struct EventSink {
+    callback: Option<EventCallback>,
+    warnings: Arc<Mutex<Vec<Warning>>>,
+}
+
+impl EventSink {
+    pub fn new(callback: EventCallback) -> Self {
+        Self {
+            callback: Some(callback),
+            ..Default::default()
+        }
+    }
+
+    pub fn emit(&self, event: ExportEvent) {
+        if let ExportEvent::Warning { path, message } = &event {
+            self.lock_warnings().push(Warning {
+                path: path.clone(),
+                message: message.clone(),
+            });
+        }
+
+        match (&self.callback, &event) {
+            (Some(callback), _) => callback(&event),
+            (None, ExportEvent::Warning { path, message }) => match path {
+                Some(path) => warn!(file = %path, "{}", message),
+                None => warn!("{}", message),
+            },
+            (None, _) => {}
+        }
+    }
+
+    /// Anzahl der bisher gemeldeten Warnungen.
+    pub fn warning_count(&self) -> usize {
+        self.lock_warnings().len()
+    }
+
+    /// Alle bisher gemeldeten Warnungen in Meldereihenfolge.
+    pub fn warnings(&self) -> Vec<Warning> {
+        self.lock_warnings().clone()
+    }
+
+    fn lock_warnings(&self) -> std::sync::MutexGuard<'_, Vec<Warning>> {
+        self.warnings.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Meldet eine Warnung zu einer Datei.
+    pub fn warn(&self, path: &str, message: impl Into<String>) {
+        self.emit(ExportEvent::Warning {
+            path: Some(path.to_string()),
+            message: message.into(),
+        });
+    }
+}
+
+impl fmt::Debug for EventSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventSink")
+            .field("callback", &self.callback.is_some())
+            .field("warnings", &self.warning_count())
+            .finish()
+    }
+}
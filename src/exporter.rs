@@ -0,0 +1,385 @@
+//! Bibliotheks-API für Exporte ohne Kommandozeile.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::cli::{OutputFormat, SelectionArgs};
+use crate::collector::{apply_read_policy, collect_files, CollectedFile, ReadErrorPolicy, WalkErrorPolicy};
+use crate::confluence::generate_confluence;
+use crate::error::{Code2MdError, Result};
+use crate::events::{EventSink, ExportEvent, Warning};
+use crate::handlers::HandlerConfig;
+use crate::jsonl::generate_jsonl;
+use crate::markdown::{generate_markdown, MarkdownConfig};
+use crate::paths;
+use crate::processor::{relative_path, retain_files, FileProcessor, LineTruncation};
+use crate::transform::TransformConfig;
+use crate::xml::generate_xml;
+
+/// Ergebnis eines Exports.
+#[derive(Debug)]
+pub struct Export {
+    /// Das generierte Dokument
+    pub document: String,
+    /// Exportierte Dateien (absolute Pfade und Metadaten)
+    pub files: Vec<CollectedFile>,
+    /// Nicht lesbare oder übersprungene Dateien und sonstige Warnungen
+    pub warnings: Vec<Warning>,
+}
+
+/// Konfigurierter Export eines Projektverzeichnisses.
+#[derive(Debug)]
+pub struct Exporter {
+    selection: SelectionArgs,
+    format: OutputFormat,
+    on_read_error: ReadErrorPolicy,
+    config: MarkdownConfig,
+}
+
+/// Builder für [`Exporter`].
+#[derive(Debug)]
+pub struct ExporterBuilder {
+    selection: SelectionArgs,
+    project_name: Option<String>,
+    format: OutputFormat,
+    on_read_error: ReadErrorPolicy,
+    include_tree: bool,
+    transform: TransformConfig,
+    handlers: HandlerConfig,
+    max_line_length: Option<usize>,
+    processors: Vec<Arc<dyn FileProcessor>>,
+    events: EventSink,
+}
+
+impl Exporter {
+    /// Beginnt die Konfiguration eines Exports für ein Projektverzeichnis.
+    pub fn builder(directory: impl Into<PathBuf>) -> ExporterBuilder {
+        ExporterBuilder {
+            selection: SelectionArgs {
+                directory: directory.into(),
+                paths: Vec::new(),
+                types: None,
+                extensions: None,
+                without_extensions: None,
+                all_text: false,
+                excludes: None,
+                only: Vec::new(),
+                no_export_ignore: false,
+                no_default_excludes: false,
+                no_exclude_categories: Vec::new(),
+                on_walk_error: Default::default(),
+                skip_nested_repos: true,
+                follow_symlinks: false,
+            },
+            project_name: None,
+            format: OutputFormat::Markdown,
+            on_read_error: ReadErrorPolicy::default(),
+            include_tree: true,
+            transform: TransformConfig::default(),
+            handlers: HandlerConfig::default(),
+            max_line_length: None,
+            processors: Vec::new(),
+            events: EventSink::default(),
+        }
+    }
+
+    /// Sammelt die zu exportierenden Dateien.
+    pub fn collect(&self) -> Result<Vec<CollectedFile>> {
+        let collected = collect_files(&self.config.base_path, &self.selection.collector_config()?)?;
+        for (path, message) in &collected.skipped {
+            self.config
+                .events
+                .warn(&relative_path(path, &collected.base_path), format!("übersprungen: {}", message));
+        }
+
+        let mut files = collected.files;
+        retain_files(&mut files, &collected.base_path, &self.config.processors);
+        apply_read_policy(&mut files, &collected.base_path, self.on_read_error, &self.config.events)?;
+
+        for file in &files {
+            self.config.events.emit(ExportEvent::FileDiscovered {
+                path: relative_path(&file.path, &collected.base_path),
+            });
+        }
+        Ok(files)
+    }
+
+    /// Sammelt die Dateien und generiert das Dokument.
+    pub fn export(&self) -> Result<Export> {
+        let files = self.collect()?;
+        let document = match self.format {
+            OutputFormat::Markdown => generate_markdown(&files, &self.config),
+            OutputFormat::Xml => generate_xml(&files, &self.config),
+            OutputFormat::Jsonl => generate_jsonl(&files, &self.config, None)?,
+            OutputFormat::Confluence => generate_confluence(&files, &self.config),
+        };
+        Ok(Export {
+            document,
+            files,
+            warnings: self.config.events.warnings(),
+        })
+    }
+}
+
+impl ExporterBuilder {
+    /// Projekttypen (z.B. `rust`, `python`), wie bei `--types`.
+    pub fn types<I, S>(mut self, types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.selection.types = Some(types.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Zusätzliche Dateiendungen, wie bei `--extensions`.
+    pub fn extensions<I, S>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.selection.extensions = Some(extensions.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Dateiendungen aus den Projekttypen entfernen, wie bei `--without-ext`.
+    pub fn without_extensions<I, S>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.selection.without_extensions = Some(extensions.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Zusätzliches Ausschlussmuster, wie bei `--exclude`.
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.selection
+            .excludes
+            .get_or_insert_with(Vec::new)
+            .push(pattern.into());
+        self
+    }
+
+    /// Deaktiviert die Standard-Ausschlüsse.
+    pub fn no_default_excludes(mut self) -> Self {
+        self.selection.no_default_excludes = true;
+        self
+    }
+
+    /// Projektname für die Überschrift (Standard: Verzeichnisname).
+    pub fn project_name(mut self, name: impl Into<String>) -> Self {
+        self.project_name = Some(name.into());
+        self
+    }
+
+    pub fn format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Verhalten bei nicht lesbaren Dateien, wie bei `--on-read-error`.
+    pub fn on_read_error(mut self, policy: ReadErrorPolicy) -> Self {
+        self.on_read_error = policy;
+        self
+    }
+
+    /// Verhalten bei nicht lesbaren Verzeichnissen, wie bei `--on-walk-error`.
+    pub fn on_walk_error(mut self, policy: WalkErrorPolicy) -> Self {
+        self.selection.on_walk_error = policy;
+        self
+    }
+
+    /// Alle Textdateien unabhängig von der Endung exportieren, wie bei `--all-text`.
+    pub fn all_text(mut self, all_text: bool) -> Self {
+        self.selection.all_text = all_text;
+        self
+    }
+
+    /// Verzeichnisse mit eigenem `.git` überspringen (Standard: ja).
+    pub fn skip_nested_repos(mut self, skip: bool) -> Self {
+        self.selection.skip_nested_repos = skip;
+        self
+    }
+
+    /// Nur Dateien mit diesen Pfadpräfixen exportieren, wie bei `--only`.
+    pub fn only<I, S>(mut self, prefixes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.selection.only = prefixes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Nur diese Teilpfade des Projekts exportieren, wie die zusätzlichen
+    /// Pfadargumente der Kommandozeile.
+    pub fn paths<I, P>(mut self, paths: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        self.selection.paths = paths.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Symlinks auf Verzeichnisse folgen, wie bei `--follow-symlinks`.
+    pub fn follow_symlinks(mut self, follow: bool) -> Self {
+        self.selection.follow_symlinks = follow;
+        self
+    }
+
+    pub fn include_tree(mut self, include: bool) -> Self {
+        self.include_tree = include;
+        self
+    }
+
+    pub fn transform(mut self, transform: TransformConfig) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    pub fn handlers(mut self, handlers: HandlerConfig) -> Self {
+        self.handlers = handlers;
+        self
+    }
+
+    /// Kürzt Zeilen auf die angegebene Länge (eingebauter [`LineTruncation`]-Prozessor).
+    pub fn max_line_length(mut self, max: usize) -> Self {
+        self.max_line_length = Some(max);
+        self
+    }
+
+    /// Registriert einen Verarbeitungsschritt. Prozessoren laufen in
+    /// Registrierungsreihenfolge nach den eingebauten Schritten.
+    pub fn processor(mut self, processor: impl FileProcessor + 'static) -> Self {
+        self.processors.push(Arc::new(processor));
+        self
+    }
+
+    /// Registriert einen Callback für Fortschritts- und Warnungsereignisse.
+    ///
+    /// Ohne Callback werden Warnungen auf stderr ausgegeben.
+    pub fn on_event(mut self, callback: impl Fn(&ExportEvent) + Send + Sync + 'static) -> Self {
+        self.events = EventSink::new(Arc::new(callback));
+        self
+    }
+
+    /// Prüft die Konfiguration und erstellt den Exporter.
+    pub fn build(self) -> Result<Exporter> {
+        self.selection.validate()?;
+        let base_path = paths::canonicalize(&self.selection.directory)
+            .map_err(|e| Code2MdError::io(&self.selection.directory, e))?;
+
+        let mut processors: Vec<Arc<dyn FileProcessor>> = Vec::new();
+        if let Some(max_length) = self.max_line_length {
+            processors.push(Arc::new(LineTruncation { max_length }));
+        }
+        processors.extend(self.processors);
+
+        let handlers = HandlerConfig {
+            output_dir: if self.handlers.output_dir.as_os_str().is_empty() {
+                base_path.clone()
+            } else {
+                self.handlers.output_dir.clone()
+            },
+            ..self.handlers
+        };
+
+        Ok(Exporter {
+            config: MarkdownConfig {
+                project_name: self
+                    .project_name
+                    .unwrap_or_else(|| self.selection.project_name()),
+                base_path,
+                include_tree: self.include_tree,
+                transform: self.transform,
+                handlers,
+                processors,
+                events: self.events,
+                ..Default::default()
+            },
+            selection: self.selection,
+            format: self.format,
+            on_read_error: self.on_read_error,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Banner;
+
+    impl FileProcessor for Banner {
+        fn name(&self) -> &str {
+            "banner"
+        }
+
+        fn filter(&self, rel_path: &str) -> bool {
+            !rel_path.starts_with("generated/")
+        }
+
+        fn transform(&self, rel_path: &str, content: String) -> String {
+            format!("# {}\n{}", rel_path, content)
+        }
+
+        fn annotate(&self, _rel_path: &str, content: &str) -> Option<String> {
+            Some(format!("{} Zeilen", content.lines().count()))
+        }
+    }
+
+    #[test]
+    fn test_exporter_with_processor() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::create_dir_all(dir.path().join("generated"))?;
+        std::fs::write(dir.path().join("main.py"), "print('ein sehr langer Text')\n")?;
+        std::fs::write(dir.path().join("generated/api.py"), "X = 1\n")?;
+
+        let export = Exporter::builder(dir.path())
+            .types(["python"])
+            .project_name("demo")
+            .max_line_length(10)
+            .processor(Banner)
+            .build()?
+            .export()?;
+
+        assert_eq!(export.files.len(), 1);
+        assert!(export.document.starts_with("# demo"));
+        assert!(export.document.contains("> 2 Zeilen"));
+        assert!(export.document.contains("# main.py\nprint('ein… [+19 chars]"));
+        assert!(!export.document.contains("generated/api.py"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_events() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join("a.py"), "A = 1\n")?;
+        std::fs::write(dir.path().join("b.py"), "B = 2\n")?;
+
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        Exporter::builder(dir.path())
+            .types(["python"])
+            .on_event(move |event| sink.lock().unwrap().push(event.clone()))
+            .build()?
+            .export()?;
+
+        let events = events.lock().unwrap();
+        assert_eq!(
+            events[..2],
+            [
+                ExportEvent::FileDiscovered { path: "a.py".to_string() },
+                ExportEvent::FileDiscovered { path: "b.py".to_string() },
+            ]
+        );
+        assert!(events.contains(&ExportEvent::FileRead { path: "b.py".to_string(), bytes: 6 }));
+        assert_eq!(
+            events.last(),
+            Some(&ExportEvent::SectionWritten { path: "b.py".to_string(), index: 2, total: 2 })
+        );
+        Ok(())
+    }
+}
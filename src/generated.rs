@@ -0,0 +1,69 @@
+//! Erkennung generierter Dateien anhand von Markierungen am Dateianfang,
+//! wie sie protoc, OpenAPI-Generatoren, `go generate` oder Bazel schreiben.
+//!
+//! Solche Dateien werden standardmäßig nicht exportiert (`--include-generated`).
+
+use crate::collector::CollectedFile;
+
+/// Anzahl Zeilen am Dateianfang, in denen nach Markierungen gesucht wird.
+pub const HEAD_LINES: usize = 10;
+
+/// Höchstens so viele Bytes werden für die Prüfung gelesen.
+const HEAD_BYTES: usize = 4096;
+
+/// Markierungen, die genau so vorkommen müssen.
+const MARKERS: &[&str] = &[
+    "@generated",
+    "DO NOT EDIT",
+    "Code generated by",
+    "Generated by the protocol buffer compiler",
+];
+
+/// Markierungen ohne Beachtung der Groß-/Kleinschreibung.
+const MARKERS_IGNORE_CASE: &[&str] = &[
+    "this file was automatically generated",
+    "this file is automatically generated",
+    "this file was generated",
+    "this file is generated",
+    "this file is auto-generated",
+    "this file was auto-generated",
+    "autogenerated file",
+    "auto-generated file",
+];
+
+/// Prüft, ob der Anfang eines Inhalts eine Markierung für generierten Code enthält.
+pub fn has_generated_marker(content: &[u8]) -> bool {
+    let head = &content[..content.len().min(HEAD_BYTES)];
+    String::from_utf8_lossy(head).lines().take(HEAD_LINES).any(|line| {
+        let lower = line.to_lowercase();
+        MARKERS.iter().any(|marker| line.contains(marker))
+            || MARKERS_IGNORE_CASE.iter().any(|marker| lower.contains(marker))
+    })
+}
+
+/// Prüft, ob eine Datei generiert ist. Nicht lesbare Dateien gelten als nicht
+/// generiert; der Fehler wird beim Einlesen gemeldet.
+pub fn is_generated(file: &CollectedFile) -> bool {
+    file.bytes().is_ok_and(|bytes| has_generated_marker(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_generated_marker() {
+        for content in [
+            "// Code generated by protoc-gen-go. DO NOT EDIT.\npackage api\n",
+            "# -*- coding: utf-8 -*-\n# Generated by the protocol buffer compiler.  DO NOT EDIT!\n",
+            "/**\n * @generated SignedSource<<abc>>\n */\n",
+            "/*\n * This file was automatically generated by OpenAPI Generator.\n */\n",
+        ] {
+            assert!(has_generated_marker(content.as_bytes()), "{}", content);
+        }
+
+        assert!(!has_generated_marker(b"fn main() {}\n"));
+        let late = format!("{}// DO NOT EDIT\n", "x\n".repeat(HEAD_LINES));
+        assert!(!has_generated_marker(late.as_bytes()));
+    }
+}
@@ -0,0 +1,77 @@
+//! Hochladen eines Exports als geheimer GitHub Gist (`--publish gist`).
+
+use std::path::PathBuf;
+
+use serde_json::{json, Map, Value};
+
+use crate::error::{Code2MdError, Result};
+use crate::http::json_request;
+
+/// Umgebungsvariablen, aus denen das Token gelesen wird (in dieser Reihenfolge).
+pub const TOKEN_ENVS: &[&str] = &["GITHUB_TOKEN", "GH_TOKEN"];
+/// Basis-URL der API, z.B. für GitHub Enterprise (in GitHub Actions gesetzt).
+pub const API_URL_ENV: &str = "GITHUB_API_URL";
+
+/// Lädt die Dateien als geheimen Gist hoch und gibt dessen URL zurück.
+pub fn upload_gist(files: &[PathBuf], description: &str) -> Result<String> {
+    let token = TOKEN_ENVS
+        .iter()
+        .find_map(|name| std::env::var(name).ok().filter(|token| !token.is_empty()))
+        .ok_or_else(|| {
+            Code2MdError::Config(format!(
+                "--publish gist benötigt die Umgebungsvariable {}.",
+                TOKEN_ENVS.join(" oder ")
+            ))
+        })?;
+    let api = std::env::var(API_URL_ENV).unwrap_or_else(|_| "https://api.github.com".to_string());
+
+    let request = json_request(
+        ureq::post(&format!("{}/gists", api.trim_end_matches('/')))
+            .set("Authorization", &format!("Bearer {}", token))
+            .set("X-GitHub-Api-Version", "2022-11-28"),
+        Some(gist_body(files, description)?),
+    )?;
+    request["html_url"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| Code2MdError::Publish("Antwort enthält keine Gist-URL.".to_string()))
+}
+
+/// Anfragekörper: eine Gist-Datei je Ausgabedatei.
+fn gist_body(files: &[PathBuf], description: &str) -> Result<Value> {
+    let mut entries = Map::new();
+    for path in files {
+        let content = std::fs::read_to_string(path).map_err(|e| Code2MdError::io(path, e))?;
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        entries.insert(name, json!({ "content": content }));
+    }
+    Ok(json!({
+        "description": description,
+        "public": false,
+        "files": entries,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gist_body() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let index = dir.path().join("app_code.md");
+        let part = dir.path().join("app_code.part1.md");
+        std::fs::write(&index, "# app\n")?;
+        std::fs::write(&part, "## Teil 1\n")?;
+
+        let body = gist_body(&[index, part], "app")?;
+        assert_eq!(body["public"], false);
+        assert_eq!(body["files"]["app_code.md"]["content"], "# app\n");
+        assert_eq!(body["files"]["app_code.part1.md"]["content"], "## Teil 1\n");
+        assert!(gist_body(&[dir.path().join("fehlt.md")], "app").is_err());
+        Ok(())
+    }
+}
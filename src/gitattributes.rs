@@ -0,0 +1,312 @@
+//! Attribute aus `.gitattributes`: die Linguist-Angaben von GitHub
+//! (`linguist-language`, `linguist-vendored`, `linguist-generated`) und
+//! `export-ignore` wie bei `git archive`.
+//!
+//! Berücksichtigt werden die Dateien vom Projektverzeichnis aufwärts bis zur
+//! Wurzel des git-Repositorys sowie die in Unterverzeichnissen. Wie bei git
+//! gewinnt die tiefere Datei und innerhalb einer Datei die spätere Zeile.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use globset::{GlobBuilder, GlobMatcher};
+
+use crate::error::{Code2MdError, Result};
+use crate::paths;
+
+/// Dateiname der Attributdateien.
+pub const FILE_NAME: &str = ".gitattributes";
+
+/// Zustand eines Attributs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttrValue {
+    /// `attr`
+    Set,
+    /// `-attr`
+    Unset,
+    /// `!attr`
+    Unspecified,
+    /// `attr=wert`
+    Value(String),
+}
+
+impl AttrValue {
+    /// Gesetzt, auch als `attr=true`; `attr=false` gilt als nicht gesetzt.
+    pub fn is_true(&self) -> bool {
+        match self {
+            AttrValue::Set => true,
+            AttrValue::Value(value) => !value.eq_ignore_ascii_case("false"),
+            AttrValue::Unset | AttrValue::Unspecified => false,
+        }
+    }
+}
+
+/// Eine Zeile einer Attributdatei.
+#[derive(Debug)]
+struct Rule {
+    /// Verzeichnis der Datei relativ zum obersten Verzeichnis, leer für dieses
+    dir: String,
+    matcher: GlobMatcher,
+    /// Pattern ohne `/` gelten für den Dateinamen in jeder Tiefe
+    basename: bool,
+    attrs: Vec<(String, AttrValue)>,
+}
+
+/// Alle geladenen Regeln, von der obersten zur tiefsten Datei.
+#[derive(Debug, Default)]
+pub struct GitAttributes {
+    rules: Vec<Rule>,
+    /// Projektverzeichnis relativ zum obersten Verzeichnis (leer oder mit `/` am Ende)
+    prefix: String,
+}
+
+impl GitAttributes {
+    /// Lädt die Attributdateien für das Projekt. `rel_dirs` sind Verzeichnisse
+    /// relativ zum Projekt, deren eigene `.gitattributes` zusätzlich gelten.
+    pub fn load<'a>(base_path: &Path, rel_dirs: impl IntoIterator<Item = &'a str>) -> Result<Self> {
+        let base_path = &paths::canonicalize(base_path).map_err(|e| Code2MdError::io(base_path, e))?;
+        // Vom Projekt aufwärts bis zur Repository-Wurzel
+        let mut upward: Vec<&Path> = Vec::new();
+        for dir in base_path.ancestors() {
+            upward.push(dir);
+            if dir.join(".git").exists() {
+                break;
+            }
+        }
+        if !upward.last().is_some_and(|dir| dir.join(".git").exists()) {
+            upward.truncate(1);
+        }
+        let top = upward.last().copied().unwrap_or(base_path);
+        let prefix = match base_path.strip_prefix(top) {
+            Ok(rel) if !rel.as_os_str().is_empty() => format!("{}/", rel.to_string_lossy().replace('\\', "/")),
+            _ => String::new(),
+        };
+
+        let mut attributes = Self {
+            rules: Vec::new(),
+            prefix: prefix.clone(),
+        };
+        for dir in upward.iter().rev() {
+            let rel = dir.strip_prefix(top).unwrap_or(Path::new(""));
+            attributes.read(&dir.join(FILE_NAME), &rel.to_string_lossy().replace('\\', "/"))?;
+        }
+
+        // Unterverzeichnisse, nach Tiefe sortiert
+        let mut nested: BTreeSet<(usize, String)> = BTreeSet::new();
+        for rel_dir in rel_dirs {
+            let mut path = String::new();
+            for component in rel_dir.split('/').filter(|c| !c.is_empty()) {
+                if !path.is_empty() {
+                    path.push('/');
+                }
+                path.push_str(component);
+                nested.insert((path.matches('/').count(), path.clone()));
+            }
+        }
+        for (_, rel_dir) in nested {
+            attributes.read(&base_path.join(&rel_dir).join(FILE_NAME), &format!("{}{}", prefix, rel_dir))?;
+        }
+        Ok(attributes)
+    }
+
+    /// Liest eine Attributdatei, falls vorhanden.
+    fn read(&mut self, path: &Path, dir: &str) -> Result<()> {
+        if !path.is_file() {
+            return Ok(());
+        }
+        let content = std::fs::read_to_string(path).map_err(|e| Code2MdError::io(path, e))?;
+        self.add(&content, dir).map_err(|e| match e {
+            Code2MdError::Config(message) => Code2MdError::Config(format!("{}: {}", path.display(), message)),
+            e => e,
+        })
+    }
+
+    /// Fügt die Regeln eines Dateiinhalts hinzu; `dir` ist das Verzeichnis
+    /// der Datei relativ zum obersten Verzeichnis.
+    pub fn add(&mut self, content: &str, dir: &str) -> Result<()> {
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let Some(pattern) = parts.next() else {
+                continue;
+            };
+            // Makros wie `[attr]binary` werden nicht ausgewertet
+            if pattern.starts_with("[attr]") {
+                continue;
+            }
+            let attrs: Vec<(String, AttrValue)> = parts.map(parse_attr).collect();
+            if attrs.is_empty() {
+                continue;
+            }
+
+            // `tests/` meint wie bei `export-ignore` üblich das Verzeichnis selbst
+            let pattern = pattern.trim_end_matches('/');
+            let basename = !pattern.contains('/');
+            let glob = GlobBuilder::new(pattern.trim_start_matches('/'))
+                .literal_separator(true)
+                .build()
+                .map_err(|e| Code2MdError::Config(format!("Ungültiges Pattern '{}': {}", pattern, e)))?;
+            self.rules.push(Rule {
+                dir: dir.trim_matches('/').to_string(),
+                matcher: glob.compile_matcher(),
+                basename,
+                attrs,
+            });
+        }
+        Ok(())
+    }
+
+    /// Wert eines Attributs für einen Pfad relativ zum Projektverzeichnis.
+    pub fn get(&self, rel_path: &str, name: &str) -> Option<&AttrValue> {
+        let full = format!("{}{}", self.prefix, rel_path.replace('\\', "/"));
+        let filename = full.rsplit('/').next().unwrap_or(&full);
+        let mut value = None;
+        for rule in &self.rules {
+            let inner = if rule.dir.is_empty() {
+                full.as_str()
+            } else {
+                match full.strip_prefix(rule.dir.as_str()).and_then(|rest| rest.strip_prefix('/')) {
+                    Some(inner) => inner,
+                    None => continue,
+                }
+            };
+            let matches = if rule.basename {
+                rule.matcher.is_match(filename)
+            } else {
+                rule.matcher.is_match(inner)
+            };
+            if matches {
+                if let Some((_, attr)) = rule.attrs.iter().rev().find(|(attr, _)| attr == name) {
+                    value = Some(attr);
+                }
+            }
+        }
+        value.filter(|value| **value != AttrValue::Unspecified)
+    }
+
+    /// Ob ein Attribut für den Pfad gesetzt ist.
+    pub fn is_set(&self, rel_path: &str, name: &str) -> bool {
+        self.get(rel_path, name).is_some_and(AttrValue::is_true)
+    }
+
+    /// Attribut, aus dem GitHub die Datei nicht als eigenen Quellcode zählt:
+    /// `linguist-vendored` oder `linguist-generated`.
+    pub fn linguist_excluded(&self, rel_path: &str) -> Option<&'static str> {
+        ["linguist-vendored", "linguist-generated"]
+            .into_iter()
+            .find(|attr| self.is_set(rel_path, attr))
+    }
+
+    /// Ob der Pfad (Datei oder Verzeichnis) wie bei `git archive` mit
+    /// `export-ignore` ausgeschlossen ist.
+    pub fn export_ignored(&self, rel_path: &str) -> bool {
+        self.is_set(rel_path, "export-ignore")
+    }
+
+    /// Sprache aus `linguist-language` als Syntax-Angabe für Codeblöcke.
+    pub fn language(&self, rel_path: &str) -> Option<String> {
+        match self.get(rel_path, "linguist-language")? {
+            AttrValue::Value(language) => Some(linguist_syntax(language)),
+            _ => None,
+        }
+    }
+
+    /// Ob keine Regeln geladen wurden.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+/// Parst eine Attributangabe wie `attr`, `-attr`, `!attr` oder `attr=wert`.
+fn parse_attr(token: &str) -> (String, AttrValue) {
+    if let Some(name) = token.strip_prefix('-') {
+        (name.to_string(), AttrValue::Unset)
+    } else if let Some(name) = token.strip_prefix('!') {
+        (name.to_string(), AttrValue::Unspecified)
+    } else if let Some((name, value)) = token.split_once('=') {
+        (name.to_string(), AttrValue::Value(value.to_string()))
+    } else {
+        (token.to_string(), AttrValue::Set)
+    }
+}
+
+/// Übersetzt einen Linguist-Sprachnamen (Bindestriche statt Leerzeichen,
+/// wie in `.gitattributes` üblich) in die Syntax-Angabe der Codeblöcke.
+pub fn linguist_syntax(language: &str) -> String {
+    let lower = language.to_lowercase();
+    match lower.as_str() {
+        "c++" => "cpp".to_string(),
+        "c#" => "csharp".to_string(),
+        "f#" => "fsharp".to_string(),
+        "shell" | "sh" => "bash".to_string(),
+        "objective-c" => "objectivec".to_string(),
+        "javascript+erb" => "erb".to_string(),
+        "vim-script" => "vim".to_string(),
+        _ => lower.replace(' ', "-"),
+    }
+}
+
+/// Verzeichnisse (relativ zum Projekt) der übergebenen Dateien.
+pub fn parent_dirs(rel_paths: &[String]) -> Vec<&str> {
+    rel_paths
+        .iter()
+        .filter_map(|rel_path| rel_path.rsplit_once('/').map(|(dir, _)| dir))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules() -> anyhow::Result<()> {
+        let mut attributes = GitAttributes::default();
+        attributes.add(
+            "# Kommentar\n\
+             vendor/** linguist-vendored\n\
+             vendor/own/** -linguist-vendored\n\
+             *.pb.go linguist-generated=true\n\
+             *.inc linguist-language=C++\n\
+             /Rakefile linguist-language=Ruby\n",
+            "",
+        )?;
+        attributes.add("*.inc linguist-language=Pascal\n", "legacy")?;
+
+        assert_eq!(attributes.linguist_excluded("vendor/lib/a.js"), Some("linguist-vendored"));
+        assert_eq!(attributes.linguist_excluded("vendor/own/b.js"), None);
+        assert_eq!(attributes.linguist_excluded("api/x.pb.go"), Some("linguist-generated"));
+        assert_eq!(attributes.linguist_excluded("src/main.go"), None);
+
+        assert_eq!(attributes.language("src/defs.inc").as_deref(), Some("cpp"));
+        assert_eq!(attributes.language("legacy/defs.inc").as_deref(), Some("pascal"));
+        assert_eq!(attributes.language("Rakefile").as_deref(), Some("ruby"));
+        assert_eq!(attributes.language("sub/Rakefile"), None);
+
+        attributes.add("/tests/fixtures/ export-ignore\n*.psd export-ignore\n", "")?;
+        assert!(attributes.export_ignored("tests/fixtures"));
+        assert!(attributes.export_ignored("assets/logo.psd"));
+        assert!(!attributes.export_ignored("tests/unit.rs"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_from_repository_root() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let repo = dir.path().join("repo");
+        std::fs::create_dir_all(repo.join(".git"))?;
+        std::fs::create_dir_all(repo.join("app/gen"))?;
+        std::fs::write(repo.join(FILE_NAME), "app/third_party/** linguist-vendored\n")?;
+        std::fs::write(repo.join("app/gen").join(FILE_NAME), "*.ts linguist-generated\n")?;
+
+        let attributes = GitAttributes::load(&repo.join("app"), ["gen"])?;
+        assert_eq!(attributes.linguist_excluded("third_party/x.c"), Some("linguist-vendored"));
+        assert_eq!(attributes.linguist_excluded("gen/api.ts"), Some("linguist-generated"));
+        assert_eq!(attributes.linguist_excluded("main.ts"), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,419 @@
+//! Spezielle Darstellung für Dateiformate, die roh eingebettet unlesbar wären.
+
+use std::path::{Component, Path, PathBuf};
+
+use base64::Engine;
+use clap::ValueEnum;
+use serde_json::Value;
+
+use crate::collector::CollectedFile;
+use crate::markdown::format_size;
+
+/// Ein darstellbarer Baustein eines Dateiabschnitts.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    /// Markdown-Text, der unverändert übernommen wird
+    Text(String),
+    /// Codeblock mit Syntax-Angabe
+    Code { syntax: String, content: String },
+}
+
+/// Darstellung von SVG-Dateien.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum SvgMode {
+    /// Quelltext als XML-Codeblock
+    #[default]
+    Source,
+    /// Grafik direkt als Inline-SVG einbetten
+    Rendered,
+}
+
+/// Konfiguration der Format-Handler.
+#[derive(Debug, Clone, Default)]
+pub struct HandlerConfig {
+    pub notebook_markdown: bool,
+    pub csv_preview: Option<usize>,
+    pub embed_images: bool,
+    pub max_embed_size: u64,
+    /// Verzeichnis des Ausgabedokuments (Basis für relative Bild-Links)
+    pub output_dir: PathBuf,
+    pub svg_mode: SvgMode,
+}
+
+/// Bildformate mit zugehörigem MIME-Typ.
+const IMAGE_TYPES: &[(&str, &str)] = &[
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("bmp", "image/bmp"),
+    ("ico", "image/x-icon"),
+    ("avif", "image/avif"),
+];
+
+/// Liest eine Datei und zerlegt sie in darstellbare Blöcke.
+pub fn render_file(file: &CollectedFile, syntax: &str, config: &HandlerConfig) -> std::io::Result<Vec<Block>> {
+    let ext = file_extension(&file.path);
+
+    // Binärformate nicht als Text lesen
+    if let Some((_, mime)) = IMAGE_TYPES.iter().find(|(e, _)| *e == ext) {
+        return render_image(file, mime, config);
+    }
+
+    Ok(render_content(&file.path, syntax, file.text()?, config))
+}
+
+/// Zerlegt einen Dateiinhalt in darstellbare Blöcke.
+///
+/// Für unbekannte Formate wird der Inhalt als einzelner Codeblock übernommen.
+pub fn render_content(path: &Path, syntax: &str, content: String, config: &HandlerConfig) -> Vec<Block> {
+    let ext = file_extension(path);
+
+    let special = match ext.as_str() {
+        "ipynb" => render_notebook(&content, config),
+        "svg" if config.svg_mode == SvgMode::Rendered => render_svg_inline(&content),
+        "csv" | "tsv" => config
+            .csv_preview
+            .and_then(|rows| render_csv_preview(&content, if ext == "tsv" { b'\t' } else { b',' }, rows)),
+        _ => None,
+    };
+
+    special.unwrap_or_else(|| {
+        vec![Block::Code {
+            syntax: syntax.to_string(),
+            content,
+        }]
+    })
+}
+
+/// Gibt zurück, ob eine Datei ohne Sonderbehandlung als einzelner
+/// Codeblock dargestellt wird.
+pub fn is_plain_code(path: &Path, config: &HandlerConfig) -> bool {
+    let ext = file_extension(path);
+    let special = IMAGE_TYPES.iter().any(|(e, _)| *e == ext)
+        || ext == "ipynb"
+        || (ext == "svg" && config.svg_mode == SvgMode::Rendered)
+        || (matches!(ext.as_str(), "csv" | "tsv") && config.csv_preview.is_some());
+    !special
+}
+
+/// Gibt die kleingeschriebene Dateiendung ohne Punkt zurück.
+fn file_extension(path: &Path) -> String {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Bindet ein Bild als relativen Link oder als Data-URI ein.
+fn render_image(file: &CollectedFile, mime: &str, config: &HandlerConfig) -> std::io::Result<Vec<Block>> {
+    let path = &file.path;
+    let alt = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let link = source_link(path, &config.output_dir);
+    let size = file.size();
+
+    if !config.embed_images {
+        return Ok(vec![Block::Text(format!("![{}]({})", alt, link))]);
+    }
+
+    if size > config.max_embed_size {
+        return Ok(vec![
+            Block::Text(format!("![{}]({})", alt, link)),
+            Block::Text(format!(
+                "*(Bild nicht eingebettet: {} überschreitet das Limit von {})*",
+                format_size(size),
+                format_size(config.max_embed_size)
+            )),
+        ]);
+    }
+
+    let data = base64::engine::general_purpose::STANDARD.encode(&*file.bytes()?);
+    Ok(vec![Block::Text(format!("![{}](data:{};base64,{})", alt, mime, data))])
+}
+
+/// Bettet eine SVG-Grafik als rohes HTML ein.
+///
+/// XML-Prolog und Leerzeilen werden entfernt, da eine Leerzeile den
+/// HTML-Block in Markdown beenden würde.
+fn render_svg_inline(content: &str) -> Option<Vec<Block>> {
+    let start = content.find("<svg")?;
+    let svg: Vec<&str> = content[start..]
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    Some(vec![Block::Text(svg.join("\n"))])
+}
+
+/// Relativer Markdown-Link von `output_dir` auf eine Datei.
+pub fn source_link(path: &Path, output_dir: &Path) -> String {
+    relative_path(path, output_dir)
+        .to_string_lossy()
+        .replace('\\', "/")
+        .replace(' ', "%20")
+}
+
+/// Berechnet den Pfad von `target` relativ zum Verzeichnis `from_dir`.
+///
+/// Beide Pfade müssen absolut sein.
+fn relative_path(target: &Path, from_dir: &Path) -> PathBuf {
+    let target: Vec<Component> = target.components().collect();
+    let from: Vec<Component> = from_dir.components().collect();
+    let common = target
+        .iter()
+        .zip(&from)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut result = PathBuf::new();
+    for _ in common..from.len() {
+        result.push("..");
+    }
+    for component in &target[common..] {
+        result.push(component);
+    }
+    result
+}
+
+/// Extrahiert die Zellen eines Jupyter-Notebooks.
+///
+/// Gibt `None` zurück, wenn der Inhalt kein gültiges Notebook ist.
+fn render_notebook(content: &str, config: &HandlerConfig) -> Option<Vec<Block>> {
+    let notebook: Value = serde_json::from_str(content.trim_start_matches('\u{feff}')).ok()?;
+    let cells = notebook.get("cells")?.as_array()?;
+
+    let language = notebook
+        .pointer("/metadata/language_info/name")
+        .or_else(|| notebook.pointer("/metadata/kernelspec/language"))
+        .and_then(Value::as_str)
+        .unwrap_or("python")
+        .to_string();
+
+    let mut blocks = Vec::new();
+
+    for (i, cell) in cells.iter().enumerate() {
+        let cell_type = cell.get("cell_type").and_then(Value::as_str).unwrap_or("");
+        let syntax = match cell_type {
+            "code" => language.clone(),
+            "markdown" if config.notebook_markdown => "markdown".to_string(),
+            _ => continue,
+        };
+
+        let source = cell_source(cell);
+        if source.trim().is_empty() {
+            continue;
+        }
+
+        blocks.push(Block::Text(format!("**Zelle {}** ({})", i + 1, cell_type)));
+        blocks.push(Block::Code {
+            syntax,
+            content: source,
+        });
+    }
+
+    if blocks.is_empty() {
+        blocks.push(Block::Text("*Notebook enthält keine Code-Zellen.*".to_string()));
+    }
+
+    Some(blocks)
+}
+
+/// Liest den Quelltext einer Zelle (String oder Liste von Zeilen).
+fn cell_source(cell: &Value) -> String {
+    match cell.get("source") {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(parts)) => parts.iter().filter_map(Value::as_str).collect(),
+        _ => String::new(),
+    }
+}
+
+/// Rendert die ersten `max_rows` Datenzeilen einer CSV/TSV-Datei als Tabelle.
+///
+/// Gibt `None` zurück, wenn die Datei nicht als Tabelle lesbar ist.
+fn render_csv_preview(content: &str, delimiter: u8, max_rows: usize) -> Option<Vec<Block>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(content.trim_start_matches('\u{feff}').as_bytes());
+
+    let mut records = reader.records();
+    let header = records.next()?.ok()?;
+    let columns = header.len();
+
+    let mut rows = Vec::new();
+    let mut total = 0usize;
+    for record in records {
+        let record = record.ok()?;
+        if total < max_rows {
+            rows.push(record);
+        }
+        total += 1;
+    }
+
+    let format_row = |cells: Vec<&str>| {
+        let cells: Vec<String> = (0..columns)
+            .map(|i| escape_table_cell(cells.get(i).copied().unwrap_or("")))
+            .collect();
+        format!("| {} |", cells.join(" | "))
+    };
+
+    let mut table = vec![
+        format_row(header.iter().collect()),
+        format!("|{}", "---|".repeat(columns)),
+    ];
+    table.extend(rows.iter().map(|r| format_row(r.iter().collect())));
+
+    Some(vec![
+        Block::Text(table.join("\n")),
+        Block::Text(format!(
+            "*(erste {} von {} Zeilen)*",
+            format_count(rows.len()),
+            format_count(total)
+        )),
+    ])
+}
+
+/// Maskiert Zeichen, die eine Markdown-Tabellenzelle zerstören würden.
+fn escape_table_cell(cell: &str) -> String {
+    cell.replace('|', "\\|").replace("\r\n", "<br>").replace('\n', "<br>")
+}
+
+/// Formatiert eine Anzahl mit Tausenderpunkten (z.B. `12.400`).
+fn format_count(n: usize) -> String {
+    let digits = n.to_string();
+    let mut result = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            result.push('.');
+        }
+        result.push(c);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTEBOOK: &str = r##"{
+        "cells": [
+            {"cell_type": "markdown", "source": ["# Titel\n", "Text"]},
+            {"cell_type": "code", "source": ["import os\n", "print(os.name)"], "outputs": []},
+            {"cell_type": "code", "source": "", "outputs": []}
+        ],
+        "metadata": {"kernelspec": {"language": "python"}}
+    }"##;
+
+    #[test]
+    fn test_render_notebook_code_cells() {
+        let blocks = render_content(
+            Path::new("analyse.ipynb"),
+            "json",
+            NOTEBOOK.to_string(),
+            &HandlerConfig::default(),
+        );
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0], Block::Text("**Zelle 2** (code)".to_string()));
+        assert_eq!(
+            blocks[1],
+            Block::Code {
+                syntax: "python".to_string(),
+                content: "import os\nprint(os.name)".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_render_notebook_with_markdown() {
+        let config = HandlerConfig {
+            notebook_markdown: true,
+            ..Default::default()
+        };
+        let blocks = render_content(Path::new("a.ipynb"), "json", NOTEBOOK.to_string(), &config);
+        assert_eq!(blocks.len(), 4);
+    }
+
+    #[test]
+    fn test_invalid_notebook_falls_back() {
+        let blocks = render_content(
+            Path::new("kaputt.ipynb"),
+            "json",
+            "{ kein json".to_string(),
+            &HandlerConfig::default(),
+        );
+        assert!(matches!(blocks[0], Block::Code { .. }));
+    }
+
+    #[test]
+    fn test_csv_preview() {
+        let config = HandlerConfig {
+            csv_preview: Some(2),
+            ..Default::default()
+        };
+        let content = "name,wert\na,1\n\"b|c\",2\nd,3\n".to_string();
+        let blocks = render_content(Path::new("daten.csv"), "csv", content, &config);
+
+        assert_eq!(
+            blocks[0],
+            Block::Text("| name | wert |\n|---|---|\n| a | 1 |\n| b\\|c | 2 |".to_string())
+        );
+        assert_eq!(blocks[1], Block::Text("*(erste 2 von 3 Zeilen)*".to_string()));
+    }
+
+    #[test]
+    fn test_render_image() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::create_dir_all(dir.path().join("assets"))?;
+        let image = dir.path().join("assets/logo.png");
+        std::fs::write(&image, [0x89, b'P', b'N', b'G'])?;
+        let image = CollectedFile::from_path(image)?;
+
+        let mut config = HandlerConfig {
+            output_dir: dir.path().join("docs"),
+            max_embed_size: 1024,
+            ..Default::default()
+        };
+        let blocks = render_file(&image, "", &config)?;
+        assert_eq!(blocks, vec![Block::Text("![logo.png](../assets/logo.png)".to_string())]);
+
+        config.embed_images = true;
+        let blocks = render_file(&image, "", &config)?;
+        assert_eq!(
+            blocks,
+            vec![Block::Text("![logo.png](data:image/png;base64,iVBORw==)".to_string())]
+        );
+
+        config.max_embed_size = 2;
+        assert_eq!(render_file(&image, "", &config)?.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_svg_modes() {
+        let svg = "<?xml version=\"1.0\"?>\n<svg width=\"10\">\n\n  <rect/>\n</svg>\n".to_string();
+
+        let blocks = render_content(Path::new("a.svg"), "xml", svg.clone(), &HandlerConfig::default());
+        assert!(matches!(&blocks[0], Block::Code { syntax, .. } if syntax == "xml"));
+
+        let config = HandlerConfig {
+            svg_mode: SvgMode::Rendered,
+            ..Default::default()
+        };
+        let blocks = render_content(Path::new("a.svg"), "xml", svg, &config);
+        assert_eq!(
+            blocks,
+            vec![Block::Text("<svg width=\"10\">\n  <rect/>\n</svg>".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_format_count() {
+        assert_eq!(format_count(20), "20");
+        assert_eq!(format_count(12_400), "12.400");
+        assert_eq!(format_count(1_234_567), "1.234.567");
+    }
+}
@@ -0,0 +1,83 @@
+//! Prüfung vorgeschriebener Datei-Header (z.B. Copyright-Hinweise).
+
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::collector::CollectedFile;
+use crate::error::{Code2MdError, Result};
+use crate::processor::relative_path;
+
+/// Ergebnis der Header-Prüfung einer Datei.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderCheck {
+    pub path: String,
+    pub passed: bool,
+}
+
+/// Erstellt das Prüfmuster aus einem regulären Ausdruck.
+///
+/// Das Muster wird am Dateianfang verankert.
+pub fn header_regex(pattern: &str) -> Result<Regex> {
+    Regex::new(&format!(r"\A(?:{})", pattern))
+        .map_err(|e| Code2MdError::Config(format!("Ungültiges Header-Muster: {}", e)))
+}
+
+/// Erstellt das Prüfmuster aus einer Header-Vorlage.
+///
+/// Die Vorlage wird wörtlich verglichen, wobei Whitespace flexibel ist und
+/// `{year}` für eine beliebige Jahreszahl steht.
+pub fn template_regex(template: &str) -> Result<Regex> {
+    let pattern = template
+        .split_whitespace()
+        .map(|word| regex::escape(word).replace(r"\{year\}", r"\d{4}"))
+        .collect::<Vec<_>>()
+        .join(r"\s+");
+    header_regex(&pattern)
+}
+
+/// Prüft alle Dateien auf den geforderten Header.
+pub fn check_headers(files: &[CollectedFile], base_path: &Path, pattern: &Regex) -> Vec<HeaderCheck> {
+    files
+        .iter()
+        .map(|file| {
+            HeaderCheck {
+                path: relative_path(&file.path, base_path),
+                passed: has_header(&file.text().unwrap_or_default(), pattern),
+            }
+        })
+        .collect()
+}
+
+/// Prüft, ob ein Inhalt mit dem Header beginnt.
+///
+/// BOM, eine Shebang-Zeile und führender Whitespace werden übersprungen.
+fn has_header(content: &str, pattern: &Regex) -> bool {
+    let mut content = content.trim_start_matches('\u{feff}');
+    if content.starts_with("#!") {
+        content = content.split_once('\n').map(|(_, rest)| rest).unwrap_or("");
+    }
+    pattern.is_match(content.trim_start())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_regex() -> anyhow::Result<()> {
+        let pattern = header_regex(r"// Copyright \d{4} Beispiel GmbH")?;
+        assert!(has_header("// Copyright 2024 Beispiel GmbH\nfn main() {}", &pattern));
+        assert!(has_header("#!/usr/bin/env rust\n\n// Copyright 2023 Beispiel GmbH", &pattern));
+        assert!(!has_header("fn main() {}\n// Copyright 2024 Beispiel GmbH", &pattern));
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_regex() -> anyhow::Result<()> {
+        let pattern = template_regex("/*\n * Copyright (c) {year} Beispiel GmbH\n */")?;
+        assert!(has_header("/*\n *  Copyright (c) 2021 Beispiel GmbH\n */\nint x;", &pattern));
+        assert!(!has_header("/* Copyright (c) Beispiel GmbH */", &pattern));
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+//! Hervorhebung von Treffern eines regulären Ausdrucks (`--annotate`).
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use regex::Regex;
+
+use crate::error::{Code2MdError, Result};
+use crate::markdown::generate_anchor;
+use crate::processor::FileProcessor;
+use crate::transform::split_line_ending;
+use crate::types::get_syntax_for_file;
+
+/// Text, der an jede Trefferzeile angehängt wird.
+pub const MATCH_MARKER: &str = "<<< TREFFER";
+
+/// Markiert Zeilen, auf die ein Muster passt, und listet alle Treffer in
+/// einem eigenen Abschnitt auf.
+#[derive(Debug)]
+pub struct HighlightMatches {
+    regex: Regex,
+    /// Treffer je Datei: (Zeile im Codeblock, Zeileninhalt)
+    matches: Mutex<BTreeMap<String, Vec<(usize, String)>>>,
+}
+
+impl HighlightMatches {
+    pub fn new(pattern: &str) -> Result<Self> {
+        let regex = Regex::new(pattern)
+            .map_err(|e| Code2MdError::Config(format!("Ungültiges Muster für --annotate: {}", e)))?;
+        Ok(Self {
+            regex,
+            matches: Mutex::new(BTreeMap::new()),
+        })
+    }
+
+    fn marker(rel_path: &str) -> String {
+        let filename = rel_path.rsplit('/').next().unwrap_or(rel_path);
+        match line_comment(get_syntax_for_file(filename)) {
+            Some(comment) => format!("  {} {}", comment, MATCH_MARKER),
+            None => format!("  {}", MATCH_MARKER),
+        }
+    }
+}
+
+/// Zeilenkommentar einer Sprache, damit der Code gültig bleibt.
+fn line_comment(syntax: &str) -> Option<&'static str> {
+    match syntax {
+        "rust" | "go" | "javascript" | "typescript" | "jsx" | "tsx" | "java" | "kotlin" | "csharp" | "c" | "cpp"
+        | "dart" | "php" | "scss" | "less" | "gradle" | "vue" => Some("//"),
+        "python" | "ruby" | "bash" | "zsh" | "fish" | "powershell" | "yaml" | "toml" | "dockerfile" | "makefile"
+        | "dotenv" | "gitignore" | "ini" | "properties" => Some("#"),
+        "sql" => Some("--"),
+        _ => None,
+    }
+}
+
+impl FileProcessor for HighlightMatches {
+    fn name(&self) -> &str {
+        "annotate"
+    }
+
+    fn transform(&self, rel_path: &str, content: String) -> String {
+        let marker = Self::marker(rel_path);
+        content
+            .split_inclusive('\n')
+            .map(|line| {
+                let (body, eol) = split_line_ending(line);
+                if self.regex.is_match(body) {
+                    format!("{}{}{}", body, marker, eol)
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect()
+    }
+
+    fn annotate(&self, rel_path: &str, content: &str) -> Option<String> {
+        let marker = Self::marker(rel_path);
+        let found: Vec<(usize, String)> = content
+            .lines()
+            .enumerate()
+            .filter_map(|(i, line)| line.strip_suffix(&marker).map(|body| (i + 1, body.trim().to_string())))
+            .collect();
+        let count = found.len();
+
+        let mut matches = self.matches.lock().unwrap_or_else(|e| e.into_inner());
+        if count == 0 {
+            matches.remove(rel_path);
+            return None;
+        }
+        matches.insert(rel_path.to_string(), found);
+        Some(format!("{} Treffer", count))
+    }
+
+    fn section(&self, rel_paths: &[String]) -> Option<String> {
+        let matches = self.matches.lock().unwrap_or_else(|e| e.into_inner());
+        let mut lines = vec![format!("## Treffer für `{}`", self.regex.as_str()), String::new()];
+        let mut total = 0;
+        for rel_path in rel_paths {
+            for (line, text) in matches.get(rel_path).into_iter().flatten() {
+                if total == 0 {
+                    lines.push("| Datei | Zeile | Inhalt |".to_string());
+                    lines.push("|---|---:|---|".to_string());
+                }
+                total += 1;
+                lines.push(format!(
+                    "| [`{}`](#{}) | {} | `{}` |",
+                    rel_path,
+                    generate_anchor(rel_path),
+                    line,
+                    text.replace('|', "\\|").replace('`', "'")
+                ));
+            }
+        }
+        if total == 0 {
+            lines.push("*Keine Treffer.*".to_string());
+        }
+        Some(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlight_matches() -> anyhow::Result<()> {
+        let highlight = HighlightMatches::new(r"\bunsafe\b")?;
+        let content = highlight.transform("src/ffi.rs", "fn a() {\n    unsafe { b() }\n}\n".to_string());
+        assert_eq!(content, "fn a() {\n    unsafe { b() }  // <<< TREFFER\n}\n");
+        assert_eq!(highlight.annotate("src/ffi.rs", &content), Some("1 Treffer".to_string()));
+        assert_eq!(highlight.annotate("src/main.rs", "fn main() {}"), None);
+
+        let section = highlight.section(&["src/ffi.rs".to_string(), "src/main.rs".to_string()]);
+        assert_eq!(
+            section.as_deref(),
+            Some("## Treffer für `\\bunsafe\\b`\n\n| Datei | Zeile | Inhalt |\n|---|---:|---|\n| [`src/ffi.rs`](#srcffirs) | 2 | `unsafe { b() }` |")
+        );
+        assert!(HighlightMatches::new("(").is_err());
+        Ok(())
+    }
+}
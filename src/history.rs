@@ -0,0 +1,152 @@
+//! Verlauf der Exporte in `.code2md/history.jsonl` (`code2md history`).
+//!
+//! Jeder erfolgreiche Lauf hängt eine Zeile mit Zeitpunkt, Options-Prüfsumme,
+//! Dateizahl, Ausgabegröße und Dauer an. So lässt sich verfolgen, wie Codebasis
+//! und Export über die Zeit wachsen.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cache::CACHE_DIR;
+use crate::error::{Code2MdError, Result};
+use crate::markdown::format_size;
+
+/// Ein Eintrag im Verlauf.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Zeitpunkt des Laufs (RFC 3339, lokale Zeit)
+    pub timestamp: String,
+    /// Prüfsumme über Optionen und Konfigurationsdatei (wie im Run-Cache)
+    pub options: String,
+    /// Ausgabedatei, relativ zum Projektverzeichnis, wenn möglich
+    pub output: String,
+    /// Anzahl exportierter Dateien
+    pub files: usize,
+    /// Größe der Ausgabedatei in Bytes
+    pub output_size: u64,
+    /// Laufzeit in Millisekunden
+    pub duration_ms: u64,
+}
+
+/// Pfad der Verlaufsdatei im Projekt.
+pub fn history_path(base_path: &Path) -> PathBuf {
+    base_path.join(CACHE_DIR).join("history.jsonl")
+}
+
+/// Hängt einen Eintrag an den Verlauf an und legt die Datei bei Bedarf an.
+pub fn append(base_path: &Path, entry: &HistoryEntry) -> Result<()> {
+    let path = history_path(base_path);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| Code2MdError::io(dir, e))?;
+    }
+    let line = serde_json::to_string(entry).map_err(|e| Code2MdError::Render(e.to_string()))?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| Code2MdError::io(&path, e))?;
+    writeln!(file, "{}", line).map_err(|e| Code2MdError::io(&path, e))
+}
+
+/// Lädt den Verlauf in zeitlicher Reihenfolge. Fehlt die Datei, ist er leer;
+/// unlesbare Zeilen werden übersprungen.
+pub fn load(base_path: &Path) -> Result<Vec<HistoryEntry>> {
+    let path = history_path(base_path);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(Code2MdError::io(&path, e)),
+    };
+    Ok(content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+}
+
+/// Formatiert die letzten `limit` Einträge als Tabelle. Die Änderung bezieht
+/// sich auf den vorherigen Lauf mit derselben Ausgabedatei.
+pub fn format_history(entries: &[HistoryEntry], limit: Option<usize>) -> Vec<String> {
+    let start = entries.len().saturating_sub(limit.unwrap_or(entries.len()));
+    let mut lines = vec![format!(
+        "{:<19}  {:>7}  {:>10}  {:>12}  {:>8}  {:<8}  {}",
+        "Zeitpunkt", "Dateien", "Größe", "Änderung", "Dauer", "Optionen", "Ausgabe"
+    )];
+    for (index, entry) in entries.iter().enumerate().skip(start) {
+        let previous = entries[..index].iter().rev().find(|e| e.output == entry.output);
+        let change = match previous {
+            Some(previous) if entry.output_size >= previous.output_size => {
+                format!("+{}", format_size(entry.output_size - previous.output_size))
+            }
+            Some(previous) => format!("-{}", format_size(previous.output_size - entry.output_size)),
+            None => "–".to_string(),
+        };
+        // Sekundengenau und ohne Zeitzone reicht für die Übersicht
+        let timestamp: String = entry.timestamp.replacen('T', " ", 1).chars().take(19).collect();
+        lines.push(format!(
+            "{:<19}  {:>7}  {:>10}  {:>12}  {:>8}  {:<8}  {}",
+            timestamp,
+            entry.files,
+            format_size(entry.output_size),
+            change,
+            format_duration(entry.duration_ms),
+            entry.options.chars().take(8).collect::<String>(),
+            entry.output
+        ));
+    }
+    lines
+}
+
+fn format_duration(ms: u64) -> String {
+    if ms < 1000 {
+        format!("{} ms", ms)
+    } else {
+        format!("{:.1} s", ms as f64 / 1000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(output: &str, files: usize, output_size: u64) -> HistoryEntry {
+        HistoryEntry {
+            timestamp: "2026-10-16T09:30:12.345+02:00".to_string(),
+            options: "0123456789abcdef".to_string(),
+            output: output.to_string(),
+            files,
+            output_size,
+            duration_ms: 1500,
+        }
+    }
+
+    #[test]
+    fn test_append_and_load() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        assert!(load(dir.path())?.is_empty());
+
+        append(dir.path(), &entry("a.md", 3, 1024))?;
+        append(dir.path(), &entry("a.md", 4, 2048))?;
+        let path = history_path(dir.path());
+        assert_eq!(path, dir.path().join(".code2md/history.jsonl"));
+        std::fs::write(&path, format!("{}kaputt\n", std::fs::read_to_string(&path)?))?;
+
+        let entries = load(dir.path())?;
+        assert_eq!(entries, vec![entry("a.md", 3, 1024), entry("a.md", 4, 2048)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_history() {
+        let entries = vec![entry("a.md", 3, 2048), entry("b.md", 1, 10), entry("a.md", 2, 1024)];
+        let lines = format_history(&entries, None);
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("Zeitpunkt"));
+        assert!(lines[1].starts_with("2026-10-16 09:30:12"));
+        assert!(lines[1].contains("1.5 s"));
+        assert!(lines[1].contains("01234567  a.md"));
+        assert!(lines[3].contains("-1.00 KB"));
+
+        let lines = format_history(&entries, Some(1));
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].ends_with("a.md"));
+    }
+}
@@ -0,0 +1,93 @@
+//! Ausführung der Hook-Befehle vor und nach dem Export.
+
+use std::path::Path;
+use std::process::Command;
+
+use tracing::info;
+
+use crate::error::{Code2MdError, Result};
+
+/// Werte, die Hooks als Umgebungsvariablen (`CODE2MD_*`) erhalten.
+#[derive(Debug, Default)]
+pub struct HookEnv {
+    pub project_name: String,
+    pub base_path: String,
+    pub output_path: String,
+    pub file_count: Option<usize>,
+    pub output_size: Option<u64>,
+}
+
+impl HookEnv {
+    fn vars(&self) -> Vec<(&'static str, String)> {
+        let mut vars = vec![
+            ("CODE2MD_PROJECT", self.project_name.clone()),
+            ("CODE2MD_BASE_PATH", self.base_path.clone()),
+            ("CODE2MD_OUTPUT", self.output_path.clone()),
+        ];
+        if let Some(count) = self.file_count {
+            vars.push(("CODE2MD_FILE_COUNT", count.to_string()));
+        }
+        if let Some(size) = self.output_size {
+            vars.push(("CODE2MD_OUTPUT_SIZE", size.to_string()));
+        }
+        vars
+    }
+}
+
+/// Führt die Befehle nacheinander in der Shell aus; der erste Fehlschlag bricht ab.
+pub fn run_hooks(stage: &str, commands: &[String], cwd: &Path, env: &HookEnv) -> Result<()> {
+    for command in commands {
+        info!(stage, "{}", command);
+
+        let hook_error = |message: String| Code2MdError::Hook {
+            stage: stage.to_string(),
+            command: command.clone(),
+            message,
+        };
+        let status = shell_command(command)
+            .current_dir(cwd)
+            .envs(env.vars())
+            .status()
+            .map_err(|e| hook_error(format!("konnte nicht gestartet werden: {}", e)))?;
+
+        if !status.success() {
+            return Err(hook_error(status.to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// Erstellt einen Shell-Aufruf für die aktuelle Plattform.
+pub fn shell_command(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_hooks_with_env() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let env = HookEnv {
+            output_path: "export.md".to_string(),
+            file_count: Some(3),
+            ..Default::default()
+        };
+        let commands = vec!["echo \"$CODE2MD_OUTPUT:$CODE2MD_FILE_COUNT\" > hook.txt".to_string()];
+
+        run_hooks("post_export", &commands, dir.path(), &env)?;
+        assert_eq!(std::fs::read_to_string(dir.path().join("hook.txt"))?, "export.md:3\n");
+
+        assert!(run_hooks("pre_export", &["exit 1".to_string()], dir.path(), &env).is_err());
+        Ok(())
+    }
+}
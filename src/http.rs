@@ -0,0 +1,31 @@
+//! HTTP-Anfragen für die Veröffentlichung von Exporten.
+
+use serde_json::Value;
+
+use crate::error::{Code2MdError, Result};
+
+/// Sendet eine Anfrage (mit optionalem JSON-Körper) und liest die
+/// JSON-Antwort. Fehlerstatus werden mit dem Antworttext gemeldet.
+pub fn json_request(request: ureq::Request, body: Option<Value>) -> Result<Value> {
+    let request = request.set("Accept", "application/json");
+    let response = match body {
+        Some(body) => request.send_json(body),
+        None => request.call(),
+    };
+    response
+        .map_err(status_error)?
+        .into_json()
+        .map_err(|e| Code2MdError::Publish(format!("Ungültige Antwort: {}", e)))
+}
+
+/// Wandelt einen ureq-Fehler in einen Veröffentlichungsfehler um.
+pub fn status_error(error: ureq::Error) -> Code2MdError {
+    match error {
+        ureq::Error::Status(status, response) => Code2MdError::Publish(format!(
+            "HTTP {}: {}",
+            status,
+            response.into_string().unwrap_or_default().trim()
+        )),
+        e => Code2MdError::Publish(e.to_string()),
+    }
+}
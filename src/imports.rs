@@ -0,0 +1,267 @@
+//! Auflösung projektinterner Imports für `--import-links`.
+//!
+//! Unterstützt werden relative Imports in JavaScript/TypeScript, Python-
+//! Module, Rust (`mod x;` und `use crate::…`) sowie `#include "…"` in C/C++.
+//! Externe Pakete und nicht exportierte Dateien werden ignoriert.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{LazyLock, OnceLock};
+
+use regex::Regex;
+
+use crate::markdown::generate_anchor;
+use crate::types::get_syntax_for_file;
+
+/// Dateiendungen, die bei JavaScript/TypeScript-Imports ergänzt werden.
+const SCRIPT_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs", "vue"];
+
+static SCRIPT_IMPORT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?:\bfrom|\bimport|\brequire\s*\(|\bimport\s*\()\s*['"](\.{1,2}/[^'"]*)['"]"#).expect("gültiges Muster")
+});
+static PYTHON_FROM: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*from\s+(\.*)([\w.]*)\s+import\s+\(?([\w\s,]+)").expect("gültiges Muster")
+});
+static PYTHON_IMPORT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*import\s+([\w.]+(?:\s*,\s*[\w.]+)*)").expect("gültiges Muster"));
+static RUST_MOD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(?:pub(?:\([^)]*\))?\s+)?mod\s+(\w+)\s*;").expect("gültiges Muster"));
+static RUST_USE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(?:pub(?:\([^)]*\))?\s+)?use\s+crate::([^;]+)").expect("gültiges Muster"));
+static C_INCLUDE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"^\s*#\s*include\s+"([^"]+)""#).expect("gültiges Muster"));
+
+/// Exportierte Dateien und ihre Linkziele.
+#[derive(Debug, Default)]
+pub struct ImportIndex {
+    files: BTreeSet<String>,
+    /// Linkziele beim Schreiben in Teilen; sonst Anker im selben Dokument
+    targets: OnceLock<BTreeMap<String, String>>,
+}
+
+impl ImportIndex {
+    /// Erstellt den Index aus relativen Pfaden mit `/` als Trenner.
+    pub fn new(files: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            files: files.into_iter().collect(),
+            targets: OnceLock::new(),
+        }
+    }
+
+    /// Setzt die Linkziele für die Ausgabe in Teilen (`--split`).
+    pub fn set_targets(&self, targets: BTreeMap<String, String>) {
+        let _ = self.targets.set(targets);
+    }
+
+    /// Linkziel des Abschnitts einer Datei.
+    pub fn link(&self, rel_path: &str) -> String {
+        self.targets
+            .get()
+            .and_then(|targets| targets.get(rel_path).cloned())
+            .unwrap_or_else(|| format!("#{}", generate_anchor(rel_path)))
+    }
+
+    /// Gibt die exportierten Dateien zurück, die eine Datei importiert, in der
+    /// Reihenfolge ihres ersten Auftretens.
+    pub fn resolve(&self, rel_path: &str, content: &str) -> Vec<String> {
+        let filename = rel_path.rsplit('/').next().unwrap_or(rel_path);
+        let dir = parent(rel_path);
+        let mut found: Vec<String> = Vec::new();
+        let mut add = |candidates: Vec<String>| {
+            if let Some(hit) = candidates.into_iter().find(|c| self.files.contains(c)) {
+                if hit != rel_path && !found.contains(&hit) {
+                    found.push(hit);
+                }
+            }
+        };
+
+        match get_syntax_for_file(filename) {
+            "javascript" | "typescript" | "jsx" | "tsx" | "vue" => {
+                for caps in SCRIPT_IMPORT.captures_iter(content) {
+                    add(script_candidates(&join(dir, &caps[1])));
+                }
+            }
+            "python" => {
+                for line in content.lines() {
+                    if let Some(caps) = PYTHON_FROM.captures(line) {
+                        let base = match caps[1].len() {
+                            0 => None,
+                            dots => Some(ancestor(dir, dots - 1)),
+                        };
+                        let module = caps[2].replace('.', "/");
+                        // `from pkg import mod` kann auch ein Untermodul importieren
+                        for name in caps[3].split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                            add(self.python_candidates(base.as_deref(), &join(&module, name)));
+                        }
+                        if !module.is_empty() {
+                            add(self.python_candidates(base.as_deref(), &module));
+                        }
+                    } else if let Some(caps) = PYTHON_IMPORT.captures(line) {
+                        for module in caps[1].split(',') {
+                            add(self.python_candidates(None, &module.trim().replace('.', "/")));
+                        }
+                    }
+                }
+            }
+            "rust" => {
+                let module_dir = match filename {
+                    "main.rs" | "lib.rs" | "mod.rs" => dir.to_string(),
+                    _ => join(dir, filename.trim_end_matches(".rs")),
+                };
+                let crate_root = self.rust_crate_root(dir);
+                for line in content.lines() {
+                    if let Some(caps) = RUST_MOD.captures(line) {
+                        let name = &caps[1];
+                        add(vec![
+                            join(&module_dir, &format!("{}.rs", name)),
+                            join(&module_dir, &format!("{}/mod.rs", name)),
+                        ]);
+                    } else if let (Some(caps), Some(root)) = (RUST_USE.captures(line), crate_root.as_deref()) {
+                        for path in use_paths(&caps[1]) {
+                            add(self.rust_module(root, &path).into_iter().collect());
+                        }
+                    }
+                }
+            }
+            "c" | "cpp" => {
+                for caps in content.lines().filter_map(|line| C_INCLUDE.captures(line)) {
+                    add(vec![join(dir, &caps[1]), normalize(&caps[1]), join("include", &caps[1])]);
+                }
+            }
+            _ => {}
+        }
+        found
+    }
+
+    /// Kandidaten für ein Python-Modul relativ zu `base` bzw. zum Projekt
+    /// oder einem `src`-Verzeichnis.
+    fn python_candidates(&self, base: Option<&str>, module: &str) -> Vec<String> {
+        let bases = match base {
+            Some(base) => vec![base.to_string()],
+            None => vec![String::new(), "src".to_string()],
+        };
+        bases
+            .iter()
+            .flat_map(|base| [join(base, &format!("{}.py", module)), join(base, &format!("{}/__init__.py", module))])
+            .collect()
+    }
+
+    /// Nächstes Verzeichnis oberhalb von `dir` mit `lib.rs` oder `main.rs`.
+    fn rust_crate_root(&self, dir: &str) -> Option<String> {
+        let mut current = Some(dir.to_string());
+        while let Some(dir) = current {
+            if ["lib.rs", "main.rs"].iter().any(|root| self.files.contains(&join(&dir, root))) {
+                return Some(dir);
+            }
+            current = (!dir.is_empty()).then(|| parent(&dir).to_string());
+        }
+        None
+    }
+
+    /// Tiefste Moduldatei zu einem Pfad wie `a::b::Item`.
+    fn rust_module(&self, root: &str, path: &str) -> Option<String> {
+        let mut dir = root.to_string();
+        let mut found = None;
+        for segment in path.split("::").map(str::trim) {
+            let file = [join(&dir, &format!("{}.rs", segment)), join(&dir, &format!("{}/mod.rs", segment))]
+                .into_iter()
+                .find(|c| self.files.contains(c));
+            match file {
+                Some(file) => found = Some(file),
+                None => break,
+            }
+            dir = join(&dir, segment);
+        }
+        found
+    }
+}
+
+/// Zerlegt den Pfad einer `use crate::…`-Anweisung; Gruppen in `{}` werden
+/// eine Ebene tief aufgelöst.
+fn use_paths(rest: &str) -> Vec<String> {
+    let rest = rest.trim();
+    match rest.split_once('{') {
+        Some((prefix, group)) => group
+            .trim_end_matches(['}', ' '])
+            .split(',')
+            .map(|item| format!("{}{}", prefix, item.trim()))
+            .collect(),
+        None => vec![rest.to_string()],
+    }
+}
+
+/// Kandidaten eines relativen JavaScript/TypeScript-Imports.
+fn script_candidates(path: &str) -> Vec<String> {
+    let mut candidates = vec![path.to_string()];
+    candidates.extend(SCRIPT_EXTENSIONS.iter().map(|ext| format!("{}.{}", path, ext)));
+    candidates.extend(SCRIPT_EXTENSIONS.iter().map(|ext| join(path, &format!("index.{}", ext))));
+    candidates
+}
+
+fn parent(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+fn ancestor(dir: &str, levels: usize) -> String {
+    (0..levels).fold(dir.to_string(), |dir, _| parent(&dir).to_string())
+}
+
+/// Verbindet Pfade und löst `.` und `..` auf.
+fn join(dir: &str, path: &str) -> String {
+    if dir.is_empty() {
+        normalize(path)
+    } else {
+        normalize(&format!("{}/{}", dir, path))
+    }
+}
+
+fn normalize(path: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_imports() {
+        let index = ImportIndex::new(
+            [
+                "web/app.ts",
+                "web/util/index.ts",
+                "web/api.js",
+                "pkg/__init__.py",
+                "pkg/models.py",
+                "pkg/views.py",
+                "src/lib.rs",
+                "src/config.rs",
+                "src/net/mod.rs",
+                "src/net/http.rs",
+                "native/io.h",
+            ]
+            .map(str::to_string),
+        );
+
+        let script = "import { a } from './util';\nconst api = require(\"./api\");\nimport React from 'react';\n";
+        assert_eq!(index.resolve("web/app.ts", script), vec!["web/util/index.ts", "web/api.js"]);
+
+        let python = "from . import models\nfrom pkg.models import User\nimport os, pkg\n";
+        assert_eq!(index.resolve("pkg/views.py", python), vec!["pkg/models.py", "pkg/__init__.py"]);
+
+        let rust = "mod config;\npub mod net;\nuse crate::net::http::Client;\nuse crate::{config::Config, fehlt};\nuse std::io;\n";
+        assert_eq!(
+            index.resolve("src/lib.rs", rust),
+            vec!["src/config.rs", "src/net/mod.rs", "src/net/http.rs"]
+        );
+        assert_eq!(index.resolve("native/io.c", "#include \"io.h\"\n#include <stdio.h>\n"), vec!["native/io.h"]);
+        assert_eq!(index.link("src/config.rs"), "#srcconfigrs");
+    }
+}
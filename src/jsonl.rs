@@ -0,0 +1,132 @@
+//! JSONL-Ausgabe mit einem JSON-Objekt pro Datei (z.B. für Vektor-Datenbanken).
+
+use serde::Serialize;
+
+use crate::collector::CollectedFile;
+use crate::error::{Code2MdError, Result};
+use crate::events::ExportEvent;
+use crate::markdown::{file_blocks, render_blocks_plain, MarkdownConfig};
+use crate::paths;
+use crate::types::syntax_for_path;
+
+/// Eintrag einer Datei in der JSONL-Ausgabe.
+#[derive(Debug, Serialize)]
+struct JsonlRecord {
+    path: String,
+    language: Option<String>,
+    size: u64,
+    content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chunks: Option<Vec<Chunk>>,
+}
+
+/// Zeilenbasierter Abschnitt eines Dateiinhalts.
+#[derive(Debug, Serialize, PartialEq)]
+struct Chunk {
+    index: usize,
+    start_line: usize,
+    end_line: usize,
+    content: String,
+}
+
+/// Generiert die JSONL-Ausgabe; optional wird jeder Inhalt zusätzlich in
+/// Abschnitte von höchstens `chunk_lines` Zeilen zerlegt.
+pub fn generate_jsonl(
+    files: &[CollectedFile],
+    config: &MarkdownConfig,
+    chunk_lines: Option<usize>,
+) -> Result<String> {
+    let mut lines: Vec<String> = Vec::new();
+
+    for (index, file) in files.iter().enumerate() {
+        if let Some(rel_str) = paths::relative_to(&file.path, &config.base_path) {
+            let syntax = syntax_for_path(&file.path);
+            let content = render_blocks_plain(file_blocks(file, &rel_str, config));
+
+            config.events.emit(ExportEvent::SectionWritten {
+                path: rel_str.clone(),
+                index: index + 1,
+                total: files.len(),
+            });
+
+            let record = JsonlRecord {
+                path: rel_str,
+                language: (!syntax.is_empty()).then(|| syntax.to_string()),
+                size: file.size(),
+                chunks: chunk_lines.map(|n| split_chunks(&content, n)),
+                content,
+            };
+            lines.push(
+                serde_json::to_string(&record).map_err(|e| Code2MdError::Render(e.to_string()))?,
+            );
+        }
+    }
+
+    lines.push(String::new());
+    Ok(lines.join("\n"))
+}
+
+/// Zerlegt einen Inhalt in Abschnitte von höchstens `max_lines` Zeilen.
+fn split_chunks(content: &str, max_lines: usize) -> Vec<Chunk> {
+    let all_lines: Vec<&str> = content.lines().collect();
+
+    all_lines
+        .chunks(max_lines.max(1))
+        .enumerate()
+        .map(|(index, chunk)| {
+            let start_line = index * max_lines.max(1) + 1;
+            Chunk {
+                index,
+                start_line,
+                end_line: start_line + chunk.len() - 1,
+                content: chunk.join("\n"),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_chunks() {
+        let chunks = split_chunks("a\nb\nc", 2);
+        assert_eq!(
+            chunks,
+            vec![
+                Chunk {
+                    index: 0,
+                    start_line: 1,
+                    end_line: 2,
+                    content: "a\nb".to_string(),
+                },
+                Chunk {
+                    index: 1,
+                    start_line: 3,
+                    end_line: 3,
+                    content: "c".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generate_jsonl() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().to_path_buf();
+        std::fs::write(base.join("main.py"), "print(1)\n")?;
+
+        let config = MarkdownConfig {
+            base_path: base.clone(),
+            ..Default::default()
+        };
+        let output = generate_jsonl(&[CollectedFile::from_path(base.join("main.py"))?], &config, None)?;
+
+        assert_eq!(
+            output,
+            "{\"path\":\"main.py\",\"language\":\"python\",\"size\":9,\"content\":\"print(1)\"}\n"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+//! code2md als Bibliothek: Projektcode sammeln, verarbeiten und exportieren.
+//!
+//! Einstiegspunkt ist [`Exporter::builder`]. Eigene Verarbeitungsschritte
+//! implementieren [`FileProcessor`] und werden am Builder registriert:
+//!
+//! ```no_run
+//! use code2md::{Exporter, FileProcessor};
+//!
+//! struct SkipFixtures;
+//!
+//! impl FileProcessor for SkipFixtures {
+//!     fn name(&self) -> &str {
+//!         "skip-fixtures"
+//!     }
+//!
+//!     fn filter(&self, rel_path: &str) -> bool {
+//!         !rel_path.starts_with("tests/fixtures/")
+//!     }
+//! }
+//!
+//! let export = Exporter::builder("./mein-projekt")
+//!     .types(["rust"])
+//!     .processor(SkipFixtures)
+//!     .build()?
+//!     .export()?;
+//! println!("{}", export.document);
+//! # Ok::<(), code2md::Code2MdError>(())
+//! ```
+
+pub mod annotations;
+pub mod bench;
+pub mod cache;
+pub mod cli;
+pub mod collector;
+pub mod config;
+pub mod confluence;
+pub mod docs;
+pub mod doctor;
+pub mod error;
+pub mod events;
+pub mod exporter;
+pub mod generated;
+pub mod gist;
+pub mod gitattributes;
+pub mod handlers;
+pub mod headers;
+pub mod highlight;
+pub mod history;
+pub mod hooks;
+pub mod http;
+pub mod imports;
+pub mod jsonl;
+pub mod license;
+pub mod manifest;
+pub mod markdown;
+pub mod mcp;
+pub mod merge;
+pub mod metadata;
+pub mod metrics;
+pub mod obsidian;
+pub mod output;
+pub mod packages;
+pub mod paths;
+pub mod permalink;
+pub mod pick;
+pub mod plugins;
+pub mod processor;
+pub mod prompt;
+pub mod report;
+pub mod review;
+pub mod selections;
+pub mod serve;
+pub mod stats;
+pub mod summary;
+pub mod testfiles;
+pub mod todos;
+pub mod tokens;
+pub mod transform;
+pub mod tree;
+pub mod tui;
+pub mod types;
+pub mod upload;
+pub mod xml;
+
+pub use error::{Code2MdError, Result};
+pub use events::{ExportEvent, Warning};
+pub use exporter::{Export, Exporter, ExporterBuilder};
+pub use processor::FileProcessor;
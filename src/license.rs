@@ -0,0 +1,239 @@
+//! Erkennung von Lizenzen über SPDX-Header und LICENSE-Dateien.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use crate::collector::{read_file, CollectedFile};
+use crate::paths::relative_to;
+use crate::processor::relative_path;
+
+/// Anzahl der Zeilen am Dateianfang, die nach SPDX-Headern durchsucht werden.
+const SPDX_SCAN_LINES: usize = 20;
+
+/// Erkennungsmerkmale bekannter Lizenztexte: alle Textteile müssen vorkommen.
+/// Spezifischere Einträge stehen vor allgemeineren.
+const LICENSE_SIGNATURES: &[(&[&str], &str)] = &[
+    (&["GNU AFFERO GENERAL PUBLIC LICENSE"], "AGPL-3.0"),
+    (&["GNU LESSER GENERAL PUBLIC LICENSE"], "LGPL"),
+    (&["GNU GENERAL PUBLIC LICENSE", "Version 3"], "GPL-3.0"),
+    (&["GNU GENERAL PUBLIC LICENSE", "Version 2"], "GPL-2.0"),
+    (&["Apache License", "Version 2.0"], "Apache-2.0"),
+    (&["Mozilla Public License Version 2.0"], "MPL-2.0"),
+    (&["Permission is hereby granted, free of charge"], "MIT"),
+    (&["Redistribution and use in source and binary forms", "Neither the name"], "BSD-3-Clause"),
+    (&["Redistribution and use in source and binary forms"], "BSD-2-Clause"),
+    (&["Permission to use, copy, modify, and/or distribute"], "ISC"),
+    (&["This is free and unencumbered software"], "Unlicense"),
+];
+
+/// Herkunft einer erkannten Lizenz.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LicenseSource {
+    /// Lizenzdatei im Verzeichnis (relativer Pfad)
+    LicenseFile(String),
+    /// SPDX-Header in der angegebenen Anzahl Dateien
+    SpdxHeader(usize),
+}
+
+/// Eine erkannte Lizenz in einem Verzeichnis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LicenseFinding {
+    pub directory: String,
+    pub license: String,
+    pub source: LicenseSource,
+}
+
+/// Ermittelt alle Lizenzen der gesammelten Dateien, gruppiert nach Verzeichnis.
+pub fn detect_licenses(files: &[CollectedFile], base_path: &Path) -> Vec<LicenseFinding> {
+    let mut findings = Vec::new();
+
+    // Verzeichnisse mit exportierten Dateien inkl. aller Elternordner
+    let mut directories: BTreeSet<PathBuf> = BTreeSet::new();
+    directories.insert(PathBuf::new());
+    for file in files {
+        if let Some(rel_path) = relative_to(&file.path, base_path) {
+            let mut current = Path::new(&rel_path).parent();
+            while let Some(dir) = current {
+                directories.insert(dir.to_path_buf());
+                current = dir.parent();
+            }
+        }
+    }
+
+    // LICENSE-Dateien
+    for dir in &directories {
+        let Ok(entries) = std::fs::read_dir(base_path.join(dir)) else {
+            continue;
+        };
+        let mut license_files: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && is_license_file(p))
+            .collect();
+        license_files.sort();
+
+        for path in license_files {
+            let license = identify_license_text(&read_file(&path).unwrap_or_default())
+                .unwrap_or_else(|| "unbekannt".to_string());
+            findings.push(LicenseFinding {
+                directory: display_dir(dir),
+                license,
+                source: LicenseSource::LicenseFile(relative_path(&path, base_path)),
+            });
+        }
+    }
+
+    // SPDX-Header
+    let mut spdx: BTreeMap<(String, String), usize> = BTreeMap::new();
+    for file in files {
+        let rel_path = relative_path(&file.path, base_path);
+        let dir = Path::new(&rel_path).parent().map(display_dir).unwrap_or_else(|| ".".to_string());
+        if let Some(id) = spdx_identifier(&file.text().unwrap_or_default()) {
+            *spdx.entry((dir, id)).or_insert(0) += 1;
+        }
+    }
+    for ((directory, license), count) in spdx {
+        findings.push(LicenseFinding {
+            directory,
+            license,
+            source: LicenseSource::SpdxHeader(count),
+        });
+    }
+
+    findings.sort_by(|a, b| {
+        (&a.directory, &a.license, &a.source).cmp(&(&b.directory, &b.license, &b.source))
+    });
+    findings
+}
+
+/// Liest einen `SPDX-License-Identifier` aus den ersten Zeilen einer Datei.
+pub fn spdx_identifier(content: &str) -> Option<String> {
+    content.lines().take(SPDX_SCAN_LINES).find_map(|line| {
+        let (_, rest) = line.split_once("SPDX-License-Identifier:")?;
+        let id = rest
+            .trim()
+            .trim_end_matches("*/")
+            .trim_end_matches("-->")
+            .trim();
+        (!id.is_empty()).then(|| id.to_string())
+    })
+}
+
+/// Ordnet einen Lizenztext einer bekannten Lizenz zu.
+fn identify_license_text(text: &str) -> Option<String> {
+    if let Some(id) = spdx_identifier(text) {
+        return Some(id);
+    }
+
+    // Zeilenumbrüche und Einrückungen vereinheitlichen
+    let normalized = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    LICENSE_SIGNATURES
+        .iter()
+        .find(|(parts, _)| parts.iter().all(|part| normalized.contains(part)))
+        .map(|(_, license)| license.to_string())
+}
+
+/// Prüft, ob ein Dateiname nach einer Lizenzdatei aussieht
+/// (`LICENSE`, `LICENSE-MIT`, `COPYING.txt`, …).
+fn is_license_file(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let (stem, ext) = match name.split_once('.') {
+        Some((stem, ext)) => (stem, ext),
+        None => (name.as_str(), ""),
+    };
+
+    let known_stem = ["license", "licence", "copying", "unlicense"]
+        .iter()
+        .any(|s| stem == *s || stem.starts_with(&format!("{}-", s)));
+    known_stem && matches!(ext, "" | "md" | "txt" | "rst")
+}
+
+/// Formatiert ein relatives Verzeichnis für die Ausgabe.
+fn display_dir(dir: &Path) -> String {
+    if dir.as_os_str().is_empty() {
+        ".".to_string()
+    } else {
+        dir.to_string_lossy().replace('\\', "/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spdx_identifier() {
+        assert_eq!(
+            spdx_identifier("// SPDX-License-Identifier: MIT OR Apache-2.0\nfn main() {}"),
+            Some("MIT OR Apache-2.0".to_string())
+        );
+        assert_eq!(
+            spdx_identifier("/* SPDX-License-Identifier: GPL-2.0-only */"),
+            Some("GPL-2.0-only".to_string())
+        );
+        assert_eq!(spdx_identifier("fn main() {}"), None);
+    }
+
+    #[test]
+    fn test_identify_license_text() {
+        assert_eq!(
+            identify_license_text("GNU GENERAL PUBLIC LICENSE\n   Version 3, 29 June 2007"),
+            Some("GPL-3.0".to_string())
+        );
+        assert_eq!(identify_license_text("Alle Rechte vorbehalten."), None);
+    }
+
+    #[test]
+    fn test_is_license_file() {
+        assert!(is_license_file(Path::new("LICENSE")));
+        assert!(is_license_file(Path::new("LICENSE-APACHE")));
+        assert!(is_license_file(Path::new("COPYING.txt")));
+        assert!(!is_license_file(Path::new("license.rs")));
+    }
+
+    #[test]
+    fn test_detect_licenses() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().canonicalize()?;
+        std::fs::create_dir_all(base.join("vendor/lib"))?;
+        std::fs::write(
+            base.join("LICENSE"),
+            "MIT License\n\nPermission is hereby granted, free of charge, to any person",
+        )?;
+        std::fs::write(base.join("main.rs"), "fn main() {}")?;
+        std::fs::write(
+            base.join("vendor/lib/a.c"),
+            "// SPDX-License-Identifier: Apache-2.0\nint a;",
+        )?;
+        std::fs::write(
+            base.join("vendor/lib/b.c"),
+            "// SPDX-License-Identifier: Apache-2.0\nint b;",
+        )?;
+
+        let files = ["main.rs", "vendor/lib/a.c", "vendor/lib/b.c"]
+            .iter()
+            .map(|name| CollectedFile::from_path(base.join(name)))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        let findings = detect_licenses(&files, &base);
+
+        assert_eq!(
+            findings,
+            vec![
+                LicenseFinding {
+                    directory: ".".to_string(),
+                    license: "MIT".to_string(),
+                    source: LicenseSource::LicenseFile("LICENSE".to_string()),
+                },
+                LicenseFinding {
+                    directory: "vendor/lib".to_string(),
+                    license: "Apache-2.0".to_string(),
+                    source: LicenseSource::SpdxHeader(2),
+                },
+            ]
+        );
+        Ok(())
+    }
+}
@@ -4,148 +4,1261 @@
 //! exportiert sie in eine übersichtliche Markdown-Datei mit Ordnerstruktur
 //! und Syntax-Highlighting.
 
-mod cli;
-mod collector;
-mod markdown;
-mod tree;
-mod types;
-
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
 
 use anyhow::Result;
 use clap::Parser;
 use colored::Colorize;
+use tracing::{debug, info, info_span, warn};
+use tracing_subscriber::EnvFilter;
 
-use cli::{Cli, Commands};
-use collector::{collect_files, CollectorConfig};
-use markdown::{format_size, generate_markdown, MarkdownConfig};
-use types::{collect_extensions, DEFAULT_EXCLUDES, PROJECT_TYPES};
+use code2md::annotations::Annotator;
+use code2md::bench::run_bench;
+use code2md::cache::{files_hash, inputs_hash, options_hash, plan_update, PartJournal, RunCache, SummaryCache, UpdateCache};
+use code2md::cli::{Cli, Commands, Flavor, LogFormat, OutputFormat, PublishTarget};
+use code2md::collector::{apply_read_policy, collect_files, CollectedFile, CollectorConfig};
+use code2md::config::{Config, CONFIG_FILE_NAME};
+use code2md::confluence::{generate_confluence, publish};
+use code2md::docs::DocsOnly;
+use code2md::doctor::{run_doctor, Severity};
+use code2md::error::Code2MdError;
+use code2md::events::{EventSink, ExportEvent};
+use code2md::generated::is_generated;
+use code2md::gist::upload_gist;
+use code2md::gitattributes::{parent_dirs, GitAttributes};
+use code2md::handlers::HandlerConfig;
+use code2md::headers::{check_headers, header_regex, template_regex};
+use code2md::highlight::HighlightMatches;
+use code2md::history::{self, format_history, HistoryEntry};
+use code2md::hooks::{run_hooks, HookEnv};
+use code2md::imports::ImportIndex;
+use code2md::jsonl::generate_jsonl;
+use code2md::manifest::{build_manifest, write_manifest};
+use code2md::markdown::{
+    estimate_size, files_within, format_size, generate_markdown, write_chunked, write_markdown, MarkdownConfig, Split,
+    SOURCE_DATE_EPOCH,
+};
+use code2md::mcp::serve_mcp;
+use code2md::obsidian::{vault_dir, write_vault};
+use code2md::merge::{diff_documents, looks_like_export, merge_documents, update_document};
+use code2md::output::{
+    backup_path, bundle_path, is_own_output, read_head, write_zip_bundle, written_by_code2md, OutputWriter,
+    OversizePolicy, SizeLimit,
+};
+use code2md::metadata::detect_metadata;
+use code2md::packages::{detect_packages, retain_packages, select_packages};
+use code2md::paths;
+use code2md::permalink::{git, Permalinks};
+use code2md::pick::pick;
+use code2md::plugins::{load_plugins, load_project_plugins};
+use code2md::processor::{relative_path, retain_files, slash_path, LineTruncation, Processors};
+use code2md::prompt::{wrap_in_prompt, PromptContext};
+use code2md::report::{RunReport, RunStatus, SkipTracker};
+use code2md::review;
+use code2md::selections;
+use code2md::serve::serve;
+use code2md::summary::Summarizer;
+use code2md::testfiles::{is_test_file, StripTests};
+use code2md::tokens::{count_file_tokens, estimate_tokens, find_model};
+use code2md::transform::TransformConfig;
+use code2md::tree::TreeOptions;
+use code2md::tui;
+use code2md::types::{is_known_syntax, syntax_for_path, unknown_syntax_extensions, ALWAYS_EXCLUDED, PROJECT_TYPES};
+use code2md::upload::upload;
+use code2md::xml::generate_xml;
 
-fn main() {
-    if let Err(e) = run() {
-        eprintln!("{} {}", "Fehler:".red().bold(), e);
-        std::process::exit(1);
+/// Exit-Codes des Programms (siehe README, Abschnitt "Exit-Codes").
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Exit {
+    /// Export ohne Warnungen abgeschlossen
+    Success = 0,
+    /// Sonstiger Fehler (Hooks, Plugins, Server, …)
+    Failure = 1,
+    /// Ungültige Optionen oder Konfiguration (wie bei clap-Fehlern)
+    InvalidConfig = 2,
+    /// Keine Datei entspricht der Auswahl
+    NoFiles = 3,
+    /// Lese- oder Schreibfehler
+    Io = 4,
+    /// Export geschrieben, aber mit Warnungen
+    Warnings = 5,
+    /// Geforderte Prüfung fehlgeschlagen (z.B. `--require-headers`)
+    CheckFailed = 6,
+}
+
+impl Exit {
+    /// Ordnet einen Fehler dem passenden Exit-Code zu.
+    fn from_error(error: &anyhow::Error) -> Self {
+        if let Some(error) = error.downcast_ref::<Code2MdError>() {
+            return match error {
+                Code2MdError::Config(_) => Exit::InvalidConfig,
+                Code2MdError::NoFiles => Exit::NoFiles,
+                Code2MdError::Walk { .. } | Code2MdError::Io { .. } => Exit::Io,
+                Code2MdError::CheckFailed(_) => Exit::CheckFailed,
+                Code2MdError::Render(_)
+                | Code2MdError::Hook { .. }
+                | Code2MdError::Plugin { .. }
+                | Code2MdError::PluginCall { .. }
+                | Code2MdError::Server(_)
+                | Code2MdError::Publish(_) => Exit::Failure,
+            };
+        }
+        if error.downcast_ref::<std::io::Error>().is_some() {
+            return Exit::Io;
+        }
+        Exit::Failure
     }
 }
 
-fn run() -> Result<()> {
+fn main() {
+    let exit = match run() {
+        Ok(exit) => exit,
+        Err(e) => {
+            eprintln!("{} {}", "Fehler:".red().bold(), e);
+            Exit::from_error(&e)
+        }
+    };
+    std::process::exit(exit as i32);
+}
+
+fn run() -> Result<Exit> {
     let cli = Cli::parse();
+    init_logging(cli.verbose, cli.log_format);
 
     // Subcommand verarbeiten
-    if let Some(Commands::ListTypes) = cli.command {
-        print_types();
-        return Ok(());
+    match cli.command {
+        Some(Commands::ListTypes) => {
+            print_types();
+            return Ok(Exit::Success);
+        }
+        Some(Commands::ServeMcp(ref selection)) => {
+            selection.validate()?;
+            serve_mcp(selection)?;
+            return Ok(Exit::Success);
+        }
+        Some(Commands::Serve { ref selection, port }) => {
+            selection.validate()?;
+            serve(selection, port)?;
+            return Ok(Exit::Success);
+        }
+        Some(Commands::Bench { ref selection, runs }) => {
+            selection.validate()?;
+            info!("Messe {} Durchläufe...", runs);
+            let report = run_bench(selection, runs)?;
+            println!();
+            println!("{}", "Pipeline-Laufzeiten:".bright_blue().bold());
+            println!("{}", report);
+            return Ok(Exit::Success);
+        }
+        Some(Commands::Tokens { ref selection, ref model, top }) => {
+            selection.validate()?;
+            let model = find_model(model)?;
+            let collected = collect_files(&selection.directory, &selection.collector_config()?)?;
+            let counts = count_file_tokens(&collected.files, &collected.base_path, model.tokenizer);
+            let total: usize = counts.iter().map(|c| c.tokens).sum();
+            let shown = top.unwrap_or(counts.len()).min(counts.len());
+
+            println!();
+            println!("{}", format!("Geschätzte Tokens ({}):", model.name).bright_blue().bold());
+            println!();
+            for count in &counts[..shown] {
+                let share = count.tokens as f64 / total.max(1) as f64 * 100.0;
+                println!("  {:>10} {:>6.1}%  {}", count.tokens, share, count.path);
+            }
+            if shown < counts.len() {
+                println!("  {:>10}          … {} weitere", "", counts.len() - shown);
+            }
+            println!();
+            println!("  {:>10}          {} Dateien", total.to_string().bold(), counts.len());
+            println!(
+                "  {:>10} {:>6.1}%  Budget für {}",
+                model.token_budget(),
+                total as f64 / model.token_budget() as f64 * 100.0,
+                model.name
+            );
+            return Ok(Exit::Success);
+        }
+        Some(Commands::Doctor { ref selection, ref config, ref output }) => {
+            let findings = run_doctor(selection, config.as_deref(), output.as_deref())?;
+            println!();
+            for finding in &findings {
+                let line = finding.to_string();
+                match finding.severity {
+                    Severity::Info => println!("{}", line.green()),
+                    Severity::Warning => println!("{}", line.yellow()),
+                    Severity::Error => println!("{}", line.red()),
+                }
+            }
+            let errors = findings.iter().filter(|f| f.severity == Severity::Error).count();
+            if errors > 0 {
+                return Err(Code2MdError::CheckFailed(format!("{} Problem(e) gefunden.", errors)).into());
+            }
+            if findings.iter().any(|f| f.severity == Severity::Warning) {
+                return Ok(Exit::Warnings);
+            }
+            return Ok(Exit::Success);
+        }
+        Some(Commands::Pick { ref selection, ref output, ref save_selection }) => {
+            selection.validate()?;
+            if !io::stdin().is_terminal() {
+                return Err(Code2MdError::Config("'code2md pick' benötigt ein Terminal.".to_string()).into());
+            }
+            let collected = collect_files(&selection.directory, &selection.collector_config()?)?;
+            let rel_paths: Vec<String> = collected
+                .files
+                .iter()
+                .map(|file| relative_path(&file.path, &collected.base_path))
+                .collect();
+            let picked = pick(&rel_paths, &mut io::stdin().lock(), &mut io::stderr())?;
+            if picked.is_empty() {
+                println!("Keine Dateien ausgewählt.");
+                return Ok(Exit::Success);
+            }
+
+            let project_name = selection.project_name();
+            let files: Vec<CollectedFile> = collected
+                .files
+                .into_iter()
+                .enumerate()
+                .filter(|(index, _)| picked.contains(index))
+                .map(|(_, file)| file)
+                .collect();
+            let config = MarkdownConfig {
+                project_name: project_name.clone(),
+                base_path: collected.base_path.clone(),
+                include_tree: true,
+                handlers: HandlerConfig {
+                    output_dir: collected.base_path.clone(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let output_path = output
+                .clone()
+                .unwrap_or_else(|| selection.directory.join(format!("{}_pick.md", project_name)));
+            fs::write(&output_path, generate_markdown(&files, &config)).map_err(|e| Code2MdError::io(&output_path, e))?;
+            if let Some(name) = save_selection {
+                let picked_paths: Vec<String> = picked.iter().map(|&index| rel_paths[index].clone()).collect();
+                let path = selections::save(&collected.base_path, name, &picked_paths)?;
+                info!("Auswahl '{}' gespeichert: {}", name, path.display());
+            }
+            println!("{} {} Datei(en) nach {}", "✓".green().bold(), files.len(), output_path.display());
+            return Ok(Exit::Success);
+        }
+        Some(Commands::History { ref directory, last }) => {
+            let base_path = paths::canonicalize(directory)?;
+            let entries = history::load(&base_path)?;
+            if entries.is_empty() {
+                println!("Noch keine Läufe aufgezeichnet ({}).", history::history_path(&base_path).display());
+                return Ok(Exit::Success);
+            }
+            println!();
+            println!("{}", format!("Verlauf ({} Läufe):", entries.len()).bright_blue().bold());
+            println!();
+            for line in format_history(&entries, last) {
+                println!("  {}", line);
+            }
+            return Ok(Exit::Success);
+        }
+        None => {}
     }
 
     // CLI validieren
     cli.validate()?;
+    let started = Instant::now();
+    let started_at = chrono::Local::now();
+
+    // Konfiguration
+    let project_name = cli.project_name();
+    let output_path = cli.output_path()?;
+    let base_path = paths::canonicalize(&cli.selection.directory)?;
+    let (mut config, config_path) = Config::load(cli.config.as_deref(), &base_path)?;
+    // Befehle und Plugins aus dem exportierten Repository nur nach ausdrücklicher Zustimmung
+    let trusted = cli.config.is_some() || cli.allow_hooks;
+    if let Some(path) = config_path.as_deref().filter(|_| !trusted) {
+        if config.strip_commands() {
+            warn!(
+                "Befehle aus {} (Hooks, [pipe_through]) werden nicht ausgeführt; mit --allow-hooks oder --config erlauben",
+                path.display()
+            );
+        }
+        if config.strip_publish_target() && cli.publish == Some(PublishTarget::Confluence) {
+            warn!(
+                "[confluence] url/user aus {} werden ignoriert; mit --allow-hooks oder --config erlauben",
+                path.display()
+            );
+        }
+    }
+    // Pfade einer weiter oben gefundenen Konfiguration beziehen sich auf deren Verzeichnis
+    let config_prefix = config_path
+        .as_deref()
+        .filter(|_| cli.config.is_none())
+        .and_then(Path::parent)
+        .and_then(|dir| base_path.strip_prefix(dir).ok());
 
-    // Extensions sammeln
-    let mut extensions: HashSet<String> = HashSet::new();
+    // Extensions und Excludes zusammenstellen
+    let extensions = cli.selection.collect_extensions_with(&config.aliases)?;
+    let exclude_patterns = cli.selection.exclude_patterns_with(&config.excludes)?;
 
-    if let Some(ref type_names) = cli.types {
-        extensions.extend(collect_extensions(type_names)?);
+    debug!(
+        directory = %base_path.display(),
+        project = %project_name,
+        output = %output_path.display(),
+        extensions = %extensions.iter().cloned().collect::<Vec<_>>().join(", "),
+        excludes = exclude_patterns.len(),
+        config = ?config_path,
+        "Konfiguration"
+    );
+
+    // Pre-Export-Hooks
+    let mut hook_env = HookEnv {
+        project_name: project_name.clone(),
+        base_path: base_path.display().to_string(),
+        output_path: output_path.display().to_string(),
+        ..Default::default()
+    };
+    run_hooks("pre_export", &config.hooks.pre_export, &base_path, &hook_env)?;
+
+    // Dateien sammeln
+    let collect_span = info_span!("collect").entered();
+    info!("Sammle Dateien...");
+
+    let mut collector_config = CollectorConfig::new(extensions.clone(), &exclude_patterns)?;
+    collector_config.on_walk_error = cli.selection.on_walk_error;
+    collector_config.skip_nested_repos = cli.selection.skip_nested_repos;
+    collector_config.all_text = cli.selection.all_text;
+    collector_config.attributes = cli.selection.export_ignore_attributes()?;
+    collector_config.follow_links = cli.selection.follow_symlinks;
+    collector_config.roots = cli.selection.sub_paths()?;
+    collector_config.set_only(&cli.selection.only);
+    collector_config.set_overrides(config.dir_overrides(config_prefix.unwrap_or(Path::new("")))?);
+    let mut collected = collect_files(&base_path, &collector_config)?;
+    let mut skips = SkipTracker::new(&collected.files, &collected.base_path);
+    for path in &collected.nested_repos {
+        info!("Verschachteltes Repository übersprungen: {}", relative_path(path, &collected.base_path));
+        skips.skip(path, "verschachteltes Repository");
+    }
+    if !collected.aliases.is_empty() {
+        info!("{} Pfade verweisen auf bereits gesammelte Dateien (als Alias aufgeführt)", collected.aliases.len());
+    }
+    // Mit --annotations erscheinen Warnungen als Annotationen statt als Logzeilen
+    let annotator = cli.annotations.map(|format| Annotator::detect(format, &base_path));
+    let events = match annotator.clone() {
+        Some(annotator) => EventSink::new(Arc::new(move |event| {
+            if let ExportEvent::Warning { path, message } = event {
+                println!("{}", annotator.warning(path.as_deref(), None, message));
+            }
+        })),
+        None => EventSink::default(),
+    };
+    for (path, message) in &collected.skipped {
+        events.warn(&relative_path(path, &collected.base_path), format!("übersprungen: {}", message));
+        skips.skip(path, format!("nicht lesbar: {}", message));
     }
 
-    // Zusätzliche Extensions hinzufügen
-    if let Some(ref exts) = cli.extensions {
-        for ext in exts {
-            let normalized = if ext.starts_with('.') {
-                ext.to_lowercase()
-            } else {
-                format!(".{}", ext.to_lowercase())
-            };
-            extensions.insert(normalized);
+    // Die eigene Ausgabe nie mit exportieren, frühere Exporte melden
+    collected.files.retain(|file| {
+        !is_own_output(&file.path, &output_path)
+            && !cli.report.as_deref().is_some_and(|report| is_own_output(&file.path, report))
+    });
+    skips.note(&collected.files, "eigene Ausgabe");
+    // Nur den Dateianfang lesen, damit nicht alle Inhalte vorzeitig im Speicher landen
+    for file in &collected.files {
+        if read_head(&file.path).is_ok_and(|head| looks_like_export(&head)) {
+            events.warn(
+                &relative_path(&file.path, &collected.base_path),
+                "sieht aus wie ein früherer code2md-Export (mit --exclude ausschließen)",
+            );
         }
     }
 
-    // Excludes zusammenstellen
-    let mut exclude_patterns: Vec<String> = if cli.no_default_excludes {
-        Vec::new()
-    } else {
-        DEFAULT_EXCLUDES.iter().map(|s| s.to_string()).collect()
+    // Auf ausgewählte Pakete beschränken
+    let packages = match &cli.packages {
+        Some(names) => select_packages(&detect_packages(&base_path)?, names)?,
+        None => Vec::new(),
     };
+    if !packages.is_empty() {
+        retain_packages(&mut collected.files, &collected.base_path, &packages);
+        skips.note(&collected.files, "nicht in den gewählten Paketen (--package)");
+        debug!(
+            packages = %packages.iter().map(|p| p.name.as_str()).collect::<Vec<_>>().join(", "),
+            "Pakete ausgewählt"
+        );
+    }
 
-    if let Some(ref excludes) = cli.excludes {
-        exclude_patterns.extend(excludes.iter().cloned());
+    // Attribute aus .gitattributes, auch aus Unterverzeichnissen
+    let mut languages = BTreeMap::new();
+    if !cli.no_linguist || !cli.selection.no_export_ignore {
+        let rel_paths: Vec<String> = collected
+            .files
+            .iter()
+            .map(|file| relative_path(&file.path, &collected.base_path))
+            .collect();
+        let attributes = GitAttributes::load(&collected.base_path, parent_dirs(&rel_paths))?;
+        if !attributes.is_empty() {
+            let mut keep = Vec::with_capacity(rel_paths.len());
+            let mut reasons = HashMap::new();
+            for rel_str in &rel_paths {
+                let reason = if !cli.selection.no_export_ignore && attributes.export_ignored(rel_str) {
+                    Some("export-ignore")
+                } else if !cli.no_linguist {
+                    attributes.linguist_excluded(rel_str)
+                } else {
+                    None
+                };
+                match reason {
+                    Some(reason) => {
+                        debug!(file = %rel_str, "Datei übersprungen ({})", reason);
+                        reasons.insert(rel_str.as_str(), reason);
+                        keep.push(false);
+                    }
+                    None => {
+                        if let Some(language) = attributes.language(rel_str).filter(|_| !cli.no_linguist) {
+                            languages.insert(rel_str.clone(), language);
+                        }
+                        keep.push(true);
+                    }
+                }
+            }
+            let skipped = keep.iter().filter(|keep| !**keep).count();
+            if skipped > 0 {
+                info!("{} Datei(en) laut .gitattributes übersprungen", skipped);
+            }
+            let mut keep = keep.into_iter();
+            collected.files.retain(|_| keep.next().unwrap_or(true));
+            skips.note_with(&collected.files, |path| {
+                format!(".gitattributes: {}", reasons.get(path).copied().unwrap_or_default())
+            });
+        }
     }
 
-    // Konfiguration
-    let project_name = cli.project_name();
-    let output_path = cli.output_path();
-    let base_path = cli.directory.canonicalize()?;
+    // Generierte Dateien anhand der Markierungen am Dateianfang überspringen
+    if !cli.include_generated {
+        let before = collected.files.len();
+        collected.files.retain(|file| {
+            let generated = is_generated(file);
+            if generated {
+                debug!(file = %relative_path(&file.path, &collected.base_path), "Generierte Datei übersprungen");
+            }
+            !generated
+        });
+        skips.note(&collected.files, "generierte Datei");
+        if collected.files.len() < before {
+            info!(
+                "{} generierte Datei(en) übersprungen (--include-generated zum Einschließen)",
+                before - collected.files.len()
+            );
+        }
+    }
 
-    // Verbose Header
-    if cli.verbose {
-        println!();
-        println!("{}", "═".repeat(60).bright_blue());
-        println!("{}", "code2md - Projekt-Export".bright_blue().bold());
-        println!("{}", "═".repeat(60).bright_blue());
-        println!("Projektverzeichnis: {}", base_path.display());
-        println!("Projektname:        {}", project_name);
-        println!("Ausgabedatei:       {}", output_path.display());
-        println!(
-            "Dateiendungen:      {}",
-            extensions.iter().cloned().collect::<Vec<_>>().join(", ")
-        );
-        println!("Ausschlüsse:        {} Patterns", exclude_patterns.len());
-        println!("{}", "═".repeat(60).bright_blue());
-        println!();
+    // Sprachen nach der Erkennung ausschließen (auch linguist-language)
+    if !cli.exclude_langs.is_empty() {
+        let excluded: HashSet<String> = cli.exclude_langs.iter().map(|lang| lang.trim().to_lowercase()).collect();
+        for language in excluded.iter().filter(|lang| !is_known_syntax(lang)) {
+            warn!("Unbekannte Sprache für --exclude-lang: {}", language);
+        }
+        let before = collected.files.len();
+        collected.files.retain(|file| {
+            let rel_str = relative_path(&file.path, &collected.base_path);
+            let language = match languages.get(&rel_str) {
+                Some(language) => language.to_lowercase(),
+                None => syntax_for_path(&file.path).to_string(),
+            };
+            !excluded.contains(&language)
+        });
+        skips.note(&collected.files, "Sprache per --exclude-lang ausgeschlossen");
+        if collected.files.len() < before {
+            info!("{} Datei(en) per --exclude-lang übersprungen", before - collected.files.len());
+        }
     }
 
-    // Dateien sammeln
-    println!("{}", "Sammle Dateien...".dimmed());
-    
-    let config = CollectorConfig::new(extensions, &exclude_patterns)?;
-    let collected = collect_files(&base_path, &config)?;
+    // Auf eine gespeicherte Auswahl beschränken
+    if let Some(name) = &cli.selection_name {
+        let listed = selections::load(&collected.base_path, name)?;
+        let present: HashSet<String> = collected
+            .files
+            .iter()
+            .map(|file| relative_path(&file.path, &collected.base_path))
+            .collect();
+        for rel_str in listed.iter().filter(|rel_str| !present.contains(*rel_str)) {
+            events.warn(rel_str, format!("aus Auswahl '{}' nicht (mehr) vorhanden oder ausgeschlossen", name));
+        }
+        let listed: HashSet<String> = listed.into_iter().collect();
+        collected
+            .files
+            .retain(|file| listed.contains(&relative_path(&file.path, &collected.base_path)));
+        skips.note(&collected.files, &format!("nicht in Auswahl '{}'", name));
+    }
+
+    // Auswahl interaktiv verfeinern
+    if let Some(count) = cli.review {
+        if io::stdin().is_terminal() {
+            let candidates = review::largest(&collected.files, &collected.base_path, count);
+            let mut input = io::stdin().lock();
+            let mut prompt = io::stderr();
+            let excluded = review::ask(&candidates, &mut input, &mut prompt)?;
+            if !excluded.is_empty() {
+                collected.files.retain(|file| {
+                    let rel_str = relative_path(&file.path, &collected.base_path);
+                    !excluded.iter().any(|candidate| candidate.contains(&rel_str))
+                });
+                skips.note(&collected.files, "bei --review abgelehnt");
+                let target = config_path.clone().unwrap_or_else(|| base_path.join(CONFIG_FILE_NAME));
+                let question = format!("Ausschlüsse in {} speichern?", target.display());
+                if review::confirm(&question, &mut input, &mut prompt)? {
+                    let patterns: Vec<String> = excluded.iter().map(review::exclude_pattern).collect();
+                    let dir = config_prefix.map(slash_path).unwrap_or_default();
+                    review::save_excludes(&target, &dir, &patterns)?;
+                    info!("{} Ausschluss/Ausschlüsse in {} gespeichert", patterns.len(), target.display());
+                }
+            }
+        } else {
+            warn!("--review benötigt ein Terminal; Auswahl bleibt unverändert");
+        }
+    }
+
+    // Prozessoren einrichten und Dateiauswahl filtern
+    let mut processors: Processors = Vec::new();
+    if cli.separate_tests || cli.no_tests {
+        processors.push(Arc::new(StripTests {
+            exclude_files: cli.no_tests,
+        }));
+    }
+    if cli.docs_only {
+        processors.push(Arc::new(DocsOnly));
+    }
+    if let Some(max_length) = cli.max_line_length {
+        processors.push(Arc::new(LineTruncation { max_length }));
+    }
+    if let Some(pattern) = &cli.annotate {
+        processors.push(Arc::new(HighlightMatches::new(pattern)?));
+    }
+    let plugins = match &cli.plugins_dir {
+        Some(dir) => load_plugins(dir)?,
+        None => load_project_plugins(&base_path, trusted)?,
+    };
+    if !plugins.is_empty() {
+        let names: Vec<&str> = plugins.iter().map(|p| p.name()).collect();
+        debug!(plugins = %names.join(", "), "Plugins geladen");
+    }
+    processors.extend(plugins);
+    retain_files(&mut collected.files, &collected.base_path, &processors);
+    skips.note(&collected.files, "von einem Prozessor ausgeschlossen");
+    apply_read_policy(&mut collected.files, &collected.base_path, cli.on_read_error, &events)?;
+    skips.note(&collected.files, "nicht lesbar");
+    if cli.separate_tests {
+        // Stabil sortieren: Testdateien ans Ende, sonst unveränderte Reihenfolge
+        collected
+            .files
+            .sort_by_key(|file| is_test_file(&relative_path(&file.path, &collected.base_path)));
+    }
 
     if collected.files.is_empty() {
-        anyhow::bail!("Keine passenden Dateien gefunden.");
+        return Err(Code2MdError::NoFiles.into());
     }
 
-    println!(
+    info!(
+        files = collected.files.len(),
         "Gefunden: {} {}",
-        collected.files.len().to_string().green().bold(),
+        collected.files.len(),
         if collected.files.len() == 1 { "Datei" } else { "Dateien" }
     );
+    for file in &collected.files {
+        if let Ok(rel) = file.path.strip_prefix(&collected.base_path) {
+            debug!(file = %rel.display(), "Datei ausgewählt");
+        }
+    }
+    drop(collect_span);
 
-    // Verbose: Dateien auflisten
-    if cli.verbose {
-        for file in &collected.files {
-            if let Ok(rel) = file.strip_prefix(&base_path) {
-                println!("  {} {}", "─".dimmed(), rel.display());
+    // Endungen ohne bekannte Sprache melden
+    let fallback_syntax = cli.fallback_syntax.clone().or_else(|| config.fallback_syntax.clone());
+    let unknown = unknown_syntax_extensions(
+        collected
+            .files
+            .iter()
+            .filter(|file| !languages.contains_key(&relative_path(&file.path, &collected.base_path)))
+            .filter_map(|file| file.path.file_name().and_then(|name| name.to_str())),
+    );
+    if !unknown.is_empty() {
+        info!(
+            "Keine Sprache bekannt für: {} (Codeblöcke {})",
+            unknown.join(", "),
+            match &fallback_syntax {
+                Some(fallback) => format!("als '{}'", fallback),
+                None => "ohne Sprachangabe".to_string(),
             }
+        );
+    }
+
+    // Unveränderte Läufe anhand des Run-Cache überspringen
+    let run_cache_path = RunCache::path(&base_path, &output_path);
+    let mut extension_list: Vec<String> = extensions.iter().cloned().collect();
+    extension_list.sort();
+    let args: Vec<String> = std::env::args()
+        .skip(1)
+        .filter(|arg| arg != "--check" && arg != "--no-cache" && arg != "--resume")
+        .collect();
+    let config_file = config_path.as_ref().and_then(|path| fs::read_to_string(path).ok());
+    let metadata = detect_metadata(&base_path);
+    // Eingaben außerhalb der gesammelten Dateien, die das Dokument verändern
+    let uses_permalinks = cli.repo_url.is_some() || config.repository.url.is_some();
+    let inputs = inputs_hash(&[
+        ("head", uses_permalinks.then(|| git(&base_path, &["rev-parse", "HEAD"]).ok()).flatten()),
+        ("source_date_epoch", cli.reproducible.then(|| std::env::var(SOURCE_DATE_EPOCH).ok()).flatten()),
+        ("metadata", metadata.as_ref().map(|metadata| format!("{:?}", metadata))),
+    ]);
+    let run_cache = RunCache {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        base_path: base_path.display().to_string(),
+        extensions: extension_list,
+        excludes: exclude_patterns.clone(),
+        options: options_hash(&args, config_file.as_deref()),
+        files: files_hash(&collected.files, &base_path),
+        inputs,
+        output: None,
+        written: Vec::new(),
+    };
+    // Eine interaktive Auswahl ist nicht reproduzierbar und umgeht den Run-Cache;
+    // --check vergleicht immer mit einem frisch erzeugten Dokument.
+    // Bei einem Treffer entfallen nur Rendern und Schreiben; Manifest, Archiv,
+    // Veröffentlichung, Hooks und Öffnen laufen wie gewohnt
+    let use_run_cache = !cli.no_cache && !cli.interactive && !cli.check;
+    let cached_written = match RunCache::load(&run_cache_path) {
+        Some(cached) if use_run_cache && !cli.resume && cached.is_current(&run_cache.clone().with_output(&output_path)) => {
+            Some(cached.written)
         }
+        _ => None,
+    };
+    let up_to_date = cached_written.is_some();
+    if up_to_date {
+        info!("Ausgabe ist laut Run-Cache aktuell, Rendern entfällt");
     }
 
-    // Markdown generieren
-    println!("{}", "Generiere Markdown...".dimmed());
+    // Header prüfen
+    let header_pattern = match (&cli.check_headers, &cli.header_template) {
+        (Some(pattern), _) => Some(header_regex(pattern)?),
+        (None, Some(template)) => Some(template_regex(&fs::read_to_string(template)?)?),
+        (None, None) => None,
+    };
+    let header_checks = header_pattern
+        .map(|pattern| check_headers(&collected.files, &collected.base_path, &pattern));
+
+    // Dokument generieren
+    let render_span = info_span!("render").entered();
+    match cli.format {
+        OutputFormat::Markdown => info!("Generiere Markdown..."),
+        OutputFormat::Xml => info!("Generiere XML..."),
+        OutputFormat::Jsonl => info!("Generiere JSONL..."),
+        OutputFormat::Confluence => info!("Generiere Confluence-Seite..."),
+    }
 
-    let md_config = MarkdownConfig {
+    let mut descriptions = config.descriptions()?;
+    if let Some(prefix) = config_prefix {
+        descriptions.set_prefix(prefix);
+    }
+    let permalinks = match &cli.repo_url {
+        Some(url) => Some(Permalinks::detect(&base_path, url.as_deref(), &config.repository)?),
+        None if config.repository.url.is_some() => Some(Permalinks::detect(&base_path, None, &config.repository)?),
+        None => None,
+    };
+    let summary_cache_path = SummaryCache::path(&base_path);
+    let mut md_config = MarkdownConfig {
         project_name: project_name.clone(),
         base_path: base_path.clone(),
         include_tree: !cli.no_tree,
+        tree: TreeOptions {
+            ascii: cli.ascii_tree,
+            stats: cli.tree_stats,
+            depth: cli.tree_depth,
+            max_entries: cli.tree_max_entries,
+        },
+        transform: TransformConfig {
+            line_ending: cli.normalize_eol,
+            keep_bom: cli.keep_bom,
+            expand_tabs: cli.expand_tabs,
+            trim_trailing_whitespace: cli.trim_trailing_whitespace,
+            pipe_through: cli.pipe_through.clone(),
+            pipe_through_languages: config.pipe_through.clone(),
+        },
+        handlers: HandlerConfig {
+            notebook_markdown: cli.notebook_markdown,
+            csv_preview: cli.csv_preview,
+            embed_images: cli.embed_images,
+            max_embed_size: cli.max_embed_size,
+            output_dir: output_dir(&output_path, &base_path),
+            svg_mode: cli.svg_mode,
+        },
+        include_hashes: cli.hashes,
+        include_licenses: cli.licenses,
+        header_checks,
+        processors,
+        events: events.clone(),
+        include_warnings: cli.warnings_appendix,
+        summarizer: cli
+            .summarize_cmd
+            .as_ref()
+            .map(|command| Summarizer::new(command, SummaryCache::load(&summary_cache_path))),
+        descriptions,
+        separate_tests: cli.separate_tests,
+        packages,
+        nested_repos: collected
+            .nested_repos
+            .iter()
+            .map(|path| relative_path(path, &collected.base_path))
+            .collect(),
+        excluded_dirs: if cli.tree_show_excluded {
+            // Das eigene Cache-Verzeichnis ist kein Teil des Projekts
+            collected
+                .excluded_dirs
+                .iter()
+                .filter(|path| !path.file_name().is_some_and(|name| ALWAYS_EXCLUDED.iter().any(|e| name == *e)))
+                .map(|path| relative_path(path, &collected.base_path))
+                .collect()
+        } else {
+            Vec::new()
+        },
+        metadata,
+        badges: config.badges.clone(),
+        include_stats: cli.stats,
+        metrics: cli.metrics.then_some(cli.metrics_sort),
+        include_todos: cli.todos,
+        // Erst nach der endgültigen Auswahl, siehe unten
+        imports: None,
+        permalinks,
+        link_sources: cli.link_sources,
+        front_matter: cli.front_matter.then(|| config.front_matter.tags.clone()),
+        meta: cli.meta.clone(),
+        fallback_syntax: fallback_syntax.clone(),
+        languages,
+        aliases: collected.alias_map(),
+        truncation_notice: None,
+        reproducible: cli.reproducible || cli.check,
+    };
+
+    // Auswahl mit Vorschau im Terminal
+    if cli.interactive {
+        if !io::stdin().is_terminal() {
+            return Err(Code2MdError::Config("--interactive benötigt ein Terminal.".to_string()).into());
+        }
+        match tui::select(&collected.files, &md_config)? {
+            Some(selection) => {
+                let mut index = 0;
+                collected.files.retain(|_| {
+                    index += 1;
+                    selection.contains(&(index - 1))
+                });
+                skips.note(&collected.files, "in --interactive abgewählt");
+                info!("{} Datei(en) ausgewählt", collected.files.len());
+            }
+            None => {
+                println!("Export abgebrochen.");
+                return Ok(Exit::Success);
+            }
+        }
+    }
+    if let Some(name) = &cli.save_selection {
+        let rel_paths: Vec<String> = collected
+            .files
+            .iter()
+            .map(|file| relative_path(&file.path, &collected.base_path))
+            .collect();
+        let path = selections::save(&collected.base_path, name, &rel_paths)?;
+        info!("Auswahl '{}' gespeichert: {}", name, path.display());
+    }
+
+    // Höchstgröße der Ausgabe vor dem Rendern abschätzen
+    let mut oversize_split = None;
+    if let Some(limit) = cli.max_output_size {
+        let estimate = estimate_size(&collected.files, &collected.base_path);
+        debug!(estimate, limit, "Geschätzte Ausgabegröße");
+        if estimate > limit {
+            match cli.on_oversize {
+                OversizePolicy::Abort => {
+                    return Err(Code2MdError::Config(format!(
+                        "Geschätzte Ausgabegröße {} überschreitet --max-output-size {}. Auswahl einschränken oder --on-oversize split bzw. truncate verwenden.",
+                        format_size(estimate),
+                        format_size(limit)
+                    ))
+                    .into());
+                }
+                OversizePolicy::Split => {
+                    if cli.format != OutputFormat::Markdown || cli.compress.is_some() || cli.append || cli.update || cli.prompt.is_some() {
+                        return Err(Code2MdError::Config(
+                            "--on-oversize split ist nur für Markdown ohne --compress, --append, --update und --prompt möglich."
+                                .to_string(),
+                        )
+                        .into());
+                    }
+                    info!("Geschätzte Ausgabegröße {} überschreitet {}, teile auf", format_size(estimate), format_size(limit));
+                    // Reserve für Überschriften, Inhaltsverzeichnis und Baum je Teil
+                    oversize_split = Some(Split::Size(limit - limit / 10));
+                }
+                OversizePolicy::Truncate => {
+                    let keep = files_within(&collected.files, &collected.base_path, limit);
+                    let omitted = collected.files.len() - keep;
+                    collected.files.truncate(keep);
+                    skips.note(&collected.files, "wegen --max-output-size weggelassen");
+                    let notice = format!(
+                        "{} Datei(en) wegen --max-output-size {} weggelassen (geschätzte Größe des vollständigen Exports: {}).",
+                        omitted,
+                        format_size(limit),
+                        format_size(estimate)
+                    );
+                    md_config.events.emit(ExportEvent::Warning {
+                        path: None,
+                        message: notice.clone(),
+                    });
+                    md_config.truncation_notice = Some(notice);
+                }
+            }
+        }
+    }
+
+    // Import-Links nur auf Dateien, die im Dokument tatsächlich vorkommen
+    if cli.import_links {
+        md_config.imports = Some(ImportIndex::new(
+            collected
+                .files
+                .iter()
+                .map(|file| relative_path(&file.path, &collected.base_path)),
+        ));
+    }
+
+    // Bestehende Ausgabe für --append bzw. --update
+    let existing = if (cli.append || cli.update) && output_path.exists() {
+        Some(fs::read_to_string(&output_path).map_err(|e| Code2MdError::io(&output_path, e))?)
+    } else {
+        None
+    };
+    // Fremde Dateien nicht stillschweigend überschreiben
+    if existing.is_none()
+        && !cli.check
+        && !cli.force
+        && !cli.backup
+        && output_path.is_file()
+        && !written_by_code2md(&output_path).map_err(|e| Code2MdError::io(&output_path, e))?
+    {
+        return Err(Code2MdError::Config(format!(
+            "'{}' existiert bereits und stammt nicht von code2md. Mit --force überschreiben oder mit --backup vorher sichern.",
+            output_path.display()
+        ))
+        .into());
+    }
+    let cache_path = UpdateCache::path(&base_path, &output_path);
+    let update_plan = cli
+        .update
+        .then(|| plan_update(&collected.files, &base_path, &UpdateCache::load(&cache_path)));
+
+    // Markdown ohne Prompt-Vorlage wird abschnittsweise direkt in die Datei geschrieben
+    let streamed = cli.format == OutputFormat::Markdown && cli.prompt.is_none() && existing.is_none() && !cli.check;
+    // Ohne Neuerzeugung wird das Dokument nur noch für --publish confluence gebraucht
+    let skip_render = up_to_date && cli.publish != Some(PublishTarget::Confluence);
+    let mut document = if streamed || skip_render {
+        None
+    } else {
+        Some(match cli.format {
+            OutputFormat::Markdown => match (&existing, &update_plan) {
+                (Some(existing), Some(plan)) if plan.is_empty() => {
+                    info!("Ausgabe ist aktuell");
+                    existing.clone()
+                }
+                (Some(existing), Some(plan)) => {
+                    info!(
+                        changed = plan.changed.len(),
+                        removed = plan.removed.len(),
+                        "Aktualisiere {} Abschnitt(e), entferne {}",
+                        plan.changed.len(),
+                        plan.removed.len()
+                    );
+                    let changed: Vec<CollectedFile> = plan
+                        .changed
+                        .iter()
+                        .map(|&index| &collected.files[index])
+                        .map(|file| CollectedFile::new(file.path.clone(), file.metadata.clone()))
+                        .collect();
+                    update_document(existing, &generate_markdown(&changed, &md_config), &plan.removed)?
+                }
+                (Some(existing), None) => {
+                    merge_documents(existing, &generate_markdown(&collected.files, &md_config))?
+                }
+                (None, _) => generate_markdown(&collected.files, &md_config),
+            },
+            OutputFormat::Xml => generate_xml(&collected.files, &md_config),
+            OutputFormat::Jsonl => generate_jsonl(&collected.files, &md_config, cli.chunk_lines)?,
+            OutputFormat::Confluence => generate_confluence(&collected.files, &md_config),
+        })
     };
 
-    let markdown = generate_markdown(&collected.files, &md_config);
+    // In Prompt-Vorlage einbetten
+    if let (Some(template_path), Some(markdown)) = (&cli.prompt, &mut document) {
+        let template = fs::read_to_string(template_path)?;
+        let context = PromptContext {
+            project_name: &project_name,
+            file_count: collected.files.len(),
+        };
+        *markdown = wrap_in_prompt(&template, markdown, &context);
+    }
+
+    drop(render_span);
+
+    // Vorhandene Ausgabe mit dem neu erzeugten Dokument vergleichen
+    if cli.check {
+        if oversize_split.is_some() {
+            return Err(Code2MdError::Config("--check unterstützt keine aufgeteilte Ausgabe.".to_string()).into());
+        }
+        let committed = match fs::read_to_string(&output_path) {
+            Ok(committed) => committed,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Err(Code2MdError::CheckFailed(format!("Ausgabedatei fehlt: {}", output_path.display())).into());
+            }
+            Err(e) => return Err(Code2MdError::io(&output_path, e).into()),
+        };
+        let diff = diff_documents(&committed, document.as_deref().unwrap_or_default());
+        if diff.is_empty() {
+            println!("{}", "✓ Ausgabe ist aktuell".green().bold());
+            println!("  Datei:   {}", output_path.display());
+            return Ok(Exit::Success);
+        }
+        println!("{}", format!("✗ Ausgabe ist veraltet: {}", output_path.display()).red().bold());
+        for line in diff.summary() {
+            println!("  {}", line);
+        }
+        return Err(Code2MdError::CheckFailed(format!(
+            "Ausgabe ist veraltet (mit --reproducible neu erzeugen): {}",
+            output_path.display()
+        ))
+        .into());
+    }
 
     // Ausgabe schreiben
-    fs::write(&output_path, &markdown)?;
+    let write_span = info_span!("write").entered();
+    if cli.output_dir.is_some() && !up_to_date {
+        if let Some(dir) = output_path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|e| Code2MdError::io(dir, e))?;
+        }
+    }
+    if cli.backup && !up_to_date && output_path.is_file() {
+        let backup = backup_path(&output_path);
+        fs::rename(&output_path, &backup).map_err(|e| Code2MdError::io(&output_path, e))?;
+        info!("Vorherige Ausgabe gesichert: {}", backup.display());
+    }
+    // Schätzungen können danebenliegen; beim Abbrechen gilt die tatsächliche Größe
+    let size_limit = cli.max_output_size.filter(|_| cli.on_oversize == OversizePolicy::Abort).unwrap_or(u64::MAX);
+    let write_output = || -> std::io::Result<()> {
+        let mut writer = SizeLimit::new(OutputWriter::create(&output_path, cli.compress)?, size_limit);
+        match &document {
+            Some(markdown) => {
+                debug!(output = %output_path.display(), bytes = markdown.len(), "Schreibe Ausgabe");
+                writer.write_all(markdown.as_bytes())?;
+            }
+            None => {
+                debug!(output = %output_path.display(), "Schreibe Ausgabe abschnittsweise");
+                write_markdown(&collected.files, &md_config, &mut writer)?;
+            }
+        }
+        writer.into_inner().finish()
+    };
+    let split = match oversize_split {
+        Some(split) => Some(split),
+        None => cli.split()?,
+    };
+    let written = match split {
+        _ if up_to_date => match cached_written {
+            Some(written) if !written.is_empty() => written,
+            _ => vec![output_path.clone()],
+        },
+        _ if cli.flavor == Flavor::Obsidian => {
+            debug!(output = %output_path.display(), "Schreibe Obsidian-Vault");
+            write_vault(&collected.files, &md_config, &output_path).map_err(|e| Code2MdError::io(&output_path, e))?
+        }
+        Some(split) => {
+            debug!(output = %output_path.display(), "Schreibe Ausgabe in Teilen");
+            // Fertige Teile festhalten, damit ein abgebrochener Lauf mit --resume fortgesetzt werden kann
+            let journal_path = PartJournal::path(&base_path, &output_path);
+            // Ein abgebrochener Lauf darf nicht als aktuell gelten
+            let _ = fs::remove_file(&run_cache_path);
+            let mut journal = (!cli.no_cache).then(|| {
+                if cli.resume {
+                    PartJournal::resume(&journal_path, &run_cache.options)
+                } else {
+                    PartJournal::new(&journal_path, &run_cache.options)
+                }
+            });
+            if let Some(journal) = journal.as_ref().filter(|journal| !journal.parts.is_empty()) {
+                info!("Setze fort: {} Teil(e) aus früherem Lauf werden geprüft", journal.parts.len());
+            }
+            write_chunked(&collected.files, &md_config, split, &output_path, journal.as_mut())
+                .map_err(|e| Code2MdError::io(&output_path, e))?
+        }
+        None => {
+            write_output().map_err(|e| {
+                // Keine halbe Ausgabe zurücklassen, die niemand öffnen kann
+                if e.kind() == std::io::ErrorKind::FileTooLarge {
+                    let _ = fs::remove_file(&output_path);
+                }
+                Code2MdError::io(&output_path, e)
+            })?;
+            vec![output_path.clone()]
+        }
+    };
+    if let Some(plan) = &update_plan {
+        plan.cache.save(&cache_path)?;
+    }
+    if let Some(summarizer) = &md_config.summarizer {
+        summarizer.cache().save(&summary_cache_path)?;
+    }
+
+    // Manifest schreiben
+    let manifest_file = if cli.manifest {
+        let mut manifest = build_manifest(&collected.files, &md_config, &output_path);
+        manifest.meta = cli.meta.iter().cloned().collect();
+        Some(write_manifest(&manifest, &output_path)?)
+    } else {
+        None
+    };
+
+    // Erzeugte Dateien bündeln
+    let bundle_file = match cli.bundle {
+        Some(bundle) => {
+            let archive_path = bundle_path(&output_path, bundle);
+            let mut generated = written.clone();
+            generated.extend(manifest_file.clone());
+            write_zip_bundle(&archive_path, &generated).map_err(|e| Code2MdError::io(&archive_path, e))?;
+            Some(archive_path)
+        }
+        None => None,
+    };
+
+    // Export veröffentlichen
+    let published = match (cli.publish, &document) {
+        (Some(PublishTarget::Confluence), Some(document)) => {
+            info!("Veröffentliche in Confluence...");
+            Some(publish(document, &project_name, &config.confluence)?)
+        }
+        (Some(PublishTarget::Gist), _) => {
+            info!("Lade Gist hoch...");
+            Some(upload_gist(&written, &format!("{} (code2md-Export)", project_name))?)
+        }
+        _ => None,
+    };
+    let uploaded = match &cli.upload {
+        Some(target) => {
+            info!("Lade Ausgabe hoch...");
+            let mut generated = written.clone();
+            generated.extend(manifest_file.clone());
+            generated.extend(bundle_file.clone());
+            upload(&generated, target)?
+        }
+        None => Vec::new(),
+    };
+    if use_run_cache {
+        run_cache.clone().with_output(&output_path).with_written(&written).save(&run_cache_path)?;
+    }
+    drop(write_span);
+
+    // Token-Budget prüfen
+    let token_estimate = match (cli.token_budget()?, split) {
+        (Some((budget, tokenizer)), None) => {
+            let tokens = match &document {
+                Some(document) => estimate_tokens(document, tokenizer),
+                None => count_file_tokens(&collected.files, &base_path, tokenizer)
+                    .iter()
+                    .map(|c| c.tokens)
+                    .sum(),
+            };
+            if tokens > budget {
+                md_config.events.emit(ExportEvent::Warning {
+                    path: None,
+                    message: format!(
+                        "Export umfasst ca. {} Tokens und überschreitet das Budget von {} Tokens",
+                        tokens, budget
+                    ),
+                });
+            }
+            Some((tokens, budget))
+        }
+        _ => None,
+    };
 
     // Statistik
-    let file_size = fs::metadata(&output_path)?.len();
+    let file_size = fs::metadata(&output_path)
+        .map_err(|e| Code2MdError::io(&output_path, e))?
+        .len();
     let size_str = format_size(file_size);
+    history::append(
+        &base_path,
+        &HistoryEntry {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            options: run_cache.options.clone(),
+            output: relative_path(&output_path, &base_path),
+            files: collected.files.len(),
+            output_size: file_size,
+            duration_ms: started.elapsed().as_millis() as u64,
+        },
+    )?;
 
     println!();
-    println!("{}", "✓ Export abgeschlossen!".green().bold());
+    if up_to_date {
+        println!("{}", "✓ Ausgabe ist aktuell".green().bold());
+    } else {
+        println!("{}", "✓ Export abgeschlossen!".green().bold());
+    }
     println!("  Datei:   {}", output_path.display());
     println!("  Größe:   {}", size_str);
     println!("  Dateien: {}", collected.files.len());
+    if cli.flavor == Flavor::Obsidian {
+        println!("  Vault:   {}", vault_dir(&output_path).display());
+    } else if written.len() > 1 {
+        println!("  Teile:   {}", written.len() - 1);
+    }
+    if let Some((tokens, budget)) = token_estimate {
+        println!("  Tokens:  ~{} von {}", tokens, budget);
+    }
+    if let Some(url) = published {
+        println!("  Online:  {}", url);
+    }
+    for url in uploaded {
+        println!("  Upload:  {}", url);
+    }
+    if let Some(path) = &manifest_file {
+        println!("  Manifest: {}", path.display());
+    }
+    if let Some(path) = &bundle_file {
+        println!("  Archiv:  {}", path.display());
+    }
+
+    // Warnungen zusammenfassen
+    let warnings = md_config.events.warnings();
+    if !warnings.is_empty() {
+        println!();
+        println!("{}", format!("⚠ {} Warnung(en):", warnings.len()).yellow().bold());
+        for warning in &warnings {
+            println!("  - {}", warning);
+        }
+    }
+
+    // Bericht für CI
+    if let Some(path) = &cli.report {
+        let status = match (up_to_date, warnings.is_empty()) {
+            (true, true) => RunStatus::UpToDate,
+            (_, true) => RunStatus::Success,
+            (_, false) => RunStatus::Warnings,
+        };
+        let mut report = RunReport::new(status, &project_name, &base_path, started_at, &skips, warnings.clone());
+        report.counts.exported = collected.files.len();
+        report.outputs = written
+            .iter()
+            .chain(&manifest_file)
+            .chain(&bundle_file)
+            .map(|path| path.display().to_string())
+            .collect();
+        report.output_size = Some(file_size);
+        report.tokens = token_estimate.map(|(tokens, _)| tokens);
+        report.write(path)?;
+    }
+
+    // Header-Verstöße melden
+    if let Some(ref checks) = md_config.header_checks {
+        let failed: Vec<_> = checks.iter().filter(|c| !c.passed).collect();
+        if !failed.is_empty() {
+            warn!(
+                "{} {} ohne gültigen Header",
+                failed.len(),
+                if failed.len() == 1 { "Datei" } else { "Dateien" }
+            );
+            for check in &failed {
+                match &annotator {
+                    Some(annotator) => println!("{}", annotator.warning(Some(&check.path), Some(1), "Header fehlt")),
+                    None => warn!(file = %check.path, "Header fehlt"),
+                }
+            }
+            if cli.require_headers {
+                return Err(Code2MdError::CheckFailed("Header-Prüfung fehlgeschlagen.".to_string()).into());
+            }
+        }
+    }
 
+    // Post-Export-Hooks
+    hook_env.file_count = Some(collected.files.len());
+    hook_env.output_size = Some(file_size);
+    run_hooks("post_export", &config.hooks.post_export, &base_path, &hook_env)?;
+
+    // Ergebnis öffnen
+    if let Some(ref open_with) = cli.open_with {
+        open_with_command(open_with.as_deref(), &output_path)?;
+    } else if cli.open {
+        open::that_detached(&output_path)?;
+    }
+
+    if !warnings.is_empty() {
+        if cli.strict {
+            return Err(Code2MdError::CheckFailed(format!(
+                "{} Warnung(en) bei --strict.",
+                warnings.len()
+            ))
+            .into());
+        }
+        return Ok(Exit::Warnings);
+    }
+    Ok(Exit::Success)
+}
+
+/// Richtet die Statusausgabe auf stderr ein; `RUST_LOG` hat Vorrang vor `-v`.
+fn init_logging(verbose: u8, format: LogFormat) {
+    let level = match verbose {
+        0 => "info",
+        1 => "debug",
+        _ => "trace",
+    };
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(format!("code2md={}", level)));
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr);
+
+    match format {
+        LogFormat::Text => subscriber.without_time().with_target(false).init(),
+        LogFormat::Json => subscriber.json().init(),
+    }
+}
+
+/// Öffnet die Ausgabedatei mit einem Programm (Standard: `$EDITOR`).
+fn open_with_command(command: Option<&str>, path: &Path) -> Result<()> {
+    let command = match command {
+        Some(command) => command.to_string(),
+        None => std::env::var("EDITOR")
+            .map_err(|_| anyhow::anyhow!("--open-with ohne Programm benötigt die Variable $EDITOR."))?,
+    };
+
+    let mut parts = command.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Leerer Befehl für --open-with."))?;
+
+    let status = std::process::Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()?;
+    if !status.success() {
+        anyhow::bail!("'{}' wurde mit {} beendet.", command, status);
+    }
     Ok(())
 }
 
+/// Ermittelt das absolute Verzeichnis der Ausgabedatei.
+fn output_dir(output_path: &Path, fallback: &Path) -> PathBuf {
+    let parent = output_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    paths::canonicalize(parent).unwrap_or_else(|_| fallback.to_path_buf())
+}
+
 /// Gibt alle verfügbaren Projekttypen aus.
 fn print_types() {
     println!();
@@ -167,3 +1280,22 @@ fn print_types() {
         println!();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_from_error() {
+        let error = anyhow::Error::from(Code2MdError::NoFiles);
+        assert_eq!(Exit::from_error(&error), Exit::NoFiles);
+
+        let error = anyhow::Error::from(Code2MdError::Config("ungültig".to_string()));
+        assert_eq!(Exit::from_error(&error), Exit::InvalidConfig);
+
+        let error = anyhow::Error::from(std::io::Error::other("voll"));
+        assert_eq!(Exit::from_error(&error), Exit::Io);
+
+        assert_eq!(Exit::from_error(&anyhow::anyhow!("sonstiges")), Exit::Failure);
+    }
+}
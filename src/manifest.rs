@@ -0,0 +1,149 @@
+//! Maschinenlesbares JSON-Manifest aller exportierten Dateien.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::collector::CollectedFile;
+use crate::error::{Code2MdError, Result};
+use crate::markdown::{base_display, file_language, generated_at, MarkdownConfig};
+use crate::processor::relative_path;
+
+/// Manifest eines Exports.
+#[derive(Debug, Serialize)]
+pub struct Manifest {
+    pub project: String,
+    /// Entfällt reproduzierbar ohne `SOURCE_DATE_EPOCH`, wie im Dokument
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generated_at: Option<String>,
+    /// Zusätzliche Angaben aus `--meta`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub meta: BTreeMap<String, String>,
+    pub base_path: String,
+    pub output: String,
+    pub files: Vec<ManifestEntry>,
+}
+
+/// Eintrag einer einzelnen Datei im Manifest.
+#[derive(Debug, Serialize)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub size: u64,
+    pub language: Option<String>,
+    pub sha256: Option<String>,
+    /// Änderungszeit; reproduzierbar nicht enthalten, da sie je Checkout abweicht
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified: Option<String>,
+}
+
+/// Gibt den Pfad der Manifest-Datei zur Ausgabedatei zurück.
+pub fn manifest_path(output_path: &Path) -> PathBuf {
+    let mut name = output_path.as_os_str().to_owned();
+    name.push(".manifest.json");
+    PathBuf::from(name)
+}
+
+/// Erstellt das Manifest für die gesammelten Dateien. Projektname,
+/// Basisverzeichnis und Zeitpunkt folgen dem Dokument (auch `--reproducible`).
+pub fn build_manifest(files: &[CollectedFile], config: &MarkdownConfig, output_path: &Path) -> Manifest {
+    let base_path = &config.base_path;
+    let entries = files
+        .iter()
+        .map(|file| {
+            let rel_str = relative_path(&file.path, base_path);
+            // Wie im Codeblock des Dokuments
+            let language = file_language(&file.path, &rel_str, config);
+
+            ManifestEntry {
+                language: (!language.is_empty()).then(|| language.to_string()),
+                path: rel_str,
+                size: file.size(),
+                sha256: file.sha256().ok(),
+                modified: file
+                    .metadata
+                    .modified()
+                    .ok()
+                    .filter(|_| !config.reproducible)
+                    .map(|t| DateTime::<Utc>::from(t).to_rfc3339()),
+            }
+        })
+        .collect();
+
+    Manifest {
+        project: config.project_name.clone(),
+        generated_at: generated_at(config).map(|now| now.to_rfc3339()),
+        meta: BTreeMap::new(),
+        base_path: base_display(config),
+        output: output_path.display().to_string(),
+        files: entries,
+    }
+}
+
+/// Schreibt das Manifest als JSON-Datei neben die Ausgabedatei.
+pub fn write_manifest(manifest: &Manifest, output_path: &Path) -> Result<PathBuf> {
+    let path = manifest_path(output_path);
+    let json = serde_json::to_string_pretty(manifest)
+        .map_err(|e| Code2MdError::Render(e.to_string()))?;
+    std::fs::write(&path, json).map_err(|e| Code2MdError::io(&path, e))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_path() {
+        assert_eq!(
+            manifest_path(Path::new("out/projekt_code.md")),
+            PathBuf::from("out/projekt_code.md.manifest.json")
+        );
+    }
+
+    #[test]
+    fn test_build_manifest() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path();
+        std::fs::create_dir_all(base.join("src"))?;
+        std::fs::write(base.join("src/main.py"), "abc")?;
+
+        let files = vec![CollectedFile::from_path(base.join("src/main.py"))?];
+        let mut config = MarkdownConfig {
+            base_path: base.to_path_buf(),
+            project_name: "demo".to_string(),
+            ..Default::default()
+        };
+        let manifest = build_manifest(&files, &config, Path::new("demo_code.md"));
+
+        assert_eq!(manifest.files.len(), 1);
+        assert!(manifest.generated_at.is_some());
+        let entry = &manifest.files[0];
+        assert_eq!(entry.path, "src/main.py");
+        assert_eq!(entry.size, 3);
+        assert_eq!(entry.language.as_deref(), Some("python"));
+        assert!(entry.sha256.is_some());
+        assert!(entry.modified.is_some());
+
+        // Reproduzierbar: gleiche Angaben wie im Dokument, keine Änderungszeiten
+        config.reproducible = true;
+        let manifest = build_manifest(&files, &config, Path::new("demo_code.md"));
+        assert_eq!(manifest.base_path, ".");
+        assert_eq!(manifest.generated_at.is_some(), std::env::var(crate::markdown::SOURCE_DATE_EPOCH).is_ok());
+        assert!(manifest.files[0].modified.is_none());
+
+        // Sprache wie im Codeblock: linguist-language und --fallback-syntax
+        std::fs::write(base.join("src/build.tpl"), "x")?;
+        let files = vec![
+            CollectedFile::from_path(base.join("src/build.tpl"))?,
+            CollectedFile::from_path(base.join("src/main.py"))?,
+        ];
+        config.languages.insert("src/main.py".to_string(), "starlark".to_string());
+        config.fallback_syntax = Some("text".to_string());
+        let manifest = build_manifest(&files, &config, Path::new("demo_code.md"));
+        assert_eq!(manifest.files[0].language.as_deref(), Some("text"));
+        assert_eq!(manifest.files[1].language.as_deref(), Some("starlark"));
+        Ok(())
+    }
+}
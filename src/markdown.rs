@@ -1,101 +1,1398 @@
 //! Markdown-Dokument-Generierung.
 
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 
-use chrono::Local;
+use chrono::{DateTime, FixedOffset, Local};
+use sha2::{Digest, Sha256};
 
-use crate::collector::read_file_content;
-use crate::tree::generate_tree;
-use crate::types::get_syntax_for_file;
+use crate::cache::PartJournal;
+use crate::collector::{CollectedFile, MMAP_THRESHOLD};
+use crate::config::FileDescriptions;
+use crate::events::{EventSink, ExportEvent};
+use crate::handlers::{is_plain_code, render_file, source_link, Block, HandlerConfig};
+use crate::headers::HeaderCheck;
+use crate::imports::ImportIndex;
+use crate::license::{detect_licenses, LicenseSource};
+use crate::merge::{closes_fence, fence_marker, section_start, SECTION_END};
+use crate::metadata::ProjectMetadata;
+use crate::metrics::{collect_metrics, compute_metrics, sort_metrics, MetricsSort};
+use crate::packages::{package_of, Package};
+use crate::paths;
+use crate::permalink::Permalinks;
+use crate::processor::{relative_path, Processors};
+use crate::stats::{compute_stats, ProjectStats};
+use crate::summary::Summarizer;
+use crate::testfiles::{is_test_file, split_test_modules};
+use crate::transform::{transform_code, LineEnding, TransformConfig};
+use crate::todos::collect_todos;
+use crate::tokens::{estimate_tokens, Tokenizer};
+use crate::tree::{generate_tree, TreeOptions};
+use crate::types::syntax_for_path;
 
 /// Konfiguration für die Markdown-Generierung.
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct MarkdownConfig {
     pub project_name: String,
     pub base_path: PathBuf,
     pub include_tree: bool,
+    /// Darstellung der Ordnerstruktur
+    pub tree: TreeOptions,
+    pub transform: TransformConfig,
+    pub handlers: HandlerConfig,
+    pub include_hashes: bool,
+    pub include_licenses: bool,
+    pub header_checks: Option<Vec<HeaderCheck>>,
+    /// Zusätzliche Verarbeitungsschritte (eingebaut, Plugins, Bibliotheksnutzer)
+    pub processors: Processors,
+    /// Empfänger für Fortschritts- und Warnungsereignisse
+    pub events: EventSink,
+    /// Anhang mit allen Warnungen des Exports
+    pub include_warnings: bool,
+    /// Externe Zusammenfassung je Datei (`--summarize-cmd`)
+    pub summarizer: Option<Summarizer>,
+    /// Beschreibungen aus der `[annotations]`-Tabelle der Konfiguration
+    pub descriptions: FileDescriptions,
+    /// Testdateien am Ende und Inline-Testmodule in einem eigenen Abschnitt „Tests“
+    pub separate_tests: bool,
+    /// Ausgewählte Pakete; Baum und Überschriften zeigen die Paketgrenzen
+    pub packages: Vec<Package>,
+    /// Übersprungene verschachtelte Repositories, im Baum vermerkt
+    pub nested_repos: Vec<String>,
+    /// Ausgeschlossene Verzeichnisse, im Baum vermerkt (`--tree-show-excluded`)
+    pub excluded_dirs: Vec<String>,
+    /// Angaben aus dem Paketmanifest für den Kopfbereich
+    pub metadata: Option<ProjectMetadata>,
+    /// Badge-Zeilen aus der Konfiguration, die unter dem Titel erscheinen
+    pub badges: Vec<String>,
+    /// Statistik-Abschnitt (Zeilen, Sprachen, Verzeichnisse, größte Dateien) ausgeben
+    pub include_stats: bool,
+    /// Metriken je Datei und Metrik-Tabelle mit dieser Sortierung ausgeben
+    pub metrics: Option<MetricsSort>,
+    /// Abschnitt mit TODO/FIXME/HACK-Kommentaren anhängen
+    pub include_todos: bool,
+    /// Projektinterne Imports je Datei als Links ausgeben
+    pub imports: Option<ImportIndex>,
+    /// Links auf die Dateien im Repository
+    pub permalinks: Option<Permalinks>,
+    /// Dateiüberschriften als relative Links auf die Quelldateien
+    pub link_sources: bool,
+    /// YAML-Front-Matter mit diesen Tags vor dem Titel ausgeben
+    pub front_matter: Option<Vec<String>>,
+    /// Zusätzliche Angaben aus `--meta` für Kopfbereich und Front Matter
+    pub meta: Vec<(String, String)>,
+    /// Sprache für Codeblöcke von Dateien mit unbekannter Endung (`--fallback-syntax`)
+    pub fallback_syntax: Option<String>,
+    /// Abweichende Sprachen je relativem Pfad (`linguist-language` aus `.gitattributes`)
+    pub languages: BTreeMap<String, String>,
+    /// Weitere Pfade derselben Datei (Symlinks, Hardlinks) je relativem Pfad
+    pub aliases: BTreeMap<String, Vec<String>>,
+    /// Hinweis im Kopfbereich, dass Dateien weggelassen wurden (`--on-oversize truncate`)
+    pub truncation_notice: Option<String>,
+    /// Zeitstempel nur aus `SOURCE_DATE_EPOCH` und relatives Basisverzeichnis (`--reproducible`)
+    pub reproducible: bool,
+}
+
+/// Umgebungsvariable mit dem Zeitstempel für `--reproducible` (Sekunden seit 1970).
+pub const SOURCE_DATE_EPOCH: &str = "SOURCE_DATE_EPOCH";
+
+/// Zeitpunkt für „Generiert am“. Reproduzierbar gilt `SOURCE_DATE_EPOCH`;
+/// ohne die Variable entfällt der Zeitpunkt, statt ein erfundenes Datum
+/// auszugeben.
+pub fn generated_at(config: &MarkdownConfig) -> Option<DateTime<FixedOffset>> {
+    if !config.reproducible {
+        return Some(Local::now().fixed_offset());
+    }
+    source_date(std::env::var(SOURCE_DATE_EPOCH).ok().as_deref())
+}
+
+/// Wertet `SOURCE_DATE_EPOCH` aus, in UTC, damit die Zeitzone keine Rolle spielt.
+fn source_date(value: Option<&str>) -> Option<DateTime<FixedOffset>> {
+    let seconds = value?.trim().parse::<i64>().ok()?;
+    DateTime::from_timestamp(seconds, 0).map(|date| date.fixed_offset())
+}
+
+/// Basisverzeichnis für den Kopfbereich; reproduzierbar nur `.`, da der
+/// absolute Pfad je Rechner verschieden ist.
+pub fn base_display(config: &MarkdownConfig) -> String {
+    if config.reproducible {
+        ".".to_string()
+    } else {
+        paths::display(&config.base_path)
+    }
 }
 
 /// Generiert das vollständige Markdown-Dokument.
-pub fn generate_markdown(files: &[PathBuf], config: &MarkdownConfig) -> String {
-    let mut lines: Vec<String> = Vec::new();
-    let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+pub fn generate_markdown(files: &[CollectedFile], config: &MarkdownConfig) -> String {
+    let mut buffer = Vec::new();
+    write_markdown(files, config, &mut buffer).expect("Schreiben in einen Puffer schlägt nicht fehl");
+    String::from_utf8(buffer).expect("Dokument besteht nur aus UTF-8-Text")
+}
+
+/// Schreibt das Markdown-Dokument abschnittsweise in `out`.
+///
+/// Es wird immer nur ein Dateiabschnitt im Speicher gehalten; große Dateien
+/// ohne Transformationen werden direkt aus einer Speicherabbildung kopiert.
+pub fn write_markdown(files: &[CollectedFile], config: &MarkdownConfig, out: impl Write) -> io::Result<()> {
+    let mut doc = DocWriter::new(out, config.transform.line_ending);
+    let now = generated_at(config);
 
     // Header
-    lines.push(format!("# {}", config.project_name));
-    lines.push(String::new());
-    lines.push(format!("> Generiert am {}", timestamp));
-    lines.push(format!("> Basisverzeichnis: `{}`", config.base_path.display()));
-    lines.push(format!("> Anzahl Dateien: {}", files.len()));
-    lines.push(String::new());
+    write_front_matter(&mut doc, config, files.len(), now)?;
+    doc.line(&format!("# {}", config.project_name))?;
+    doc.line("")?;
+    write_badges(&mut doc, config)?;
+    write_generated(&mut doc, now)?;
+    doc.line(&format!("> Basisverzeichnis: `{}`", base_display(config)))?;
+    doc.line(&format!("> Anzahl Dateien: {}", files.len()))?;
+    write_metadata(&mut doc, config)?;
+    if let Some(notice) = &config.truncation_notice {
+        doc.line(&format!("> **Gekürzt:** {}", notice))?;
+    }
+    doc.line("")?;
 
     // Inhaltsverzeichnis
-    lines.push("## Inhaltsverzeichnis".to_string());
-    lines.push(String::new());
-    
+    doc.line("## Inhaltsverzeichnis")?;
+    doc.line("")?;
+
+    let mut section = 1;
     if config.include_tree {
-        lines.push("1. [Ordnerstruktur](#ordnerstruktur)".to_string());
-        lines.push("2. [Dateien](#dateien)".to_string());
-    } else {
-        lines.push("1. [Dateien](#dateien)".to_string());
+        doc.line(&format!("{}. [Ordnerstruktur](#ordnerstruktur)", section))?;
+        section += 1;
+    }
+    if config.include_stats {
+        doc.line(&format!("{}. [Statistik](#statistik)", section))?;
+        section += 1;
     }
+    if config.metrics.is_some() {
+        doc.line(&format!("{}. [Metriken](#metriken)", section))?;
+        section += 1;
+    }
+    doc.line(&format!("{}. [Dateien](#dateien)", section))?;
+    section += 1;
 
-    for file in files {
-        if let Ok(rel_path) = file.strip_prefix(&config.base_path) {
-            let rel_str = rel_path.to_string_lossy();
-            let anchor = generate_anchor(&rel_str);
-            lines.push(format!("   - [`{}`](#{})", rel_str, anchor));
+    let tests = test_split(files, config);
+    for file in &files[..tests.start] {
+        if let Some(rel_str) = paths::relative_to(&file.path, &config.base_path) {
+            doc.line(&toc_entry(&rel_str))?;
+        }
+    }
+    if !tests.is_empty(files) {
+        doc.line(&format!("{}. [Tests](#tests)", section))?;
+        section += 1;
+        for file in &files[tests.start..] {
+            doc.line(&toc_entry(&relative_path(&file.path, &config.base_path)))?;
+        }
+        for (rel_str, _) in &tests.modules {
+            doc.line(&format!("   - [`{}` (Tests)](#{})", rel_str, generate_anchor(&format!("{} (Tests)", rel_str))))?;
         }
     }
-    lines.push(String::new());
+    if config.include_licenses {
+        doc.line(&format!("{}. [Lizenzen](#lizenzen)", section))?;
+        section += 1;
+    }
+    if config.header_checks.is_some() {
+        doc.line(&format!("{}. [Header-Prüfung](#header-prüfung)", section))?;
+        section += 1;
+    }
+    if config.include_todos {
+        doc.line(&format!("{}. [Offene Punkte](#offene-punkte)", section))?;
+        section += 1;
+    }
+    if config.include_hashes {
+        doc.line(&format!("{}. [Prüfsummen](#prüfsummen)", section))?;
+        section += 1;
+    }
+    if config.include_warnings {
+        doc.line(&format!("{}. [Warnungen](#warnungen)", section))?;
+    }
+    doc.line("")?;
 
     // Ordnerstruktur
     if config.include_tree {
-        lines.push("---".to_string());
+        write_tree(&mut doc, files, config)?;
+    }
+
+    if config.include_stats {
+        let stats = compute_stats(files, &config.base_path, &config.languages);
+        write_statistics(&mut doc, &stats, |rel_str| format!("#{}", generate_anchor(rel_str)))?;
+    }
+    if let Some(sort) = config.metrics {
+        write_metrics(&mut doc, files, sort, config, |rel_str| format!("#{}", generate_anchor(rel_str)))?;
+    }
+
+    // Dateien
+    doc.line("---")?;
+    doc.line("")?;
+    doc.line("## Dateien")?;
+    doc.line("")?;
+
+    let mut hashes = write_files(&mut doc, files, 0..tests.start, config)?;
+
+    // Tests
+    if !tests.is_empty(files) {
+        doc.line("---")?;
+        doc.line("")?;
+        doc.line("## Tests")?;
+        doc.line("")?;
+        hashes.extend(write_files(&mut doc, files, tests.start..files.len(), config)?);
+        for (rel_str, modules) in &tests.modules {
+            let content = modules.join("\n\n");
+            let fence = code_fence(&content);
+            doc.line(&format!("### `{}` (Tests)", rel_str))?;
+            doc.line("")?;
+            doc.line(&format!("{}rust", fence))?;
+            doc.line(&content)?;
+            doc.line(&fence)?;
+            doc.line("")?;
+        }
+    }
+
+    write_appendices(&mut doc, files, &hashes, config, |rel_str| format!("#{}", generate_anchor(rel_str)))?;
+    doc.flush()
+}
+
+/// Aufteilung in Code und Tests für `--separate-tests`.
+struct TestSplit {
+    /// Index der ersten Testdatei; Testdateien stehen am Ende der Liste
+    start: usize,
+    /// Inline-Testmodule der übrigen Rust-Dateien
+    modules: Vec<(String, Vec<String>)>,
+}
+
+impl TestSplit {
+    fn is_empty(&self, files: &[CollectedFile]) -> bool {
+        self.start == files.len() && self.modules.is_empty()
+    }
+}
+
+/// Ermittelt die Testdateien am Ende der Liste und die Inline-Testmodule.
+fn test_split(files: &[CollectedFile], config: &MarkdownConfig) -> TestSplit {
+    if !config.separate_tests {
+        return TestSplit {
+            start: files.len(),
+            modules: Vec::new(),
+        };
+    }
+    let trailing = files
+        .iter()
+        .rev()
+        .take_while(|file| is_test_file(&relative_path(&file.path, &config.base_path)))
+        .count();
+    let start = files.len() - trailing;
+    let modules = files[..start]
+        .iter()
+        .filter(|file| file.path.extension().is_some_and(|ext| ext == "rs"))
+        .filter_map(|file| {
+            let (_, modules) = split_test_modules(&file.text().ok()?);
+            (!modules.is_empty()).then(|| (relative_path(&file.path, &config.base_path), modules))
+        })
+        .collect();
+    TestSplit { start, modules }
+}
+
+/// Aufteilung der Ausgabe in mehrere Teile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Split {
+    /// Höchstens so viele geschätzte Tokens je Teil
+    Tokens { budget: usize, tokenizer: Tokenizer },
+    /// Höchstens so viele Bytes Dateiinhalt je Teil
+    Size(u64),
+    /// Ein Teil je Verzeichnis der obersten Ebene
+    Dir,
+}
+
+/// Geschätzte Tokens für Überschrift, Markierungen und Fences eines Abschnitts.
+const SECTION_OVERHEAD_TOKENS: usize = 30;
+
+/// Geschätzte Bytes für Überschrift, Metadaten und Fences eines Abschnitts.
+const SECTION_OVERHEAD_BYTES: u64 = 120;
+
+/// Geschätzte Bytes für Kopfbereich und Rahmen des Dokuments.
+const DOCUMENT_OVERHEAD_BYTES: u64 = 1024;
+
+/// Schätzt die Größe eines Abschnitts: Inhalt plus Überschrift und die
+/// Einträge in Inhaltsverzeichnis und Baum, die den Pfad wiederholen.
+fn estimate_section_size(file: &CollectedFile, base_path: &Path) -> u64 {
+    file.size() + SECTION_OVERHEAD_BYTES + 4 * relative_path(&file.path, base_path).len() as u64
+}
+
+/// Schätzt die Größe des Markdown-Dokuments, ohne es zu erzeugen.
+pub fn estimate_size(files: &[CollectedFile], base_path: &Path) -> u64 {
+    DOCUMENT_OVERHEAD_BYTES + files.iter().map(|file| estimate_section_size(file, base_path)).sum::<u64>()
+}
+
+/// Anzahl der Dateien vom Anfang, deren Abschnitte nach Schätzung zusammen
+/// in `limit` Bytes passen.
+pub fn files_within(files: &[CollectedFile], base_path: &Path, limit: u64) -> usize {
+    let mut used = DOCUMENT_OVERHEAD_BYTES;
+    files
+        .iter()
+        .take_while(|file| {
+            used += estimate_section_size(file, base_path);
+            used <= limit
+        })
+        .count()
+}
+
+/// Teilt die Dateien in zusammenhängende Teile auf; die Reihenfolge bleibt
+/// erhalten. Eine Datei, die allein das Limit überschreitet, bildet einen
+/// eigenen Teil.
+pub fn plan_chunks(files: &[CollectedFile], base_path: &Path, split: Split) -> Vec<Range<usize>> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut used: u64 = 0;
+
+    for (index, file) in files.iter().enumerate() {
+        let starts_new = match split {
+            Split::Tokens { budget, tokenizer } => {
+                let cost = (estimate_tokens(&file.text().unwrap_or_default(), tokenizer)
+                    + SECTION_OVERHEAD_TOKENS) as u64;
+                let full = index > start && used + cost > budget as u64;
+                used = if full { cost } else { used + cost };
+                full
+            }
+            Split::Size(limit) => {
+                let cost = file.size();
+                let full = index > start && used + cost > limit;
+                used = if full { cost } else { used + cost };
+                full
+            }
+            Split::Dir => index > start && top_dir(&files[index - 1], base_path) != top_dir(file, base_path),
+        };
+        if starts_new {
+            chunks.push(start..index);
+            start = index;
+        }
+    }
+    if start < files.len() {
+        chunks.push(start..files.len());
+    }
+    chunks
+}
+
+/// Verzeichnis der obersten Ebene einer Datei (leer für Dateien im Basisverzeichnis).
+fn top_dir(file: &CollectedFile, base_path: &Path) -> String {
+    let rel_str = relative_path(&file.path, base_path);
+    match rel_str.split_once('/') {
+        Some((first, _)) => first.to_string(),
+        None => String::new(),
+    }
+}
+
+/// Sucht die `README.md` direkt im Verzeichnis eines Teils.
+fn directory_readme(files: &[CollectedFile], range: Range<usize>, base_path: &Path) -> Option<usize> {
+    let dir = top_dir(&files[range.start], base_path);
+    range.into_iter().find(|&index| {
+        let rel_str = relative_path(&files[index].path, base_path);
+        let (parent, name) = rel_str.rsplit_once('/').unwrap_or(("", &rel_str));
+        parent == dir && name.eq_ignore_ascii_case("README.md")
+    })
+}
+
+/// Stuft Markdown-Überschriften außerhalb von Codeblöcken um eine Ebene herab,
+/// damit sie unter der Überschrift des Teils stehen.
+fn demote_headings(content: &str) -> Vec<String> {
+    let mut fence = None;
+    content
+        .trim()
+        .lines()
+        .map(|line| {
+            match fence {
+                Some(open) => {
+                    if closes_fence(line, open) {
+                        fence = None;
+                    }
+                }
+                None => {
+                    if let Some((c, len, _)) = fence_marker(line) {
+                        fence = Some((c, len));
+                    } else {
+                        let level = line.chars().take_while(|c| *c == '#').count();
+                        let rest = &line[level..];
+                        if (1..6).contains(&level) && (rest.is_empty() || rest.starts_with(' ')) {
+                            return format!("#{}", line);
+                        }
+                    }
+                }
+            }
+            line.to_string()
+        })
+        .collect()
+}
+
+/// Gibt den Pfad des Teils `number` (ab 1) zur Ausgabedatei zurück.
+pub fn part_path(output_path: &Path, number: usize) -> PathBuf {
+    let stem = output_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = output_path
+        .extension()
+        .map(|e| e.to_string_lossy().to_string())
+        .unwrap_or_else(|| "md".to_string());
+    output_path.with_file_name(format!("{}.part{}.{}", stem, number, ext))
+}
+
+/// Schreibt die Ausgabe aufgeteilt in mehrere Teile.
+///
+/// `output_path` wird zur Übersicht mit Liste der Teile, Ordnerstruktur,
+/// Dateiindex und Anhängen; jeder Teil beginnt mit „Teil N von M“, Links zum
+/// vorherigen und nächsten Teil sowie zur Übersicht und einem lokalen
+/// Inhaltsverzeichnis seiner Dateien. Gibt alle
+/// geschriebenen Dateien zurück, die Übersicht zuerst.
+///
+/// Mit `journal` wird jeder fertige Teil festgehalten; Teile, die dort mit
+/// gleichem Eingabestand und unveränderter Datei verzeichnet sind, werden
+/// nicht neu geschrieben (`--resume`).
+pub fn write_chunked(
+    files: &[CollectedFile],
+    config: &MarkdownConfig,
+    split: Split,
+    output_path: &Path,
+    mut journal: Option<&mut PartJournal>,
+) -> io::Result<Vec<PathBuf>> {
+    let chunks = plan_chunks(files, &config.base_path, split);
+    let line_ending = config.transform.line_ending;
+    let link = |path: &Path| {
+        path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+    let index_link = link(output_path);
+    let part_links: Vec<String> = (1..=chunks.len())
+        .map(|n| link(&part_path(output_path, n)))
+        .collect();
+
+    let intros: Vec<Option<usize>> = chunks
+        .iter()
+        .map(|range| match split {
+            Split::Dir => directory_readme(files, range.clone(), &config.base_path),
+            _ => None,
+        })
+        .collect();
+
+    // Verweise auf die Abschnitte in den Teilen
+    let mut targets = BTreeMap::new();
+    for (number, range) in chunks.iter().enumerate() {
+        for (index, file) in files.iter().enumerate().take(range.end).skip(range.start) {
+            let rel_str = relative_path(&file.path, &config.base_path);
+            // Die Einleitung steht am Anfang des Teils und hat keinen eigenen Anker
+            let target = if intros[number] == Some(index) {
+                part_links[number].clone()
+            } else {
+                format!("{}#{}", part_links[number], generate_anchor(&rel_str))
+            };
+            targets.insert(rel_str, (number + 1, target));
+        }
+    }
+    if let Some(imports) = &config.imports {
+        imports.set_targets(targets.iter().map(|(rel_str, (_, target))| (rel_str.clone(), target.clone())).collect());
+    }
+
+    let mut written = vec![output_path.to_path_buf()];
+    let mut hashes = Vec::new();
+    for (number, range) in chunks.iter().enumerate() {
+        let path = part_path(output_path, number + 1);
+        let input = journal
+            .as_deref()
+            .map(|journal| journal.input(number + 1, chunks.len(), &files[range.clone()], &config.base_path));
+        if let (Some(journal), Some(input)) = (journal.as_deref(), &input) {
+            if journal.is_complete(number + 1, input, &path) {
+                if config.include_hashes {
+                    hashes.extend(files[range.clone()].iter().filter_map(|file| {
+                        file.sha256().ok().map(|hash| (relative_path(&file.path, &config.base_path), hash))
+                    }));
+                }
+                written.push(path);
+                continue;
+            }
+        }
+        let mut doc = DocWriter::new(std::fs::File::create(&path)?, line_ending);
+
+        // Navigation
+        let mut nav = Vec::new();
+        if number > 0 {
+            nav.push(format!("[← Teil {}]({})", number, part_links[number - 1]));
+        }
+        nav.push(format!("[Übersicht]({})", index_link));
+        if number + 1 < chunks.len() {
+            nav.push(format!("[Teil {} →]({})", number + 2, part_links[number + 1]));
+        }
+        let nav = nav.join(" · ");
+
+        doc.line(&format!("# {} – Teil {} von {}", config.project_name, number + 1, chunks.len()))?;
+        doc.line("")?;
+        doc.line(&nav)?;
+        doc.line("")?;
+
+        // README des Verzeichnisses als Einleitung statt als Codeblock
+        let intro = intros[number];
+        if let Some(index) = intro {
+            let file = &files[index];
+            let rel_str = relative_path(&file.path, &config.base_path);
+            match file.text() {
+                Ok(content) => {
+                    for line in demote_headings(&content) {
+                        doc.line(&line)?;
+                    }
+                    doc.line("")?;
+                }
+                Err(e) => config
+                    .events
+                    .warn(&rel_str, format!("Datei konnte nicht gelesen werden: {}", e)),
+            }
+            if config.include_hashes {
+                if let Ok(hash) = file.sha256() {
+                    hashes.push((rel_str.clone(), hash));
+                }
+            }
+            config.events.emit(ExportEvent::SectionWritten {
+                path: rel_str,
+                index: index + 1,
+                total: files.len(),
+            });
+        }
+
+        // Lokales Inhaltsverzeichnis
+        let count = range.len() - intro.map_or(0, |_| 1);
+        doc.line(&format!("## Inhalt ({} {})", count, if count == 1 { "Datei" } else { "Dateien" }))?;
+        doc.line("")?;
+        for (index, file) in files.iter().enumerate().take(range.end).skip(range.start) {
+            if Some(index) == intro {
+                continue;
+            }
+            let rel_str = relative_path(&file.path, &config.base_path);
+            doc.line(&format!("- [`{}`](#{})", rel_str, generate_anchor(&rel_str)))?;
+        }
+        doc.line("")?;
+        doc.line("---")?;
+        doc.line("")?;
+
+        match intro {
+            Some(index) => {
+                hashes.extend(write_files(&mut doc, files, range.start..index, config)?);
+                hashes.extend(write_files(&mut doc, files, index + 1..range.end, config)?);
+            }
+            None => hashes.extend(write_files(&mut doc, files, range.clone(), config)?),
+        }
+
+        doc.line("---")?;
+        doc.line("")?;
+        doc.line(&nav)?;
+        doc.line("")?;
+        doc.flush()?;
+        drop(doc);
+        if let (Some(journal), Some(input)) = (journal.as_deref_mut(), input) {
+            journal.complete(number + 1, input, &path)?;
+        }
+        written.push(path);
+    }
+
+    // Übersicht zuletzt, damit sie alle Warnungen der Teile enthält
+    let mut doc = DocWriter::new(std::fs::File::create(output_path)?, line_ending);
+    let now = generated_at(config);
+    write_front_matter(&mut doc, config, files.len(), now)?;
+    doc.line(&format!("# {}", config.project_name))?;
+    doc.line("")?;
+    write_badges(&mut doc, config)?;
+    write_generated(&mut doc, now)?;
+    doc.line(&format!("> Basisverzeichnis: `{}`", base_display(config)))?;
+    doc.line(&format!("> Anzahl Dateien: {}", files.len()))?;
+    doc.line(&format!("> Anzahl Teile: {}", chunks.len()))?;
+    write_metadata(&mut doc, config)?;
+    doc.line("")?;
+    doc.line("## Teile")?;
+    doc.line("")?;
+    for (number, range) in chunks.iter().enumerate() {
+        let first = relative_path(&files[range.start].path, &config.base_path);
+        let last = relative_path(&files[range.end - 1].path, &config.base_path);
+        let span = if range.len() == 1 {
+            format!("`{}`", first)
+        } else {
+            format!("`{}` … `{}`", first, last)
+        };
+        doc.line(&format!(
+            "{}. [Teil {}]({}) – {} {} ({})",
+            number + 1,
+            number + 1,
+            part_links[number],
+            range.len(),
+            if range.len() == 1 { "Datei" } else { "Dateien" },
+            span
+        ))?;
+    }
+    doc.line("")?;
+    if config.include_tree {
+        write_tree(&mut doc, files, config)?;
+    }
+
+    if config.include_stats {
+        let stats = compute_stats(files, &config.base_path, &config.languages);
+        write_statistics(&mut doc, &stats, |rel_str| {
+            targets.get(rel_str).map(|(_, target)| target.clone()).unwrap_or_default()
+        })?;
+    }
+    if let Some(sort) = config.metrics {
+        write_metrics(&mut doc, files, sort, config, |rel_str| {
+            targets.get(rel_str).map(|(_, target)| target.clone()).unwrap_or_default()
+        })?;
+    }
+
+    // Dateiindex: jede Datei mit Link auf ihren Abschnitt im jeweiligen Teil
+    doc.line("---")?;
+    doc.line("")?;
+    doc.line("## Dateiindex")?;
+    doc.line("")?;
+    doc.line("| Datei | Teil |")?;
+    doc.line("|---|---|")?;
+    for file in files {
+        let rel_str = relative_path(&file.path, &config.base_path);
+        if let Some((number, target)) = targets.get(&rel_str) {
+            doc.line(&format!("| [`{}`]({}) | {} |", rel_str, target, number))?;
+        }
+    }
+    doc.line("")?;
+    write_appendices(&mut doc, files, &hashes, config, |rel_str| {
+        targets.get(rel_str).map(|(_, target)| target.clone()).unwrap_or_default()
+    })?;
+    doc.flush()?;
+
+    Ok(written)
+}
+
+/// Schreibt die Dateiabschnitte `range` und gibt die berechneten Prüfsummen zurück.
+fn write_files<W: Write>(
+    doc: &mut DocWriter<W>,
+    files: &[CollectedFile],
+    range: Range<usize>,
+    config: &MarkdownConfig,
+) -> io::Result<Vec<(String, String)>> {
+    let mut hashes: Vec<(String, String)> = Vec::new();
+    let mut current_package = None;
+
+    // Abschnitte stapelweise parallel rendern und in Reihenfolge schreiben
+    let indices: Vec<usize> = range.collect();
+    for batch in indices.chunks(RENDER_BATCH) {
+        let rendered = parallel_map(batch, |&index| render_prepared(&files[index], config));
+        for (&index, prepared) in batch.iter().zip(rendered) {
+            let Some(prepared) = prepared else {
+                continue;
+            };
+            let rel_str = prepared.rel_str;
+
+            // Überschrift beim Wechsel in ein anderes Paket
+            let package = package_of(&config.packages, &rel_str);
+            if let Some(package) = package.filter(|p| current_package != Some(*p)) {
+                doc.line(&format!("## Paket `{}`", package.name))?;
+                doc.line("")?;
+                doc.line(&format!("> Verzeichnis: `{}`", package.path))?;
+                doc.line("")?;
+            }
+            current_package = package;
+
+            doc.line(&section_start(&rel_str))?;
+            match prepared.lines {
+                Some(lines) => {
+                    for line in lines {
+                        doc.line(&line)?;
+                    }
+                }
+                None => write_section(doc, &files[index], &rel_str, &prepared.meta, config)?,
+            }
+            doc.line(SECTION_END)?;
+            doc.line("")?;
+            if let Some(hash) = prepared.hash {
+                hashes.push((rel_str.clone(), hash));
+            }
+            config.events.emit(ExportEvent::SectionWritten {
+                path: rel_str,
+                index: index + 1,
+                total: files.len(),
+            });
+        }
+    }
+
+    Ok(hashes)
+}
+
+/// Anzahl Dateien, deren Abschnitte gemeinsam parallel gerendert werden;
+/// begrenzt den Speicherbedarf der gepufferten Abschnitte.
+const RENDER_BATCH: usize = 64;
+
+/// Vorbereiteter Abschnitt einer Datei.
+struct PreparedSection {
+    rel_str: String,
+    hash: Option<String>,
+    meta: Vec<String>,
+    /// Gerenderte Zeilen; `None` für große Dateien, die beim Schreiben direkt
+    /// aus der Speicherabbildung kommen
+    lines: Option<Vec<String>>,
+}
+
+/// Berechnet Prüfsumme, Metadaten und Inhalt eines Abschnitts.
+fn render_prepared(file: &CollectedFile, config: &MarkdownConfig) -> Option<PreparedSection> {
+    let rel_str = paths::relative_to(&file.path, &config.base_path)?;
+    let hash = if config.include_hashes { file.sha256().ok() } else { None };
+    let meta = section_meta(file, &rel_str, hash.as_deref(), config);
+    let streamed = file.size() >= MMAP_THRESHOLD
+        && config.processors.is_empty()
+        && is_plain_code(&file.path, &config.handlers);
+    let lines = (!streamed).then(|| render_section(file, &rel_str, &meta, config));
+    Some(PreparedSection { rel_str, hash, meta, lines })
+}
+
+/// Wendet `f` auf alle Elemente an, verteilt auf die verfügbaren Kerne; die
+/// Ergebnisse behalten die Reihenfolge der Eingabe.
+fn parallel_map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get()).min(items.len());
+    if threads <= 1 {
+        return items.iter().map(f).collect();
+    }
+    let chunk_size = items.len().div_ceil(threads);
+    let f = &f;
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(f).collect::<Vec<R>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+            .collect()
+    })
+}
+
+/// Metadaten-Zeile eines Dateiabschnitts (Prüfsumme, Link, Aliase, Metriken).
+fn section_meta(file: &CollectedFile, rel_str: &str, hash: Option<&str>, config: &MarkdownConfig) -> Vec<String> {
+    let mut meta: Vec<String> = Vec::new();
+    if let Some(hash) = hash {
+        meta.push(format!("SHA-256: `{}`", hash));
+    }
+    if let Some(links) = &config.permalinks {
+        meta.push(format!("[{}]({})", links.label, links.url(rel_str)));
+    }
+    if let Some(aliases) = config.aliases.get(rel_str) {
+        let list: Vec<String> = aliases.iter().map(|alias| format!("`{}`", alias)).collect();
+        meta.push(format!("Auch unter: {}", list.join(", ")));
+    }
+    if config.metrics.is_some() {
+        if let Some(metrics) = file.text().ok().and_then(|content| compute_metrics(rel_str, &content)) {
+            meta.push(metrics.summary());
+        }
+    }
+    meta
+}
+
+/// Abschnitt einer Datei, wie er im Export erscheint (für Vorschauen).
+pub fn preview_section(file: &CollectedFile, config: &MarkdownConfig) -> Vec<String> {
+    let rel_str = relative_path(&file.path, &config.base_path);
+    let hash = if config.include_hashes { file.sha256().ok() } else { None };
+    let meta = section_meta(file, &rel_str, hash.as_deref(), config);
+    render_section(file, &rel_str, &meta, config)
+}
+
+/// Schreibt die Badge-Zeilen unter den Titel. Zeilen mit Platzhaltern, für
+/// die das Manifest keinen Wert liefert, entfallen.
+fn write_badges<W: Write>(doc: &mut DocWriter<W>, config: &MarkdownConfig) -> io::Result<()> {
+    let metadata = config.metadata.clone().unwrap_or_default();
+    let badges: Vec<String> = config.badges.iter().filter_map(|badge| metadata.fill(badge)).collect();
+    if badges.is_empty() {
+        return Ok(());
+    }
+    for badge in badges {
+        doc.line(&badge)?;
+    }
+    doc.line("")
+}
+
+/// Zeile „Generiert am“, sofern ein Zeitpunkt feststeht.
+fn write_generated<W: Write>(doc: &mut DocWriter<W>, now: Option<DateTime<FixedOffset>>) -> io::Result<()> {
+    match now {
+        Some(now) => doc.line(&format!("> Generiert am {}", now.format("%Y-%m-%d %H:%M:%S"))),
+        None => Ok(()),
+    }
+}
+
+/// Schlüssel, die der Front Matter selbst belegt.
+const FRONT_MATTER_KEYS: &[&str] = &["title", "date", "project", "files", "tags"];
+
+/// Schreibt den YAML-Front-Matter für Static-Site-Generatoren (Hugo, Jekyll).
+fn write_front_matter<W: Write>(
+    doc: &mut DocWriter<W>,
+    config: &MarkdownConfig,
+    file_count: usize,
+    now: Option<DateTime<FixedOffset>>,
+) -> io::Result<()> {
+    let Some(tags) = &config.front_matter else {
+        return Ok(());
+    };
+    doc.line("---")?;
+    doc.line(&format!("title: {}", yaml_string(&config.project_name)))?;
+    if let Some(now) = now {
+        doc.line(&format!("date: {}", now.format("%Y-%m-%dT%H:%M:%S%:z")))?;
+    }
+    doc.line(&format!("project: {}", yaml_string(&config.project_name)))?;
+    doc.line(&format!("files: {}", file_count))?;
+    for (key, value) in &config.meta {
+        if !FRONT_MATTER_KEYS.contains(&key.as_str()) {
+            doc.line(&format!("{}: {}", key, yaml_string(value)))?;
+        }
+    }
+    if tags.is_empty() {
+        doc.line("tags: []")?;
+    } else {
+        doc.line("tags:")?;
+        for tag in tags {
+            doc.line(&format!("  - {}", yaml_string(tag)))?;
+        }
+    }
+    doc.line("---")?;
+    doc.line("")
+}
+
+/// Zeichenkette in doppelten Anführungszeichen für YAML.
+pub fn yaml_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Schreibt die Projektangaben aus dem Manifest in den Kopfbereich.
+fn write_metadata<W: Write>(doc: &mut DocWriter<W>, config: &MarkdownConfig) -> io::Result<()> {
+    for line in config.metadata.iter().flat_map(ProjectMetadata::header_lines) {
+        doc.line(&format!("> {}", line))?;
+    }
+    for (key, value) in &config.meta {
+        doc.line(&format!("> {}: {}", key, value))?;
+    }
+    Ok(())
+}
+
+/// Schreibt die Ordnerstruktur als Codeblock.
+fn write_tree<W: Write>(doc: &mut DocWriter<W>, files: &[CollectedFile], config: &MarkdownConfig) -> io::Result<()> {
+    doc.line("---")?;
+    doc.line("")?;
+    doc.line("## Ordnerstruktur")?;
+    doc.line("")?;
+    doc.line("```")?;
+
+    let mut labels: BTreeMap<String, String> = config
+        .packages
+        .iter()
+        .map(|p| (p.path.clone(), format!("Paket {}", p.name)))
+        .collect();
+    for path in &config.nested_repos {
+        labels.insert(path.clone(), "Repository, übersprungen".to_string());
+    }
+    for path in &config.excluded_dirs {
+        labels.insert(path.clone(), "ausgeschlossen".to_string());
+    }
+    for tree_line in generate_tree(files, &config.base_path, &config.project_name, &labels, &config.tree) {
+        doc.line(&tree_line)?;
+    }
+
+    doc.line("```")?;
+    doc.line("")
+}
+
+/// Schreibt den Statistik-Abschnitt; `link` liefert das Linkziel einer Datei.
+fn write_statistics<W: Write>(
+    doc: &mut DocWriter<W>,
+    stats: &ProjectStats,
+    link: impl Fn(&str) -> String,
+) -> io::Result<()> {
+    doc.line("---")?;
+    doc.line("")?;
+    doc.line("## Statistik")?;
+    doc.line("")?;
+    doc.line(&format!("> Zeilen gesamt: {}", stats.total_lines))?;
+    doc.line("")?;
+
+    doc.line("### Sprachen")?;
+    doc.line("")?;
+    doc.line("| Sprache | Dateien | Zeilen |")?;
+    doc.line("|---|---:|---:|")?;
+    for (language, language_stats) in stats.languages_by_lines() {
+        doc.line(&format!("| {} | {} | {} |", language, language_stats.files, language_stats.lines))?;
+    }
+    doc.line("")?;
+
+    doc.line("### Verzeichnisse")?;
+    doc.line("")?;
+    doc.line("| Verzeichnis | Dateien |")?;
+    doc.line("|---|---:|")?;
+    for (directory, count) in &stats.directories {
+        let directory = if directory == "." { "./".to_string() } else { format!("{}/", directory) };
+        doc.line(&format!("| `{}` | {} |", directory, count))?;
+    }
+    doc.line("")?;
+
+    doc.line("### Größte Dateien")?;
+    doc.line("")?;
+    doc.line("| Datei | Größe |")?;
+    doc.line("|---|---:|")?;
+    for (rel_str, size) in &stats.largest {
+        doc.line(&format!("| [`{}`]({}) | {} |", rel_str, link(rel_str), format_size(*size)))?;
+    }
+    doc.line("")
+}
+
+/// Schreibt die Metrik-Tabelle; `link` liefert das Linkziel einer Datei.
+fn write_metrics<W: Write>(
+    doc: &mut DocWriter<W>,
+    files: &[CollectedFile],
+    sort: MetricsSort,
+    config: &MarkdownConfig,
+    link: impl Fn(&str) -> String,
+) -> io::Result<()> {
+    let mut metrics = collect_metrics(files, &config.base_path);
+    sort_metrics(&mut metrics, sort);
+
+    doc.line("---")?;
+    doc.line("")?;
+    doc.line("## Metriken")?;
+    doc.line("")?;
+    if metrics.is_empty() {
+        doc.line("*Keine Dateien mit unterstützter Sprache.*")?;
+        return doc.line("");
+    }
+    doc.line("| Datei | LOC | Kommentare | Tiefe | Funktionen |")?;
+    doc.line("|---|---:|---:|---:|---:|")?;
+    for (rel_str, m) in &metrics {
+        doc.line(&format!(
+            "| [`{}`]({}) | {} | {} % | {} | {} |",
+            rel_str,
+            link(rel_str),
+            m.loc,
+            m.comment_ratio(),
+            m.max_nesting,
+            m.functions
+        ))?;
+    }
+    doc.line("")
+}
+
+/// Schreibt Lizenzen, Header-Prüfung, offene Punkte, Abschnitte der
+/// Prozessoren, Prüfsummen und Warnungen, soweit aktiviert. `link` liefert
+/// das Linkziel einer Datei.
+fn write_appendices<W: Write>(
+    doc: &mut DocWriter<W>,
+    files: &[CollectedFile],
+    hashes: &[(String, String)],
+    config: &MarkdownConfig,
+    link: impl Fn(&str) -> String,
+) -> io::Result<()> {
+    // Lizenzübersicht
+    if config.include_licenses {
+        doc.line("---")?;
+        doc.line("")?;
+        doc.line("## Lizenzen")?;
+        doc.line("")?;
+
+        let findings = detect_licenses(files, &config.base_path);
+        if findings.is_empty() {
+            doc.line("*Keine Lizenzangaben gefunden.*")?;
+        } else {
+            doc.line("| Verzeichnis | Lizenz | Nachweis |")?;
+            doc.line("|---|---|---|")?;
+            for finding in findings {
+                let source = match finding.source {
+                    LicenseSource::LicenseFile(path) => format!("`{}`", path),
+                    LicenseSource::SpdxHeader(1) => "SPDX-Header (1 Datei)".to_string(),
+                    LicenseSource::SpdxHeader(n) => format!("SPDX-Header ({} Dateien)", n),
+                };
+                doc.line(&format!(
+                    "| `{}` | {} | {} |",
+                    finding.directory, finding.license, source
+                ))?;
+            }
+        }
+        doc.line("")?;
+    }
+
+    // Header-Prüfung
+    if let Some(ref checks) = config.header_checks {
+        let passed = checks.iter().filter(|c| c.passed).count();
+
+        doc.line("---")?;
+        doc.line("")?;
+        doc.line("## Header-Prüfung")?;
+        doc.line("")?;
+        doc.line(&format!("{} von {} Dateien konform.", passed, checks.len()))?;
+        doc.line("")?;
+        doc.line("| Datei | Status |")?;
+        doc.line("|---|---|")?;
+        for check in checks {
+            let status = if check.passed { "✓ OK" } else { "✗ fehlt" };
+            doc.line(&format!("| `{}` | {} |", check.path, status))?;
+        }
+        doc.line("")?;
+    }
+
+    // Offene Punkte
+    if config.include_todos {
+        doc.line("---")?;
+        doc.line("")?;
+        doc.line("## Offene Punkte")?;
+        doc.line("")?;
+
+        let todos = collect_todos(files, &config.base_path);
+        if todos.is_empty() {
+            doc.line("*Keine TODO-, FIXME- oder HACK-Kommentare gefunden.*")?;
+        } else {
+            doc.line("| Datei | Zeile | Art | Text |")?;
+            doc.line("|---|---:|---|---|")?;
+            for todo in todos {
+                doc.line(&format!(
+                    "| [`{}`]({}) | {} | {} | {} |",
+                    todo.path,
+                    link(&todo.path),
+                    todo.line,
+                    todo.marker,
+                    todo.text.replace('|', "\\|")
+                ))?;
+            }
+        }
+        doc.line("")?;
+    }
+
+    // Zusätzliche Abschnitte der Prozessoren
+    if !config.processors.is_empty() {
+        let rel_paths: Vec<String> = files
+            .iter()
+            .map(|f| relative_path(&f.path, &config.base_path))
+            .collect();
+        for processor in &config.processors {
+            if let Some(extra) = processor.section(&rel_paths) {
+                doc.line("---")?;
+                doc.line("")?;
+                doc.line(extra.trim_end())?;
+                doc.line("")?;
+            }
+        }
+    }
+
+    // Prüfsummen im sha256sum-Format
+    if config.include_hashes {
+        doc.line("---")?;
+        doc.line("")?;
+        doc.line("## Prüfsummen")?;
+        doc.line("")?;
+        doc.line("```text")?;
+        for (rel_str, hash) in hashes {
+            doc.line(&format!("{}  {}", hash, rel_str))?;
+        }
+        doc.line("```")?;
+        doc.line("")?;
+    }
+
+    // Warnungen (nicht lesbare oder übersprungene Dateien)
+    if config.include_warnings {
+        doc.line("---")?;
+        doc.line("")?;
+        doc.line("## Warnungen")?;
+        doc.line("")?;
+
+        let warnings = config.events.warnings();
+        if warnings.is_empty() {
+            doc.line("*Keine Warnungen.*")?;
+        } else {
+            for warning in warnings {
+                match warning.path {
+                    Some(path) => doc.line(&format!("- `{}`: {}", path, warning.message))?,
+                    None => doc.line(&format!("- {}", warning.message))?,
+                }
+            }
+        }
+        doc.line("")?;
+    }
+
+    Ok(())
+}
+
+/// Schreibt den Abschnitt einer Datei.
+///
+/// Große Dateien, die ohne Sonderbehandlung und Transformationen als
+/// Codeblock erscheinen, werden direkt aus der Speicherabbildung geschrieben.
+fn write_section<W: Write>(
+    doc: &mut DocWriter<W>,
+    file: &CollectedFile,
+    rel_str: &str,
+    meta: &[String],
+    config: &MarkdownConfig,
+) -> io::Result<()> {
+    let mapped = if file.size() >= MMAP_THRESHOLD
+        && config.processors.is_empty()
+        && is_plain_code(&file.path, &config.handlers)
+    {
+        file.bytes().ok()
+    } else {
+        None
+    };
+
+    if let Some(map) = mapped {
+        let syntax = code_syntax(file_syntax(&file.path, rel_str, config), config);
+        if let Ok(content) = std::str::from_utf8(&map) {
+            if config.transform.is_noop(syntax, content) {
+                config.events.emit(ExportEvent::FileRead {
+                    path: rel_str.to_string(),
+                    bytes: map.len() as u64,
+                });
+                let summary = file_summary(rel_str, content, config);
+                let imports = import_line(rel_str, content, config);
+                let mut meta = meta.to_vec();
+                if let Some(hash) = config.include_hashes.then(|| block_hash(&map, content, config)).flatten() {
+                    meta.insert(1.min(meta.len()), hash);
+                }
+                for line in section_header(rel_str, &meta, config, summary.as_deref(), imports.as_deref()) {
+                    doc.line(&line)?;
+                }
+                let fence = code_fence(content);
+                doc.line(&format!("{}{}", fence, syntax))?;
+                doc.line(content.trim_end())?;
+                doc.line(&fence)?;
+                return doc.line("");
+            }
+        }
+    }
+
+    for line in render_section(file, rel_str, meta, config) {
+        doc.line(&line)?;
+    }
+    Ok(())
+}
+
+/// Rendert den Abschnitt einer Datei (Überschrift, Metadaten und Inhalt).
+pub fn render_section(file: &CollectedFile, rel_str: &str, meta: &[String], config: &MarkdownConfig) -> Vec<String> {
+    let blocks = file_blocks(file, rel_str, config);
+
+    let content = if config.processors.is_empty() && config.summarizer.is_none() {
+        String::new()
+    } else {
+        blocks
+            .iter()
+            .filter_map(|block| match block {
+                Block::Code { content, .. } => Some(content.as_str()),
+                Block::Text(_) => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    // Metadaten-Zeile inkl. Angaben der Prozessoren
+    let mut meta = meta.to_vec();
+    if config.include_hashes {
+        if let ([Block::Code { content, .. }], Ok(raw)) = (blocks.as_slice(), file.bytes()) {
+            if let Some(hash) = block_hash(&raw, content, config) {
+                meta.insert(1.min(meta.len()), hash);
+            }
+        }
+    }
+    meta.extend(
+        config
+            .processors
+            .iter()
+            .filter_map(|p| p.annotate(rel_str, &content)),
+    );
+
+    let summary = if content.trim().is_empty() {
+        None
+    } else {
+        file_summary(rel_str, &content, config)
+    };
+    let imports = config
+        .imports
+        .as_ref()
+        .and_then(|_| file.text().ok())
+        .and_then(|raw| import_line(rel_str, &raw, config));
+    let mut lines = section_header(rel_str, &meta, config, summary.as_deref(), imports.as_deref());
+    lines.extend(render_blocks(blocks));
+    lines
+}
+
+/// Prüfsumme des eingebetteten Codeblocks, wenn er vom Dateiinhalt abweicht
+/// (BOM, Zeilenenden, Leerzeilen am Ende, Transformationen).
+///
+/// Gehasht werden die Zeilen zwischen den Fences samt abschließendem
+/// Zeilenende, also der Block, wie er sich aus dem Export herauskopieren
+/// lässt. `SHA-256` bezieht sich dagegen immer auf die Datei auf der Platte.
+fn block_hash(raw: &[u8], content: &str, config: &MarkdownConfig) -> Option<String> {
+    let line_ending = config.transform.line_ending;
+    let embedded = format!("{}{}", line_ending.apply(content.trim_end()), line_ending.separator());
+    (embedded.as_bytes() != raw).then(|| format!("Block-SHA-256: `{:x}`", Sha256::digest(embedded.as_bytes())))
+}
+
+/// Überschrift, Beschreibung, Metadaten-Zeile, Imports und Zusammenfassung
+/// eines Dateiabschnitts.
+fn section_header(
+    rel_str: &str,
+    meta: &[String],
+    config: &MarkdownConfig,
+    summary: Option<&str>,
+    imports: Option<&str>,
+) -> Vec<String> {
+    let heading = if config.link_sources {
+        let link = source_link(&config.base_path.join(rel_str), &config.handlers.output_dir);
+        format!("### [`{}`]({})", rel_str, link)
+    } else {
+        format!("### `{}`", rel_str)
+    };
+    let mut lines = vec![heading, String::new()];
+    if let Some(description) = config.descriptions.describe(rel_str) {
+        lines.extend(description.trim().lines().map(|line| format!("> {}", line).trim_end().to_string()));
+        lines.push(String::new());
+    }
+    if !meta.is_empty() {
+        lines.push(format!("> {}", meta.join(" · ")));
         lines.push(String::new());
-        lines.push("## Ordnerstruktur".to_string());
+    }
+    if let Some(imports) = imports {
+        lines.push(format!("> {}", imports));
         lines.push(String::new());
-        lines.push("```".to_string());
-        
-        let tree = generate_tree(files, &config.base_path, &config.project_name);
-        for tree_line in tree {
-            lines.push(tree_line);
-        }
-        
-        lines.push("```".to_string());
+    }
+    if let Some(summary) = summary {
+        lines.push(summary.to_string());
         lines.push(String::new());
     }
+    lines
+}
 
-    // Dateien
-    lines.push("---".to_string());
-    lines.push(String::new());
-    lines.push("## Dateien".to_string());
-    lines.push(String::new());
+/// Zeile mit Links auf die importierten Dateien, falls aktiviert.
+fn import_line(rel_str: &str, content: &str, config: &MarkdownConfig) -> Option<String> {
+    let index = config.imports.as_ref()?;
+    let imports = index.resolve(&rel_str.replace('\\', "/"), content);
+    if imports.is_empty() {
+        return None;
+    }
+    let links: Vec<String> = imports
+        .iter()
+        .map(|path| format!("[`{}`]({})", path, index.link(path)))
+        .collect();
+    Some(format!("Imports: {}", links.join(", ")))
+}
 
-    for file in files {
-        if let Ok(rel_path) = file.strip_prefix(&config.base_path) {
-            let rel_str = rel_path.to_string_lossy();
-            let filename = file.file_name()
-                .map(|s| s.to_string_lossy().to_string())
-                .unwrap_or_default();
-            
-            let syntax = get_syntax_for_file(&filename);
-            let content = read_file_content(file);
+/// Fasst einen Dateiinhalt zusammen; Fehler werden als Warnung gemeldet.
+fn file_summary(rel_str: &str, content: &str, config: &MarkdownConfig) -> Option<String> {
+    let summarizer = config.summarizer.as_ref()?;
+    match summarizer.summarize(content) {
+        Ok(summary) => Some(summary),
+        Err(e) => {
+            config
+                .events
+                .warn(rel_str, format!("Zusammenfassung fehlgeschlagen: {}", e));
+            None
+        }
+    }
+}
+
+/// Gibt die Syntax-Angabe für den Codeblock einer Datei zurück.
+fn file_syntax<'a>(file: &Path, rel_str: &str, config: &'a MarkdownConfig) -> &'a str {
+    if let Some(language) = config.languages.get(rel_str) {
+        return language;
+    }
+    syntax_for_path(file)
+}
 
-            lines.push(format!("### `{}`", rel_str));
-            lines.push(String::new());
-            lines.push(format!("```{}", syntax));
-            lines.push(content.trim_end().to_string());
-            lines.push("```".to_string());
-            lines.push(String::new());
+/// Sprache, mit der der Codeblock einer Datei ausgezeichnet wird: Angaben
+/// aus `linguist-language`, sonst nach Endung, sonst `--fallback-syntax`.
+pub fn file_language<'a>(file: &Path, rel_str: &str, config: &'a MarkdownConfig) -> &'a str {
+    code_syntax(file_syntax(file, rel_str, config), config)
+}
+
+/// Sprache eines Codeblocks; ohne bekannte Sprache gilt `--fallback-syntax`.
+fn code_syntax<'a>(syntax: &'a str, config: &'a MarkdownConfig) -> &'a str {
+    match &config.fallback_syntax {
+        Some(fallback) if syntax.is_empty() => fallback,
+        _ => syntax,
+    }
+}
+
+/// Zerlegt eine Datei in Blöcke und wendet Transformationen und Prozessoren
+/// auf alle Codeblöcke an.
+pub fn file_blocks(file: &CollectedFile, rel_str: &str, config: &MarkdownConfig) -> Vec<Block> {
+    let syntax = file_syntax(&file.path, rel_str, config);
+
+    let blocks = match render_file(file, syntax, &config.handlers) {
+        Ok(blocks) => blocks,
+        Err(e) => {
+            config
+                .events
+                .warn(rel_str, format!("Datei konnte nicht gelesen werden: {}", e));
+            return vec![Block::Text("*(Datei konnte nicht gelesen werden)*".to_string())];
         }
+    };
+    config.events.emit(ExportEvent::FileRead {
+        path: rel_str.to_string(),
+        bytes: file.size(),
+    });
+
+    blocks
+        .into_iter()
+        .map(|block| match block {
+            Block::Code { syntax, content } => {
+                let content = transform_code(&content, &syntax, &config.transform, |warning| {
+                    config.events.warn(rel_str, warning)
+                });
+                let content = config
+                    .processors
+                    .iter()
+                    .fold(content, |content, p| p.transform(rel_str, content));
+                let syntax = code_syntax(&syntax, config).to_string();
+                Block::Code { syntax, content }
+            }
+            text => text,
+        })
+        .collect()
+}
+
+/// Rendert die Blöcke eines Dateiabschnitts als Markdown-Zeilen.
+pub fn render_blocks(blocks: Vec<Block>) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for block in blocks {
+        match block {
+            Block::Text(text) => lines.push(text),
+            Block::Code { syntax, content } => {
+                let fence = code_fence(&content);
+                lines.push(format!("{}{}", fence, syntax));
+                lines.push(content.trim_end().to_string());
+                lines.push(fence);
+            }
+        }
+        lines.push(String::new());
+    }
+
+    lines
+}
+
+/// Rendert die Blöcke eines Dateiabschnitts als Fließtext für andere
+/// Ausgabeformate: ein einzelner Codeblock wird ohne Fence übernommen.
+pub fn render_blocks_plain(blocks: Vec<Block>) -> String {
+    if let [Block::Code { content, .. }] = blocks.as_slice() {
+        return content.trim_end().to_string();
     }
 
-    lines.join("\n")
+    render_blocks(blocks).join("\n").trim_end().to_string()
+}
+
+/// Wählt einen Code-Fence, der länger ist als jede Backtick-Folge im Inhalt.
+fn code_fence(content: &str) -> String {
+    let longest = content
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+/// Eintrag einer Datei im Inhaltsverzeichnis.
+pub fn toc_entry(rel_str: &str) -> String {
+    format!("   - [`{}`](#{})", rel_str, generate_anchor(rel_str))
 }
 
 /// Generiert einen Markdown-Anker aus einem Pfad.
-fn generate_anchor(path: &str) -> String {
+pub fn generate_anchor(path: &str) -> String {
     path.chars()
         .filter_map(|c| {
             if c.is_alphanumeric() {
@@ -123,6 +1420,34 @@ pub fn format_size(bytes: u64) -> String {
     }
 }
 
+/// Schreibt Dokumentzeilen, verbunden durch das konfigurierte Zeilenende.
+struct DocWriter<W: Write> {
+    out: io::BufWriter<W>,
+    line_ending: LineEnding,
+    first: bool,
+}
+
+impl<W: Write> DocWriter<W> {
+    fn new(out: W, line_ending: LineEnding) -> Self {
+        Self {
+            out: io::BufWriter::new(out),
+            line_ending,
+            first: true,
+        }
+    }
+
+    fn line(&mut self, line: &str) -> io::Result<()> {
+        if !std::mem::take(&mut self.first) {
+            self.out.write_all(self.line_ending.separator().as_bytes())?;
+        }
+        self.out.write_all(self.line_ending.apply(line).as_bytes())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -133,10 +1458,650 @@ mod tests {
         assert_eq!(generate_anchor("config/settings.json"), "configsettingsjson");
     }
 
+    #[test]
+    fn test_toc_entry_path_flavors() {
+        use crate::processor::slash_path;
+
+        let unix = toc_entry(&slash_path(Path::new("src/utils/helpers.py")));
+        assert_eq!(toc_entry(&slash_path(Path::new("src\\utils\\helpers.py"))), unix);
+        assert_eq!(unix, "   - [`src/utils/helpers.py`](#srcutilshelperspy)");
+    }
+
+    #[test]
+    fn test_code_fence() {
+        assert_eq!(code_fence("fn main() {}"), "```");
+        assert_eq!(code_fence("Beispiel:\n```rust\nx\n```"), "````");
+    }
+
     #[test]
     fn test_format_size() {
         assert_eq!(format_size(500), "500 Bytes");
         assert_eq!(format_size(1536), "1.50 KB");
         assert_eq!(format_size(2_097_152), "2.00 MB");
     }
+
+    #[test]
+    fn test_unreadable_file_warning() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().to_path_buf();
+        std::fs::write(base.join("fehlt.rs"), "")?;
+        let file = CollectedFile::from_path(base.join("fehlt.rs"))?;
+        std::fs::remove_file(&file.path)?;
+
+        let config = MarkdownConfig {
+            base_path: base,
+            include_warnings: true,
+            ..Default::default()
+        };
+        let document = generate_markdown(&[file], &config);
+
+        assert_eq!(config.events.warning_count(), 1);
+        assert!(document.contains("*(Datei konnte nicht gelesen werden)*"));
+        assert!(document.contains("## Warnungen"));
+        assert!(document.contains("- `fehlt.rs`: Datei konnte nicht gelesen werden:"));
+        assert!(!document.contains("[Fehler:"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_hashes_match_extracted_block() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join("a.rs"), "fn a() {}\n")?;
+        std::fs::write(dir.path().join("b.rs"), "\u{feff}fn b() {}\r\n\r\n")?;
+        let files = vec![
+            CollectedFile::from_path(dir.path().join("a.rs"))?,
+            CollectedFile::from_path(dir.path().join("b.rs"))?,
+        ];
+        let mut config = MarkdownConfig {
+            base_path: dir.path().to_path_buf(),
+            include_hashes: true,
+            ..Default::default()
+        };
+        config.transform.line_ending = LineEnding::Lf;
+        let document = generate_markdown(&files, &config);
+
+        // Codeblock und Metadaten-Zeile eines Abschnitts herauslösen
+        let extract = |name: &str| {
+            let mut lines = document.lines().skip_while(|line| *line != format!("### `{}`", name)).skip(2);
+            let meta = lines.next().unwrap_or_default().to_string();
+            let fence = lines.find(|line| line.starts_with("```")).unwrap_or_default();
+            let fence = fence.trim_end_matches(|c: char| c != '`');
+            let block: String = lines.take_while(|line| *line != fence).map(|line| format!("{}\n", line)).collect();
+            (meta, format!("{:x}", Sha256::digest(block.as_bytes())))
+        };
+
+        let (meta, hash) = extract("a.rs");
+        assert_eq!(meta, format!("> SHA-256: `{}`", files[0].sha256()?));
+        assert_eq!(hash, files[0].sha256()?);
+
+        let (meta, hash) = extract("b.rs");
+        assert_eq!(meta, format!("> SHA-256: `{}` · Block-SHA-256: `{}`", files[1].sha256()?, hash));
+        assert_ne!(hash, files[1].sha256()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_description_under_heading() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().to_path_buf();
+        std::fs::write(base.join("main.rs"), "fn main() {}\n")?;
+        let file = CollectedFile::from_path(base.join("main.rs"))?;
+
+        let config = MarkdownConfig {
+            base_path: base,
+            include_hashes: true,
+            descriptions: crate::config::Config::parse("[annotations]\n\"main.rs\" = \"Zeile 1\\n\\nZeile 2\"")?
+                .descriptions()?,
+            ..Default::default()
+        };
+        let document = generate_markdown(&[file], &config);
+
+        assert!(document.contains("### `main.rs`\n\n> Zeile 1\n>\n> Zeile 2\n\n> SHA-256: `"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_badges_under_title() {
+        let config = MarkdownConfig {
+            project_name: "demo".to_string(),
+            badges: vec![
+                "![Build](https://ci.example.com/badge.svg)".to_string(),
+                "![Version](https://img.shields.io/badge/version-{version}-blue)".to_string(),
+                "![Lizenz](https://img.shields.io/badge/license-{license}-green)".to_string(),
+            ],
+            metadata: Some(ProjectMetadata {
+                version: Some("1.2.0".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let document = generate_markdown(&[], &config);
+
+        assert!(document.starts_with(
+            "# demo\n\n![Build](https://ci.example.com/badge.svg)\n![Version](https://img.shields.io/badge/version-1.2.0-blue)\n\n> Generiert am"
+        ));
+    }
+
+    #[test]
+    fn test_front_matter() {
+        let config = MarkdownConfig {
+            project_name: "de\"mo".to_string(),
+            front_matter: Some(vec!["code".to_string()]),
+            meta: vec![
+                ("build".to_string(), "42".to_string()),
+                ("title".to_string(), "ignoriert".to_string()),
+            ],
+            ..Default::default()
+        };
+        let document = generate_markdown(&[], &config);
+
+        assert!(document.starts_with("---\ntitle: \"de\\\"mo\"\ndate: "));
+        assert!(document.contains("\nproject: \"de\\\"mo\"\nfiles: 0\nbuild: \"42\"\ntags:\n  - \"code\"\n---\n\n# de\"mo\n"));
+        assert!(document.contains("> Anzahl Dateien: 0\n> build: 42\n> title: ignoriert\n"));
+    }
+
+    #[test]
+    fn test_reproducible_header() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join("main.rs"), "fn main() {}\n")?;
+        let file = CollectedFile::from_path(dir.path().join("main.rs"))?;
+        let config = MarkdownConfig {
+            base_path: dir.path().to_path_buf(),
+            front_matter: Some(Vec::new()),
+            reproducible: true,
+            ..Default::default()
+        };
+        let document = generate_markdown(std::slice::from_ref(&file), &config);
+        assert_eq!(document, generate_markdown(&[file], &config));
+        assert!(document.contains("> Basisverzeichnis: `.`\n"));
+        if std::env::var(SOURCE_DATE_EPOCH).is_err() {
+            assert!(!document.contains("date: "));
+            assert!(!document.contains("> Generiert am"));
+            assert!(crate::merge::looks_like_export(document.as_bytes()));
+        }
+
+        assert_eq!(source_date(None), None);
+        assert_eq!(source_date(Some("kaputt")), None);
+        let date = source_date(Some("1700000000\n")).map(|date| date.to_rfc3339());
+        assert_eq!(date.as_deref(), Some("2023-11-14T22:13:20+00:00"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_crlf_document() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join("a.rs"), "fn a() {\r\n}\n")?;
+        std::fs::write(dir.path().join("b.py"), "x = 1\ny = 2\n")?;
+        let files = vec![
+            CollectedFile::from_path(dir.path().join("a.rs"))?,
+            CollectedFile::from_path(dir.path().join("b.py"))?,
+        ];
+        let mut config = MarkdownConfig {
+            base_path: dir.path().to_path_buf(),
+            include_tree: true,
+            ..Default::default()
+        };
+        config.transform.line_ending = LineEnding::Crlf;
+        let document = generate_markdown(&files, &config);
+
+        assert!(document.contains("fn a() {\r\n}\r\n```"));
+        assert!(document.contains("x = 1\r\ny = 2\r\n```"));
+        assert_eq!(document.matches('\n').count(), document.matches("\r\n").count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_statistics_section() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().to_path_buf();
+        std::fs::create_dir(base.join("src"))?;
+        std::fs::write(base.join("src/main.rs"), "fn main() {\n}\n")?;
+        let file = CollectedFile::from_path(base.join("src/main.rs"))?;
+
+        let config = MarkdownConfig {
+            base_path: base,
+            include_stats: true,
+            ..Default::default()
+        };
+        let document = generate_markdown(&[file], &config);
+
+        assert!(document.contains("1. [Statistik](#statistik)"));
+        assert!(document.contains("## Statistik\n\n> Zeilen gesamt: 2\n"));
+        assert!(document.contains("| rust | 1 | 2 |"));
+        assert!(document.contains("| `src/` | 1 |"));
+        assert!(document.contains("| [`src/main.rs`](#srcmainrs) | 14 Bytes |"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_metrics_table() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().to_path_buf();
+        std::fs::write(base.join("a.rs"), "fn a() {}\n")?;
+        std::fs::write(base.join("b.py"), "# b\ndef b():\n    if x:\n        pass\n")?;
+        let files = vec![CollectedFile::from_path(base.join("a.rs"))?, CollectedFile::from_path(base.join("b.py"))?];
+
+        let config = MarkdownConfig {
+            base_path: base,
+            metrics: Some(MetricsSort::Nesting),
+            ..Default::default()
+        };
+        let document = generate_markdown(&files, &config);
+
+        assert!(document.contains("### `a.rs`\n\n> 1 LOC, 0 % Kommentare, Tiefe 0, 1 Funktion\n"));
+        assert!(document.contains(
+            "| [`b.py`](#bpy) | 3 | 25 % | 2 | 1 |\n| [`a.rs`](#ars) | 1 | 0 % | 0 | 1 |\n"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_todos_appendix() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().to_path_buf();
+        std::fs::write(base.join("lib.rs"), "fn a() {}\n// FIXME: a | b prüfen\n")?;
+        let file = CollectedFile::from_path(base.join("lib.rs"))?;
+
+        let config = MarkdownConfig {
+            base_path: base,
+            include_todos: true,
+            ..Default::default()
+        };
+        let document = generate_markdown(&[file], &config);
+
+        assert!(document.contains("2. [Offene Punkte](#offene-punkte)"));
+        assert!(document.contains("## Offene Punkte\n\n| Datei | Zeile | Art | Text |\n|---|---:|---|---|\n| [`lib.rs`](#librs) | 2 | FIXME | a \\| b prüfen |\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_links() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().to_path_buf();
+        std::fs::write(base.join("app.ts"), "import { a } from './util';\n")?;
+        std::fs::write(base.join("util.ts"), "export const a = 1;\n")?;
+        let files = vec![CollectedFile::from_path(base.join("app.ts"))?, CollectedFile::from_path(base.join("util.ts"))?];
+
+        let config = MarkdownConfig {
+            base_path: base,
+            imports: Some(ImportIndex::new(["app.ts".to_string(), "util.ts".to_string()])),
+            ..Default::default()
+        };
+        let document = generate_markdown(&files, &config);
+
+        assert!(document.contains("### `app.ts`\n\n> Imports: [`util.ts`](#utilts)\n\n```typescript\n"));
+        assert!(document.contains("### `util.ts`\n\n```typescript\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_permalink_in_meta_line() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().to_path_buf();
+        std::fs::write(base.join("main.rs"), "fn main() {}\n")?;
+        let file = CollectedFile::from_path(base.join("main.rs"))?;
+
+        let config = MarkdownConfig {
+            base_path: base,
+            permalinks: Some(Permalinks {
+                base: "https://github.com/acme/app".to_string(),
+                rev: "abc123".to_string(),
+                prefix: String::new(),
+                template: crate::permalink::DEFAULT_TEMPLATE.to_string(),
+                label: "Auf GitHub ansehen".to_string(),
+            }),
+            ..Default::default()
+        };
+        let document = generate_markdown(&[file], &config);
+
+        assert!(document.contains(
+            "### `main.rs`\n\n> [Auf GitHub ansehen](https://github.com/acme/app/blob/abc123/main.rs)\n"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_fallback_syntax() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().to_path_buf();
+        std::fs::write(base.join("rules.xyz"), "a -> b\n")?;
+        std::fs::write(base.join("main.py"), "x = 1\n")?;
+        let files = vec![
+            CollectedFile::from_path(base.join("rules.xyz"))?,
+            CollectedFile::from_path(base.join("main.py"))?,
+        ];
+
+        let mut config = MarkdownConfig {
+            base_path: base,
+            ..Default::default()
+        };
+        assert!(generate_markdown(&files, &config).contains("```\na -> b\n```"));
+
+        config.fallback_syntax = Some("text".to_string());
+        let document = generate_markdown(&files, &config);
+        assert!(document.contains("```text\na -> b\n```"));
+        assert!(document.contains("```python\nx = 1\n```"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_aliases() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().to_path_buf();
+        std::fs::write(base.join("main.py"), "x = 1\n")?;
+        let files = vec![CollectedFile::from_path(base.join("main.py"))?];
+
+        let config = MarkdownConfig {
+            base_path: base,
+            aliases: BTreeMap::from([("main.py".to_string(), vec!["lib/main.py".to_string(), "app.py".to_string()])]),
+            ..Default::default()
+        };
+        let document = generate_markdown(&files, &config);
+        assert!(document.contains("> Auch unter: `lib/main.py`, `app.py`"));
+        assert_eq!(document.matches("x = 1").count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_link_sources() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().to_path_buf();
+        std::fs::create_dir_all(base.join("src"))?;
+        std::fs::write(base.join("src/main.rs"), "fn main() {}\n")?;
+        let file = CollectedFile::from_path(base.join("src/main.rs"))?;
+
+        let config = MarkdownConfig {
+            base_path: base.clone(),
+            handlers: HandlerConfig {
+                output_dir: base.join("docs"),
+                ..Default::default()
+            },
+            link_sources: true,
+            ..Default::default()
+        };
+        let document = generate_markdown(&[file], &config);
+
+        assert!(document.contains("### [`src/main.rs`](../src/main.rs)\n"));
+        assert!(document.contains("- [`src/main.rs`](#srcmainrs)"));
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_summary_under_heading() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().to_path_buf();
+        std::fs::write(base.join("main.rs"), "fn main() {}\n")?;
+        let file = CollectedFile::from_path(base.join("main.rs"))?;
+
+        let config = MarkdownConfig {
+            base_path: base,
+            summarizer: Some(Summarizer::new("echo Startet das Programm.", Default::default())),
+            ..Default::default()
+        };
+        let document = generate_markdown(&[file], &config);
+
+        assert!(document.contains("### `main.rs`\n\nStartet das Programm.\n\n```rust\n"));
+        assert_eq!(config.events.warning_count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_separate_tests_section() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().to_path_buf();
+        std::fs::create_dir_all(base.join("tests"))?;
+        std::fs::write(base.join("lib.rs"), "pub fn f() {}\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn t() {}\n}\n")?;
+        std::fs::write(base.join("tests/cli.rs"), "#[test]\nfn cli() {}\n")?;
+        let files = ["lib.rs", "tests/cli.rs"]
+            .iter()
+            .map(|name| CollectedFile::from_path(base.join(name)))
+            .collect::<std::io::Result<Vec<_>>>()?;
+
+        let config = MarkdownConfig {
+            base_path: base,
+            separate_tests: true,
+            ..Default::default()
+        };
+        let document = generate_markdown(&files, &config);
+
+        assert!(document.contains("2. [Tests](#tests)\n   - [`tests/cli.rs`](#testsclirs)\n   - [`lib.rs` (Tests)](#librstests)\n"));
+        let tests = document.find("## Tests").unwrap_or_default();
+        assert!(tests > document.find("### `lib.rs`").unwrap_or(usize::MAX));
+        assert!(document[tests..].contains("### `tests/cli.rs`"));
+        assert!(document[tests..].contains("### `lib.rs` (Tests)\n\n```rust\n#[cfg(test)]\nmod tests {"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_package_headings() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().to_path_buf();
+        std::fs::create_dir_all(base.join("packages/web"))?;
+        for name in ["packages/web/a.ts", "packages/web/b.ts"] {
+            std::fs::write(base.join(name), "export {}\n")?;
+        }
+        let files = ["packages/web/a.ts", "packages/web/b.ts"]
+            .iter()
+            .map(|name| CollectedFile::from_path(base.join(name)))
+            .collect::<std::io::Result<Vec<_>>>()?;
+
+        let config = MarkdownConfig {
+            project_name: "demo".to_string(),
+            base_path: base,
+            include_tree: true,
+            packages: vec![Package {
+                name: "@acme/web".to_string(),
+                path: "packages/web".to_string(),
+            }],
+            ..Default::default()
+        };
+        let document = generate_markdown(&files, &config);
+
+        assert_eq!(document.matches("## Paket `@acme/web`\n\n> Verzeichnis: `packages/web`").count(), 1);
+        assert!(document.contains("└── web/  [Paket @acme/web]"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_plan_chunks() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path();
+        std::fs::create_dir_all(base.join("src"))?;
+        std::fs::create_dir_all(base.join("tests"))?;
+        let names = ["main.rs", "src/a.rs", "src/b.rs", "tests/t.rs"];
+        for name in names {
+            std::fs::write(base.join(name), "x".repeat(100))?;
+        }
+        let files = names
+            .iter()
+            .map(|name| CollectedFile::from_path(base.join(name)))
+            .collect::<std::io::Result<Vec<_>>>()?;
+
+        assert_eq!(plan_chunks(&files, base, Split::Dir), vec![0..1, 1..3, 3..4]);
+        assert_eq!(plan_chunks(&files, base, Split::Size(250)), vec![0..2, 2..4]);
+        assert_eq!(plan_chunks(&files, base, Split::Size(50)), vec![0..1, 1..2, 2..3, 3..4]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_estimate_size() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().to_path_buf();
+        let mut files = Vec::new();
+        for name in ["a.rs", "b.rs", "c.rs"] {
+            std::fs::write(base.join(name), "fn f() {}\n".repeat(500))?;
+            files.push(CollectedFile::from_path(base.join(name))?);
+        }
+        let config = MarkdownConfig {
+            base_path: base.clone(),
+            ..Default::default()
+        };
+
+        // Die Schätzung liegt nahe an der tatsächlichen Größe
+        let actual = generate_markdown(&files, &config).len() as u64;
+        let estimate = estimate_size(&files, &base);
+        assert!(estimate >= actual && estimate < actual * 2, "{} / {}", estimate, actual);
+
+        assert_eq!(files_within(&files, &base, estimate), 3);
+        assert_eq!(files_within(&files, &base, estimate - 1), 2);
+        assert_eq!(files_within(&files, &base, 100), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_chunked_navigation() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().join("projekt");
+        std::fs::create_dir_all(&base)?;
+        for name in ["a.rs", "b.rs", "c.rs"] {
+            std::fs::write(base.join(name), "fn f() {}")?;
+        }
+        let files = ["a.rs", "b.rs", "c.rs"]
+            .iter()
+            .map(|name| CollectedFile::from_path(base.join(name)))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        let config = MarkdownConfig {
+            project_name: "demo".to_string(),
+            base_path: base,
+            ..Default::default()
+        };
+
+        let output = dir.path().join("demo_code.md");
+        let written = write_chunked(&files, &config, Split::Size(9), &output, None)?;
+        assert_eq!(written.len(), 4);
+        assert_eq!(written[2], dir.path().join("demo_code.part2.md"));
+
+        let part = std::fs::read_to_string(&written[2])?;
+        assert!(part.starts_with("# demo – Teil 2 von 3\n\n"));
+        assert!(part.contains("[← Teil 1](demo_code.part1.md) · [Übersicht](demo_code.md) · [Teil 3 →](demo_code.part3.md)"));
+        assert!(part.contains("## Inhalt (1 Datei)\n\n- [`b.rs`](#brs)\n"));
+        assert!(part.contains(&section_start("b.rs")));
+        assert!(!part.contains(&section_start("a.rs")));
+
+        let index = std::fs::read_to_string(&output)?;
+        assert!(index.contains("> Anzahl Teile: 3"));
+        assert!(index.contains("3. [Teil 3](demo_code.part3.md) – 1 Datei (`c.rs`)"));
+        assert!(index.contains("| [`b.rs`](demo_code.part2.md#brs) | 2 |"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parallel_map_keeps_order() {
+        let items: Vec<usize> = (0..1000).collect();
+        assert_eq!(parallel_map(&items, |n| n * 2), items.iter().map(|n| n * 2).collect::<Vec<_>>());
+        assert!(parallel_map(&[] as &[usize], |n| *n).is_empty());
+    }
+
+    #[test]
+    fn test_split_resume() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().join("projekt");
+        std::fs::create_dir_all(&base)?;
+        for name in ["a.rs", "b.rs", "c.rs"] {
+            std::fs::write(base.join(name), "fn f() {}")?;
+        }
+        let files = ["a.rs", "b.rs", "c.rs"]
+            .iter()
+            .map(|name| CollectedFile::from_path(base.join(name)))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        let config = MarkdownConfig {
+            project_name: "demo".to_string(),
+            base_path: base.clone(),
+            ..Default::default()
+        };
+        let output = dir.path().join("demo_code.md");
+        let journal_path = PartJournal::path(&base, &output);
+
+        let mut journal = PartJournal::new(&journal_path, "optionen");
+        write_chunked(&files, &config, Split::Size(9), &output, Some(&mut journal))?;
+        assert_eq!(journal.parts.len(), 3);
+
+        // Abgebrochener Teil 2 und veränderte Teil-3-Datei werden neu geschrieben
+        let part1 = dir.path().join("demo_code.part1.md");
+        let part2 = dir.path().join("demo_code.part2.md");
+        let part3 = dir.path().join("demo_code.part3.md");
+        std::fs::write(&part2, "# demo – Teil 2")?;
+        std::fs::write(&part3, "beschädigt")?;
+        std::fs::write(&part1, std::fs::read_to_string(&part1)?)?;
+        let modified = std::fs::metadata(&part1)?.modified()?;
+
+        let mut journal = PartJournal::resume(&journal_path, "optionen");
+        assert_eq!(journal.parts.len(), 3);
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        let written = write_chunked(&files, &config, Split::Size(9), &output, Some(&mut journal))?;
+        assert_eq!(written.len(), 4);
+        assert_eq!(std::fs::metadata(&part1)?.modified()?, modified);
+        assert!(std::fs::read_to_string(&part2)?.contains(&section_start("b.rs")));
+        assert!(std::fs::read_to_string(&part3)?.contains(&section_start("c.rs")));
+
+        // Andere Optionen verwerfen das Protokoll
+        assert!(PartJournal::resume(&journal_path, "andere").parts.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_dir_readme_intro() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().join("projekt");
+        std::fs::create_dir_all(base.join("src"))?;
+        std::fs::write(base.join("main.rs"), "fn main() {}")?;
+        std::fs::write(base.join("src/README.md"), "# Quellen\n\nAlle Module.\n\n```\n# kein Titel\n```\n")?;
+        std::fs::write(base.join("src/lib.rs"), "pub fn f() {}")?;
+        let files = ["main.rs", "src/README.md", "src/lib.rs"]
+            .iter()
+            .map(|name| CollectedFile::from_path(base.join(name)))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        let config = MarkdownConfig {
+            project_name: "demo".to_string(),
+            base_path: base,
+            ..Default::default()
+        };
+
+        let output = dir.path().join("demo_code.md");
+        let written = write_chunked(&files, &config, Split::Dir, &output, None)?;
+        let part = std::fs::read_to_string(&written[2])?;
+        assert!(part.contains(
+            "[Übersicht](demo_code.md)\n\n## Quellen\n\nAlle Module.\n\n```\n# kein Titel\n```\n\n## Inhalt (1 Datei)\n\n- [`src/lib.rs`](#srclibrs)\n"
+        ));
+        assert!(!part.contains(&section_start("src/README.md")));
+
+        let index = std::fs::read_to_string(&output)?;
+        assert!(index.contains("| [`src/README.md`](demo_code.part2.md) | 2 |"));
+        Ok(())
+    }
+
+    struct Passthrough;
+
+    impl crate::processor::FileProcessor for Passthrough {
+        fn name(&self) -> &str {
+            "passthrough"
+        }
+    }
+
+    #[test]
+    fn test_mapped_section_matches_rendered() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().canonicalize()?;
+        let line = "x = 1\n";
+        std::fs::write(base.join("gross.py"), line.repeat(MMAP_THRESHOLD as usize / line.len() + 1))?;
+        let file = CollectedFile::from_path(base.join("gross.py"))?;
+
+        let without_timestamp = |doc: String| {
+            doc.lines()
+                .filter(|l| !l.starts_with("> Generiert am"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let mut config = MarkdownConfig {
+            base_path: base,
+            ..Default::default()
+        };
+        let mapped = without_timestamp(generate_markdown(std::slice::from_ref(&file), &config));
+        config.processors = vec![std::sync::Arc::new(Passthrough)];
+        let rendered = without_timestamp(generate_markdown(std::slice::from_ref(&file), &config));
+
+        assert_eq!(mapped, rendered);
+        Ok(())
+    }
 }
@@ -0,0 +1,298 @@
+//! MCP-Server (Model Context Protocol) über stdio.
+//!
+//! Stellt die Sammel- und Markdown-Pipeline als Tools bereit, damit
+//! KI-Agenten ein Projekt gezielt abfragen können, statt einen kompletten
+//! Export zu lesen. Nachrichten sind JSON-RPC 2.0, eine pro Zeile.
+
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+use serde_json::{json, Value};
+use tracing::info;
+
+use crate::cli::SelectionArgs;
+use crate::collector::{collect_files, CollectedFile, CollectorConfig};
+use crate::error::{Code2MdError, Result};
+use crate::handlers::HandlerConfig;
+use crate::markdown::{generate_markdown, render_section, MarkdownConfig};
+use crate::paths;
+
+/// Unterstützte Protokollversion.
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// Startet den MCP-Server und verarbeitet Anfragen bis zum Ende von stdin.
+pub fn serve_mcp(selection: &SelectionArgs) -> Result<()> {
+    let server = McpServer::new(selection)?;
+
+    // stdout ist für das Protokoll reserviert, Statusmeldungen gehen nach stderr
+    info!(directory = %server.base_path.display(), "MCP-Server gestartet");
+
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+
+    for line in stdin.lock().lines() {
+        let line = line.map_err(server_error)?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<Value>(&line) {
+            Ok(request) => server.handle(&request),
+            Err(e) => Some(error_response(Value::Null, -32700, &format!("Parse error: {}", e))),
+        };
+
+        if let Some(response) = response {
+            writeln!(stdout, "{}", response).map_err(server_error)?;
+            stdout.flush().map_err(server_error)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Zustand des MCP-Servers.
+struct McpServer {
+    base_path: PathBuf,
+    project_name: String,
+    collector: CollectorConfig,
+}
+
+impl McpServer {
+    fn new(selection: &SelectionArgs) -> Result<Self> {
+        Ok(Self {
+            base_path: paths::canonicalize(&selection.directory).map_err(|e| Code2MdError::io(&selection.directory, e))?,
+            project_name: selection.project_name(),
+            collector: selection.collector_config()?,
+        })
+    }
+
+    /// Verarbeitet eine JSON-RPC-Nachricht. Benachrichtigungen (ohne `id`)
+    /// werden nicht beantwortet.
+    fn handle(&self, request: &Value) -> Option<Value> {
+        let id = request.get("id")?.clone();
+        let method = request.get("method").and_then(Value::as_str).unwrap_or("");
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = match method {
+            "initialize" => Ok(json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": { "tools": {} },
+                "serverInfo": {
+                    "name": "code2md",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            })),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => Ok(self.call_tool(&params)),
+            _ => Err(format!("Methode nicht gefunden: {}", method)),
+        };
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(message) => error_response(id, -32601, &message),
+        })
+    }
+
+    /// Führt ein Tool aus. Fehler werden als Tool-Ergebnis mit `isError` gemeldet.
+    fn call_tool(&self, params: &Value) -> Value {
+        let name = params.get("name").and_then(Value::as_str).unwrap_or("");
+        let args = params.get("arguments").cloned().unwrap_or(Value::Null);
+        let arg = |key: &str| args.get(key).and_then(Value::as_str).unwrap_or("").to_string();
+
+        let result = match name {
+            "list_files" => self.list_files(&arg("prefix")),
+            "get_file" => self.get_file(&arg("path")),
+            "export_subtree" => self.export_subtree(&arg("path")),
+            _ => Err(Code2MdError::Server(format!("Unbekanntes Tool: {}", name))),
+        };
+
+        match result {
+            Ok(text) => json!({ "content": [{ "type": "text", "text": text }], "isError": false }),
+            Err(e) => json!({ "content": [{ "type": "text", "text": e.to_string() }], "isError": true }),
+        }
+    }
+
+    /// Sammelt die Dateien unterhalb eines relativen Pfad-Präfixes.
+    fn files_under(&self, prefix: &str) -> Result<Vec<CollectedFile>> {
+        let prefix = prefix.trim_matches('/');
+        let collected = collect_files(&self.base_path, &self.collector)?;
+
+        Ok(collected
+            .files
+            .into_iter()
+            .filter(|file| {
+                let rel = self.relative(&file.path);
+                prefix.is_empty() || rel == prefix || rel.starts_with(&format!("{}/", prefix))
+            })
+            .collect())
+    }
+
+    fn list_files(&self, prefix: &str) -> Result<String> {
+        let files = self.files_under(prefix)?;
+        Ok(files
+            .iter()
+            .map(|f| self.relative(&f.path))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    fn get_file(&self, path: &str) -> Result<String> {
+        let path = path.trim_matches('/');
+        let file = self
+            .files_under(path)?
+            .into_iter()
+            .find(|f| self.relative(&f.path) == path)
+            .ok_or_else(|| Code2MdError::Server(format!("Datei nicht im Export enthalten: {}", path)))?;
+
+        Ok(render_section(&file, path, &[], &self.markdown_config()).join("\n"))
+    }
+
+    fn export_subtree(&self, path: &str) -> Result<String> {
+        let files = self.files_under(path)?;
+        if files.is_empty() {
+            return Err(Code2MdError::Server(format!(
+                "Keine passenden Dateien unter '{}' gefunden.",
+                path
+            )));
+        }
+        Ok(generate_markdown(&files, &self.markdown_config()))
+    }
+
+    fn markdown_config(&self) -> MarkdownConfig {
+        MarkdownConfig {
+            project_name: self.project_name.clone(),
+            base_path: self.base_path.clone(),
+            include_tree: true,
+            handlers: HandlerConfig {
+                output_dir: self.base_path.clone(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn relative(&self, file: &std::path::Path) -> String {
+        file.strip_prefix(&self.base_path)
+            .unwrap_or(file)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+}
+
+/// Beschreibt die angebotenen Tools.
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "list_files",
+            "description": "Listet alle exportierten Projektdateien (relative Pfade).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "prefix": { "type": "string", "description": "Optionales Verzeichnis-Präfix, z.B. src/api" }
+                }
+            }
+        },
+        {
+            "name": "get_file",
+            "description": "Liefert den Markdown-Abschnitt einer einzelnen Datei.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Relativer Dateipfad" }
+                },
+                "required": ["path"]
+            }
+        },
+        {
+            "name": "export_subtree",
+            "description": "Exportiert ein Verzeichnis (oder das ganze Projekt) als Markdown-Dokument.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Relatives Verzeichnis; leer für das ganze Projekt" }
+                }
+            }
+        }
+    ])
+}
+
+fn server_error(e: io::Error) -> Code2MdError {
+    Code2MdError::Server(format!("stdio: {}", e))
+}
+
+/// Erstellt eine JSON-RPC-Fehlerantwort.
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_server(base: &std::path::Path) -> anyhow::Result<McpServer> {
+        let selection = SelectionArgs {
+            directory: base.to_path_buf(),
+            paths: Vec::new(),
+            types: Some(vec!["python".to_string()]),
+            extensions: None,
+            without_extensions: None,
+            all_text: false,
+            excludes: None,
+            only: Vec::new(),
+            no_export_ignore: false,
+            no_default_excludes: false,
+            no_exclude_categories: Vec::new(),
+            on_walk_error: Default::default(),
+            skip_nested_repos: true,
+            follow_symlinks: false,
+        };
+        Ok(McpServer::new(&selection)?)
+    }
+
+    #[test]
+    fn test_initialize_and_notifications() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let server = test_server(dir.path())?;
+
+        let response = server
+            .handle(&json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize" }))
+            .unwrap();
+        assert_eq!(response["result"]["serverInfo"]["name"], "code2md");
+
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert!(server.handle(&notification).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_tools() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::create_dir_all(dir.path().join("src/api"))?;
+        std::fs::write(dir.path().join("src/api/routes.py"), "ROUTES = []")?;
+        std::fs::write(dir.path().join("main.py"), "print(1)")?;
+        let server = test_server(dir.path())?;
+
+        let call = |name: &str, args: Value| {
+            server
+                .handle(&json!({
+                    "jsonrpc": "2.0",
+                    "id": 2,
+                    "method": "tools/call",
+                    "params": { "name": name, "arguments": args },
+                }))
+                .unwrap()
+        };
+
+        let response = call("list_files", json!({ "prefix": "src" }));
+        assert_eq!(response["result"]["content"][0]["text"], "src/api/routes.py");
+
+        let response = call("get_file", json!({ "path": "main.py" }));
+        let text = response["result"]["content"][0]["text"].as_str().unwrap();
+        assert!(text.starts_with("### `main.py`"));
+
+        let response = call("get_file", json!({ "path": "../etc/passwd" }));
+        assert_eq!(response["result"]["isError"], true);
+        Ok(())
+    }
+}
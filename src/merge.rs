@@ -0,0 +1,444 @@
+//! Zusammenführen eines neuen Exports mit einem bestehenden Dokument (`--append`).
+//!
+//! Dateiabschnitte werden durch HTML-Kommentare markiert, die in gerendertem
+//! Markdown unsichtbar sind. Markierungen innerhalb von Codeblöcken werden
+//! ignoriert, damit exportierter Quelltext den Aufbau nicht stört.
+
+use std::path::Path;
+
+use crate::error::{Code2MdError, Result};
+use crate::markdown::toc_entry;
+use crate::tree::tree_from_paths;
+
+const START_PREFIX: &str = "<!-- code2md:file ";
+const MARKER_SUFFIX: &str = " -->";
+const GENERATED_PREFIX: &str = "> Generiert am ";
+const FILE_COUNT_PREFIX: &str = "> Anzahl Dateien: ";
+/// Steht auch in reproduzierbaren Exporten ohne Zeitpunkt im Kopfbereich.
+const BASE_PREFIX: &str = "> Basisverzeichnis: `";
+
+/// Zeilen am Dokumentanfang, in denen [`looks_like_export`] den Kopfbereich sucht.
+const HEADER_LINES: usize = 40;
+
+/// Markierung am Ende eines Dateiabschnitts.
+pub const SECTION_END: &str = "<!-- /code2md:file -->";
+
+/// Markierung am Anfang des Dateiabschnitts für `path`.
+pub fn section_start(path: &str) -> String {
+    format!("{}{}{}", START_PREFIX, path, MARKER_SUFFIX)
+}
+
+/// Teil eines Export-Dokuments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Part {
+    /// Text außerhalb von Dateiabschnitten
+    Text(String),
+    /// Dateiabschnitt inkl. Markierungen
+    Section { path: String, text: String },
+}
+
+impl Part {
+    fn text(&self) -> &str {
+        match self {
+            Part::Text(text) | Part::Section { text, .. } => text,
+        }
+    }
+}
+
+/// Zerlegt ein Dokument in Dateiabschnitte und den Text dazwischen.
+///
+/// Zeilenenden bleiben erhalten; die Teile ergeben zusammengesetzt wieder
+/// das Originaldokument. Ein nicht abgeschlossener Abschnitt gilt als Text.
+pub fn parse_document(document: &str) -> Vec<Part> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut section: Option<String> = None;
+    let mut fence: Option<(char, usize)> = None;
+
+    for line in document.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if let Some(open) = fence {
+            if closes_fence(trimmed, open) {
+                fence = None;
+            }
+        } else if let Some((c, n, _)) = fence_marker(trimmed) {
+            fence = Some((c, n));
+        } else if section.is_none() {
+            if let Some(path) = trimmed
+                .strip_prefix(START_PREFIX)
+                .and_then(|rest| rest.strip_suffix(MARKER_SUFFIX))
+            {
+                if !text.is_empty() {
+                    parts.push(Part::Text(std::mem::take(&mut text)));
+                }
+                section = Some(path.to_string());
+            }
+        } else if trimmed == SECTION_END {
+            text.push_str(line);
+            parts.push(Part::Section {
+                path: section.take().unwrap_or_default(),
+                text: std::mem::take(&mut text),
+            });
+            continue;
+        }
+        text.push_str(line);
+    }
+
+    if !text.is_empty() {
+        parts.push(Part::Text(text));
+    }
+    parts
+}
+
+/// Führt die Dateiabschnitte von `fresh` in das bestehende Dokument ein.
+///
+/// Abschnitte gleicher Pfade werden an ihrer Stelle ersetzt, neue hinter dem
+/// letzten bestehenden Abschnitt angefügt. Dateianzahl, Inhaltsverzeichnis und
+/// Ordnerstruktur werden an die zusammengeführten Abschnitte angepasst; der
+/// übrige Text des bestehenden Dokuments bleibt unverändert.
+pub fn merge_documents(existing: &str, fresh: &str) -> Result<String> {
+    update_document(existing, fresh, &[])
+}
+
+/// Wie [`merge_documents`], entfernt zusätzlich die Abschnitte der Pfade in
+/// `removed` samt der folgenden Leerzeile.
+pub fn update_document(existing: &str, fresh: &str, removed: &[String]) -> Result<String> {
+    let mut parts = parse_document(existing);
+    let Some(last) = parts.iter().rposition(|p| matches!(p, Part::Section { .. })) else {
+        return Err(Code2MdError::Config(
+            "Das bestehende Dokument enthält keine code2md-Dateiabschnitte.".to_string(),
+        ));
+    };
+
+    let fresh_parts = parse_document(fresh);
+    let generated_line = match fresh_parts.first() {
+        Some(Part::Text(preamble)) => preamble
+            .lines()
+            .find(|line| line.starts_with(GENERATED_PREFIX))
+            .map(str::to_string),
+        _ => None,
+    };
+    let mut fresh_sections: Vec<(String, String)> = fresh_parts
+        .into_iter()
+        .filter_map(|part| match part {
+            Part::Section { path, text } => Some((path, text)),
+            Part::Text(_) => None,
+        })
+        .collect();
+
+    for part in &mut parts {
+        if let Part::Section { path, text } = part {
+            if let Some(index) = fresh_sections.iter().position(|(p, _)| p == path) {
+                *text = fresh_sections.remove(index).1;
+            }
+        }
+    }
+
+    let separator = if existing.contains("\r\n") { "\r\n" } else { "\n" };
+    let appended = fresh_sections
+        .into_iter()
+        .flat_map(|(path, text)| [Part::Text(separator.to_string()), Part::Section { path, text }]);
+    parts.splice(last + 1..last + 1, appended);
+
+    let mut index = 0;
+    while index < parts.len() {
+        match &parts[index] {
+            Part::Section { path, .. } if removed.contains(path) => {
+                parts.remove(index);
+                if matches!(parts.get(index), Some(Part::Text(text)) if text.trim().is_empty()) {
+                    parts.remove(index);
+                }
+            }
+            _ => index += 1,
+        }
+    }
+
+    let paths: Vec<&str> = parts
+        .iter()
+        .filter_map(|part| match part {
+            Part::Section { path, .. } => Some(path.as_str()),
+            Part::Text(_) => None,
+        })
+        .collect();
+
+    let mut document = String::new();
+    for (index, part) in parts.iter().enumerate() {
+        match part {
+            Part::Text(text) if index == 0 => document.push_str(&update_overview(text, &paths, generated_line.as_deref(), separator)),
+            part => document.push_str(part.text()),
+        }
+    }
+    Ok(document)
+}
+
+/// Unterschiede zwischen einer vorhandenen und einer neu erzeugten Ausgabe
+/// (`--check`), je Dateiabschnitt.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocumentDiff {
+    pub changed: Vec<String>,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Erste abweichende Zeile (ab 1), `None` bei gleichem Inhalt
+    pub first_line: Option<usize>,
+}
+
+impl DocumentDiff {
+    pub fn is_empty(&self) -> bool {
+        self.first_line.is_none()
+    }
+
+    /// Zusammenfassung, eine Zeile je Datei; ohne abweichende Abschnitte ein
+    /// Hinweis auf die erste abweichende Zeile.
+    pub fn summary(&self) -> Vec<String> {
+        let mut lines: Vec<String> = Vec::new();
+        lines.extend(self.changed.iter().map(|path| format!("geändert: {}", path)));
+        lines.extend(self.added.iter().map(|path| format!("neu:      {}", path)));
+        lines.extend(self.removed.iter().map(|path| format!("entfernt: {}", path)));
+        if let (true, Some(line)) = (lines.is_empty(), self.first_line) {
+            lines.push(format!("Kopfbereich oder Übersicht geändert (ab Zeile {})", line));
+        }
+        lines
+    }
+}
+
+/// Vergleicht zwei Dokumente. Dateiabschnitte werden über ihre Markierungen
+/// zugeordnet; Formate ohne Markierungen liefern nur die erste abweichende Zeile.
+pub fn diff_documents(existing: &str, fresh: &str) -> DocumentDiff {
+    let first_line = existing
+        .split_inclusive('\n')
+        .zip(fresh.split_inclusive('\n'))
+        .position(|(old, new)| old != new)
+        .or_else(|| {
+            let (old, new) = (existing.split_inclusive('\n').count(), fresh.split_inclusive('\n').count());
+            (old != new).then(|| old.min(new))
+        })
+        .map(|index| index + 1);
+    let sections = |document: &str| -> Vec<(String, String)> {
+        parse_document(document)
+            .into_iter()
+            .filter_map(|part| match part {
+                Part::Section { path, text } => Some((path, text)),
+                Part::Text(_) => None,
+            })
+            .collect()
+    };
+    let (old, new) = (sections(existing), sections(fresh));
+    let mut diff = DocumentDiff {
+        first_line,
+        ..Default::default()
+    };
+    for (path, text) in &new {
+        match old.iter().find(|(p, _)| p == path) {
+            Some((_, old_text)) if old_text != text => diff.changed.push(path.clone()),
+            Some(_) => {}
+            None => diff.added.push(path.clone()),
+        }
+    }
+    diff.removed = old
+        .into_iter()
+        .filter(|(path, _)| !new.iter().any(|(p, _)| p == path))
+        .map(|(path, _)| path)
+        .collect();
+    diff
+}
+
+/// Passt Zeitstempel, Dateianzahl, Inhaltsverzeichnis und Ordnerstruktur im
+/// Text vor dem ersten Dateiabschnitt an.
+fn update_overview(preamble: &str, paths: &[&str], generated_line: Option<&str>, separator: &str) -> String {
+    let mut out = String::new();
+    let mut lines = preamble.split_inclusive('\n');
+    let mut toc_written = false;
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim_end();
+
+        // Ohne Zeitpunkt im neuen Export (reproduzierbar) entfällt der alte
+        if trimmed.starts_with(GENERATED_PREFIX) {
+            if let Some(generated) = generated_line {
+                out.push_str(generated);
+                out.push_str(separator);
+            }
+            continue;
+        }
+        if trimmed.starts_with(FILE_COUNT_PREFIX) {
+            out.push_str(&format!("{}{}{}", FILE_COUNT_PREFIX, paths.len(), separator));
+            continue;
+        }
+        if trimmed.starts_with("   - [`") {
+            if !toc_written {
+                for path in paths {
+                    out.push_str(&toc_entry(path));
+                    out.push_str(separator);
+                }
+                toc_written = true;
+            }
+            continue;
+        }
+
+        out.push_str(line);
+        if trimmed != "## Ordnerstruktur" {
+            continue;
+        }
+
+        // Bis zum öffnenden Codeblock übernehmen, dann den Baum neu erzeugen
+        for line in lines.by_ref() {
+            out.push_str(line);
+            if line.trim_end() == "```" {
+                break;
+            }
+        }
+        let mut project_name = String::new();
+        for line in lines.by_ref() {
+            if line.trim_end() == "```" {
+                let rel_paths: Vec<&Path> = paths.iter().map(Path::new).collect();
+                for tree_line in tree_from_paths(&rel_paths, &project_name) {
+                    out.push_str(&tree_line);
+                    out.push_str(separator);
+                }
+                out.push_str(line);
+                break;
+            }
+            if project_name.is_empty() {
+                project_name = line.trim_end().trim_end_matches('/').to_string();
+            }
+        }
+    }
+    out
+}
+
+/// Erkennt eine Codeblock-Begrenzung (```` ``` ```` oder `~~~`) und liefert
+/// Zeichen, Länge und den Rest der Zeile.
+pub fn fence_marker(line: &str) -> Option<(char, usize, &str)> {
+    let line = line.trim_start_matches(' ');
+    let c = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = line.chars().take_while(|x| *x == c).count();
+    (len >= 3).then(|| (c, len, &line[len..]))
+}
+
+/// Prüft, ob ein Inhalt wie ein Markdown-Export von code2md beginnt
+/// (Kopfbereich mit Zeitpunkt oder Basisverzeichnis und Anzahl der Dateien).
+pub fn looks_like_export(content: &[u8]) -> bool {
+    let head = String::from_utf8_lossy(&content[..content.len().min(8192)]);
+    let lines: Vec<&str> = head.lines().take(HEADER_LINES).collect();
+    lines.iter().any(|line| line.starts_with(GENERATED_PREFIX) || line.starts_with(BASE_PREFIX))
+        && lines.iter().any(|line| line.starts_with(FILE_COUNT_PREFIX))
+}
+
+/// Prüft, ob eine Zeile den geöffneten Codeblock schließt.
+pub fn closes_fence(line: &str, (open_char, open_len): (char, usize)) -> bool {
+    fence_marker(line).is_some_and(|(c, len, rest)| c == open_char && len >= open_len && rest.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_looks_like_export() {
+        let export = document(&[("a.rs", "fn a() {}")]).replace("# demo\n", "# demo\n\n> Generiert am 01.01.2024 10:00:00\n");
+        assert!(looks_like_export(export.as_bytes()));
+        assert!(!looks_like_export(b"# README\n\n> Generiert am Montag\n"));
+        let reproducible = document(&[("a.rs", "fn a() {}")]).replace("# demo\n", "# demo\n\n> Basisverzeichnis: `.`\n");
+        assert!(looks_like_export(reproducible.as_bytes()));
+        assert!(!looks_like_export(include_bytes!("merge.rs")));
+    }
+
+    #[test]
+    fn test_diff_documents() {
+        let old = document(&[("a.rs", "fn a() {}"), ("b.rs", "fn b() {}")]);
+        assert!(diff_documents(&old, &old).is_empty());
+        assert!(diff_documents(&old, &old).summary().is_empty());
+
+        let new = document(&[("a.rs", "fn a() { 1 }"), ("c.rs", "fn c() {}")]);
+        let diff = diff_documents(&old, &new);
+        assert_eq!(diff.changed, vec!["a.rs"]);
+        assert_eq!(diff.added, vec!["c.rs"]);
+        assert_eq!(diff.removed, vec!["b.rs"]);
+        assert_eq!(diff.summary().len(), 3);
+
+        let diff = diff_documents(&old, &old.replace("# demo", "# anders"));
+        assert_eq!(diff.first_line, Some(1));
+        assert_eq!(diff.summary(), vec!["Kopfbereich oder Übersicht geändert (ab Zeile 1)"]);
+        assert_eq!(diff_documents("a\n", "a\nb\n").first_line, Some(2));
+    }
+
+    fn section(path: &str, content: &str) -> String {
+        format!(
+            "{}\n### `{}`\n\n```\n{}\n```\n\n{}\n",
+            section_start(path),
+            path,
+            content,
+            SECTION_END
+        )
+    }
+
+    fn document(sections: &[(&str, &str)]) -> String {
+        let paths: Vec<&Path> = sections.iter().map(|(p, _)| Path::new(*p)).collect();
+        let mut doc = format!(
+            "# demo\n\n> Anzahl Dateien: {}\n\n## Inhaltsverzeichnis\n\n1. [Ordnerstruktur](#ordnerstruktur)\n2. [Dateien](#dateien)\n",
+            sections.len()
+        );
+        for (path, _) in sections {
+            doc.push_str(&toc_entry(path));
+            doc.push('\n');
+        }
+        doc.push_str("\n---\n\n## Ordnerstruktur\n\n```\n");
+        for line in tree_from_paths(&paths, "demo") {
+            doc.push_str(&line);
+            doc.push('\n');
+        }
+        doc.push_str("```\n\n---\n\n## Dateien\n\n");
+        for (path, content) in sections {
+            doc.push_str(&section(path, content));
+            doc.push('\n');
+        }
+        doc
+    }
+
+    #[test]
+    fn test_parse_ignores_markers_in_code() {
+        let content = format!("{}\n{}", section_start("fake.rs"), SECTION_END);
+        let doc = format!("Vorspann\n{}", section("a.rs", &content));
+        let parts = parse_document(&doc);
+
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0], Part::Text("Vorspann\n".to_string()));
+        assert!(matches!(&parts[1], Part::Section { path, .. } if path == "a.rs"));
+        assert_eq!(parts.iter().map(Part::text).collect::<String>(), doc);
+    }
+
+    #[test]
+    fn test_merge_documents() -> anyhow::Result<()> {
+        let existing = document(&[("a.rs", "alt"), ("b.rs", "bleibt")]);
+        let existing = existing.replace("## Dateien\n", "## Dateien\n\nManuelle Notiz.\n");
+        let fresh = document(&[("a.rs", "neu"), ("c.rs", "dazu")]);
+
+        let merged = merge_documents(&existing, &fresh)?;
+        let expected = document(&[("a.rs", "neu"), ("b.rs", "bleibt"), ("c.rs", "dazu")])
+            .replace("## Dateien\n", "## Dateien\n\nManuelle Notiz.\n");
+        assert_eq!(merged, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_document_removes_sections() -> anyhow::Result<()> {
+        let existing = document(&[("a.rs", "alt"), ("b.rs", "weg"), ("c.rs", "bleibt")])
+            .replace("# demo\n", "# demo\n\n> Generiert am 01.01.2024 10:00:00\n");
+        let fresh = document(&[("a.rs", "neu")]).replace("# demo\n", "# demo\n\n> Generiert am 02.01.2024 10:00:00\n");
+
+        let updated = update_document(&existing, &fresh, &["b.rs".to_string()])?;
+        let expected = document(&[("a.rs", "neu"), ("c.rs", "bleibt")])
+            .replace("# demo\n", "# demo\n\n> Generiert am 02.01.2024 10:00:00\n");
+        assert_eq!(updated, expected);
+
+        // Reproduzierbar erzeugt: ohne Zeitpunkt entfällt auch der alte
+        let updated = update_document(&existing, &document(&[("a.rs", "neu")]), &["b.rs".to_string()])?;
+        assert_eq!(updated, document(&[("a.rs", "neu"), ("c.rs", "bleibt")]).replace("# demo\n", "# demo\n\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_requires_sections() {
+        assert!(merge_documents("# Kein Export\n", &document(&[("a.rs", "x")])).is_err());
+    }
+}
@@ -0,0 +1,159 @@
+//! Projektangaben aus Paketmanifesten (Cargo.toml, package.json, pyproject.toml).
+
+use std::path::Path;
+
+/// Name, Version, Beschreibung und Lizenz eines Projekts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectMetadata {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub license: Option<String>,
+    /// Dateiname des Manifests, aus dem die Angaben stammen
+    pub source: String,
+}
+
+impl ProjectMetadata {
+    /// Zeilen für den Kopfbereich des Dokuments (ohne `> `).
+    pub fn header_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(name) = &self.name {
+            let version = self.version.as_deref().map(|v| format!(" {}", v)).unwrap_or_default();
+            lines.push(format!("Paket: `{}`{} (aus `{}`)", name, version, self.source));
+        }
+        if let Some(description) = &self.description {
+            lines.push(format!("Beschreibung: {}", description.split_whitespace().collect::<Vec<_>>().join(" ")));
+        }
+        if let Some(license) = &self.license {
+            lines.push(format!("Lizenz: {}", license));
+        }
+        lines
+    }
+
+    /// Ersetzt `{name}`, `{version}`, `{description}` und `{license}` in einer
+    /// Vorlage. Gibt `None` zurück, wenn ein verwendeter Wert fehlt.
+    pub fn fill(&self, template: &str) -> Option<String> {
+        let mut result = template.to_string();
+        for (placeholder, value) in [
+            ("{name}", &self.name),
+            ("{version}", &self.version),
+            ("{description}", &self.description),
+            ("{license}", &self.license),
+        ] {
+            if result.contains(placeholder) {
+                result = result.replace(placeholder, value.as_deref()?);
+            }
+        }
+        Some(result)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.name.is_none() && self.version.is_none() && self.description.is_none() && self.license.is_none()
+    }
+}
+
+/// Liest die Projektangaben aus dem ersten vorhandenen Manifest im
+/// Basisverzeichnis. Ungültige Manifeste werden ignoriert.
+pub fn detect_metadata(base_path: &Path) -> Option<ProjectMetadata> {
+    let read = |name: &str| std::fs::read_to_string(base_path.join(name)).ok();
+
+    read("Cargo.toml")
+        .and_then(|content| from_cargo(&content))
+        .or_else(|| read("package.json").and_then(|content| from_package_json(&content)))
+        .or_else(|| read("pyproject.toml").and_then(|content| from_pyproject(&content)))
+        .filter(|metadata| !metadata.is_empty())
+}
+
+/// `[package]` bzw. `[workspace.package]` einer Cargo.toml.
+fn from_cargo(content: &str) -> Option<ProjectMetadata> {
+    let value: toml::Value = toml::from_str(content).ok()?;
+    let package = value
+        .get("package")
+        .or_else(|| value.get("workspace").and_then(|w| w.get("package")))?;
+    Some(ProjectMetadata {
+        name: toml_str(package, "name"),
+        version: toml_str(package, "version"),
+        description: toml_str(package, "description"),
+        license: toml_str(package, "license"),
+        source: "Cargo.toml".to_string(),
+    })
+}
+
+fn from_package_json(content: &str) -> Option<ProjectMetadata> {
+    let value: serde_json::Value = serde_json::from_str(content).ok()?;
+    let field = |key: &str| value.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    Some(ProjectMetadata {
+        name: field("name"),
+        version: field("version"),
+        description: field("description"),
+        // Veraltete Form: "license": { "type": "MIT" }
+        license: field("license").or_else(|| {
+            value
+                .get("license")
+                .and_then(|l| l.get("type"))
+                .and_then(|t| t.as_str())
+                .map(str::to_string)
+        }),
+        source: "package.json".to_string(),
+    })
+}
+
+/// `[project]` (PEP 621) oder `[tool.poetry]` einer pyproject.toml.
+fn from_pyproject(content: &str) -> Option<ProjectMetadata> {
+    let value: toml::Value = toml::from_str(content).ok()?;
+    let project = value
+        .get("project")
+        .or_else(|| value.get("tool").and_then(|t| t.get("poetry")))?;
+    // PEP 621 erlaubt license = { text = "…" } oder { file = "…" }
+    let license = toml_str(project, "license").or_else(|| {
+        project
+            .get("license")
+            .and_then(|l| l.get("text").or_else(|| l.get("file")))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    });
+    Some(ProjectMetadata {
+        name: toml_str(project, "name"),
+        version: toml_str(project, "version"),
+        description: toml_str(project, "description"),
+        license,
+        source: "pyproject.toml".to_string(),
+    })
+}
+
+fn toml_str(table: &toml::Value, key: &str) -> Option<String> {
+    table.get(key).and_then(|v| v.as_str()).map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_metadata() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        assert_eq!(detect_metadata(dir.path()), None);
+
+        std::fs::write(
+            dir.path().join("pyproject.toml"),
+            "[project]\nname = \"demo\"\nversion = \"0.3.1\"\nlicense = { text = \"MIT\" }\n",
+        )?;
+        let metadata = detect_metadata(dir.path()).unwrap_or_default();
+        assert_eq!(
+            metadata.header_lines(),
+            vec!["Paket: `demo` 0.3.1 (aus `pyproject.toml`)", "Lizenz: MIT"]
+        );
+
+        // Cargo.toml hat Vorrang
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"code2md\"\ndescription = \"\"\"Export von\n  Quellcode\"\"\"\n",
+        )?;
+        let metadata = detect_metadata(dir.path()).unwrap_or_default();
+        assert_eq!(metadata.name.as_deref(), Some("code2md"));
+        assert_eq!(metadata.header_lines()[1], "Beschreibung: Export von Quellcode");
+        assert_eq!(metadata.fill("![{name}](x)").as_deref(), Some("![code2md](x)"));
+        assert_eq!(metadata.fill("version-{version}"), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,245 @@
+//! Einfache Code-Metriken je Datei für `--metrics`.
+//!
+//! Die Werte beruhen auf zeilenweisen Heuristiken und sollen nur zeigen, wo
+//! sich ein genauerer Blick lohnt.
+
+use std::path::Path;
+
+use clap::ValueEnum;
+
+use crate::collector::CollectedFile;
+use crate::docs::apply_braces;
+use crate::processor::relative_path;
+use crate::types::get_syntax_for_file;
+
+/// Sortierung der Metrik-Tabelle (jeweils absteigend).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum MetricsSort {
+    /// Codezeilen
+    #[default]
+    Loc,
+    /// Kommentaranteil
+    Comments,
+    /// Maximale Verschachtelungstiefe
+    Nesting,
+    /// Anzahl Funktionen
+    Functions,
+}
+
+/// Metriken einer Datei.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FileMetrics {
+    /// Codezeilen ohne Leer- und reine Kommentarzeilen
+    pub loc: usize,
+    pub comment_lines: usize,
+    pub max_nesting: usize,
+    pub functions: usize,
+}
+
+impl FileMetrics {
+    /// Kommentaranteil in Prozent der Code- und Kommentarzeilen.
+    pub fn comment_ratio(&self) -> usize {
+        let total = self.loc + self.comment_lines;
+        (self.comment_lines * 100 + total / 2).checked_div(total).unwrap_or(0)
+    }
+
+    /// Kurzform für die Metadaten-Zeile einer Datei.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} LOC, {} % Kommentare, Tiefe {}, {} {}",
+            self.loc,
+            self.comment_ratio(),
+            self.max_nesting,
+            self.functions,
+            if self.functions == 1 { "Funktion" } else { "Funktionen" }
+        )
+    }
+}
+
+/// Wie Kommentare, Verschachtelung und Funktionen einer Sprache erkannt werden.
+#[derive(Debug, Clone, Copy)]
+struct Style {
+    line_comment: &'static str,
+    /// Verschachtelung über `{}` statt über die Einrückung
+    braces: bool,
+    functions: Functions,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Functions {
+    /// Schlüsselwort vor dem Namen, z.B. `fn` oder `def`
+    Keyword(&'static [&'static str]),
+    /// Signatur im C-Stil: `typ name(...) {`
+    CStyle,
+}
+
+fn style(syntax: &str) -> Option<Style> {
+    let braced = |functions| Some(Style { line_comment: "//", braces: true, functions });
+    let indented = |line_comment, keywords| Some(Style { line_comment, braces: false, functions: Functions::Keyword(keywords) });
+    match syntax {
+        "rust" => braced(Functions::Keyword(&["fn"])),
+        "go" => braced(Functions::Keyword(&["func"])),
+        "javascript" | "typescript" | "jsx" | "tsx" | "vue" => braced(Functions::Keyword(&["function", "=>"])),
+        "php" => braced(Functions::Keyword(&["function"])),
+        "kotlin" => braced(Functions::Keyword(&["fun"])),
+        "java" | "csharp" | "c" | "cpp" | "dart" => braced(Functions::CStyle),
+        "python" => indented("#", &["def"]),
+        "ruby" => indented("#", &["def"]),
+        "bash" | "zsh" | "fish" | "powershell" => indented("#", &["function"]),
+        _ => None,
+    }
+}
+
+/// Berechnet die Metriken eines Quelltexts; `None` für Sprachen ohne Regeln.
+pub fn compute_metrics(rel_path: &str, content: &str) -> Option<FileMetrics> {
+    let filename = rel_path.rsplit(['/', '\\']).next().unwrap_or(rel_path);
+    let style = style(get_syntax_for_file(filename))?;
+    let mut metrics = FileMetrics::default();
+    let mut in_block_comment = false;
+    let mut depth = 0;
+    let mut indents: Vec<usize> = Vec::new();
+    let lines: Vec<&str> = content.lines().collect();
+
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        // Kommentare
+        if in_block_comment {
+            metrics.comment_lines += 1;
+            in_block_comment = !trimmed.contains("*/");
+            continue;
+        }
+        if trimmed.starts_with(style.line_comment) {
+            metrics.comment_lines += 1;
+            continue;
+        }
+        if style.braces && trimmed.starts_with("/*") {
+            metrics.comment_lines += 1;
+            in_block_comment = !trimmed.contains("*/");
+            continue;
+        }
+        metrics.loc += 1;
+
+        // Verschachtelung
+        if style.braces {
+            depth = apply_braces(depth, line);
+            metrics.max_nesting = metrics.max_nesting.max(depth);
+        } else {
+            let indent = line.len() - line.trim_start().len();
+            while indents.last().is_some_and(|&last| last >= indent) {
+                indents.pop();
+            }
+            indents.push(indent);
+            // Eingerückte Zeilen liegen eine Ebene unter ihrem Block
+            metrics.max_nesting = metrics.max_nesting.max(indents.len() - 1);
+        }
+
+        // Funktionen
+        let is_function = match style.functions {
+            Functions::Keyword(keywords) => keywords.iter().any(|keyword| has_keyword(trimmed, keyword)),
+            Functions::CStyle => {
+                let next = lines[i + 1..].iter().map(|l| l.trim()).find(|l| !l.is_empty());
+                is_c_signature(trimmed, next)
+            }
+        };
+        if is_function {
+            metrics.functions += 1;
+        }
+    }
+
+    Some(metrics)
+}
+
+/// Metriken aller Dateien mit bekannter Sprache.
+pub fn collect_metrics(files: &[CollectedFile], base_path: &Path) -> Vec<(String, FileMetrics)> {
+    files
+        .iter()
+        .filter_map(|file| {
+            let rel_str = relative_path(&file.path, base_path);
+            let content = file.text().ok()?;
+            compute_metrics(&rel_str, &content).map(|metrics| (rel_str, metrics))
+        })
+        .collect()
+}
+
+/// Sortiert Metriken absteigend nach dem gewählten Wert, bei Gleichstand nach Pfad.
+pub fn sort_metrics(metrics: &mut [(String, FileMetrics)], sort: MetricsSort) {
+    let key = |m: &FileMetrics| match sort {
+        MetricsSort::Loc => m.loc,
+        MetricsSort::Comments => m.comment_ratio(),
+        MetricsSort::Nesting => m.max_nesting,
+        MetricsSort::Functions => m.functions,
+    };
+    metrics.sort_by(|a, b| key(&b.1).cmp(&key(&a.1)).then_with(|| a.0.cmp(&b.0)));
+}
+
+/// Prüft, ob ein Schlüsselwort als eigenes Wort vor einem Namen bzw. einer
+/// Parameterliste steht. `=>` zählt überall.
+fn has_keyword(line: &str, keyword: &str) -> bool {
+    if keyword == "=>" {
+        return line.contains("=>");
+    }
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    line.match_indices(keyword).any(|(pos, _)| {
+        let before = line[..pos].chars().next_back();
+        let after = line[pos + keyword.len()..].chars().next();
+        !before.is_some_and(is_ident) && after.is_some_and(|c| c == ' ' || c == '(')
+    })
+}
+
+/// Erkennt Funktionsdefinitionen im C-Stil, deren Rumpf auf derselben oder
+/// der nächsten Zeile beginnt.
+fn is_c_signature(line: &str, next: Option<&str>) -> bool {
+    const CONTROL: &[&str] = &[
+        "if", "else", "for", "foreach", "while", "do", "switch", "catch", "try", "return", "new", "using", "lock",
+        "synchronized",
+    ];
+    // `} else if (...) {` beginnt mit der schließenden Klammer des Vorgängers
+    let line = line.trim_start_matches(['}', ' ']);
+    let Some(paren) = line.find('(') else {
+        return false;
+    };
+    let first_word: String = line.chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+    let opens_body = line.ends_with('{') || (line.ends_with(')') && next == Some("{"));
+    opens_body
+        && !CONTROL.contains(&first_word.as_str())
+        && !line[..paren].contains('=')
+        && line[..paren].split_whitespace().count() >= 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_metrics() {
+        let rust = "// Einstieg\nfn main() {\n    if true {\n        run();\n    }\n}\n\n/* Hilfs-\n   funktion */\nfn run() {}\n";
+        let metrics = compute_metrics("src/main.rs", rust).unwrap_or_default();
+        assert_eq!(metrics, FileMetrics { loc: 6, comment_lines: 3, max_nesting: 2, functions: 2 });
+        assert_eq!(metrics.comment_ratio(), 33);
+
+        let python = "def a():\n    for x in y:\n        pass\n\n# Ende\nclass B:\n    def c(self):\n        return 1\n";
+        let metrics = compute_metrics("app.py", python).unwrap_or_default();
+        assert_eq!(metrics, FileMetrics { loc: 6, comment_lines: 1, max_nesting: 2, functions: 2 });
+
+        let java = "public class A {\n    public int size()\n    {\n        if (x) {\n            return 1;\n        } else if (y) {\n            return 2;\n        }\n    }\n}\n";
+        assert_eq!(compute_metrics("A.java", java).map(|m| m.functions), Some(1));
+        assert_eq!(compute_metrics("README.md", "# Titel"), None);
+    }
+
+    #[test]
+    fn test_sort_metrics() {
+        let metric = |loc, max_nesting| FileMetrics { loc, max_nesting, ..Default::default() };
+        let mut metrics = vec![
+            ("a.rs".to_string(), metric(10, 5)),
+            ("b.rs".to_string(), metric(30, 1)),
+            ("c.rs".to_string(), metric(20, 5)),
+        ];
+        sort_metrics(&mut metrics, MetricsSort::Nesting);
+        let order: Vec<&str> = metrics.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(order, vec!["a.rs", "c.rs", "b.rs"]);
+    }
+}
@@ -0,0 +1,208 @@
+//! Ausgabe als Obsidian-Vault (`--flavor obsidian`).
+//!
+//! Jede Datei wird zu einer eigenen Notiz mit YAML-Front-Matter im Ordner
+//! neben der Übersichtsnotiz; Verweise verwenden Wikilinks.
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::collector::CollectedFile;
+use crate::events::ExportEvent;
+use crate::imports::ImportIndex;
+use crate::markdown::{file_blocks, generated_at, render_blocks, yaml_string, MarkdownConfig};
+use crate::processor::relative_path;
+use crate::tree::generate_tree;
+use crate::types::get_syntax_for_file;
+
+/// Ordner der Notizen: Ausgabepfad ohne Endung.
+pub fn vault_dir(output_path: &Path) -> PathBuf {
+    output_path.with_extension("")
+}
+
+/// Schreibt die Übersichtsnotiz nach `output_path` und je Datei eine Notiz
+/// in den Vault-Ordner. Gibt alle geschriebenen Dateien zurück.
+pub fn write_vault(files: &[CollectedFile], config: &MarkdownConfig, output_path: &Path) -> io::Result<Vec<PathBuf>> {
+    let dir = vault_dir(output_path);
+    let folder = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let index_note = output_path
+        .file_stem()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let note_link = |rel_str: &str| format!("[[{}/{}.md|{}]]", folder, rel_str, rel_str);
+
+    // Imports werden immer als Wikilinks aufgelöst
+    let rel_paths: Vec<String> = files.iter().map(|f| relative_path(&f.path, &config.base_path)).collect();
+    let owned_index;
+    let imports = match &config.imports {
+        Some(index) => index,
+        None => {
+            owned_index = ImportIndex::new(rel_paths.iter().cloned());
+            &owned_index
+        }
+    };
+
+    let mut written = Vec::with_capacity(files.len() + 1);
+    for (index, (file, rel_str)) in files.iter().zip(&rel_paths).enumerate() {
+        let path = dir.join(format!("{}.md", rel_str));
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let blocks = file_blocks(file, rel_str, config);
+        let syntax = get_syntax_for_file(rel_str.rsplit('/').next().unwrap_or(rel_str));
+        let mut note = front_matter(&note_properties(rel_str, syntax, file.size()), &note_tags(rel_str, syntax));
+        note.push(format!("# {}", rel_str.rsplit('/').next().unwrap_or(rel_str)));
+        note.push(String::new());
+        note.push(format!("> Pfad: `{}` · Übersicht: [[{}]]", rel_str, index_note));
+        let linked: Vec<String> = file
+            .text()
+            .map(|content| imports.resolve(rel_str, &content))
+            .unwrap_or_default()
+            .iter()
+            .map(|target| note_link(target))
+            .collect();
+        if !linked.is_empty() {
+            note.push(format!("> Imports: {}", linked.join(", ")));
+        }
+        note.push(String::new());
+        if let Some(description) = config.descriptions.describe(rel_str) {
+            note.extend(description.trim().lines().map(|line| format!("> {}", line).trim_end().to_string()));
+            note.push(String::new());
+        }
+        note.extend(render_blocks(blocks));
+
+        write_note(&path, &note, config)?;
+        written.push(path);
+        config.events.emit(ExportEvent::SectionWritten {
+            path: rel_str.clone(),
+            index: index + 1,
+            total: files.len(),
+        });
+    }
+
+    // Übersichtsnotiz
+    let mut properties = BTreeMap::new();
+    properties.insert("project", yaml_string(&config.project_name));
+    if let Some(now) = generated_at(config) {
+        properties.insert("generated", yaml_string(&now.format("%Y-%m-%d %H:%M:%S").to_string()));
+    }
+    properties.insert("files", files.len().to_string());
+    for (key, value) in &config.meta {
+        properties.entry(key.as_str()).or_insert_with(|| yaml_string(value));
+    }
+    let mut note = front_matter(&properties, &["code".to_string(), "code/index".to_string()]);
+    note.push(format!("# {}", config.project_name));
+    note.push(String::new());
+    if config.include_tree {
+        note.push("## Ordnerstruktur".to_string());
+        note.push(String::new());
+        note.push("```".to_string());
+        note.extend(generate_tree(files, &config.base_path, &config.project_name, &BTreeMap::new(), &config.tree));
+        note.push("```".to_string());
+        note.push(String::new());
+    }
+    note.push("## Dateien".to_string());
+    note.push(String::new());
+    note.extend(rel_paths.iter().map(|rel_str| format!("- {}", note_link(rel_str))));
+    note.push(String::new());
+    write_note(output_path, &note, config)?;
+    written.insert(0, output_path.to_path_buf());
+
+    Ok(written)
+}
+
+fn write_note(path: &Path, lines: &[String], config: &MarkdownConfig) -> io::Result<()> {
+    let line_ending = config.transform.line_ending;
+    let mut out = io::BufWriter::new(std::fs::File::create(path)?);
+    for line in lines {
+        out.write_all(line_ending.apply(line).as_bytes())?;
+        out.write_all(line_ending.separator().as_bytes())?;
+    }
+    out.flush()
+}
+
+/// Eigenschaften einer Dateinotiz.
+fn note_properties(rel_str: &str, syntax: &str, size: u64) -> BTreeMap<&'static str, String> {
+    let mut properties = BTreeMap::new();
+    properties.insert("path", yaml_string(rel_str));
+    if !syntax.is_empty() {
+        properties.insert("language", yaml_string(syntax));
+    }
+    properties.insert("size", size.to_string());
+    properties
+}
+
+/// Tags einer Dateinotiz: `code`, Sprache und Verzeichnis.
+fn note_tags(rel_str: &str, syntax: &str) -> Vec<String> {
+    let mut tags = vec!["code".to_string()];
+    if !syntax.is_empty() {
+        tags.push(format!("lang/{}", tag_part(syntax)));
+    }
+    if let Some((dir, _)) = rel_str.rsplit_once('/') {
+        let parts: Vec<String> = dir.split('/').map(tag_part).collect();
+        tags.push(format!("dir/{}", parts.join("/")));
+    }
+    tags
+}
+
+/// Ersetzt Zeichen, die in Obsidian-Tags nicht erlaubt sind.
+fn tag_part(part: &str) -> String {
+    part.chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' || c == '-' { c } else { '-' })
+        .collect()
+}
+
+/// YAML-Front-Matter mit Eigenschaften und Tags.
+fn front_matter(properties: &BTreeMap<&str, String>, tags: &[String]) -> Vec<String> {
+    let mut lines = vec!["---".to_string()];
+    lines.extend(properties.iter().map(|(key, value)| format!("{}: {}", key, value)));
+    lines.push("tags:".to_string());
+    lines.extend(tags.iter().map(|tag| format!("  - {}", tag)));
+    lines.push("---".to_string());
+    lines.push(String::new());
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_vault() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().join("app");
+        std::fs::create_dir_all(base.join("src/my util"))?;
+        std::fs::write(base.join("src/main.py"), "from .helpers import greet\n")?;
+        std::fs::write(base.join("src/helpers.py"), "def greet(): pass\n")?;
+        let files = vec![
+            CollectedFile::from_path(base.join("src/helpers.py"))?,
+            CollectedFile::from_path(base.join("src/main.py"))?,
+        ];
+        let config = MarkdownConfig {
+            project_name: "app".to_string(),
+            base_path: base,
+            ..Default::default()
+        };
+
+        let output = dir.path().join("app_code.md");
+        let written = write_vault(&files, &config, &output)?;
+        assert_eq!(written.len(), 3);
+
+        let index = std::fs::read_to_string(&output)?;
+        assert!(index.starts_with("---\nfiles: 2\n"));
+        assert!(index.contains("- [[app_code/src/main.py.md|src/main.py]]\n"));
+
+        let note = std::fs::read_to_string(dir.path().join("app_code/src/main.py.md"))?;
+        assert!(note.starts_with(
+            "---\nlanguage: \"python\"\npath: \"src/main.py\"\nsize: 27\ntags:\n  - code\n  - lang/python\n  - dir/src\n---\n\n# main.py\n"
+        ));
+        assert!(note.contains("> Imports: [[app_code/src/helpers.py.md|src/helpers.py]]\n"));
+        assert!(note.contains("```python\nfrom .helpers import greet\n```"));
+        assert_eq!(note_tags("a b/c.rs", "rust"), vec!["code", "lang/rust", "dir/a-b"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,361 @@
+//! Schreiben der Ausgabedatei, optional komprimiert oder als Archiv gebündelt.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use flate2::write::GzEncoder;
+
+use crate::manifest::manifest_path;
+use crate::markdown::format_size;
+use crate::merge::looks_like_export;
+use crate::obsidian::vault_dir;
+use crate::paths;
+
+/// Kompressionsverfahren für die Ausgabedatei.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Compression {
+    /// gzip (`.gz`)
+    Gzip,
+    /// Zstandard (`.zst`)
+    Zstd,
+}
+
+impl Compression {
+    /// Gibt die Dateiendung ohne Punkt zurück.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Compression::Gzip => "gz",
+            Compression::Zstd => "zst",
+        }
+    }
+}
+
+/// Archivformat für `--bundle`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Bundle {
+    /// ZIP-Archiv mit allen erzeugten Dateien
+    Zip,
+}
+
+/// Prüft, ob eine Datei zur Ausgabe gehört: die Ausgabedatei selbst, ihre
+/// Teile (`--split`), das Manifest, das Archiv oder eine Notiz im Vault
+/// (`--flavor obsidian`). `path` muss kanonisch sein.
+pub fn is_own_output(path: &Path, output_path: &Path) -> bool {
+    let Some(name) = output_path.file_name() else {
+        return false;
+    };
+    let dir = output_path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let Ok(dir) = paths::canonicalize(dir) else {
+        return false;
+    };
+    let output = dir.join(name);
+    if path == output
+        || path == manifest_path(&output)
+        || path == bundle_path(&output, Bundle::Zip)
+        || path.starts_with(vault_dir(&output))
+    {
+        return true;
+    }
+
+    // Teile: <stem>.part<N>.<ext> neben der Ausgabedatei
+    let stem = output.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let ext = output.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_else(|| "md".to_string());
+    path.parent() == Some(dir.as_path())
+        && path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .and_then(|n| {
+                let number = n.strip_prefix(&format!("{}.part", stem))?.strip_suffix(&format!(".{}", ext))?.to_string();
+                Some(!number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+            })
+            .unwrap_or(false)
+}
+
+/// Gibt den Pfad des Archivs zur Ausgabedatei zurück.
+pub fn bundle_path(output_path: &Path, bundle: Bundle) -> PathBuf {
+    match bundle {
+        Bundle::Zip => output_path.with_extension("zip"),
+    }
+}
+
+/// Packt die erzeugten Dateien (Dokument, Manifest, …) in ein ZIP-Archiv.
+///
+/// Die Dateien liegen im Archiv ohne Verzeichnis unter ihrem Dateinamen.
+pub fn write_zip_bundle(archive_path: &Path, files: &[PathBuf]) -> io::Result<()> {
+    let mut zip = zip::ZipWriter::new(BufWriter::new(File::create(archive_path)?));
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    for file in files {
+        let name = file
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        zip.start_file(name, options).map_err(io::Error::other)?;
+        io::copy(&mut File::open(file)?, &mut zip)?;
+    }
+
+    zip.finish().map_err(io::Error::other)?.flush()
+}
+
+/// Anzahl Bytes, die [`written_by_code2md`] vom Dateianfang prüft.
+const HEAD_LEN: u64 = 8192;
+
+/// Liest höchstens die ersten [`HEAD_LEN`] Bytes einer Datei, etwa für
+/// [`looks_like_export`](crate::merge::looks_like_export). Anders als
+/// [`CollectedFile::bytes`](crate::collector::CollectedFile::bytes) bleibt
+/// nichts im Speicher zurück.
+pub fn read_head(path: &Path) -> io::Result<Vec<u8>> {
+    let mut head = Vec::new();
+    File::open(path)?.take(HEAD_LEN).read_to_end(&mut head)?;
+    Ok(head)
+}
+
+/// Prüft anhand des Dateianfangs, ob eine Datei von code2md stammt:
+/// Markdown mit Export-Kopfbereich oder `<!-- code2md:`-Markierungen, die
+/// Übersichtsnotiz eines Vaults, XML mit `<documents>`, eine Confluence-Seite
+/// oder JSONL-Datensätze mit `path` und `content`. Mit `.gz` bzw. `.zst`
+/// komprimierte Ausgaben werden dafür entpackt.
+pub fn written_by_code2md(path: &Path) -> io::Result<bool> {
+    let file = File::open(path)?;
+    let reader: Box<dyn Read> = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("gz") => Box::new(flate2::read::GzDecoder::new(file)),
+        Some("zst") => Box::new(zstd::Decoder::new(file)?),
+        _ => Box::new(file),
+    };
+    let mut head = Vec::new();
+    // Nicht entpackbare Dateien stammen nicht von code2md
+    if reader.take(HEAD_LEN).read_to_end(&mut head).is_err() {
+        return Ok(false);
+    }
+    let text = String::from_utf8_lossy(&head);
+    let first_line = text.lines().next().unwrap_or_default();
+    let jsonl_record = serde_json::from_str::<serde_json::Value>(first_line)
+        .is_ok_and(|record| record.get("path").is_some() && record.get("content").is_some());
+    Ok(looks_like_export(&head)
+        || text.contains("<!-- code2md:")
+        || (first_line == "---" && text.contains("\n  - code/index\n"))
+        || first_line == "<documents>"
+        || first_line.starts_with("<p>Generiert am ")
+        || first_line.starts_with("<p>Anzahl Dateien: ")
+        || jsonl_record)
+}
+
+/// Pfad der Sicherung einer Ausgabedatei (`--backup`): `<datei>.bak`.
+pub fn backup_path(output_path: &Path) -> PathBuf {
+    let mut name = output_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    output_path.with_file_name(name)
+}
+
+/// Verhalten, wenn die Ausgabe `--max-output-size` überschreiten würde.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum OversizePolicy {
+    /// Export abbrechen
+    #[default]
+    Abort,
+    /// In Teile unterhalb der Höchstgröße aufteilen (wie `--split size`)
+    Split,
+    /// Dateien am Ende weglassen und einen Hinweis einfügen
+    Truncate,
+}
+
+/// Schreibziel, das nach `limit` Bytes mit [`io::ErrorKind::FileTooLarge`]
+/// abbricht, statt die Ausgabe weiter wachsen zu lassen.
+pub struct SizeLimit<W> {
+    inner: W,
+    limit: u64,
+    written: u64,
+}
+
+impl<W: Write> SizeLimit<W> {
+    pub fn new(inner: W, limit: u64) -> Self {
+        Self { inner, limit, written: 0 }
+    }
+
+    /// Gibt das innere Schreibziel zurück.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for SizeLimit<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written + buf.len() as u64 > self.limit {
+            return Err(io::Error::new(
+                io::ErrorKind::FileTooLarge,
+                format!("Ausgabe überschreitet --max-output-size ({})", format_size(self.limit)),
+            ));
+        }
+        let written = self.inner.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Ausgabedatei, die beim Schreiben komprimiert wird.
+pub enum OutputWriter {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    Zstd(zstd::Encoder<'static, BufWriter<File>>),
+}
+
+impl OutputWriter {
+    /// Legt die Ausgabedatei an.
+    pub fn create(path: &Path, compression: Option<Compression>) -> io::Result<Self> {
+        let file = BufWriter::new(File::create(path)?);
+        Ok(match compression {
+            None => OutputWriter::Plain(file),
+            Some(Compression::Gzip) => OutputWriter::Gzip(GzEncoder::new(file, flate2::Compression::default())),
+            Some(Compression::Zstd) => OutputWriter::Zstd(zstd::Encoder::new(file, 0)?),
+        })
+    }
+
+    /// Schließt den komprimierten Datenstrom ab und schreibt alle Puffer.
+    pub fn finish(self) -> io::Result<()> {
+        let mut file = match self {
+            OutputWriter::Plain(file) => file,
+            OutputWriter::Gzip(encoder) => encoder.finish()?,
+            OutputWriter::Zstd(encoder) => encoder.finish()?,
+        };
+        file.flush()
+    }
+}
+
+impl Write for OutputWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            OutputWriter::Plain(file) => file.write(buf),
+            OutputWriter::Gzip(encoder) => encoder.write(buf),
+            OutputWriter::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            OutputWriter::Plain(file) => file.flush(),
+            OutputWriter::Gzip(encoder) => encoder.flush(),
+            OutputWriter::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compressed_roundtrip() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let text = "# Projekt\n".repeat(100);
+
+        for compression in [Compression::Gzip, Compression::Zstd] {
+            let path = dir.path().join(format!("out.md.{}", compression.extension()));
+            let mut writer = OutputWriter::create(&path, Some(compression))?;
+            writer.write_all(text.as_bytes())?;
+            writer.finish()?;
+
+            let mut decoded = String::new();
+            match compression {
+                Compression::Gzip => flate2::read::GzDecoder::new(File::open(&path)?).read_to_string(&mut decoded)?,
+                Compression::Zstd => zstd::Decoder::new(File::open(&path)?)?.read_to_string(&mut decoded)?,
+            };
+            assert_eq!(decoded, text);
+            assert!(std::fs::metadata(&path)?.len() < text.len() as u64);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_head() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("gross.txt");
+        std::fs::write(&path, "x".repeat(20_000))?;
+        assert_eq!(read_head(&path)?.len(), HEAD_LEN as usize);
+        Ok(())
+    }
+
+    #[test]
+    fn test_written_by_code2md() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cases: [(&str, &str, bool); 9] = [
+            ("a.md", "# demo\n\n> Generiert am 2024-01-01 10:00:00\n> Basisverzeichnis: `.`\n> Anzahl Dateien: 1\n", true),
+            ("r.md", "# demo\n\n> Basisverzeichnis: `.`\n> Anzahl Dateien: 1\n", true),
+            ("b.xml", "<documents>\n<document index=\"1\">\n", true),
+            ("page.html", "<p>Anzahl Dateien: 3</p>\n<ac:structured-macro ac:name=\"toc\" />\n", true),
+            ("c.jsonl", "{\"path\":\"a.rs\",\"language\":\"rust\",\"content\":\"\"}\n", true),
+            ("d.md", "---\nfiles: 2\ntags:\n  - code\n  - code/index\n---\n", true),
+            ("notes.md", "# Notizen\n\nNicht überschreiben!\n", false),
+            ("e.jsonl", "{\"event\":\"x\"}\n", false),
+            ("f.xml", "<?xml version=\"1.0\"?>\n<config/>\n", false),
+        ];
+        for (name, content, expected) in cases {
+            let path = dir.path().join(name);
+            std::fs::write(&path, content)?;
+            assert_eq!(written_by_code2md(&path)?, expected, "{}", name);
+        }
+
+        let path = dir.path().join("g.md.gz");
+        let mut writer = OutputWriter::create(&path, Some(Compression::Gzip))?;
+        writer.write_all(cases[0].1.as_bytes())?;
+        writer.finish()?;
+        assert!(written_by_code2md(&path)?);
+        assert_eq!(backup_path(&path), dir.path().join("g.md.gz.bak"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_size_limit() {
+        let mut writer = SizeLimit::new(Vec::new(), 10);
+        writer.write_all(b"12345").unwrap();
+        writer.write_all(b"67890").unwrap();
+        let error = writer.write_all(b"x").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::FileTooLarge);
+        assert_eq!(writer.into_inner(), b"1234567890");
+    }
+
+    #[test]
+    fn test_is_own_output() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = paths::canonicalize(dir.path())?;
+        let output = base.join("docs/app_code.md");
+        std::fs::create_dir_all(base.join("docs"))?;
+
+        for own in ["docs/app_code.md", "docs/app_code.part2.md", "docs/app_code.md.manifest.json", "docs/app_code/src/a.rs.md"] {
+            assert!(is_own_output(&base.join(own), &output), "{}", own);
+        }
+        for other in ["docs/guide.md", "app_code.md", "docs/app_code.partx.md"] {
+            assert!(!is_own_output(&base.join(other), &output), "{}", other);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_zip_bundle() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let document = dir.path().join("demo_code.md");
+        let manifest = dir.path().join("demo_code.md.manifest.json");
+        std::fs::write(&document, "# demo\n")?;
+        std::fs::write(&manifest, "{}")?;
+
+        let archive_path = bundle_path(&document, Bundle::Zip);
+        assert_eq!(archive_path, dir.path().join("demo_code.zip"));
+        write_zip_bundle(&archive_path, &[document, manifest])?;
+
+        let mut archive = zip::ZipArchive::new(File::open(&archive_path)?)?;
+        assert_eq!(archive.len(), 2);
+        let mut content = String::new();
+        archive.by_name("demo_code.md")?.read_to_string(&mut content)?;
+        assert_eq!(content, "# demo\n");
+        assert!(archive.by_name("demo_code.md.manifest.json").is_ok());
+        Ok(())
+    }
+}
@@ -0,0 +1,301 @@
+//! Erkennung von Paketen in Monorepos (`--packages`).
+//!
+//! Unterstützt werden npm/Yarn-Workspaces (`package.json`), pnpm
+//! (`pnpm-workspace.yaml`), Go-Workspaces (`go.work`) und Cargo-Workspaces.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use globset::Glob;
+
+use crate::collector::CollectedFile;
+use crate::error::{Code2MdError, Result};
+use crate::processor::relative_path;
+
+/// Paket eines Monorepos.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Package {
+    /// Name aus dem Paketmanifest, sonst der Verzeichnisname
+    pub name: String,
+    /// Verzeichnis relativ zum Projekt, mit `/` als Trenner
+    pub path: String,
+}
+
+/// Sucht alle Pakete der Workspace-Definitionen im Basisverzeichnis.
+pub fn detect_packages(base_path: &Path) -> Result<Vec<Package>> {
+    let mut packages: BTreeMap<String, Package> = BTreeMap::new();
+    let mut add = |patterns: Vec<String>, manifest: &str, name_of: fn(&str) -> Option<String>| {
+        for dir in expand_patterns(base_path, &patterns) {
+            let Ok(content) = std::fs::read_to_string(base_path.join(&dir).join(manifest)) else {
+                continue;
+            };
+            let name = name_of(&content)
+                .unwrap_or_else(|| dir.rsplit('/').next().unwrap_or(&dir).to_string());
+            packages.entry(dir.clone()).or_insert(Package { name, path: dir });
+        }
+    };
+
+    // npm/Yarn: "workspaces": [...] oder { "packages": [...] }
+    if let Some(json) = read_optional(base_path, "package.json")? {
+        let value: serde_json::Value = serde_json::from_str(&json)
+            .map_err(|e| Code2MdError::Config(format!("Ungültige package.json: {}", e)))?;
+        let workspaces = value
+            .get("workspaces")
+            .map(|w| w.get("packages").unwrap_or(w))
+            .and_then(|w| w.as_array())
+            .map(|list| list.iter().filter_map(|p| p.as_str().map(str::to_string)).collect());
+        if let Some(patterns) = workspaces {
+            add(patterns, "package.json", npm_name);
+        }
+    }
+
+    // pnpm: Liste unter "packages:"
+    if let Some(yaml) = read_optional(base_path, "pnpm-workspace.yaml")? {
+        add(pnpm_patterns(&yaml), "package.json", npm_name);
+    }
+
+    // Go: use-Direktiven in go.work
+    if let Some(work) = read_optional(base_path, "go.work")? {
+        add(go_work_dirs(&work), "go.mod", go_module_name);
+    }
+
+    // Cargo: [workspace] members
+    if let Some(manifest) = read_optional(base_path, "Cargo.toml")? {
+        let value: toml::Value = toml::from_str(&manifest)
+            .map_err(|e| Code2MdError::Config(format!("Ungültige Cargo.toml: {}", e)))?;
+        let members = value
+            .get("workspace")
+            .and_then(|w| w.get("members"))
+            .and_then(|m| m.as_array())
+            .map(|list| list.iter().filter_map(|p| p.as_str().map(str::to_string)).collect());
+        if let Some(patterns) = members {
+            add(patterns, "Cargo.toml", cargo_name);
+        }
+    }
+
+    Ok(packages.into_values().collect())
+}
+
+/// Wählt Pakete nach Name oder Pfad aus.
+pub fn select_packages(packages: &[Package], names: &[String]) -> Result<Vec<Package>> {
+    if packages.is_empty() {
+        return Err(Code2MdError::Config(
+            "Keine Pakete gefunden (package.json-Workspaces, pnpm-workspace.yaml, go.work oder Cargo-Workspace).".to_string(),
+        ));
+    }
+    names
+        .iter()
+        .map(|name| {
+            let name = name.trim().trim_end_matches('/');
+            packages
+                .iter()
+                .find(|p| p.name == name || p.path == name)
+                .cloned()
+                .ok_or_else(|| {
+                    let known: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+                    Code2MdError::Config(format!(
+                        "Unbekanntes Paket '{}'. Verfügbar: {}",
+                        name,
+                        known.join(", ")
+                    ))
+                })
+        })
+        .collect()
+}
+
+/// Gibt das Paket zurück, in dem eine Datei liegt (tiefstes Paket zuerst).
+pub fn package_of<'a>(packages: &'a [Package], rel_path: &str) -> Option<&'a Package> {
+    packages
+        .iter()
+        .filter(|p| rel_path.strip_prefix(&p.path).is_some_and(|rest| rest.starts_with('/')))
+        .max_by_key(|p| p.path.len())
+}
+
+/// Behält nur Dateien innerhalb der ausgewählten Pakete.
+pub fn retain_packages(files: &mut Vec<CollectedFile>, base_path: &Path, packages: &[Package]) {
+    files.retain(|file| package_of(packages, &relative_path(&file.path, base_path)).is_some());
+}
+
+/// Liest eine Datei im Basisverzeichnis, falls vorhanden.
+fn read_optional(base_path: &Path, name: &str) -> Result<Option<String>> {
+    let path = base_path.join(name);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    std::fs::read_to_string(&path)
+        .map(Some)
+        .map_err(|e| Code2MdError::io(&path, e))
+}
+
+/// Expandiert Workspace-Muster zu existierenden Verzeichnissen; Muster mit
+/// führendem `!` schließen Verzeichnisse wieder aus.
+fn expand_patterns(base_path: &Path, patterns: &[String]) -> Vec<String> {
+    let mut dirs = Vec::new();
+    let mut excluded = Vec::new();
+    for pattern in patterns {
+        let (target, pattern) = match pattern.strip_prefix('!') {
+            Some(rest) => (&mut excluded, rest),
+            None => (&mut dirs, pattern.as_str()),
+        };
+        let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+        let components: Vec<&str> = pattern.split('/').filter(|c| !c.is_empty() && *c != ".").collect();
+        expand_components(base_path, String::new(), &components, target);
+    }
+    dirs.retain(|dir| !excluded.contains(dir));
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+/// Expandiert die Komponenten eines Musters rekursiv ab `prefix`.
+fn expand_components(base_path: &Path, prefix: String, components: &[&str], out: &mut Vec<String>) {
+    let Some((first, rest)) = components.split_first() else {
+        if !prefix.is_empty() {
+            out.push(prefix);
+        }
+        return;
+    };
+    let join = |name: &str| {
+        if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", prefix, name)
+        }
+    };
+
+    if !first.contains(['*', '?', '[', '{']) {
+        if base_path.join(join(first)).is_dir() {
+            expand_components(base_path, join(first), rest, out);
+        }
+        return;
+    }
+
+    let Ok(entries) = std::fs::read_dir(base_path.join(&prefix)) else {
+        return;
+    };
+    let matcher = Glob::new(first).ok().map(|g| g.compile_matcher());
+    let mut names: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| !name.starts_with('.') && name != "node_modules")
+        .collect();
+    names.sort();
+
+    for name in names {
+        if *first == "**" {
+            // Beliebige Tiefe: Rest hier und in allen Unterverzeichnissen prüfen
+            expand_components(base_path, join(&name), rest, out);
+            expand_components(base_path, join(&name), components, out);
+        } else if matcher.as_ref().is_some_and(|m| m.is_match(&name)) {
+            expand_components(base_path, join(&name), rest, out);
+        }
+    }
+}
+
+/// Liest die Paketmuster aus einer `pnpm-workspace.yaml`.
+fn pnpm_patterns(yaml: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+    for line in yaml.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with([' ', '\t', '-']) {
+            in_packages = trimmed == "packages:";
+            continue;
+        }
+        if let Some(item) = trimmed.strip_prefix('-').filter(|_| in_packages) {
+            let item = item.split(" #").next().unwrap_or(item).trim();
+            patterns.push(item.trim_matches(['\'', '"']).to_string());
+        }
+    }
+    patterns
+}
+
+/// Liest die Verzeichnisse der `use`-Direktiven einer `go.work`.
+fn go_work_dirs(work: &str) -> Vec<String> {
+    let mut dirs = Vec::new();
+    let mut in_block = false;
+    for line in work.lines() {
+        let line = line.split("//").next().unwrap_or(line).trim();
+        if in_block {
+            if line == ")" {
+                in_block = false;
+            } else if !line.is_empty() {
+                dirs.push(line.to_string());
+            }
+        } else if let Some(rest) = line.strip_prefix("use") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_block = true;
+            } else if !rest.is_empty() {
+                dirs.push(rest.to_string());
+            }
+        }
+    }
+    dirs
+}
+
+fn npm_name(json: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    value.get("name")?.as_str().map(str::to_string)
+}
+
+fn go_module_name(go_mod: &str) -> Option<String> {
+    go_mod
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("module "))
+        .map(|name| name.trim().to_string())
+}
+
+fn cargo_name(manifest: &str) -> Option<String> {
+    let value: toml::Value = toml::from_str(manifest).ok()?;
+    value.get("package")?.get("name")?.as_str().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_packages() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path();
+        for (path, content) in [
+            ("package.json", r#"{"workspaces": ["packages/*", "!packages/alt"]}"#),
+            ("packages/web/package.json", r#"{"name": "@acme/web"}"#),
+            ("packages/api/package.json", "{}"),
+            ("packages/alt/package.json", "{}"),
+            ("packages/docs/README.md", ""),
+            ("pnpm-workspace.yaml", "packages:\n  - 'tools/**'\n"),
+            ("tools/cli/nested/package.json", r#"{"name": "cli"}"#),
+            ("go.work", "go 1.22\n\nuse (\n\t./services/auth // Login\n)\n"),
+            ("services/auth/go.mod", "module example.com/auth\n"),
+        ] {
+            let path = base.join(path);
+            std::fs::create_dir_all(path.parent().unwrap_or(base))?;
+            std::fs::write(path, content)?;
+        }
+
+        let packages = detect_packages(base)?;
+        let names: Vec<(&str, &str)> = packages.iter().map(|p| (p.name.as_str(), p.path.as_str())).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("api", "packages/api"),
+                ("@acme/web", "packages/web"),
+                ("example.com/auth", "services/auth"),
+                ("cli", "tools/cli/nested"),
+            ]
+        );
+
+        let selected = select_packages(&packages, &["@acme/web".to_string(), "services/auth".to_string()])?;
+        assert_eq!(selected.len(), 2);
+        assert!(select_packages(&packages, &["fehlt".to_string()]).is_err());
+        assert_eq!(package_of(&packages, "packages/web/src/a.ts").map(|p| p.name.as_str()), Some("@acme/web"));
+        assert_eq!(package_of(&packages, "packages/webapp/a.ts"), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+//! Pfadbehandlung unabhängig vom Betriebssystem.
+//!
+//! Unter Windows liefert `canonicalize` Pfade mit `\\?\`-Präfix (z.B.
+//! `\\?\C:\projekt` oder `\\?\UNC\server\freigabe`). [`canonicalize`] lässt
+//! das Präfix weg, wo es nicht nötig ist; nur Pfade über 260 Zeichen behalten
+//! es. Die Standardbibliothek ergänzt es bei Dateizugriffen selbst, sodass
+//! lange Pfade durchgängig funktionieren. Vergleiche und Anzeigen gehen über
+//! [`relative_to`] und [`display`], damit beide Schreibweisen zusammenpassen.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Kanonischer, absoluter Pfad ohne unnötiges `\\?\`-Präfix.
+pub fn canonicalize(path: &Path) -> io::Result<PathBuf> {
+    dunce::canonicalize(path)
+}
+
+/// Pfad als Text ohne `\\?\`-Präfix; `\\?\UNC\server\…` wird zu `\\server\…`.
+pub fn display(path: &Path) -> String {
+    let text = path.to_string_lossy();
+    if let Some(rest) = text.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{}", rest)
+    } else if let Some(rest) = text.strip_prefix(r"\\?\") {
+        rest.to_string()
+    } else {
+        text.into_owned()
+    }
+}
+
+/// Pfad relativ zu `base` mit `/` als Trenner, auch wenn nur einer der beiden
+/// Pfade das `\\?\`-Präfix trägt. `None`, wenn der Pfad nicht unter `base` liegt.
+pub fn relative_to(path: &Path, base: &Path) -> Option<String> {
+    if let Ok(rel) = path.strip_prefix(base) {
+        return Some(rel.to_string_lossy().replace('\\', "/"));
+    }
+    let path = display(path);
+    let base = display(base);
+    let rest = path.strip_prefix(base.trim_end_matches(['/', '\\']))?;
+    if rest.is_empty() {
+        return Some(String::new());
+    }
+    rest.strip_prefix(['/', '\\']).map(|rel| rel.replace('\\', "/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(display(Path::new(r"\\?\C:\projekt")), r"C:\projekt");
+        assert_eq!(display(Path::new(r"\\?\UNC\server\share\app")), r"\\server\share\app");
+        assert_eq!(display(Path::new("/home/app")), "/home/app");
+    }
+
+    #[test]
+    fn test_relative_to() -> anyhow::Result<()> {
+        assert_eq!(relative_to(Path::new(r"\\?\C:\repo\src\main.rs"), Path::new(r"C:\repo")).as_deref(), Some("src/main.rs"));
+        assert_eq!(relative_to(Path::new(r"C:\repo\src\main.rs"), Path::new(r"\\?\C:\repo\")).as_deref(), Some("src/main.rs"));
+        assert_eq!(relative_to(Path::new(r"\\?\UNC\srv\share\a.rs"), Path::new(r"\\srv\share")).as_deref(), Some("a.rs"));
+        assert_eq!(relative_to(Path::new(r"C:\repository\a.rs"), Path::new(r"C:\repo")), None);
+        assert_eq!(relative_to(Path::new("/app/src/a.rs"), Path::new("/app")).as_deref(), Some("src/a.rs"));
+
+        // Lange Pfade (über 260 Zeichen) bleiben nutzbar
+        let dir = tempfile::tempdir()?;
+        let base = canonicalize(dir.path())?;
+        let deep = (0..30).fold(base.clone(), |path, i| path.join(format!("verzeichnis_{:02}", i)));
+        std::fs::create_dir_all(&deep)?;
+        std::fs::write(deep.join("datei.rs"), "fn main() {}\n")?;
+        let file = canonicalize(&deep.join("datei.rs"))?;
+        assert!(file.as_os_str().len() > 260);
+        let rel = relative_to(&file, &base).unwrap_or_default();
+        assert!(rel.starts_with("verzeichnis_00/verzeichnis_01/") && rel.ends_with("/datei.rs"));
+        Ok(())
+    }
+}
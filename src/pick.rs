@@ -0,0 +1,117 @@
+//! Auswahl einzelner Dateien per unscharfer Suche (`code2md pick`).
+//!
+//! Suchbegriffe werden wie bei skim bzw. fzf als Fragmente gewertet
+//! (`srcapi` findet `src/api/users.py`). Aus den Treffern werden Dateien per
+//! Nummer übernommen; eine leere Suche beendet die Auswahl.
+
+use std::io::{self, BufRead, Write};
+
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+
+/// Höchstens so viele Treffer werden je Suche angezeigt.
+pub const MAX_MATCHES: usize = 10;
+
+/// Indizes der besten Treffer für eine Suche, nach Bewertung absteigend.
+pub fn fuzzy_matches(query: &str, paths: &[String], limit: usize) -> Vec<usize> {
+    let matcher = SkimMatcherV2::default().ignore_case();
+    let mut scored: Vec<(i64, usize)> = paths
+        .iter()
+        .enumerate()
+        .filter_map(|(index, path)| matcher.fuzzy_match(path, query).map(|score| (score, index)))
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| paths[a.1].len().cmp(&paths[b.1].len())).then(a.1.cmp(&b.1)));
+    scored.into_iter().take(limit).map(|(_, index)| index).collect()
+}
+
+/// Fragt Suchbegriffe und Nummern ab und liefert die Indizes der gewählten
+/// Pfade in ihrer ursprünglichen Reihenfolge. Eine erneut gewählte Nummer
+/// nimmt die Datei wieder heraus.
+pub fn pick<R: BufRead, W: Write>(paths: &[String], input: &mut R, output: &mut W) -> io::Result<Vec<usize>> {
+    let mut picked: Vec<usize> = Vec::new();
+    loop {
+        write!(output, "Suche ({} gewählt, leer = fertig): ", picked.len())?;
+        output.flush()?;
+        let query = match read_line(input)? {
+            Some(query) if !query.is_empty() => query,
+            _ => break,
+        };
+
+        let matches = fuzzy_matches(&query, paths, MAX_MATCHES);
+        if matches.is_empty() {
+            writeln!(output, "  Keine Treffer.")?;
+            continue;
+        }
+        for (number, &index) in matches.iter().enumerate() {
+            let mark = if picked.contains(&index) { "*" } else { " " };
+            writeln!(output, "  {}{:>2}  {}", mark, number + 1, paths[index])?;
+        }
+        write!(output, "Nummern (z.B. 1 3, * = alle, leer = keine): ")?;
+        output.flush()?;
+        let Some(answer) = read_line(input)? else {
+            break;
+        };
+        let chosen: Vec<usize> = if answer == "*" {
+            matches.clone()
+        } else {
+            answer
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter_map(|number| number.parse::<usize>().ok())
+                .filter_map(|number| number.checked_sub(1).and_then(|n| matches.get(n).copied()))
+                .collect()
+        };
+        for index in chosen {
+            match picked.iter().position(|&p| p == index) {
+                Some(pos) => {
+                    picked.remove(pos);
+                }
+                None => picked.push(index),
+            }
+        }
+    }
+    picked.sort_unstable();
+    Ok(picked)
+}
+
+/// Liest eine Zeile ohne Zeilenende; `None` am Ende der Eingabe.
+fn read_line<R: BufRead>(input: &mut R) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths() -> Vec<String> {
+        ["README.md", "src/api/users.py", "src/api/orders.py", "src/models/user.py", "tests/test_users.py"]
+            .iter()
+            .map(|p| p.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_fuzzy_matches() {
+        let paths = paths();
+        assert_eq!(fuzzy_matches("srcapiusr", &paths, MAX_MATCHES), vec![1]);
+        assert_eq!(fuzzy_matches("ORDERS", &paths, MAX_MATCHES), vec![2]);
+        assert_eq!(fuzzy_matches("users", &paths, 1).len(), 1);
+        assert!(fuzzy_matches("xyz", &paths, MAX_MATCHES).is_empty());
+    }
+
+    #[test]
+    fn test_pick() -> anyhow::Result<()> {
+        let paths = paths();
+        let mut output = Vec::new();
+        let picked = pick(&paths, &mut "orders\n1\nreadme\n*\norders\n1\nuser\n1\n\n".as_bytes(), &mut output)?;
+        let names: Vec<&str> = picked.iter().map(|&i| paths[i].as_str()).collect();
+        assert_eq!(names.len(), 2);
+        assert_eq!(names[0], "README.md");
+        assert!(names[1].contains("user"));
+        assert!(String::from_utf8(output)?.contains("*"));
+        Ok(())
+    }
+}
@@ -0,0 +1,383 @@
+//! Plugin-System auf Basis von WebAssembly-Modulen.
+//!
+//! Plugins liegen als `*.wasm` oder `*.wat` in einem Plugin-Verzeichnis und
+//! können folgende Funktionen exportieren (alle optional):
+//!
+//! - `filter(path_ptr, path_len) -> i32`: `0` schließt die Datei vom Export aus
+//! - `transform(path_ptr, path_len, content_ptr, content_len) -> i64`: ersetzt den Dateiinhalt
+//! - `section(paths_ptr, paths_len) -> i64`: hängt einen Markdown-Abschnitt an
+//!
+//! Zur Übergabe von Zeichenketten muss jedes Plugin `memory` und
+//! `alloc(len) -> i32` exportieren. Ergebnisse vom Typ `i64` kodieren Zeiger
+//! und Länge als `(ptr << 32) | len`; negative Werte bedeuten „kein Ergebnis“.
+//! Die Pfadliste für `section` ist durch Zeilenumbrüche getrennt.
+//!
+//! Jeder Aufruf darf höchstens [`FUEL_PER_CALL`] Fuel (etwa eine Einheit je
+//! WASM-Instruktion) verbrauchen; Endlosschleifen brechen so ab, statt den
+//! Export aufzuhalten.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tracing::warn;
+
+use crate::error::{Code2MdError, Result};
+use crate::processor::{FileProcessor, Processors};
+
+/// Standard-Plugin-Verzeichnis relativ zum Projektverzeichnis.
+pub const DEFAULT_PLUGIN_DIR: &str = ".code2md/plugins";
+
+/// Rechenbudget je Plugin-Aufruf (und für die Instanziierung).
+pub const FUEL_PER_CALL: u64 = 1_000_000_000;
+
+/// Lädt alle Plugins aus einem Verzeichnis (alphabetisch sortiert).
+///
+/// Existiert das Verzeichnis nicht, wird eine leere Liste zurückgegeben.
+pub fn load_plugins(dir: &Path) -> Result<Processors> {
+    plugin_files(dir)?
+        .iter()
+        .map(|path| {
+            let plugin = wasm::Plugin::load(path).map_err(|message| Code2MdError::Plugin {
+                path: path.clone(),
+                message,
+            })?;
+            Ok(Arc::new(plugin) as Arc<dyn FileProcessor>)
+        })
+        .collect()
+}
+
+/// Lädt die Plugins aus `.code2md/plugins` im Projekt.
+///
+/// Anders als bei `--plugins-dir` stammen sie aus dem exportierten
+/// Repository und könnten Inhalte unbemerkt umschreiben. Sie werden daher
+/// nur mit `trusted` (`--allow-hooks` oder `--config`) geladen. Ohne
+/// Plugin-Unterstützung werden sie mit einer Warnung übergangen.
+pub fn load_project_plugins(base_path: &Path, trusted: bool) -> Result<Processors> {
+    let dir = base_path.join(DEFAULT_PLUGIN_DIR);
+    let files = plugin_files(&dir)?;
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !trusted {
+        warn!(
+            "Plugins in {} werden nicht geladen; mit --allow-hooks oder --config erlauben",
+            dir.display()
+        );
+        return Ok(Vec::new());
+    }
+    if !cfg!(feature = "plugins") {
+        warn!(
+            "Plugins in {} werden ignoriert: code2md wurde ohne Plugin-Unterstützung gebaut (Feature `plugins`)",
+            dir.display()
+        );
+        return Ok(Vec::new());
+    }
+    load_plugins(&dir)
+}
+
+/// Plugin-Dateien eines Verzeichnisses, alphabetisch sortiert.
+fn plugin_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| Code2MdError::io(dir, e))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.is_file()
+                && matches!(
+                    p.extension().and_then(|e| e.to_str()),
+                    Some("wasm") | Some("wat")
+                )
+        })
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+/// Fehlertolerante Anbindung eines Plugins: Fehler werden gemeldet und das
+/// Plugin für die betroffene Datei übersprungen.
+impl FileProcessor for wasm::Plugin {
+    fn name(&self) -> &str {
+        self.name()
+    }
+
+    fn filter(&self, rel_path: &str) -> bool {
+        self.call_filter(rel_path)
+            .unwrap_or_else(|e| warn(self, e))
+            .unwrap_or(true)
+    }
+
+    fn transform(&self, rel_path: &str, content: String) -> String {
+        self.call_transform(rel_path, &content)
+            .unwrap_or_else(|e| warn(self, e))
+            .unwrap_or(content)
+    }
+
+    fn section(&self, rel_paths: &[String]) -> Option<String> {
+        self.call_section(&rel_paths.join("\n"))
+            .unwrap_or_else(|e| warn(self, e))
+    }
+}
+
+/// Meldet einen Plugin-Fehler.
+fn warn<T>(plugin: &wasm::Plugin, error: Code2MdError) -> Option<T> {
+    warn!(plugin = plugin.name(), "{}", error);
+    None
+}
+
+#[cfg(feature = "plugins")]
+mod wasm {
+    use std::path::Path;
+    use std::sync::Mutex;
+
+    use wasmtime::{format_err, Config, Engine, Instance, Memory, Module, Result, Store, Trap};
+
+    use super::FUEL_PER_CALL;
+    use crate::error::Code2MdError;
+
+    /// Ein geladenes WASM-Plugin.
+    pub struct Plugin {
+        name: String,
+        state: Mutex<(Store<()>, Instance)>,
+    }
+
+    impl Plugin {
+        pub fn load(path: &Path) -> Result<Self, String> {
+            let engine = Engine::new(Config::new().consume_fuel(true)).map_err(|e| e.to_string())?;
+            let module = Module::from_file(&engine, path).map_err(|e| e.to_string())?;
+            let mut store = Store::new(&engine, ());
+            store.set_fuel(FUEL_PER_CALL).map_err(|e| e.to_string())?;
+            let instance = Instance::new(&mut store, &module, &[]).map_err(|e| describe(&e))?;
+
+            Ok(Self {
+                name: path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                state: Mutex::new((store, instance)),
+            })
+        }
+
+        pub fn name(&self) -> &str {
+            &self.name
+        }
+
+        /// Ruft `filter` auf; `None`, wenn das Plugin keinen Filter exportiert.
+        pub fn call_filter(&self, path: &str) -> Result<Option<bool>, Code2MdError> {
+            self.with_instance(|store, instance| {
+                let Ok(func) = instance.get_typed_func::<(i32, i32), i32>(&mut *store, "filter") else {
+                    return Ok(None);
+                };
+
+                let (ptr, len) = write_str(store, instance, path)?;
+                Ok(Some(func.call(&mut *store, (ptr, len))? != 0))
+            })
+        }
+
+        /// Ruft `transform` auf; `None`, wenn der Inhalt unverändert bleibt.
+        pub fn call_transform(&self, path: &str, content: &str) -> Result<Option<String>, Code2MdError> {
+            self.with_instance(|store, instance| {
+                let Ok(func) =
+                    instance.get_typed_func::<(i32, i32, i32, i32), i64>(&mut *store, "transform")
+                else {
+                    return Ok(None);
+                };
+
+                let (path_ptr, path_len) = write_str(store, instance, path)?;
+                let (content_ptr, content_len) = write_str(store, instance, content)?;
+                let packed = func.call(&mut *store, (path_ptr, path_len, content_ptr, content_len))?;
+                read_result(store, instance, packed)
+            })
+        }
+
+        /// Ruft `section` auf; `None`, wenn das Plugin keinen Abschnitt liefert.
+        pub fn call_section(&self, paths: &str) -> Result<Option<String>, Code2MdError> {
+            self.with_instance(|store, instance| {
+                let Ok(func) = instance.get_typed_func::<(i32, i32), i64>(&mut *store, "section") else {
+                    return Ok(None);
+                };
+
+                let (ptr, len) = write_str(store, instance, paths)?;
+                let packed = func.call(&mut *store, (ptr, len))?;
+                read_result(store, instance, packed)
+            })
+        }
+
+        /// Führt einen Aufruf mit exklusivem Zugriff auf die Instanz und
+        /// frischem Rechenbudget aus.
+        fn with_instance<T>(
+            &self,
+            call: impl FnOnce(&mut Store<()>, &Instance) -> Result<T>,
+        ) -> Result<T, Code2MdError> {
+            let error = |message: String| Code2MdError::PluginCall {
+                name: self.name.clone(),
+                message,
+            };
+            let mut guard = self.state.lock().map_err(|_| error("Plugin blockiert".to_string()))?;
+            let (store, instance) = &mut *guard;
+            store.set_fuel(FUEL_PER_CALL).map_err(|e| error(e.to_string()))?;
+            call(store, instance).map_err(|e| error(describe(&e)))
+        }
+    }
+
+    /// Fehlermeldung eines Aufrufs; ein aufgebrauchtes Budget wird benannt.
+    fn describe(error: &wasmtime::Error) -> String {
+        match error.downcast_ref::<Trap>() {
+            Some(Trap::OutOfFuel) => format!("Rechenbudget von {} Fuel aufgebraucht (Endlosschleife?)", FUEL_PER_CALL),
+            _ => error.to_string(),
+        }
+    }
+
+    fn memory(store: &mut Store<()>, instance: &Instance) -> Result<Memory> {
+        instance
+            .get_memory(&mut *store, "memory")
+            .ok_or_else(|| format_err!("Export 'memory' fehlt"))
+    }
+
+    /// Kopiert eine Zeichenkette in den Speicher des Plugins.
+    fn write_str(store: &mut Store<()>, instance: &Instance, value: &str) -> Result<(i32, i32)> {
+        let alloc = instance.get_typed_func::<i32, i32>(&mut *store, "alloc")?;
+        let len = i32::try_from(value.len())?;
+        let ptr = alloc.call(&mut *store, len)?;
+        memory(store, instance)?.write(&mut *store, ptr as u32 as usize, value.as_bytes())?;
+        Ok((ptr, len))
+    }
+
+    /// Liest ein als `(ptr << 32) | len` kodiertes Ergebnis.
+    fn read_result(store: &mut Store<()>, instance: &Instance, packed: i64) -> Result<Option<String>> {
+        if packed < 0 {
+            return Ok(None);
+        }
+        let ptr = (packed >> 32) as usize;
+        let len = (packed & 0xffff_ffff) as usize;
+
+        let mut buffer = vec![0u8; len];
+        memory(store, instance)?.read(&mut *store, ptr, &mut buffer)?;
+        Ok(Some(String::from_utf8(buffer)?))
+    }
+}
+
+#[cfg(not(feature = "plugins"))]
+mod wasm {
+    use std::path::Path;
+
+    use crate::error::Code2MdError;
+
+    /// Platzhalter ohne Plugin-Unterstützung; kann nicht instanziiert werden.
+    pub enum Plugin {}
+
+    impl Plugin {
+        pub fn load(_path: &Path) -> Result<Self, String> {
+            Err("code2md wurde ohne Plugin-Unterstützung gebaut (Feature `plugins`)".to_string())
+        }
+
+        pub fn name(&self) -> &str {
+            match *self {}
+        }
+
+        pub fn call_filter(&self, _path: &str) -> Result<Option<bool>, Code2MdError> {
+            match *self {}
+        }
+
+        pub fn call_transform(&self, _path: &str, _content: &str) -> Result<Option<String>, Code2MdError> {
+            match *self {}
+        }
+
+        pub fn call_section(&self, _paths: &str) -> Result<Option<String>, Code2MdError> {
+            match *self {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_dir_loads_nothing() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        assert!(load_plugins(&dir.path().join(DEFAULT_PLUGIN_DIR))?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_project_plugins_need_trust() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let plugin_dir = dir.path().join(DEFAULT_PLUGIN_DIR);
+        std::fs::create_dir_all(&plugin_dir)?;
+        std::fs::write(plugin_dir.join("kaputt.wat"), "(module")?;
+
+        // Nicht vertrauenswürdig: weder geladen noch ein Fehler
+        assert!(load_project_plugins(dir.path(), false)?.is_empty());
+        // Vertrauenswürdig: ohne Feature ignoriert, mit Feature ein Ladefehler
+        assert_eq!(load_project_plugins(dir.path(), true).is_err(), cfg!(feature = "plugins"));
+        Ok(())
+    }
+
+    /// Plugin, das `.lock`-Dateien ausschließt, Inhalte durch `REDACTED`
+    /// ersetzt und einen festen Abschnitt anhängt.
+    #[cfg(feature = "plugins")]
+    const TEST_PLUGIN: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (global $next (mut i32) (i32.const 1024))
+          (data (i32.const 0) "REDACTED## Plugin\n\nHallo")
+          (func (export "alloc") (param $len i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr (global.get $next))
+            (global.set $next (i32.add (global.get $next) (local.get $len)))
+            (local.get $ptr))
+          (func (export "filter") (param $ptr i32) (param $len i32) (result i32)
+            ;; Endung ".lock" (Little Endian: "lock" = 0x6b636f6c)
+            (i32.ne
+              (i32.load (i32.sub (i32.add (local.get $ptr) (local.get $len)) (i32.const 4)))
+              (i32.const 0x6b636f6c)))
+          (func (export "transform") (param i32 i32 i32 i32) (result i64)
+            (i64.const 8))
+          (func (export "section") (param i32 i32) (result i64)
+            (i64.or (i64.shl (i64.const 8) (i64.const 32)) (i64.const 16))))
+    "#;
+
+    #[cfg(feature = "plugins")]
+    #[test]
+    fn test_wasm_plugin() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let plugin_dir = dir.path().join("plugins");
+        std::fs::create_dir_all(&plugin_dir)?;
+        std::fs::write(plugin_dir.join("redact.wat"), TEST_PLUGIN)?;
+
+        let plugins = load_plugins(&plugin_dir)?;
+        let plugin = &plugins[0];
+        assert_eq!(plugin.name(), "redact");
+        assert!(plugin.filter("src/main.rs"));
+        assert!(!plugin.filter("Cargo.lock"));
+        assert_eq!(plugin.transform("a.rs", "geheim".to_string()), "REDACTED");
+        assert_eq!(plugin.section(&[]), Some("## Plugin\n\nHallo".to_string()));
+        Ok(())
+    }
+
+    #[cfg(feature = "plugins")]
+    #[test]
+    fn test_endless_plugin_runs_out_of_fuel() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("loop.wat");
+        std::fs::write(
+            &path,
+            r#"(module
+                 (memory (export "memory") 1)
+                 (func (export "alloc") (param i32) (result i32) (i32.const 0))
+                 (func (export "filter") (param i32 i32) (result i32) (loop (br 0)) (i32.const 0)))"#,
+        )?;
+
+        let plugin = wasm::Plugin::load(&path).map_err(anyhow::Error::msg)?;
+        let error = plugin.call_filter("a.rs").unwrap_err();
+        assert!(matches!(&error, Code2MdError::PluginCall { name, .. } if name == "loop"));
+        assert!(error.to_string().contains("Rechenbudget"));
+        // Jeder Aufruf bekommt ein neues Budget; fehlertolerant bleibt die Datei erhalten
+        assert!(FileProcessor::filter(&plugin, "a.rs"));
+        Ok(())
+    }
+}
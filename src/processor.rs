@@ -0,0 +1,157 @@
+//! Erweiterungspunkt für die Verarbeitung einzelner Dateien.
+//!
+//! Ein [`FileProcessor`] kann Dateien vom Export ausschließen, Codeblöcke
+//! umschreiben, Metadaten an Dateiabschnitte hängen und eigene Abschnitte
+//! beisteuern. Eingebaute Verarbeitungsschritte (Zeilenkürzung, WASM-Plugins)
+//! nutzen dieselbe Schnittstelle wie eigene Implementierungen.
+
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::collector::CollectedFile;
+use crate::paths::relative_to;
+use crate::transform::split_line_ending;
+
+/// Verarbeitungsschritt für exportierte Dateien.
+///
+/// Alle Methoden haben Standardimplementierungen, die nichts verändern.
+/// Pfade sind relativ zum Projektverzeichnis und verwenden `/` als Trenner.
+pub trait FileProcessor: Send + Sync {
+    /// Name für Statusausgaben und Warnungen.
+    fn name(&self) -> &str;
+
+    /// Gibt `false` zurück, um eine Datei vom Export auszuschließen.
+    fn filter(&self, _rel_path: &str) -> bool {
+        true
+    }
+
+    /// Schreibt den Inhalt eines Codeblocks um.
+    fn transform(&self, _rel_path: &str, content: String) -> String {
+        content
+    }
+
+    /// Liefert eine Metadaten-Angabe für den Dateiabschnitt.
+    fn annotate(&self, _rel_path: &str, _content: &str) -> Option<String> {
+        None
+    }
+
+    /// Liefert einen zusätzlichen Markdown-Abschnitt für das Dokument.
+    fn section(&self, _rel_paths: &[String]) -> Option<String> {
+        None
+    }
+}
+
+impl fmt::Debug for dyn FileProcessor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FileProcessor({})", self.name())
+    }
+}
+
+/// Registrierte Verarbeitungsschritte in Ausführungsreihenfolge.
+pub type Processors = Vec<Arc<dyn FileProcessor>>;
+
+/// Entfernt alle Dateien, die von mindestens einem Prozessor abgelehnt werden.
+pub fn retain_files(files: &mut Vec<CollectedFile>, base_path: &Path, processors: &[Arc<dyn FileProcessor>]) {
+    if processors.is_empty() {
+        return;
+    }
+    files.retain(|file| {
+        let rel_path = relative_path(&file.path, base_path);
+        processors.iter().all(|p| p.filter(&rel_path))
+    });
+}
+
+/// Gibt den Pfad relativ zum Projektverzeichnis mit `/` als Trenner zurück.
+pub fn relative_path(file: &Path, base_path: &Path) -> String {
+    relative_to(file, base_path).unwrap_or_else(|| slash_path(file))
+}
+
+/// Gibt einen Pfad für die Anzeige mit `/` als Trenner zurück, damit Baum,
+/// Inhaltsverzeichnis und Anker unter Windows und Linux gleich aussehen.
+pub fn slash_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Kürzt überlange Zeilen und vermerkt die Anzahl ausgelassener Zeichen.
+#[derive(Debug, Clone)]
+pub struct LineTruncation {
+    pub max_length: usize,
+}
+
+impl FileProcessor for LineTruncation {
+    fn name(&self) -> &str {
+        "line-truncation"
+    }
+
+    fn transform(&self, _rel_path: &str, content: String) -> String {
+        content
+            .split_inclusive('\n')
+            .map(|line| {
+                let (body, eol) = split_line_ending(line);
+                format!("{}{}", truncate_line(body, self.max_length), eol)
+            })
+            .collect()
+    }
+}
+
+/// Kürzt eine Zeile auf `max` Zeichen und hängt einen Hinweis mit der
+/// Anzahl ausgelassener Zeichen an.
+fn truncate_line(line: &str, max: usize) -> String {
+    let total = line.chars().count();
+    if total <= max {
+        return line.to_string();
+    }
+
+    let cut = line
+        .char_indices()
+        .nth(max)
+        .map(|(i, _)| i)
+        .unwrap_or(line.len());
+    format!("{}… [+{} chars]", &line[..cut], total - max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct SkipLockFiles;
+
+    impl FileProcessor for SkipLockFiles {
+        fn name(&self) -> &str {
+            "skip-lock-files"
+        }
+
+        fn filter(&self, rel_path: &str) -> bool {
+            !rel_path.ends_with(".lock")
+        }
+    }
+
+    #[test]
+    fn test_retain_files() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path();
+        std::fs::write(base.join("Cargo.lock"), "")?;
+        std::fs::write(base.join("main.rs"), "")?;
+        let mut files = vec![
+            CollectedFile::from_path(base.join("Cargo.lock"))?,
+            CollectedFile::from_path(base.join("main.rs"))?,
+        ];
+        let processors: Processors = vec![Arc::new(SkipLockFiles)];
+
+        retain_files(&mut files, base, &processors);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, base.join("main.rs"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_line_truncation() {
+        let truncation = LineTruncation { max_length: 5 };
+        assert_eq!(
+            truncation.transform("a.txt", "kurz\nüberlange Zeile\n".to_string()),
+            "kurz\nüberl… [+10 chars]\n"
+        );
+    }
+}
@@ -0,0 +1,60 @@
+//! Einbettung des Exports in eine Prompt-Vorlage für KI-Assistenten.
+
+use chrono::Local;
+
+/// Platzhalter für das generierte Dokument.
+const DOCUMENT_PLACEHOLDER: &str = "{document}";
+
+/// Werte für die Platzhalter einer Prompt-Vorlage.
+#[derive(Debug)]
+pub struct PromptContext<'a> {
+    pub project_name: &'a str,
+    pub file_count: usize,
+}
+
+/// Setzt das Dokument in die Prompt-Vorlage ein.
+///
+/// Unterstützte Platzhalter: `{project}`, `{file_count}`, `{date}` und
+/// `{document}`. Fehlt `{document}`, wird das Dokument an die Vorlage angehängt.
+pub fn wrap_in_prompt(template: &str, document: &str, context: &PromptContext) -> String {
+    let filled = template
+        .replace("{project}", context.project_name)
+        .replace("{file_count}", &context.file_count.to_string())
+        .replace("{date}", &Local::now().format("%Y-%m-%d").to_string());
+
+    match filled.split_once(DOCUMENT_PLACEHOLDER) {
+        Some((before, after)) => format!("{}{}{}", before, document, after),
+        None => format!("{}\n\n{}", filled.trim_end(), document),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_in_prompt() {
+        let context = PromptContext {
+            project_name: "demo",
+            file_count: 3,
+        };
+        let template = "Projekt {project} mit {file_count} Dateien:\n\n{document}\n\nAufgabe: Review.";
+
+        assert_eq!(
+            wrap_in_prompt(template, "# demo", &context),
+            "Projekt demo mit 3 Dateien:\n\n# demo\n\nAufgabe: Review."
+        );
+    }
+
+    #[test]
+    fn test_wrap_without_document_placeholder() {
+        let context = PromptContext {
+            project_name: "demo",
+            file_count: 1,
+        };
+        assert_eq!(
+            wrap_in_prompt("Bitte prüfen:\n", "# demo", &context),
+            "Bitte prüfen:\n\n# demo"
+        );
+    }
+}
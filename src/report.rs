@@ -0,0 +1,210 @@
+//! Maschinenlesbarer Bericht über einen Lauf (`--report`).
+//!
+//! Der Bericht enthält Zählwerte, übersprungene Dateien mit Grund, Warnungen,
+//! Laufzeit und die erzeugten Dateien, damit CI-Pipelines den Zustand eines
+//! Exports prüfen können, ohne die Konsolenausgabe auszuwerten.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+
+use crate::collector::CollectedFile;
+use crate::error::{Code2MdError, Result};
+use crate::events::Warning;
+use crate::processor::relative_path;
+
+/// Ergebnis eines Laufs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    /// Export ohne Warnungen
+    Success,
+    /// Export mit Warnungen
+    Warnings,
+    /// Ausgabe laut Run-Cache aktuell, nichts geschrieben
+    UpToDate,
+}
+
+/// Eine Datei, die gefunden, aber nicht exportiert wurde.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkippedFile {
+    /// Pfad relativ zum Projektverzeichnis
+    pub path: String,
+    pub reason: String,
+}
+
+/// Zählwerte eines Laufs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ReportCounts {
+    /// Gesammelte Dateien vor allen Filtern
+    pub found: usize,
+    pub exported: usize,
+    pub skipped: usize,
+    pub warnings: usize,
+}
+
+/// Bericht eines Laufs.
+#[derive(Debug, Serialize)]
+pub struct RunReport {
+    pub version: String,
+    pub status: RunStatus,
+    pub project: String,
+    pub base_path: String,
+    pub started_at: String,
+    pub duration_ms: u64,
+    pub counts: ReportCounts,
+    pub skipped: Vec<SkippedFile>,
+    pub warnings: Vec<Warning>,
+    /// Erzeugte Dateien (Ausgabe, Teile, Manifest, Archiv)
+    pub outputs: Vec<String>,
+    /// Größe der Ausgabedatei in Bytes
+    pub output_size: Option<u64>,
+    /// Geschätzte Tokens bei `--token-budget`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<usize>,
+}
+
+impl RunReport {
+    /// Bericht mit Übersprungenen und Warnungen; Laufzeit bis jetzt. Exportierte
+    /// Dateien und Ausgaben ergänzt der Aufrufer.
+    pub fn new(
+        status: RunStatus,
+        project: &str,
+        base_path: &Path,
+        started_at: DateTime<Local>,
+        skips: &SkipTracker,
+        warnings: Vec<Warning>,
+    ) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            status,
+            project: project.to_string(),
+            base_path: base_path.display().to_string(),
+            started_at: started_at.to_rfc3339(),
+            duration_ms: (Local::now() - started_at).num_milliseconds().max(0) as u64,
+            counts: ReportCounts {
+                found: skips.found,
+                exported: 0,
+                skipped: skips.skipped.len(),
+                warnings: warnings.len(),
+            },
+            skipped: skips.skipped.clone(),
+            warnings,
+            outputs: Vec::new(),
+            output_size: None,
+            tokens: None,
+        }
+    }
+
+    /// Schreibt den Bericht als JSON und legt das Verzeichnis bei Bedarf an.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|e| Code2MdError::io(dir, e))?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| Code2MdError::Render(e.to_string()))?;
+        std::fs::write(path, json).map_err(|e| Code2MdError::io(path, e))
+    }
+}
+
+/// Hält fest, welche Dateien bei den einzelnen Filterschritten wegfallen.
+#[derive(Debug)]
+pub struct SkipTracker {
+    base_path: PathBuf,
+    found: usize,
+    present: Vec<String>,
+    skipped: Vec<SkippedFile>,
+}
+
+impl SkipTracker {
+    /// Beginnt mit den gesammelten Dateien.
+    pub fn new(files: &[CollectedFile], base_path: &Path) -> Self {
+        Self {
+            base_path: base_path.to_path_buf(),
+            found: files.len(),
+            present: files.iter().map(|file| relative_path(&file.path, base_path)).collect(),
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Vermerkt einen Pfad, der gar nicht erst gesammelt wurde.
+    pub fn skip(&mut self, path: &Path, reason: impl Into<String>) {
+        self.skipped.push(SkippedFile {
+            path: relative_path(path, &self.base_path),
+            reason: reason.into(),
+        });
+    }
+
+    /// Vermerkt alle seit dem letzten Aufruf entfallenen Dateien mit `reason`.
+    pub fn note(&mut self, files: &[CollectedFile], reason: &str) {
+        self.note_with(files, |_| reason.to_string());
+    }
+
+    /// Wie [`SkipTracker::note`], mit einem Grund je Pfad.
+    pub fn note_with(&mut self, files: &[CollectedFile], mut reason: impl FnMut(&str) -> String) {
+        // Filter entfernen nur; gleiche Länge heißt unverändert
+        if files.len() == self.present.len() {
+            return;
+        }
+        let current: HashSet<String> = files.iter().map(|file| relative_path(&file.path, &self.base_path)).collect();
+        for path in std::mem::take(&mut self.present) {
+            if current.contains(&path) {
+                self.present.push(path);
+            } else {
+                let reason = reason(&path);
+                self.skipped.push(SkippedFile { path, reason });
+            }
+        }
+    }
+
+    pub fn skipped(&self) -> &[SkippedFile] {
+        &self.skipped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skip_tracker_and_report() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path();
+        for name in ["a.rs", "b.rs", "c.rs"] {
+            std::fs::write(base.join(name), "fn main() {}\n")?;
+        }
+        let mut files = vec![
+            CollectedFile::from_path(base.join("a.rs"))?,
+            CollectedFile::from_path(base.join("b.rs"))?,
+            CollectedFile::from_path(base.join("c.rs"))?,
+        ];
+        let mut tracker = SkipTracker::new(&files, base);
+        tracker.skip(&base.join("kaputt"), "nicht lesbar");
+        files.remove(1);
+        tracker.note(&files, "generierte Datei");
+        tracker.note(&files, "unverändert");
+        files.remove(0);
+        tracker.note_with(&files, |path| format!("{} abgewählt", path));
+        assert_eq!(tracker.found, 3);
+        let reasons: Vec<(&str, &str)> =
+            tracker.skipped().iter().map(|s| (s.path.as_str(), s.reason.as_str())).collect();
+        assert_eq!(
+            reasons,
+            vec![("kaputt", "nicht lesbar"), ("b.rs", "generierte Datei"), ("a.rs", "a.rs abgewählt")]
+        );
+
+        let mut report = RunReport::new(RunStatus::UpToDate, "demo", base, Local::now(), &tracker, Vec::new());
+        report.counts.exported = files.len();
+        report.outputs = vec!["demo_code.md".to_string()];
+        assert_eq!(report.counts.skipped, 3);
+        let path = base.join("reports/run.json");
+        report.write(&path)?;
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        assert_eq!(json["status"], "up_to_date");
+        assert_eq!(json["counts"]["exported"], 1);
+        assert_eq!(json["skipped"][1]["reason"], "generierte Datei");
+        assert!(json.get("tokens").is_none());
+        Ok(())
+    }
+}
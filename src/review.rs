@@ -0,0 +1,283 @@
+//! Interaktive Nachauswahl nach dem Sammeln (`--review`).
+//!
+//! Die größten Dateien und Verzeichnisse der Auswahl werden einzeln
+//! abgefragt; abgelehnte Pfade entfallen und lassen sich als `exclude` eines
+//! `[dir."…"]`-Abschnitts in `code2md.toml` speichern.
+
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use crate::collector::CollectedFile;
+use crate::error::{Code2MdError, Result};
+use crate::markdown::format_size;
+use crate::processor::relative_path;
+
+/// Anzahl abgefragter Einträge ohne Angabe bei `--review`.
+pub const DEFAULT_COUNT: usize = 10;
+
+/// Eine Datei oder ein Verzeichnis zur Abfrage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    /// Relativer Pfad, Verzeichnisse mit abschließendem `/`
+    pub path: String,
+    /// Gesamtgröße in Bytes
+    pub size: u64,
+    /// Anzahl enthaltener Dateien (1 bei Dateien)
+    pub files: usize,
+}
+
+impl Candidate {
+    fn is_dir(&self) -> bool {
+        self.path.ends_with('/')
+    }
+
+    /// Prüft, ob ein relativer Pfad zu diesem Eintrag gehört.
+    pub fn contains(&self, rel_path: &str) -> bool {
+        if self.is_dir() {
+            rel_path.starts_with(&self.path)
+        } else {
+            rel_path == self.path
+        }
+    }
+}
+
+/// Die `count` größten Dateien und Verzeichnisse, nach Größe absteigend.
+pub fn largest(files: &[CollectedFile], base_path: &Path, count: usize) -> Vec<Candidate> {
+    let mut dirs: BTreeMap<String, (u64, usize)> = BTreeMap::new();
+    let mut candidates = Vec::new();
+    for file in files {
+        let rel = relative_path(&file.path, base_path);
+        let size = file.metadata.len();
+        for (pos, _) in rel.match_indices('/') {
+            let entry = dirs.entry(rel[..=pos].to_string()).or_default();
+            entry.0 += size;
+            entry.1 += 1;
+        }
+        candidates.push(Candidate { path: rel, size, files: 1 });
+    }
+    candidates.extend(dirs.into_iter().map(|(path, (size, files))| Candidate { path, size, files }));
+    candidates.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+    candidates.truncate(count);
+    candidates
+}
+
+/// Fragt jeden Eintrag ab und liefert die ausgeschlossenen. Einträge in
+/// bereits ausgeschlossenen Verzeichnissen werden übersprungen; `a` beendet
+/// die Abfrage und behält den Rest.
+pub fn ask<R: BufRead, W: Write>(candidates: &[Candidate], input: &mut R, output: &mut W) -> io::Result<Vec<Candidate>> {
+    let mut excluded: Vec<Candidate> = Vec::new();
+    for (index, candidate) in candidates.iter().enumerate() {
+        if excluded.iter().any(|e| e.contains(&candidate.path)) {
+            continue;
+        }
+        let detail = if candidate.is_dir() {
+            format!("{}, {} Dateien", format_size(candidate.size), candidate.files)
+        } else {
+            format_size(candidate.size)
+        };
+        write!(
+            output,
+            "[{}/{}] {} ({}) – aufnehmen? [J/n/a] ",
+            index + 1,
+            candidates.len(),
+            candidate.path,
+            detail
+        )?;
+        output.flush()?;
+        match read_answer(input)?.as_deref() {
+            Some("n" | "nein") => excluded.push(candidate.clone()),
+            Some("a" | "abbrechen") | None => break,
+            _ => {}
+        }
+    }
+    Ok(excluded)
+}
+
+/// Stellt eine Ja/Nein-Frage; Standard ist Nein.
+pub fn confirm<R: BufRead, W: Write>(question: &str, input: &mut R, output: &mut W) -> io::Result<bool> {
+    write!(output, "{} [j/N] ", question)?;
+    output.flush()?;
+    Ok(matches!(read_answer(input)?.as_deref(), Some("j" | "ja" | "y" | "yes")))
+}
+
+/// Liest eine Antwortzeile in Kleinbuchstaben; `None` am Ende der Eingabe.
+fn read_answer<R: BufRead>(input: &mut R) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim().to_lowercase()))
+}
+
+/// Ausschlussmuster für `[dir."…"].exclude`; Verzeichnisse ohne `/` am Ende.
+pub fn exclude_pattern(candidate: &Candidate) -> String {
+    candidate.path.trim_end_matches('/').to_string()
+}
+
+/// Ergänzt die Muster im `exclude` des Abschnitts `[dir."<dir>"]` einer
+/// Konfigurationsdatei. Fehlen Datei oder Abschnitt, werden sie angelegt;
+/// der übrige Inhalt bleibt unverändert.
+pub fn save_excludes(config_path: &Path, dir: &str, patterns: &[String]) -> Result<()> {
+    let content = match std::fs::read_to_string(config_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(Code2MdError::io(config_path, e)),
+    };
+    let updated = add_excludes(&content, dir, patterns)?;
+    std::fs::write(config_path, updated).map_err(|e| Code2MdError::io(config_path, e))
+}
+
+/// Wie [`save_excludes`], auf dem Inhalt einer Konfigurationsdatei.
+pub fn add_excludes(content: &str, dir: &str, patterns: &[String]) -> Result<String> {
+    let key = if dir.is_empty() { "./".to_string() } else { format!("{}/", dir.trim_end_matches('/')) };
+    let lines: Vec<&str> = content.lines().collect();
+    let normalize = |key: &str| key.trim_start_matches("./").trim_matches('/').to_string();
+
+    // Abschnitt des Verzeichnisses suchen
+    let header = lines.iter().position(|line| {
+        line.trim()
+            .strip_prefix("[dir.\"")
+            .and_then(|rest| rest.strip_suffix("\"]"))
+            .is_some_and(|found| normalize(found) == normalize(&key))
+    });
+    let Some(header) = header else {
+        let mut updated = content.to_string();
+        if !updated.is_empty() && !updated.ends_with('\n') {
+            updated.push('\n');
+        }
+        if !updated.is_empty() {
+            updated.push('\n');
+        }
+        updated.push_str(&format!("[dir.\"{}\"]\n{}\n", key, exclude_line(patterns)));
+        return Ok(updated);
+    };
+    let end = lines[header + 1..]
+        .iter()
+        .position(|line| line.trim_start().starts_with('['))
+        .map_or(lines.len(), |pos| header + 1 + pos);
+
+    // Vorhandenes `exclude` um neue Muster ergänzen
+    let section = crate::config::Config::parse(&lines[header..end].join("\n"))?;
+    let mut merged: Vec<String> = section.dir.into_values().next().map(|d| d.exclude).unwrap_or_default();
+    let existing = lines[header + 1..end].iter().position(|line| line.trim_start().starts_with("exclude"));
+    for pattern in patterns {
+        if !merged.contains(pattern) {
+            merged.push(pattern.clone());
+        }
+    }
+
+    let mut updated: Vec<String> = lines[..=header].iter().map(|line| line.to_string()).collect();
+    match existing {
+        Some(pos) => {
+            let start = header + 1 + pos;
+            // Mehrzeilige Arrays reichen bis zur schließenden Klammer
+            let close = (start..end).find(|&i| array_closed(&lines[start..=i].join("\n"))).unwrap_or(start);
+            updated.extend(lines[header + 1..start].iter().map(|line| line.to_string()));
+            updated.push(exclude_line(&merged));
+            updated.extend(lines[close + 1..].iter().map(|line| line.to_string()));
+        }
+        None => {
+            updated.push(exclude_line(&merged));
+            updated.extend(lines[header + 1..].iter().map(|line| line.to_string()));
+        }
+    }
+    let mut updated = updated.join("\n");
+    updated.push('\n');
+    Ok(updated)
+}
+
+fn exclude_line(patterns: &[String]) -> String {
+    let quoted: Vec<String> = patterns.iter().map(|p| format!("{:?}", p)).collect();
+    format!("exclude = [{}]", quoted.join(", "))
+}
+
+/// Prüft, ob der Text einer Zuweisung ein vollständiges Array enthält.
+fn array_closed(text: &str) -> bool {
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for c in text.chars() {
+        match quote {
+            Some(q) => {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' && q == '"' {
+                    escaped = true;
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '[' => depth += 1,
+                ']' => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        return true;
+                    }
+                }
+                '#' => return false,
+                _ => {}
+            },
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_largest_and_ask() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path();
+        std::fs::create_dir_all(base.join("fixtures/big"))?;
+        std::fs::write(base.join("fixtures/big/a.json"), "x".repeat(5000))?;
+        std::fs::write(base.join("fixtures/b.json"), "x".repeat(3000))?;
+        std::fs::write(base.join("main.py"), "x".repeat(100))?;
+        let files = vec![
+            CollectedFile::from_path(base.join("fixtures/big/a.json"))?,
+            CollectedFile::from_path(base.join("fixtures/b.json"))?,
+            CollectedFile::from_path(base.join("main.py"))?,
+        ];
+
+        let candidates = largest(&files, base, 3);
+        let paths: Vec<&str> = candidates.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, vec!["fixtures/", "fixtures/big/", "fixtures/big/a.json"]);
+        assert_eq!(candidates[0].files, 2);
+
+        // Nach dem Ausschluss von fixtures/ wird nichts darin mehr abgefragt
+        let mut output = Vec::new();
+        let excluded = ask(&candidates, &mut "n\n".as_bytes(), &mut output)?;
+        assert_eq!(excluded, vec![candidates[0].clone()]);
+        assert_eq!(String::from_utf8(output)?.matches("aufnehmen?").count(), 1);
+
+        let excluded = ask(&candidates, &mut "\nj\nn\n".as_bytes(), &mut Vec::new())?;
+        assert_eq!(excluded.iter().map(exclude_pattern).collect::<Vec<_>>(), vec!["fixtures/big/a.json"]);
+        assert!(confirm("Speichern?", &mut "ja\n".as_bytes(), &mut Vec::new())?);
+        assert!(!confirm("Speichern?", &mut "".as_bytes(), &mut Vec::new())?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_excludes() -> anyhow::Result<()> {
+        let patterns = vec!["fixtures".to_string()];
+        assert_eq!(add_excludes("", "", &patterns)?, "[dir.\"./\"]\nexclude = [\"fixtures\"]\n");
+
+        let content = "badges = []\n\n[dir.\"app/\"]\ntypes = [\"python\"]\nexclude = [\n  \"dist\",\n]\n\n[repository]\nurl = \"x\"\n";
+        let updated = add_excludes(content, "app", &patterns)?;
+        assert_eq!(
+            updated,
+            "badges = []\n\n[dir.\"app/\"]\ntypes = [\"python\"]\nexclude = [\"dist\", \"fixtures\"]\n\n[repository]\nurl = \"x\"\n"
+        );
+        let config = crate::config::Config::parse(&updated)?;
+        assert_eq!(config.dir["app/"].exclude, vec!["dist", "fixtures"]);
+
+        // Abschnitt ohne exclude
+        let updated = add_excludes("[dir.\"./\"]\ntypes = [\"rust\"]\n", "", &patterns)?;
+        assert_eq!(updated, "[dir.\"./\"]\nexclude = [\"fixtures\"]\ntypes = [\"rust\"]\n");
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+//! Gespeicherte Dateiauswahlen in `.code2md/selections/<name>.list`.
+//!
+//! Eine Auswahl ist eine Liste relativer Pfade, eine pro Zeile; Leerzeilen
+//! und Zeilen mit `#` werden ignoriert. So lassen sich kuratierte Exporte aus
+//! `--interactive` oder `code2md pick` nach Codeänderungen neu erzeugen.
+
+use std::path::{Path, PathBuf};
+
+use crate::cache::CACHE_DIR;
+use crate::error::{Code2MdError, Result};
+
+/// Pfad der Auswahl `name` im Projekt.
+pub fn selection_path(base_path: &Path, name: &str) -> Result<PathBuf> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(Code2MdError::Config(format!(
+            "Ungültiger Auswahlname '{}' (erlaubt: Buchstaben, Ziffern, '-', '_', '.')",
+            name
+        )));
+    }
+    Ok(base_path.join(CACHE_DIR).join("selections").join(format!("{}.list", name)))
+}
+
+/// Speichert die relativen Pfade als Auswahl `name` und gibt den Dateipfad zurück.
+pub fn save(base_path: &Path, name: &str, paths: &[String]) -> Result<PathBuf> {
+    let path = selection_path(base_path, name)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| Code2MdError::io(dir, e))?;
+    }
+    let mut content = format!("# code2md-Auswahl '{}'\n", name);
+    for rel_path in paths {
+        content.push_str(rel_path);
+        content.push('\n');
+    }
+    std::fs::write(&path, content).map_err(|e| Code2MdError::io(&path, e))?;
+    Ok(path)
+}
+
+/// Lädt die Auswahl `name`.
+pub fn load(base_path: &Path, name: &str) -> Result<Vec<String>> {
+    let path = selection_path(base_path, name)?;
+    let content = std::fs::read_to_string(&path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => {
+            Code2MdError::Config(format!("Auswahl '{}' nicht gefunden ({}).", name, path.display()))
+        }
+        _ => Code2MdError::io(&path, e),
+    })?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.replace('\\', "/"))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let paths = vec!["src/main.rs".to_string(), "Cargo.toml".to_string()];
+        let path = save(dir.path(), "bug-123", &paths)?;
+        assert_eq!(path, dir.path().join(".code2md/selections/bug-123.list"));
+        assert_eq!(load(dir.path(), "bug-123")?, paths);
+
+        std::fs::write(&path, "# Kommentar\n\nsrc\\lib.rs\n")?;
+        assert_eq!(load(dir.path(), "bug-123")?, vec!["src/lib.rs"]);
+
+        assert!(load(dir.path(), "fehlt").is_err());
+        assert!(selection_path(dir.path(), "../x").is_err());
+        assert!(selection_path(dir.path(), "").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,276 @@
+//! Lokaler HTTP-Vorschauserver mit Live-Reload.
+//!
+//! Der Export wird als HTML ausgeliefert und bei Dateiänderungen neu
+//! generiert; geöffnete Browser laden über eine WebSocket-Verbindung neu.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::Duration;
+
+use pulldown_cmark::{html, Options, Parser};
+use tracing::{info, warn};
+use tungstenite::Message;
+
+use crate::cli::SelectionArgs;
+use crate::collector::{collect_files, CollectedFile, CollectorConfig};
+use crate::error::{Code2MdError, Result};
+use crate::handlers::HandlerConfig;
+use crate::markdown::{generate_markdown, MarkdownConfig};
+use crate::paths;
+
+/// Intervall, in dem das Projekt auf Änderungen geprüft wird.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Aktuell ausgelieferte Vorschau.
+struct Preview {
+    version: u64,
+    html: String,
+}
+
+/// Startet den Vorschauserver und blockiert, bis der Prozess beendet wird.
+pub fn serve(selection: &SelectionArgs, port: u16) -> Result<()> {
+    let base_path =
+        paths::canonicalize(&selection.directory).map_err(|e| Code2MdError::io(&selection.directory, e))?;
+    let project_name = selection.project_name();
+    let collector = selection.collector_config()?;
+
+    let (fingerprint, html) = render_preview(&base_path, &project_name, &collector)?;
+    let preview = Arc::new(RwLock::new(Preview { version: 0, html }));
+
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .map_err(|e| Code2MdError::Server(format!("Port {} nicht verfügbar: {}", port, e)))?;
+    info!("Vorschau läuft auf http://127.0.0.1:{}/ (Strg+C zum Beenden)", port);
+
+    // Änderungen überwachen
+    {
+        let preview = Arc::clone(&preview);
+        thread::spawn(move || watch(base_path, project_name, collector, fingerprint, preview));
+    }
+
+    for stream in listener.incoming().flatten() {
+        let preview = Arc::clone(&preview);
+        thread::spawn(move || {
+            if let Err(e) = handle_connection(stream, &preview) {
+                warn!("Verbindungsfehler: {}", e);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Prüft das Projekt periodisch und generiert die Vorschau bei Änderungen neu.
+fn watch(
+    base_path: PathBuf,
+    project_name: String,
+    collector: CollectorConfig,
+    mut fingerprint: u64,
+    preview: Arc<RwLock<Preview>>,
+) {
+    loop {
+        thread::sleep(POLL_INTERVAL);
+
+        let current = match collect_files(&base_path, &collector) {
+            Ok(collected) => files_fingerprint(&collected.files),
+            Err(_) => continue,
+        };
+        if current == fingerprint {
+            continue;
+        }
+
+        match render_preview(&base_path, &project_name, &collector) {
+            Ok((new_fingerprint, html)) => {
+                fingerprint = new_fingerprint;
+                if let Ok(mut preview) = preview.write() {
+                    preview.version += 1;
+                    preview.html = html;
+                }
+                info!("Änderung erkannt, Vorschau aktualisiert.");
+            }
+            Err(e) => warn!("Fehler beim Generieren: {}", e),
+        }
+    }
+}
+
+/// Beantwortet eine HTTP-Anfrage oder übernimmt sie als WebSocket.
+fn handle_connection(mut stream: TcpStream, preview: &RwLock<Preview>) -> Result<()> {
+    // Anfragekopf nur ansehen, damit der WebSocket-Handshake ihn noch lesen kann
+    let mut buffer = [0u8; 4096];
+    let n = stream.peek(&mut buffer).map_err(server_error)?;
+    let request = String::from_utf8_lossy(&buffer[..n]);
+    let path = request.split_whitespace().nth(1).unwrap_or("/");
+    let port = stream.local_addr().map_err(server_error)?.port();
+    let allowed = host_allowed(&request, port);
+
+    if allowed && path == "/livereload" {
+        return serve_websocket(stream, preview);
+    }
+
+    // Restliche Anfrage verwerfen
+    let mut discard = [0u8; 4096];
+    let _ = stream.read(&mut discard).map_err(server_error)?;
+
+    let (status, body) = if !allowed {
+        ("403 Forbidden", "Unbekannter Host".to_string())
+    } else if path == "/" {
+        let html = preview.read().map(|p| p.html.clone()).unwrap_or_default();
+        ("200 OK", html)
+    } else {
+        ("404 Not Found", "Nicht gefunden".to_string())
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+    .map_err(server_error)?;
+    Ok(())
+}
+
+/// Prüft den `Host`-Header gegen die eigene Adresse. Schützt vor DNS-Rebinding:
+/// Eine fremde Seite, deren Name auf 127.0.0.1 zeigt, darf die Vorschau nicht lesen.
+fn host_allowed(request: &str, port: u16) -> bool {
+    request
+        .lines()
+        .skip(1)
+        .take_while(|line| !line.trim().is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("host"))
+        .is_some_and(|(_, host)| {
+            let host = host.trim();
+            host.eq_ignore_ascii_case(&format!("127.0.0.1:{}", port))
+                || host.eq_ignore_ascii_case(&format!("localhost:{}", port))
+        })
+}
+
+/// Hält eine WebSocket-Verbindung offen und meldet neue Versionen.
+fn serve_websocket(stream: TcpStream, preview: &RwLock<Preview>) -> Result<()> {
+    let mut socket = tungstenite::accept(stream).map_err(server_error)?;
+    let mut seen = preview.read().map(|p| p.version).unwrap_or(0);
+
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        let version = preview.read().map(|p| p.version).unwrap_or(seen);
+        if version != seen {
+            seen = version;
+            socket.send(Message::text("reload")).map_err(server_error)?;
+        }
+    }
+}
+
+/// Generiert den Export und rendert ihn als HTML-Seite.
+fn render_preview(
+    base_path: &Path,
+    project_name: &str,
+    collector: &CollectorConfig,
+) -> Result<(u64, String)> {
+    let collected = collect_files(base_path, collector)?;
+    let config = MarkdownConfig {
+        project_name: project_name.to_string(),
+        base_path: collected.base_path.clone(),
+        include_tree: true,
+        handlers: HandlerConfig {
+            output_dir: collected.base_path.clone(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let markdown = generate_markdown(&collected.files, &config);
+    Ok((
+        files_fingerprint(&collected.files),
+        render_html(&markdown, project_name),
+    ))
+}
+
+fn server_error(e: impl std::fmt::Display) -> Code2MdError {
+    Code2MdError::Server(e.to_string())
+}
+
+/// Berechnet einen Fingerabdruck aus Pfaden, Größen und Änderungszeiten.
+fn files_fingerprint(files: &[CollectedFile]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for file in files {
+        file.path.hash(&mut hasher);
+        file.size().hash(&mut hasher);
+        file.metadata.modified().ok().hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Wandelt Markdown in eine vollständige HTML-Seite mit Live-Reload-Skript um.
+pub fn render_html(markdown: &str, title: &str) -> String {
+    let parser = Parser::new_ext(markdown, Options::ENABLE_TABLES);
+    let mut body = String::new();
+    html::push_html(&mut body, parser);
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="de">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: system-ui, sans-serif; max-width: 960px; margin: 2rem auto; padding: 0 1rem; line-height: 1.5; }}
+pre {{ background: #f6f8fa; padding: 1rem; overflow-x: auto; }}
+code {{ font-family: ui-monospace, monospace; font-size: 0.9em; }}
+table {{ border-collapse: collapse; }}
+th, td {{ border: 1px solid #ddd; padding: 0.25rem 0.5rem; }}
+</style>
+</head>
+<body>
+{body}
+<script>
+const socket = new WebSocket(`ws://${{location.host}}/livereload`);
+socket.onmessage = () => location.reload();
+</script>
+</body>
+</html>
+"#,
+        title = title.replace('<', "&lt;"),
+        body = body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_html() {
+        let html = render_html("# Demo\n\n```rust\nfn main() {}\n```", "Demo");
+        assert!(html.contains("<h1>Demo</h1>"));
+        assert!(html.contains("<code class=\"language-rust\">"));
+        assert!(html.contains("/livereload"));
+    }
+
+    #[test]
+    fn test_host_allowed() {
+        let request = |host: &str| format!("GET / HTTP/1.1\r\nHost: {}\r\nAccept: */*\r\n\r\n", host);
+        assert!(host_allowed(&request("127.0.0.1:8080"), 8080));
+        assert!(host_allowed(&request("localhost:8080"), 8080));
+        assert!(!host_allowed(&request("localhost:9090"), 8080));
+        assert!(!host_allowed(&request("evil.example:8080"), 8080));
+        assert!(!host_allowed("GET / HTTP/1.1\r\n\r\nHost: localhost:8080\r\n", 8080));
+    }
+
+    #[test]
+    fn test_files_fingerprint_changes() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("a.py");
+        std::fs::write(&file, "a")?;
+        let before = files_fingerprint(&[CollectedFile::from_path(&file)?]);
+
+        std::fs::write(&file, "ab")?;
+        assert_ne!(before, files_fingerprint(&[CollectedFile::from_path(&file)?]));
+        Ok(())
+    }
+}
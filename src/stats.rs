@@ -0,0 +1,118 @@
+//! Projektstatistik für `--stats`.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::collector::CollectedFile;
+use crate::processor::relative_path;
+use crate::types::get_syntax_for_file;
+
+/// Anzahl der größten Dateien in der Statistik.
+pub const LARGEST_FILES: usize = 10;
+
+/// Kennzahlen je Sprache.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LanguageStats {
+    pub files: usize,
+    pub lines: usize,
+}
+
+/// Kennzahlen über alle exportierten Dateien.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectStats {
+    pub total_lines: usize,
+    /// Sprache (Syntax-Angabe des Codeblocks) → Dateien und Zeilen
+    pub languages: BTreeMap<String, LanguageStats>,
+    /// Verzeichnis relativ zum Projekt → Anzahl Dateien
+    pub directories: BTreeMap<String, usize>,
+    /// Größte Dateien als (relativer Pfad, Bytes), absteigend
+    pub largest: Vec<(String, u64)>,
+}
+
+impl ProjectStats {
+    /// Sprachen absteigend nach Zeilen.
+    pub fn languages_by_lines(&self) -> Vec<(&str, &LanguageStats)> {
+        let mut languages: Vec<(&str, &LanguageStats)> =
+            self.languages.iter().map(|(name, stats)| (name.as_str(), stats)).collect();
+        languages.sort_by(|a, b| b.1.lines.cmp(&a.1.lines).then_with(|| a.0.cmp(b.0)));
+        languages
+    }
+}
+
+/// Berechnet die Statistik. Binärdateien zählen als Dateien ohne Zeilen;
+/// `languages` enthält abweichende Sprachen je Pfad (`linguist-language`).
+pub fn compute_stats(files: &[CollectedFile], base_path: &Path, languages: &BTreeMap<String, String>) -> ProjectStats {
+    let mut stats = ProjectStats::default();
+    let mut sizes = Vec::with_capacity(files.len());
+
+    for file in files {
+        let rel_str = relative_path(&file.path, base_path);
+        let lines = file.bytes().map(|bytes| count_lines(&bytes)).unwrap_or(0);
+        stats.total_lines += lines;
+
+        let filename = rel_str.rsplit('/').next().unwrap_or(&rel_str);
+        let language = match languages.get(&rel_str).map(String::as_str) {
+            Some(language) => language,
+            None => match get_syntax_for_file(filename) {
+                "" => "Sonstige",
+                syntax => syntax,
+            },
+        };
+        let entry = stats.languages.entry(language.to_string()).or_default();
+        entry.files += 1;
+        entry.lines += lines;
+
+        let directory = rel_str.rsplit_once('/').map_or(".", |(dir, _)| dir);
+        *stats.directories.entry(directory.to_string()).or_default() += 1;
+
+        sizes.push((rel_str, file.size()));
+    }
+
+    sizes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    sizes.truncate(LARGEST_FILES);
+    stats.largest = sizes;
+    stats
+}
+
+/// Zählt die Zeilen eines Textinhalts; Binärinhalte haben keine Zeilen.
+pub fn count_lines(bytes: &[u8]) -> usize {
+    if bytes.is_empty() || bytes[..bytes.len().min(8192)].contains(&0) {
+        return 0;
+    }
+    let newlines = bytes.iter().filter(|&&b| b == b'\n').count();
+    newlines + usize::from(!bytes.ends_with(b"\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_stats() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path();
+        std::fs::create_dir_all(base.join("src/util"))?;
+        for (path, content) in [
+            ("src/main.rs", "fn main() {\n    run();\n}\n".as_bytes()),
+            ("src/util/mod.rs", b"pub fn run() {}"),
+            ("build.py", b"print(1)\nprint(2)\n"),
+            ("logo.bin", b"\x00\x01\n\x02"),
+        ] {
+            std::fs::write(base.join(path), content)?;
+        }
+        let files: Vec<CollectedFile> = ["src/main.rs", "src/util/mod.rs", "build.py", "logo.bin"]
+            .iter()
+            .map(|p| CollectedFile::from_path(base.join(p)))
+            .collect::<std::io::Result<_>>()?;
+
+        let stats = compute_stats(&files, base, &BTreeMap::new());
+        assert_eq!(stats.total_lines, 6);
+        assert_eq!(stats.languages["rust"], LanguageStats { files: 2, lines: 4 });
+        assert_eq!(stats.languages["Sonstige"], LanguageStats { files: 1, lines: 0 });
+        assert_eq!(stats.languages_by_lines()[0].0, "rust");
+        assert_eq!(stats.directories["."], 2);
+        assert_eq!(stats.directories["src/util"], 1);
+        assert_eq!(stats.largest[0], ("src/main.rs".to_string(), 25));
+        Ok(())
+    }
+}
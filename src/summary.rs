@@ -0,0 +1,79 @@
+//! Zusammenfassungen einzelner Dateien über einen externen Befehl (`--summarize-cmd`).
+
+use std::sync::{Mutex, MutexGuard};
+
+use crate::cache::SummaryCache;
+use crate::error::{Code2MdError, Result};
+use crate::transform::pipe_through;
+
+/// Fasst Dateiinhalte mit einem Shell-Befehl zusammen.
+///
+/// Der Befehl erhält den Inhalt über stdin und gibt die Zusammenfassung auf
+/// stdout aus. Ergebnisse werden nach Befehl und Inhalt zwischengespeichert.
+#[derive(Debug)]
+pub struct Summarizer {
+    command: String,
+    cache: Mutex<SummaryCache>,
+}
+
+impl Summarizer {
+    pub fn new(command: impl Into<String>, cache: SummaryCache) -> Self {
+        Self {
+            command: command.into(),
+            cache: Mutex::new(cache),
+        }
+    }
+
+    /// Liefert die Zusammenfassung eines Inhalts als einzelnen Absatz.
+    pub fn summarize(&self, content: &str) -> Result<String> {
+        let key = SummaryCache::key(&self.command, content);
+        if let Some(summary) = self.lock_cache().summaries.get(&key) {
+            return Ok(summary.clone());
+        }
+
+        let output = pipe_through(content, &self.command)?;
+        let summary = output.split_whitespace().collect::<Vec<_>>().join(" ");
+        if summary.is_empty() {
+            return Err(Code2MdError::Render(format!(
+                "'{}' lieferte keine Zusammenfassung",
+                self.command
+            )));
+        }
+        self.lock_cache().summaries.insert(key, summary.clone());
+        Ok(summary)
+    }
+
+    /// Gibt den aktuellen Cache-Stand zurück.
+    pub fn cache(&self) -> SummaryCache {
+        self.lock_cache().clone()
+    }
+
+    fn lock_cache(&self) -> MutexGuard<'_, SummaryCache> {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize_cached() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let counter = dir.path().join("aufrufe");
+        let command = format!("echo x >> '{}'; wc -l | tr -d ' '; echo Zeilen", counter.display());
+        let summarizer = Summarizer::new(command, SummaryCache::default());
+
+        assert_eq!(summarizer.summarize("a\nb\n")?, "2 Zeilen");
+        assert_eq!(summarizer.summarize("a\nb\n")?, "2 Zeilen");
+        assert_eq!(std::fs::read_to_string(&counter)?.lines().count(), 1);
+
+        // Der Cache überdauert den Summarizer
+        let again = Summarizer::new(summarizer.command.clone(), summarizer.cache());
+        assert_eq!(again.summarize("a\nb\n")?, "2 Zeilen");
+        assert_eq!(std::fs::read_to_string(&counter)?.lines().count(), 1);
+
+        assert!(Summarizer::new("true", SummaryCache::default()).summarize("a").is_err());
+        Ok(())
+    }
+}
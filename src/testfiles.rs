@@ -0,0 +1,151 @@
+//! Erkennung von Testcode für `--separate-tests` und `--no-tests`.
+
+use std::path::Path;
+
+use crate::docs::apply_braces;
+use crate::processor::FileProcessor;
+
+/// Verzeichnisnamen, deren Inhalt als Testcode gilt.
+const TEST_DIRS: &[&str] = &["tests", "test", "__tests__", "spec"];
+
+/// Endungen von JavaScript/TypeScript-Dateien für `.test.*`/`.spec.*`.
+const SCRIPT_EXTENSIONS: &[&str] = &["js", "jsx", "ts", "tsx", "mjs", "cjs"];
+
+/// Prüft anhand von Verzeichnis und Dateiname, ob eine Datei Testcode enthält.
+pub fn is_test_file(rel_path: &str) -> bool {
+    let rel_path = rel_path.replace('\\', "/");
+    let mut components: Vec<&str> = rel_path.split('/').collect();
+    let filename = components.pop().unwrap_or_default();
+    if components.iter().any(|dir| TEST_DIRS.contains(dir)) {
+        return true;
+    }
+
+    let (stem, ext) = filename.rsplit_once('.').unwrap_or((filename, ""));
+    match ext {
+        "go" | "rs" => stem.ends_with("_test"),
+        "py" => stem.starts_with("test_") || stem.ends_with("_test"),
+        "rb" => stem.ends_with("_spec") || stem.ends_with("_test"),
+        "java" | "kt" | "cs" => stem.ends_with("Test") || stem.ends_with("Tests"),
+        ext if SCRIPT_EXTENSIONS.contains(&ext) => stem.ends_with(".test") || stem.ends_with(".spec"),
+        _ => false,
+    }
+}
+
+/// Trennt `#[cfg(test)]`-Module aus Rust-Quelltext heraus.
+///
+/// Gibt den Code ohne Testmodule und die Testmodule zurück. Externe
+/// Testmodule (`mod tests;`) werden nur aus dem Code entfernt.
+pub fn split_test_modules(content: &str) -> (String, Vec<String>) {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut code: Vec<&str> = Vec::new();
+    let mut modules = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        if lines[i].trim() != "#[cfg(test)]" {
+            code.push(lines[i]);
+            i += 1;
+            continue;
+        }
+
+        let Some(decl) = (i + 1..lines.len()).find(|&j| !lines[j].trim().is_empty()) else {
+            code.push(lines[i]);
+            break;
+        };
+        let trimmed = lines[decl].trim();
+        let is_module = trimmed.starts_with("mod ") || trimmed.starts_with("pub mod ") || trimmed.starts_with("pub(crate) mod ");
+        if !is_module {
+            code.push(lines[i]);
+            i += 1;
+            continue;
+        }
+
+        let mut end = decl;
+        if !trimmed.ends_with(';') {
+            let mut depth = 0;
+            for (j, line) in lines.iter().enumerate().skip(decl) {
+                depth = apply_braces(depth, line);
+                end = j;
+                if depth == 0 && line.contains('}') {
+                    break;
+                }
+            }
+            modules.push(lines[i..=end].join("\n"));
+        }
+        i = end + 1;
+    }
+
+    while code.last().is_some_and(|l| l.trim().is_empty()) {
+        code.pop();
+    }
+    let mut code = code.join("\n");
+    if content.ends_with('\n') {
+        code.push('\n');
+    }
+    (code, modules)
+}
+
+/// Entfernt Testcode aus dem Export.
+///
+/// Inline-Testmodule in Rust-Dateien werden immer entfernt; mit
+/// `exclude_files` entfallen zusätzlich erkannte Testdateien ganz.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StripTests {
+    pub exclude_files: bool,
+}
+
+impl FileProcessor for StripTests {
+    fn name(&self) -> &str {
+        "strip-tests"
+    }
+
+    fn filter(&self, rel_path: &str) -> bool {
+        !(self.exclude_files && is_test_file(rel_path))
+    }
+
+    fn transform(&self, rel_path: &str, content: String) -> String {
+        if Path::new(rel_path).extension().is_some_and(|ext| ext == "rs") && !is_test_file(rel_path) {
+            split_test_modules(&content).0
+        } else {
+            content
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_test_file() {
+        for path in [
+            "tests/cli.rs",
+            "pkg/api/handler_test.go",
+            "src/__tests__/app.js",
+            "web/button.spec.ts",
+            "lib/util.test.tsx",
+            "app/test_models.py",
+            "src/main/java/FooTest.java",
+            "spec/models/user_spec.rb",
+        ] {
+            assert!(is_test_file(path), "{}", path);
+        }
+        for path in ["src/main.rs", "pkg/api/handler.go", "web/spec.ts", "src/testing.py", "Contest.java"] {
+            assert!(!is_test_file(path), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_split_test_modules() {
+        let source = "pub fn add(a: u32) -> u32 {\n    a + 1\n}\n\n#[cfg(test)]\nmod tests {\n    use super::*;\n\n    #[test]\n    fn t() {\n        assert_eq!(add(1), 2);\n    }\n}\n";
+        let (code, modules) = split_test_modules(source);
+        assert_eq!(code, "pub fn add(a: u32) -> u32 {\n    a + 1\n}\n");
+        assert_eq!(modules.len(), 1);
+        assert!(modules[0].starts_with("#[cfg(test)]\nmod tests {"));
+        assert!(modules[0].ends_with("    }\n}"));
+
+        let (code, modules) = split_test_modules("fn a() {}\n#[cfg(test)]\nmod tests;\n#[cfg(test)]\nfn helper() {}\n");
+        assert_eq!(code, "fn a() {}\n#[cfg(test)]\nfn helper() {}\n");
+        assert!(modules.is_empty());
+    }
+}
@@ -0,0 +1,104 @@
+//! Erkennung offener Punkte (TODO, FIXME, HACK) für `--todos`.
+
+use std::path::Path;
+
+use crate::collector::CollectedFile;
+use crate::processor::relative_path;
+
+/// Markierungen, nach denen gesucht wird.
+pub const MARKERS: &[&str] = &["TODO", "FIXME", "HACK"];
+
+/// Ein offener Punkt im Quelltext.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TodoItem {
+    /// Pfad relativ zum Projekt
+    pub path: String,
+    /// Zeilennummer, beginnend bei 1
+    pub line: usize,
+    pub marker: &'static str,
+    /// Kommentartext nach der Markierung
+    pub text: String,
+}
+
+/// Sucht Markierungen als eigenständige Wörter in Großschreibung, gefolgt von
+/// Doppelpunkt, Klammer, Leerzeichen oder Zeilenende. Erwähnungen wie
+/// `"TODO"` oder `TODO-Liste` zählen nicht.
+pub fn find_todos(rel_path: &str, content: &str) -> Vec<TodoItem> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let mut items = Vec::new();
+    for (number, line) in content.lines().enumerate() {
+        // Bei mehreren Markierungen zählt die erste in der Zeile
+        let found = MARKERS
+            .iter()
+            .filter_map(|&marker| {
+                line.match_indices(marker)
+                    .map(|(pos, _)| pos)
+                    .find(|&pos| {
+                        !line[..pos].chars().next_back().is_some_and(is_ident)
+                            && line[pos + marker.len()..]
+                                .chars()
+                                .next()
+                                .is_none_or(|c| matches!(c, ':' | '(' | ' ' | '\t' | '!'))
+                    })
+                    .map(|pos| (pos, marker))
+            })
+            .min_by_key(|(pos, _)| *pos);
+        if let Some((pos, marker)) = found {
+            items.push(TodoItem {
+                path: rel_path.to_string(),
+                line: number + 1,
+                marker,
+                text: clean_text(&line[pos + marker.len()..]),
+            });
+        }
+    }
+    items
+}
+
+/// Offene Punkte aller Dateien in Dateireihenfolge.
+pub fn collect_todos(files: &[CollectedFile], base_path: &Path) -> Vec<TodoItem> {
+    files
+        .iter()
+        .flat_map(|file| {
+            let rel_str = relative_path(&file.path, base_path);
+            file.text().map(|content| find_todos(&rel_str, &content)).unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Entfernt Autorangaben wie `(alice)`, Doppelpunkte und Kommentarenden.
+fn clean_text(rest: &str) -> String {
+    let mut text = rest.trim_start();
+    if text.starts_with('(') {
+        if let Some(end) = text.find(')') {
+            text = &text[end + 1..];
+        }
+    }
+    let text = text.trim_start_matches([':', '-', ' ']).trim_end();
+    let text = text
+        .strip_suffix("*/")
+        .or_else(|| text.strip_suffix("-->"))
+        .unwrap_or(text);
+    text.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_todos() {
+        let content = "fn a() {\n    // TODO(alice): Fehler behandeln\n    let TODO_LIST = 1; /* FIXME */\n}\n# HACK - Workaround für #12\nTODOS, \"TODO\" und TODO-Listen sind keine Marker\n<!-- TODO: Bild ersetzen -->\n";
+        let items = find_todos("src/a.rs", content);
+        let found: Vec<(usize, &str, &str)> = items.iter().map(|i| (i.line, i.marker, i.text.as_str())).collect();
+        assert_eq!(
+            found,
+            vec![
+                (2, "TODO", "Fehler behandeln"),
+                (3, "FIXME", ""),
+                (5, "HACK", "Workaround für #12"),
+                (7, "TODO", "Bild ersetzen"),
+            ]
+        );
+    }
+}
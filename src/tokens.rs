@@ -0,0 +1,247 @@
+//! Schätzung von Token-Zahlen für Sprachmodelle.
+//!
+//! Die Schätzung bildet das Verhalten von BPE-Tokenizern grob nach:
+//! Wörter zerfallen abhängig vom Vokabular in mehrere Tokens, Satzzeichen
+//! werden paarweise zusammengefasst, Einrückungen hängen am Zeilenumbruch.
+//! Die Werte sind Schätzungen, keine exakten Tokenizer-Ergebnisse.
+
+use std::path::Path;
+
+use crate::collector::CollectedFile;
+use crate::error::{Code2MdError, Result};
+use crate::processor::relative_path;
+
+/// Tokenizer-Familie, nach der geschätzt wird.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tokenizer {
+    /// OpenAI cl100k_base (GPT-4, GPT-3.5)
+    Cl100k,
+    /// OpenAI o200k_base (GPT-4o, o1, o3)
+    O200k,
+    /// Anthropic Claude
+    Claude,
+    /// Meta Llama 3
+    Llama3,
+}
+
+impl Tokenizer {
+    /// Durchschnittliche Zeichen je Token innerhalb von Wörtern.
+    fn chars_per_token(&self) -> f64 {
+        match self {
+            Tokenizer::Cl100k => 4.0,
+            Tokenizer::O200k => 4.4,
+            Tokenizer::Claude => 3.8,
+            Tokenizer::Llama3 => 4.2,
+        }
+    }
+}
+
+/// Anteil des Kontextfensters, der für den Export genutzt wird; der Rest
+/// bleibt für Prompt und Antwort.
+const BUDGET_PERCENT: usize = 80;
+
+/// Bekanntes Sprachmodell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Model {
+    pub name: &'static str,
+    pub tokenizer: Tokenizer,
+    /// Größe des Kontextfensters in Tokens
+    pub context_window: usize,
+}
+
+impl Model {
+    /// Token-Budget für einen Export mit diesem Modell.
+    pub fn token_budget(&self) -> usize {
+        self.context_window * BUDGET_PERCENT / 100
+    }
+}
+
+/// Bekannte Modelle für `--model`.
+pub static MODELS: &[Model] = &[
+    Model {
+        name: "gpt-4o",
+        tokenizer: Tokenizer::O200k,
+        context_window: 128_000,
+    },
+    Model {
+        name: "gpt-4o-mini",
+        tokenizer: Tokenizer::O200k,
+        context_window: 128_000,
+    },
+    Model {
+        name: "gpt-4.1",
+        tokenizer: Tokenizer::O200k,
+        context_window: 1_047_576,
+    },
+    Model {
+        name: "o1",
+        tokenizer: Tokenizer::O200k,
+        context_window: 200_000,
+    },
+    Model {
+        name: "o3",
+        tokenizer: Tokenizer::O200k,
+        context_window: 200_000,
+    },
+    Model {
+        name: "gpt-4",
+        tokenizer: Tokenizer::Cl100k,
+        context_window: 8_192,
+    },
+    Model {
+        name: "gpt-4-turbo",
+        tokenizer: Tokenizer::Cl100k,
+        context_window: 128_000,
+    },
+    Model {
+        name: "gpt-3.5-turbo",
+        tokenizer: Tokenizer::Cl100k,
+        context_window: 16_385,
+    },
+    Model {
+        name: "claude-3.5-sonnet",
+        tokenizer: Tokenizer::Claude,
+        context_window: 200_000,
+    },
+    Model {
+        name: "claude-3-opus",
+        tokenizer: Tokenizer::Claude,
+        context_window: 200_000,
+    },
+    Model {
+        name: "claude-sonnet-4",
+        tokenizer: Tokenizer::Claude,
+        context_window: 200_000,
+    },
+    Model {
+        name: "claude-opus-4",
+        tokenizer: Tokenizer::Claude,
+        context_window: 200_000,
+    },
+    Model {
+        name: "llama-3",
+        tokenizer: Tokenizer::Llama3,
+        context_window: 8_192,
+    },
+    Model {
+        name: "llama-3.1",
+        tokenizer: Tokenizer::Llama3,
+        context_window: 128_000,
+    },
+];
+
+/// Sucht ein Modell anhand seines Namens (ohne Groß-/Kleinschreibung).
+pub fn find_model(name: &str) -> Result<&'static Model> {
+    MODELS
+        .iter()
+        .find(|m| m.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| {
+            let known: Vec<&str> = MODELS.iter().map(|m| m.name).collect();
+            Code2MdError::Config(format!(
+                "Unbekanntes Modell '{}'. Verfügbar: {}",
+                name,
+                known.join(", ")
+            ))
+        })
+}
+
+/// Schätzt die Anzahl der Tokens eines Textes.
+pub fn estimate_tokens(text: &str, tokenizer: Tokenizer) -> usize {
+    let chars_per_token = tokenizer.chars_per_token();
+    let mut tokens = 0;
+    let mut word = 0usize;
+    let mut punctuation = 0usize;
+    let mut in_newline = false;
+
+    let flush = |len: &mut usize, per_token: f64| -> usize {
+        let count = (*len as f64 / per_token).ceil() as usize;
+        *len = 0;
+        count
+    };
+
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            tokens += flush(&mut punctuation, 2.0);
+            word += 1;
+            in_newline = false;
+        } else if c.is_whitespace() {
+            tokens += flush(&mut word, chars_per_token) + flush(&mut punctuation, 2.0);
+            // Einrückung bildet mit dem Zeilenumbruch ein Token
+            if c == '\n' && !in_newline {
+                tokens += 1;
+                in_newline = true;
+            }
+        } else if c.is_ascii() {
+            tokens += flush(&mut word, chars_per_token);
+            punctuation += 1;
+            in_newline = false;
+        } else {
+            // Nicht-ASCII-Zeichen werden meist einzeln oder in Bytes kodiert
+            tokens += flush(&mut word, chars_per_token) + flush(&mut punctuation, 2.0) + 1;
+            in_newline = false;
+        }
+    }
+    tokens + flush(&mut word, chars_per_token) + flush(&mut punctuation, 2.0)
+}
+
+/// Geschätzte Tokens einer Datei.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileTokens {
+    pub path: String,
+    pub tokens: usize,
+}
+
+/// Schätzt die Tokens aller Dateien, absteigend sortiert.
+///
+/// Nicht lesbare Dateien werden mit 0 Tokens aufgeführt.
+pub fn count_file_tokens(files: &[CollectedFile], base_path: &Path, tokenizer: Tokenizer) -> Vec<FileTokens> {
+    let mut counts: Vec<FileTokens> = files
+        .iter()
+        .map(|file| {
+            FileTokens {
+                path: relative_path(&file.path, base_path),
+                tokens: estimate_tokens(&file.text().unwrap_or_default(), tokenizer),
+            }
+        })
+        .collect();
+    counts.sort_by(|a, b| b.tokens.cmp(&a.tokens).then_with(|| a.path.cmp(&b.path)));
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens("", Tokenizer::Cl100k), 0);
+        assert_eq!(estimate_tokens("fn main() {}", Tokenizer::Cl100k), 4);
+        assert_eq!(estimate_tokens("fn main() {\n    run();\n}\n", Tokenizer::Cl100k), 11);
+        // Lange Bezeichner zerfallen in mehrere Tokens
+        assert_eq!(estimate_tokens("configuration_manager", Tokenizer::Cl100k), 6);
+        assert_eq!(estimate_tokens("configuration_manager", Tokenizer::O200k), 5);
+    }
+
+    #[test]
+    fn test_find_model() {
+        assert_eq!(find_model("GPT-4o").map(|m| m.tokenizer).ok(), Some(Tokenizer::O200k));
+        assert!(find_model("unbekannt").is_err());
+        assert_eq!(find_model("claude-sonnet-4").map(|m| m.token_budget()).ok(), Some(160_000));
+    }
+
+    #[test]
+    fn test_count_file_tokens_sorted() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        std::fs::write(dir.path().join("klein.rs"), "fn a() {}")?;
+        std::fs::write(dir.path().join("gross.rs"), "fn b() { println!(\"hallo welt\"); }")?;
+        let files = ["klein.rs", "gross.rs"]
+            .iter()
+            .map(|name| CollectedFile::from_path(dir.path().join(name)))
+            .collect::<std::io::Result<Vec<_>>>()?;
+
+        let counts = count_file_tokens(&files, dir.path(), Tokenizer::Cl100k);
+        assert_eq!(counts[0].path, "gross.rs");
+        assert!(counts[0].tokens > counts[1].tokens);
+        Ok(())
+    }
+}
@@ -0,0 +1,287 @@
+//! Inhalts-Transformationen, die vor dem Einbetten auf jede Datei angewendet werden.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::Stdio;
+
+use clap::ValueEnum;
+
+use crate::error::{Code2MdError, Result};
+use crate::hooks::shell_command;
+
+/// Zeilenende-Behandlung für das erzeugte Dokument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum LineEnding {
+    /// Unix-Zeilenenden (`\n`)
+    Lf,
+    /// Windows-Zeilenenden (`\r\n`)
+    Crlf,
+    /// Zeilenenden der Quelldateien unverändert übernehmen
+    #[default]
+    Keep,
+}
+
+impl LineEnding {
+    /// Gibt das Trennzeichen für Dokumentzeilen zurück.
+    pub fn separator(&self) -> &'static str {
+        match self {
+            LineEnding::Crlf => "\r\n",
+            LineEnding::Lf | LineEnding::Keep => "\n",
+        }
+    }
+
+    /// Setzt das Zeilenende in einer Dokumentzeile. Eingebettete Inhalte
+    /// sind dann bereits auf `\n` vereinheitlicht und erhalten bei `Crlf`
+    /// ebenfalls `\r\n`, damit keine gemischten Enden entstehen.
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        match self {
+            LineEnding::Crlf if text.contains('\n') => Cow::Owned(text.replace("\r\n", "\n").replace('\n', "\r\n")),
+            _ => Cow::Borrowed(text),
+        }
+    }
+
+    /// Verbindet Dokumentzeilen mit dem Zeilenende.
+    pub fn join(&self, lines: &[String]) -> String {
+        self.apply(&lines.join("\n")).into_owned()
+    }
+}
+
+/// Konfiguration der Inhalts-Transformationen.
+#[derive(Debug, Clone, Default)]
+pub struct TransformConfig {
+    pub line_ending: LineEnding,
+    pub keep_bom: bool,
+    pub expand_tabs: Option<usize>,
+    pub trim_trailing_whitespace: bool,
+    /// Externer Befehl für alle Dateien ohne sprachspezifischen Befehl
+    pub pipe_through: Option<String>,
+    /// Externe Befehle je Sprache (Syntax-Name, z.B. `python`)
+    pub pipe_through_languages: HashMap<String, String>,
+}
+
+impl TransformConfig {
+    /// Gibt den externen Befehl für eine Sprache zurück.
+    fn pipe_command(&self, syntax: &str) -> Option<&str> {
+        self.pipe_through_languages
+            .get(syntax)
+            .or(self.pipe_through.as_ref())
+            .map(String::as_str)
+    }
+
+    /// Prüft, ob die Transformationen einen Inhalt unverändert lassen würden.
+    pub fn is_noop(&self, syntax: &str, content: &str) -> bool {
+        self.pipe_command(syntax).is_none()
+            && self.expand_tabs.is_none()
+            && !self.trim_trailing_whitespace
+            && (self.keep_bom || !content.starts_with('\u{feff}'))
+            && (self.line_ending == LineEnding::Keep || !content.contains('\r'))
+    }
+}
+
+/// Leitet einen Codeblock durch den passenden externen Befehl und wendet
+/// danach alle übrigen Transformationen an.
+///
+/// Schlägt der Befehl fehl, wird der Fehler an `warn` gemeldet und der
+/// unveränderte Inhalt verwendet.
+pub fn transform_code(
+    content: &str,
+    syntax: &str,
+    config: &TransformConfig,
+    warn: impl FnOnce(String),
+) -> String {
+    let piped = config.pipe_command(syntax).and_then(|command| {
+        match pipe_through(content, command) {
+            Ok(output) => Some(output),
+            Err(e) => {
+                warn(e.to_string());
+                None
+            }
+        }
+    });
+
+    transform_content(piped.as_deref().unwrap_or(content), config)
+}
+
+/// Leitet einen Inhalt über stdin durch einen Shell-Befehl und liefert stdout.
+pub fn pipe_through(content: &str, command: &str) -> Result<String> {
+    let mut child = shell_command(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| Code2MdError::Render(format!("'{}' konnte nicht gestartet werden: {}", command, e)))?;
+
+    // In eigenem Thread schreiben, damit volle Pipes nicht blockieren
+    let mut stdin = child.stdin.take().expect("stdin ist gepiped");
+    let input = content.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| Code2MdError::Render(format!("'{}' fehlgeschlagen: {}", command, e)))?;
+    let _ = writer.join();
+
+    if !output.status.success() {
+        return Err(Code2MdError::Render(format!(
+            "'{}' fehlgeschlagen ({}): {}",
+            command,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Wendet alle konfigurierten Transformationen auf einen Dateiinhalt an.
+pub fn transform_content(content: &str, config: &TransformConfig) -> String {
+    let mut content = content;
+
+    // UTF-8 BOM entfernen
+    if !config.keep_bom {
+        content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    }
+
+    // Zeilenenden vereinheitlichen; CRLF wird erst beim Zusammenfügen
+    // des Dokuments gesetzt, damit keine gemischten Enden entstehen.
+    let content = match config.line_ending {
+        LineEnding::Keep => content.to_string(),
+        LineEnding::Lf | LineEnding::Crlf => content.replace("\r\n", "\n").replace('\r', "\n"),
+    };
+
+    if config.expand_tabs.is_none() && !config.trim_trailing_whitespace {
+        return content;
+    }
+
+    // Zeilenweise Whitespace-Normalisierung (Zeilenenden bleiben erhalten)
+    content
+        .split_inclusive('\n')
+        .map(|line| {
+            let (body, eol) = split_line_ending(line);
+            let body = match config.expand_tabs {
+                Some(width) => expand_tabs(body, width),
+                None => body.to_string(),
+            };
+            let body = if config.trim_trailing_whitespace {
+                body.trim_end().to_string()
+            } else {
+                body
+            };
+            format!("{}{}", body, eol)
+        })
+        .collect()
+}
+
+/// Trennt das Zeilenende (`\n` oder `\r\n`) vom Zeileninhalt.
+pub fn split_line_ending(line: &str) -> (&str, &str) {
+    if let Some(body) = line.strip_suffix("\r\n") {
+        (body, "\r\n")
+    } else if let Some(body) = line.strip_suffix('\n') {
+        (body, "\n")
+    } else {
+        (line, "")
+    }
+}
+
+/// Ersetzt Tabs durch Leerzeichen bis zum nächsten Tabstopp.
+fn expand_tabs(line: &str, width: usize) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut column = 0;
+
+    for c in line.chars() {
+        if c == '\t' {
+            let spaces = if width == 0 { 0 } else { width - column % width };
+            result.push_str(&" ".repeat(spaces));
+            column += spaces;
+        } else {
+            result.push(c);
+            column += 1;
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_bom() {
+        let config = TransformConfig::default();
+        assert_eq!(transform_content("\u{feff}fn main() {}", &config), "fn main() {}");
+
+        let config = TransformConfig {
+            keep_bom: true,
+            ..Default::default()
+        };
+        assert_eq!(transform_content("\u{feff}x", &config), "\u{feff}x");
+    }
+
+    #[test]
+    fn test_normalize_line_endings() {
+        let config = TransformConfig {
+            line_ending: LineEnding::Lf,
+            ..Default::default()
+        };
+        assert_eq!(transform_content("a\r\nb\rc\n", &config), "a\nb\nc\n");
+
+        let config = TransformConfig::default();
+        assert_eq!(transform_content("a\r\nb", &config), "a\r\nb");
+    }
+
+    #[test]
+    fn test_expand_tabs_and_trim() {
+        let config = TransformConfig {
+            expand_tabs: Some(4),
+            trim_trailing_whitespace: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            transform_content("\tif x:  \r\nab\tc\t\n", &config),
+            "    if x:\r\nab  c\n"
+        );
+    }
+
+    #[test]
+    fn test_line_ending_apply() {
+        assert_eq!(LineEnding::Crlf.apply("a\nb\r\nc"), "a\r\nb\r\nc");
+        assert_eq!(LineEnding::Lf.apply("a\r\nb"), "a\r\nb");
+        assert_eq!(LineEnding::Crlf.join(&["a".to_string(), "b\nc".to_string()]), "a\r\nb\r\nc");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_is_noop() {
+        let mut config = TransformConfig::default();
+        assert!(config.is_noop("rust", "fn main() {}\r\n"));
+        assert!(!config.is_noop("rust", "\u{feff}fn main() {}\n"));
+
+        config.line_ending = LineEnding::Lf;
+        assert!(config.is_noop("rust", "fn main() {}\n"));
+        assert!(!config.is_noop("rust", "fn main() {}\r\n"));
+
+        config.pipe_through_languages.insert("rust".to_string(), "rustfmt".to_string());
+        assert!(!config.is_noop("rust", "fn main() {}\n"));
+    }
+
+    #[test]
+    fn test_pipe_through() {
+        let mut config = TransformConfig {
+            pipe_through: Some("tr a-z A-Z".to_string()),
+            ..Default::default()
+        };
+        config
+            .pipe_through_languages
+            .insert("rust".to_string(), "rev".to_string());
+
+        assert_eq!(transform_code("print(1)\n", "python", &config, |_| {}), "PRINT(1)\n");
+        assert_eq!(transform_code("abc\n", "rust", &config, |_| {}), "cba\n");
+
+        config.pipe_through = Some("exit 3".to_string());
+        let mut warning = None;
+        let content = transform_code("x = 1\n", "python", &config, |w| warning = Some(w));
+        assert_eq!(content, "x = 1\n");
+        assert!(warning.unwrap().contains("exit 3"));
+    }
+}
@@ -1,13 +1,123 @@
 //! Generierung der Ordnerstruktur als Baum.
 
-use std::collections::HashSet;
-use std::path::{Path, PathBuf};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::collector::CollectedFile;
+use crate::markdown::format_size;
+use crate::processor::slash_path;
+use crate::stats::count_lines;
+
+/// Darstellungsoptionen für den Baum.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TreeOptions {
+    /// `|--`/`` `-- `` statt Rahmenzeichen (`--ascii-tree`)
+    pub ascii: bool,
+    /// Größe und Zeilen an Dateien, Summen an Verzeichnissen (`--tree-stats`)
+    pub stats: bool,
+    /// Verzeichnisse unterhalb dieser Tiefe zusammenfassen (`--tree-depth`)
+    pub depth: Option<usize>,
+    /// Höchstens so viele Einträge je Verzeichnis, der Rest als eine Zeile (`--tree-max-entries`)
+    pub max_entries: Option<usize>,
+}
+
+/// Verbindungszeichen einer Baumdarstellung.
+struct Connectors {
+    branch: &'static str,
+    last: &'static str,
+    pipe: &'static str,
+    ellipsis: &'static str,
+}
+
+impl Connectors {
+    fn new(options: &TreeOptions) -> Self {
+        if options.ascii {
+            Self { branch: "|-- ", last: "`-- ", pipe: "|   ", ellipsis: "..." }
+        } else {
+            Self { branch: "├── ", last: "└── ", pipe: "│   ", ellipsis: "…" }
+        }
+    }
+}
+
+/// Eine gerenderte Zeile mit optionaler Angabe, die später ausgerichtet wird.
+struct TreeLine {
+    text: String,
+    label: Option<String>,
+}
+
+/// Gemeinsame Angaben beim Rendern.
+struct RenderContext<'a> {
+    labels: &'a BTreeMap<String, String>,
+    connectors: Connectors,
+    options: &'a TreeOptions,
+}
+
+impl RenderContext<'_> {
+    /// Angaben hinter einem Eintrag: Größe und Zeilen, dann das Label.
+    fn annotation(&self, node: &TreeNode, path: &str) -> Option<String> {
+        let mut parts = Vec::new();
+        // Verzeichnisse, die nur wegen eines Labels im Baum stehen, ohne Summe
+        if self.options.stats && (!node.is_dir || node.weight.files > 0) {
+            parts.push(node.weight.describe());
+        }
+        if let Some(label) = self.labels.get(path).filter(|_| node.is_dir) {
+            parts.push(format!("[{}]", label));
+        }
+        (!parts.is_empty()).then(|| parts.join("  "))
+    }
+
+    /// Zeile für ausgeblendete Einträge, z.B. `… (42 weitere Dateien)`;
+    /// `more` unterscheidet gekürzte Verzeichnisse von zusammengefassten.
+    fn ellipsis(&self, prefix: &str, hidden: &[TreeNode], more: bool) -> TreeLine {
+        let weight = hidden.iter().fold(Weight::default(), |sum, node| sum.add(node.weight));
+        // Leere Verzeichnisse (nur mit Label) zählen als Einträge
+        let count = match (weight.files, hidden.len()) {
+            (0, 1) => format!("1 {}Eintrag", if more { "weiterer " } else { "" }),
+            (0, entries) => format!("{} {}Einträge", entries, if more { "weitere " } else { "" }),
+            (1, _) => format!("1 {}Datei", if more { "weitere " } else { "" }),
+            (files, _) => format!("{} {}Dateien", files, if more { "weitere " } else { "" }),
+        };
+        TreeLine {
+            text: format!("{}{}{} ({})", prefix, self.connectors.last, self.connectors.ellipsis, count),
+            label: self.options.stats.then(|| weight.describe()),
+        }
+    }
+}
+
+/// Größe, Zeilen und Anzahl Dateien einer Datei bzw. Summe eines Verzeichnisses.
+#[derive(Debug, Clone, Copy, Default)]
+struct Weight {
+    size: u64,
+    lines: usize,
+    files: usize,
+}
+
+impl Weight {
+    /// Eine Datei ohne Größenangaben.
+    const FILE: Weight = Weight { size: 0, lines: 0, files: 1 };
+
+    fn add(self, other: Weight) -> Weight {
+        Weight {
+            size: self.size + other.size,
+            lines: self.lines + other.lines,
+            files: self.files + other.files,
+        }
+    }
+
+    fn describe(&self) -> String {
+        let unit = if self.lines == 1 { "Zeile" } else { "Zeilen" };
+        format!("({}, {} {})", format_size(self.size), self.lines, unit)
+    }
+}
 
 /// Repräsentiert einen Knoten im Dateibaum.
 #[derive(Debug)]
 struct TreeNode {
     name: String,
     is_dir: bool,
+    weight: Weight,
     children: Vec<TreeNode>,
 }
 
@@ -16,12 +126,14 @@ impl TreeNode {
         Self {
             name,
             is_dir,
+            weight: Weight::default(),
             children: Vec::new(),
         }
     }
 
-    /// Fügt einen Pfad zum Baum hinzu.
-    fn add_path(&mut self, components: &[&str], is_file: bool) {
+    /// Fügt einen Pfad zum Baum hinzu; `weight` zählt zu allen Knoten auf dem Weg.
+    fn add_path(&mut self, components: &[&str], is_file: bool, weight: Weight) {
+        self.weight = self.weight.add(weight);
         if components.is_empty() {
             return;
         }
@@ -35,11 +147,11 @@ impl TreeNode {
         
         match child {
             Some(existing) => {
-                existing.add_path(remaining, is_file);
+                existing.add_path(remaining, is_file, weight);
             }
             None => {
                 let mut new_child = TreeNode::new(name.to_string(), is_dir);
-                new_child.add_path(remaining, is_file);
+                new_child.add_path(remaining, is_file, weight);
                 self.children.push(new_child);
             }
         }
@@ -60,75 +172,154 @@ impl TreeNode {
         }
     }
 
-    /// Rendert den Baum als Zeilen.
-    fn render(&self, prefix: &str, is_last: bool, lines: &mut Vec<String>, is_root: bool) {
-        if !is_root {
-            let connector = if is_last { "└── " } else { "├── " };
+    /// Rendert den Baum als Zeilen; Labels ergänzen Verzeichnisse (nach
+    /// relativem Pfad) um eine Angabe in eckigen Klammern.
+    fn render(&self, prefix: &str, is_last: bool, lines: &mut Vec<TreeLine>, is_root: bool, path: &str, context: &RenderContext) {
+        let connectors = &context.connectors;
+        if is_root {
+            lines.push(TreeLine {
+                text: format!("{}/", self.name),
+                label: context.annotation(self, path),
+            });
+        } else {
+            let connector = if is_last { connectors.last } else { connectors.branch };
             let suffix = if self.is_dir { "/" } else { "" };
-            lines.push(format!("{}{}{}{}", prefix, connector, self.name, suffix));
+            lines.push(TreeLine {
+                text: format!("{}{}{}{}", prefix, connector, self.name, suffix),
+                label: context.annotation(self, path),
+            });
+        }
+
+        if self.children.is_empty() {
+            return;
+        }
+        let new_prefix = if is_root {
+            String::new()
+        } else {
+            format!("{}{}", prefix, if is_last { "    " } else { connectors.pipe })
+        };
+
+        // Unterhalb der Tiefengrenze nur noch die Anzahl der Dateien
+        let level = if is_root { 0 } else { path.split('/').count() };
+        if context.options.depth.is_some_and(|depth| level >= depth) {
+            lines.push(context.ellipsis(&new_prefix, &self.children, false));
+            return;
         }
 
-        let child_count = self.children.len();
-        for (i, child) in self.children.iter().enumerate() {
-            let is_last_child = i == child_count - 1;
-            let new_prefix = if is_root {
-                String::new()
+        let (shown, hidden) = match context.options.max_entries {
+            Some(max) if self.children.len() > max => self.children.split_at(max),
+            _ => (self.children.as_slice(), &[][..]),
+        };
+        for (i, child) in shown.iter().enumerate() {
+            let is_last_child = i == shown.len() - 1 && hidden.is_empty();
+            let child_path = if is_root {
+                child.name.clone()
             } else {
-                format!("{}{}", prefix, if is_last { "    " } else { "│   " })
+                format!("{}/{}", path, child.name)
             };
-            child.render(&new_prefix, is_last_child, lines, false);
+            child.render(&new_prefix, is_last_child, lines, false, &child_path, context);
+        }
+        if !hidden.is_empty() {
+            lines.push(context.ellipsis(&new_prefix, hidden, true));
         }
     }
 }
 
 /// Generiert eine Baumdarstellung der Ordnerstruktur.
-pub fn generate_tree(files: &[PathBuf], base_path: &Path, project_name: &str) -> Vec<String> {
+pub fn generate_tree(
+    files: &[CollectedFile],
+    base_path: &Path,
+    project_name: &str,
+    labels: &BTreeMap<String, String>,
+    options: &TreeOptions,
+) -> Vec<String> {
+    let entries: Vec<(String, Weight)> = files
+        .iter()
+        .filter_map(|file| {
+            let rel_path = file.path.strip_prefix(base_path).ok()?;
+            let weight = if options.stats {
+                Weight {
+                    size: file.size(),
+                    lines: file.bytes().map(|bytes| count_lines(&bytes)).unwrap_or(0),
+                    files: 1,
+                }
+            } else {
+                Weight::FILE
+            };
+            Some((slash_path(rel_path), weight))
+        })
+        .collect();
+    build_tree(&entries, project_name, labels, options)
+}
+
+/// Generiert eine Baumdarstellung aus relativen Dateipfaden.
+pub fn tree_from_paths(rel_paths: &[&Path], project_name: &str) -> Vec<String> {
+    labeled_tree(rel_paths, project_name, &BTreeMap::new(), &TreeOptions::default())
+}
+
+/// Generiert eine Baumdarstellung mit Angaben an einzelnen Verzeichnissen.
+///
+/// Verzeichnisse aus `labels` erscheinen auch ohne enthaltene Dateien. Die
+/// Angaben stehen untereinander; breite Zeichen (CJK, Emoji) zählen dabei
+/// doppelt, damit die Spalte im Terminal gerade bleibt.
+pub fn labeled_tree(
+    rel_paths: &[&Path],
+    project_name: &str,
+    labels: &BTreeMap<String, String>,
+    options: &TreeOptions,
+) -> Vec<String> {
+    let entries: Vec<(String, Weight)> = rel_paths.iter().map(|path| (slash_path(path), Weight::FILE)).collect();
+    build_tree(&entries, project_name, labels, options)
+}
+
+fn build_tree(
+    entries: &[(String, Weight)],
+    project_name: &str,
+    labels: &BTreeMap<String, String>,
+    options: &TreeOptions,
+) -> Vec<String> {
     // Root-Knoten erstellen
     let mut root = TreeNode::new(project_name.to_string(), true);
 
-    // Alle Dateipfade sammeln und auch Zwischenordner hinzufügen
-    let mut all_paths: HashSet<PathBuf> = HashSet::new();
-    
-    for file in files {
-        if let Ok(rel_path) = file.strip_prefix(base_path) {
-            // Datei selbst hinzufügen
-            all_paths.insert(rel_path.to_path_buf());
-            
-            // Alle Elternordner hinzufügen
-            let mut current = rel_path.to_path_buf();
-            while let Some(parent) = current.parent() {
-                if parent.as_os_str().is_empty() {
-                    break;
-                }
-                all_paths.insert(parent.to_path_buf());
-                current = parent.to_path_buf();
-            }
+    // Dateien zum Baum hinzufügen
+    for (rel_str, weight) in entries {
+        let components: Vec<&str> = rel_str.split('/').filter(|c| !c.is_empty() && *c != ".").collect();
+
+        if !components.is_empty() {
+            root.add_path(&components, true, *weight);
         }
     }
 
-    // Dateien zum Baum hinzufügen
-    for file in files {
-        if let Ok(rel_path) = file.strip_prefix(base_path) {
-            let components: Vec<&str> = rel_path
-                .components()
-                .filter_map(|c| c.as_os_str().to_str())
-                .collect();
-            
-            if !components.is_empty() {
-                root.add_path(&components, true);
-            }
-        }
+    for dir in labels.keys() {
+        let components: Vec<&str> = dir.split('/').filter(|c| !c.is_empty()).collect();
+        root.add_path(&components, false, Weight::default());
     }
 
     // Baum sortieren
     root.sort();
 
     // Baum rendern
-    let mut lines = Vec::new();
-    lines.push(format!("{}/", project_name));
-    root.render("", true, &mut lines, true);
+    let context = RenderContext {
+        labels,
+        connectors: Connectors::new(options),
+        options,
+    };
+    let mut rendered = Vec::new();
+    root.render("", true, &mut rendered, true, "", &context);
 
-    lines
+    let column = rendered
+        .iter()
+        .filter(|line| line.label.is_some())
+        .map(|line| line.text.width())
+        .max()
+        .unwrap_or(0);
+    rendered
+        .into_iter()
+        .map(|line| match line.label {
+            Some(label) => format!("{}{}  {}", line.text, " ".repeat(column - line.text.width()), label),
+            None => line.text,
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -136,17 +327,137 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_generate_tree() {
-        let base = PathBuf::from("/project");
-        let files = vec![
-            PathBuf::from("/project/src/main.py"),
-            PathBuf::from("/project/src/utils/helpers.py"),
-            PathBuf::from("/project/config.json"),
-        ];
+    fn test_generate_tree() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path();
+        std::fs::create_dir_all(base.join("src/utils"))?;
+        let mut files = Vec::new();
+        for name in ["src/main.py", "src/utils/helpers.py", "config.json"] {
+            std::fs::write(base.join(name), "")?;
+            files.push(CollectedFile::from_path(base.join(name))?);
+        }
 
-        let tree = generate_tree(&files, &base, "project");
+        let tree = generate_tree(&files, base, "project", &BTreeMap::new(), &TreeOptions::default());
         
         assert!(!tree.is_empty());
         assert!(tree[0].contains("project"));
+
+        let labels = BTreeMap::from([("src/utils".to_string(), "Paket utils".to_string())]);
+        let tree = generate_tree(&files, base, "project", &labels, &TreeOptions::default());
+        assert!(tree.contains(&"│   ├── utils/  [Paket utils]".to_string()));
+
+        // Verzeichnisse ohne Dateien erscheinen nur mit Angabe
+        let labels = BTreeMap::from([("vendor/lib".to_string(), "Repository, übersprungen".to_string())]);
+        let tree = generate_tree(&files, base, "project", &labels, &TreeOptions::default());
+        assert!(tree.contains(&"│   └── lib/  [Repository, übersprungen]".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_tree_path_flavors() {
+        let unix = [Path::new("src/utils/helpers.py"), Path::new("src/main.py")];
+        let windows = [Path::new("src\\utils\\helpers.py"), Path::new("src\\main.py")];
+        let tree = tree_from_paths(&unix, "project");
+        assert_eq!(tree_from_paths(&windows, "project"), tree);
+        assert!(tree.contains(&"    │   └── helpers.py".to_string()));
+    }
+
+    #[test]
+    fn test_ascii_tree_and_wide_labels() {
+        let paths = [Path::new("src/main.rs"), Path::new("文档/说明.md"), Path::new("a/x.rs")];
+        let labels = BTreeMap::from([
+            ("文档".to_string(), "Paket docs".to_string()),
+            ("a".to_string(), "Paket a".to_string()),
+        ]);
+        let ascii = TreeOptions {
+            ascii: true,
+            ..Default::default()
+        };
+        let tree = labeled_tree(&paths, "project", &labels, &ascii);
+        assert_eq!(
+            tree,
+            vec![
+                "project/",
+                "|-- a/     [Paket a]",
+                "|   `-- x.rs",
+                "|-- src/",
+                "|   `-- main.rs",
+                "`-- 文档/  [Paket docs]",
+                "    `-- 说明.md",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tree_stats() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path();
+        std::fs::create_dir_all(base.join("src"))?;
+        let mut files = Vec::new();
+        for (name, content) in [("src/a.rs", "a\nb\n"), ("src/b.rs", "c\n"), ("README.md", "x".repeat(2048).as_str())] {
+            std::fs::write(base.join(name), content)?;
+            files.push(CollectedFile::from_path(base.join(name))?);
+        }
+
+        let options = TreeOptions {
+            stats: true,
+            ..Default::default()
+        };
+        let tree = generate_tree(&files, base, "project", &BTreeMap::new(), &options);
+        assert_eq!(
+            tree,
+            vec![
+                "project/       (2.01 KB, 4 Zeilen)",
+                "├── src/       (6 Bytes, 3 Zeilen)",
+                "│   ├── a.rs   (4 Bytes, 2 Zeilen)",
+                "│   └── b.rs   (2 Bytes, 1 Zeile)",
+                "└── README.md  (2.00 KB, 1 Zeile)",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_tree_depth_and_collapse() {
+        let paths = [
+            Path::new("src/api/users.py"),
+            Path::new("src/api/orders.py"),
+            Path::new("src/main.py"),
+            Path::new("data/1.json"),
+            Path::new("data/2.json"),
+            Path::new("data/3.json"),
+            Path::new("data/4.json"),
+        ];
+        let options = TreeOptions {
+            depth: Some(1),
+            ..Default::default()
+        };
+        let tree = labeled_tree(&paths, "project", &BTreeMap::new(), &options);
+        assert_eq!(
+            tree,
+            vec!["project/", "├── data/", "│   └── … (4 Dateien)", "└── src/", "    └── … (3 Dateien)"]
+        );
+
+        let options = TreeOptions {
+            ascii: true,
+            max_entries: Some(2),
+            ..Default::default()
+        };
+        let tree = labeled_tree(&paths, "project", &BTreeMap::new(), &options);
+        assert_eq!(
+            tree,
+            vec![
+                "project/",
+                "|-- data/",
+                "|   |-- 1.json",
+                "|   |-- 2.json",
+                "|   `-- ... (2 weitere Dateien)",
+                "`-- src/",
+                "    |-- api/",
+                "    |   |-- orders.py",
+                "    |   `-- users.py",
+                "    `-- main.py",
+            ]
+        );
     }
 }
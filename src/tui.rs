@@ -0,0 +1,264 @@
+//! Interaktive Dateiauswahl mit Vorschau (`--interactive`).
+//!
+//! Links stehen die gesammelten Dateien, rechts der Abschnitt der markierten
+//! Datei so, wie er mit den aktuellen Einstellungen im Export erscheint –
+//! inklusive Metadaten, Sprache des Codeblocks und den Änderungen der
+//! Prozessoren (Kürzungen, Hervorhebungen, Zusammenfassungen).
+
+use std::collections::HashMap;
+use std::io;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::collector::CollectedFile;
+use crate::markdown::{preview_section, MarkdownConfig};
+use crate::pick::fuzzy_matches;
+use crate::processor::relative_path;
+
+/// Zeilen, um die die Vorschau mit Bild↑/Bild↓ springt.
+const PAGE: u16 = 20;
+
+/// Ergebnis einer Tastatureingabe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// Mit der Auswahl exportieren
+    Export,
+    /// Ohne Export beenden
+    Abort,
+}
+
+/// Zustand der Auswahl.
+pub struct App<'a> {
+    files: &'a [CollectedFile],
+    config: &'a MarkdownConfig,
+    paths: Vec<String>,
+    selected: Vec<bool>,
+    filter: String,
+    visible: Vec<usize>,
+    list: ListState,
+    scroll: u16,
+    previews: HashMap<usize, Vec<String>>,
+}
+
+impl<'a> App<'a> {
+    /// Startet mit allen Dateien ausgewählt.
+    pub fn new(files: &'a [CollectedFile], config: &'a MarkdownConfig) -> Self {
+        let paths: Vec<String> = files.iter().map(|file| relative_path(&file.path, &config.base_path)).collect();
+        let mut app = Self {
+            files,
+            config,
+            selected: vec![true; paths.len()],
+            visible: (0..paths.len()).collect(),
+            paths,
+            filter: String::new(),
+            list: ListState::default(),
+            scroll: 0,
+            previews: HashMap::new(),
+        };
+        app.list.select((!app.visible.is_empty()).then_some(0));
+        app
+    }
+
+    /// Indizes der ausgewählten Dateien in ursprünglicher Reihenfolge.
+    pub fn selection(&self) -> Vec<usize> {
+        (0..self.files.len()).filter(|&index| self.selected[index]).collect()
+    }
+
+    /// Index der markierten Datei.
+    fn current(&self) -> Option<usize> {
+        self.list.selected().and_then(|pos| self.visible.get(pos).copied())
+    }
+
+    /// Verarbeitet eine Taste; `Some`, wenn die Auswahl beendet ist.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Action> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Enter => return Some(Action::Export),
+            KeyCode::Esc if self.filter.is_empty() => return Some(Action::Abort),
+            KeyCode::Esc => self.set_filter(String::new()),
+            KeyCode::Char('c') if ctrl => return Some(Action::Abort),
+            KeyCode::Char('a') if ctrl => {
+                // Alle sichtbaren umschalten: abwählen, wenn alle gewählt sind
+                let all = self.visible.iter().all(|&index| self.selected[index]);
+                for &index in &self.visible {
+                    self.selected[index] = !all;
+                }
+            }
+            KeyCode::Char(' ') => {
+                if let Some(index) = self.current() {
+                    self.selected[index] = !self.selected[index];
+                    self.move_cursor(1);
+                }
+            }
+            KeyCode::Char(c) if !ctrl => self.set_filter(format!("{}{}", self.filter, c)),
+            KeyCode::Backspace => {
+                let mut filter = self.filter.clone();
+                filter.pop();
+                self.set_filter(filter);
+            }
+            KeyCode::Up => self.move_cursor(-1),
+            KeyCode::Down => self.move_cursor(1),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(PAGE),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(PAGE),
+            _ => {}
+        }
+        None
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        if self.visible.is_empty() {
+            return;
+        }
+        let pos = self.list.selected().unwrap_or(0) as isize + delta;
+        self.list.select(Some(pos.clamp(0, self.visible.len() as isize - 1) as usize));
+        self.scroll = 0;
+    }
+
+    fn set_filter(&mut self, filter: String) {
+        self.visible = if filter.is_empty() {
+            (0..self.paths.len()).collect()
+        } else {
+            fuzzy_matches(&filter, &self.paths, usize::MAX)
+        };
+        self.filter = filter;
+        self.list.select((!self.visible.is_empty()).then_some(0));
+        self.scroll = 0;
+    }
+
+    /// Abschnitt der markierten Datei; einmal gerendert und zwischengespeichert.
+    fn preview(&mut self) -> &[String] {
+        let Some(index) = self.current() else {
+            return &[];
+        };
+        let (files, config) = (self.files, self.config);
+        self.previews.entry(index).or_insert_with(|| preview_section(&files[index], config))
+    }
+
+    /// Zeichnet Liste, Vorschau und Hilfezeile.
+    pub fn draw(&mut self, frame: &mut Frame) {
+        let [main, help] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [left, right] = Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(main);
+
+        let items: Vec<ListItem> = self
+            .visible
+            .iter()
+            .map(|&index| {
+                let mark = if self.selected[index] { "[x]" } else { "[ ]" };
+                ListItem::new(format!("{} {}", mark, self.paths[index]))
+            })
+            .collect();
+        let chosen = self.selected.iter().filter(|s| **s).count();
+        let mut title = format!(" Dateien ({}/{} gewählt) ", chosen, self.files.len());
+        if !self.filter.is_empty() {
+            title = format!("{}– Suche: {} ", title, self.filter);
+        }
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let mut state = self.list.clone();
+        frame.render_stateful_widget(list, left, &mut state);
+
+        let title = match self.current() {
+            Some(index) => format!(" Vorschau: {} ", self.paths[index]),
+            None => " Vorschau ".to_string(),
+        };
+        let scroll = self.scroll;
+        let lines: Vec<Line> = self.preview().iter().map(|line| Line::raw(line.clone())).collect();
+        let preview = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .scroll((scroll, 0));
+        frame.render_widget(preview, right);
+
+        frame.render_widget(
+            Paragraph::new(
+                "↑↓ bewegen · Leertaste wählen · Strg+A alle · Tippen sucht · Bild↑↓ Vorschau · Eingabe exportieren · Esc abbrechen",
+            ),
+            help,
+        );
+    }
+}
+
+/// Zeigt die Auswahl im Terminal an. Liefert die gewählten Indizes oder
+/// `None`, wenn abgebrochen wurde.
+pub fn select(files: &[CollectedFile], config: &MarkdownConfig) -> io::Result<Option<Vec<usize>>> {
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, App::new(files, config));
+    ratatui::restore();
+    result
+}
+
+fn run(terminal: &mut DefaultTerminal, mut app: App) -> io::Result<Option<Vec<usize>>> {
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match app.handle_key(key) {
+                Some(Action::Export) => return Ok(Some(app.selection())),
+                Some(Action::Abort) => return Ok(None),
+                None => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_selection_and_preview() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().to_path_buf();
+        std::fs::create_dir_all(base.join("src"))?;
+        std::fs::write(base.join("src/main.py"), "print('hallo')\n")?;
+        std::fs::write(base.join("src/util.py"), "X = 1\n")?;
+        let files = vec![
+            CollectedFile::from_path(base.join("src/main.py"))?,
+            CollectedFile::from_path(base.join("src/util.py"))?,
+        ];
+        let config = MarkdownConfig {
+            base_path: base,
+            ..Default::default()
+        };
+
+        let mut app = App::new(&files, &config);
+        assert_eq!(app.selection(), vec![0, 1]);
+
+        // Suche nach "util", abwählen, Suche leeren
+        for c in "util".chars() {
+            app.handle_key(key(KeyCode::Char(c)));
+        }
+        assert_eq!(app.current(), Some(1));
+        app.handle_key(key(KeyCode::Char(' ')));
+        assert_eq!(app.selection(), vec![0]);
+        assert_eq!(app.handle_key(key(KeyCode::Esc)), None);
+        assert_eq!(app.visible, vec![0, 1]);
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 20))?;
+        terminal.draw(|frame| app.draw(frame))?;
+        let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+        assert!(screen.contains("[x] src/main.py"));
+        assert!(screen.contains("[ ] src/util.py"));
+        assert!(screen.contains("```python"));
+        assert!(screen.contains("print('hallo')"));
+
+        assert_eq!(app.handle_key(KeyEvent::new(KeyCode::Char('a'), KeyModifiers::CONTROL)), None);
+        assert_eq!(app.selection(), vec![0, 1]);
+        assert_eq!(app.handle_key(key(KeyCode::Enter)), Some(Action::Export));
+        assert_eq!(app.handle_key(key(KeyCode::Esc)), Some(Action::Abort));
+        Ok(())
+    }
+}
@@ -1,6 +1,10 @@
 //! Projekttyp-Definitionen und Syntax-Highlighting-Mapping.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::error::{Code2MdError, Result};
 
 /// Definition eines Projekttyps mit zugehörigen Dateiendungen.
 #[derive(Debug, Clone)]
@@ -8,6 +12,7 @@ pub struct ProjectType {
     pub name: &'static str,
     pub description: &'static str,
     pub extensions: &'static [&'static str],
+    #[allow(dead_code)]
     pub default_syntax: &'static str,
 }
 
@@ -19,6 +24,12 @@ pub static PROJECT_TYPES: &[ProjectType] = &[
         extensions: &[".py", ".pyi", ".pyw"],
         default_syntax: "python",
     },
+    ProjectType {
+        name: "jupyter",
+        description: "Jupyter Notebooks",
+        extensions: &[".ipynb"],
+        default_syntax: "python",
+    },
     ProjectType {
         name: "arduino",
         description: "Arduino/C++ Projekte",
@@ -99,63 +110,80 @@ pub static PROJECT_TYPES: &[ProjectType] = &[
     },
 ];
 
-/// Standard-Ausschlüsse für Ordner und Dateien.
-pub static DEFAULT_EXCLUDES: &[&str] = &[
-    // Abhängigkeiten
-    "node_modules",
-    "vendor",
-    "packages",
-    ".pub-cache",
-    // Python
-    "__pycache__",
-    ".pytest_cache",
-    ".mypy_cache",
-    ".ruff_cache",
-    "venv",
-    ".venv",
-    "env",
-    ".env",
-    "*.egg-info",
-    // Build-Ordner
-    "dist",
-    "build",
-    "out",
-    "target",
-    "bin",
-    "obj",
+/// Standard-Ausschlüsse nach Kategorien; einzeln abschaltbar mit
+/// `--no-exclude-category` und per `[excludes]` erweiterbar.
+pub static EXCLUDE_CATEGORIES: &[(&str, &[&str])] = &[
+    // Abhängigkeiten und virtuelle Umgebungen
+    (
+        "deps",
+        &["node_modules", "vendor", "packages", ".pub-cache", "venv", ".venv", "env", ".env", "*.egg-info"],
+    ),
+    // Build-Ordner und Caches
+    (
+        "build",
+        &["dist", "build", "out", "target", "bin", "obj", "__pycache__", ".pytest_cache", ".mypy_cache", ".ruff_cache"],
+    ),
     // IDE/Editor
-    ".idea",
-    ".vscode",
-    ".vs",
-    "*.swp",
-    "*.swo",
+    ("ide", &[".idea", ".vscode", ".vs", "*.swp", "*.swo"]),
+    // Betriebssystem
+    ("os", &[".DS_Store", "Thumbs.db"]),
     // Versionskontrolle
-    ".git",
-    ".svn",
-    ".hg",
-    // OS
-    ".DS_Store",
-    "Thumbs.db",
+    ("vcs", &[".git", ".svn", ".hg"]),
     // Logs & temporäre Dateien
-    "*.log",
-    "logs",
-    "tmp",
-    "temp",
-    ".tmp",
+    ("logs", &["*.log", "logs", "tmp", "temp", ".tmp"]),
     // Coverage & Tests
-    "coverage",
-    ".coverage",
-    "htmlcov",
-    ".tox",
-    ".nox",
+    ("coverage", &["coverage", ".coverage", "htmlcov", ".tox", ".nox"]),
 ];
 
-/// Syntax-Highlighting Mapping für Dateiendungen.
-pub fn get_syntax_map() -> HashMap<&'static str, &'static str> {
-    HashMap::from([
+/// Ausschlüsse, die mit den Standard-Ausschlüssen immer gelten (Cache von code2md).
+pub static ALWAYS_EXCLUDED: &[&str] = &[".code2md"];
+
+/// Zusätzliche Ausschlüsse je Kategorie aus der Tabelle `[excludes]`.
+pub type ExcludeCategories = BTreeMap<String, Vec<String>>;
+
+/// Stellt die Standard-Ausschlüsse ohne die abgeschalteten Kategorien
+/// zusammen. Kategorien aus der Konfiguration ergänzen gleichnamige oder
+/// kommen als eigene hinzu.
+pub fn default_excludes(disabled: &[String], extra: &ExcludeCategories) -> Result<Vec<String>> {
+    let disabled: Vec<String> = disabled.iter().map(|name| name.to_lowercase()).collect();
+    for name in &disabled {
+        let known = EXCLUDE_CATEGORIES.iter().any(|(category, _)| category == name)
+            || extra.keys().any(|category| category.to_lowercase() == *name);
+        if !known {
+            let mut names: Vec<&str> = EXCLUDE_CATEGORIES.iter().map(|(category, _)| *category).collect();
+            names.extend(extra.keys().map(String::as_str));
+            return Err(Code2MdError::Config(format!(
+                "Unbekannte Ausschluss-Kategorie: '{}'. Verfügbar: {}",
+                name,
+                names.join(", ")
+            )));
+        }
+    }
+
+    let mut patterns: Vec<String> = Vec::new();
+    for (category, defaults) in EXCLUDE_CATEGORIES {
+        if !disabled.iter().any(|name| name == category) {
+            patterns.extend(defaults.iter().map(|pattern| pattern.to_string()));
+        }
+    }
+    for (category, additional) in extra {
+        if !disabled.contains(&category.to_lowercase()) {
+            patterns.extend(additional.iter().cloned());
+        }
+    }
+    patterns.extend(ALWAYS_EXCLUDED.iter().map(|pattern| pattern.to_string()));
+    Ok(patterns)
+}
+
+/// Syntax-Highlighting Mapping für Dateiendungen (mit Punkt, klein
+/// geschrieben). Die Tabelle wird beim ersten Zugriff einmal aufgebaut.
+pub fn get_syntax_map() -> &'static HashMap<&'static str, &'static str> {
+    static SYNTAX_MAP: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+    SYNTAX_MAP.get_or_init(|| HashMap::from([
         (".py", "python"),
         (".pyi", "python"),
         (".pyw", "python"),
+        (".ipynb", "json"),
         (".js", "javascript"),
         (".mjs", "javascript"),
         (".cjs", "javascript"),
@@ -174,6 +202,9 @@ pub fn get_syntax_map() -> HashMap<&'static str, &'static str> {
         (".yml", "yaml"),
         (".toml", "toml"),
         (".xml", "xml"),
+        (".svg", "xml"),
+        (".csv", "csv"),
+        (".tsv", "tsv"),
         (".md", "markdown"),
         (".rst", "rst"),
         (".php", "php"),
@@ -212,72 +243,137 @@ pub fn get_syntax_map() -> HashMap<&'static str, &'static str> {
         (".xaml", "xml"),
         (".adoc", "asciidoc"),
         (".txt", "text"),
-    ])
+    ]))
 }
 
+/// Sprachen für Dateien, die am Namen statt an der Endung erkannt werden.
+const SPECIAL_SYNTAXES: &[&str] = &["dockerfile", "makefile", "dotenv", "gitignore"];
+
 /// Findet einen Projekttyp anhand seines Namens.
 pub fn find_project_type(name: &str) -> Option<&'static ProjectType> {
     PROJECT_TYPES.iter().find(|pt| pt.name == name.to_lowercase())
 }
 
-/// Sammelt alle Extensions für die angegebenen Projekttypen.
-pub fn collect_extensions(type_names: &[String]) -> anyhow::Result<HashSet<String>> {
+/// Benutzerdefinierte Typ-Bündel aus der Tabelle `[aliases]`.
+pub type TypeAliases = BTreeMap<String, Vec<String>>;
+
+/// Sammelt alle Extensions für die angegebenen Projekttypen. Aliase werden
+/// rekursiv aufgelöst und haben Vorrang vor gleichnamigen Projekttypen.
+pub fn collect_extensions(type_names: &[String], aliases: &TypeAliases) -> Result<HashSet<String>> {
     let mut extensions = HashSet::new();
-    
+    let mut stack = Vec::new();
+
     for name in type_names {
-        match find_project_type(name) {
-            Some(pt) => {
-                for ext in pt.extensions {
-                    extensions.insert(ext.to_string());
-                }
-            }
-            None => {
-                anyhow::bail!(
-                    "Unbekannter Projekttyp: '{}'. Nutze --list-types für verfügbare Typen.",
-                    name
-                );
+        add_type(name, aliases, &mut stack, &mut extensions)?;
+    }
+
+    Ok(extensions)
+}
+
+fn add_type(name: &str, aliases: &TypeAliases, stack: &mut Vec<String>, extensions: &mut HashSet<String>) -> Result<()> {
+    let key = name.to_lowercase();
+    if let Some(members) = aliases.iter().find(|(alias, _)| alias.to_lowercase() == key).map(|(_, m)| m) {
+        if stack.contains(&key) {
+            stack.push(key);
+            return Err(Code2MdError::Config(format!("Zyklischer Typ-Alias: {}", stack.join(" → "))));
+        }
+        stack.push(key);
+        for member in members {
+            add_type(member, aliases, stack, extensions)?;
+        }
+        stack.pop();
+        return Ok(());
+    }
+
+    match find_project_type(name) {
+        Some(pt) => {
+            for ext in pt.extensions {
+                extensions.insert(ext.to_string());
             }
+            Ok(())
         }
+        None => Err(Code2MdError::Config(format!(
+            "Unbekannter Projekttyp: '{}'. Nutze --list-types für verfügbare Typen.",
+            name
+        ))),
     }
-    
-    Ok(extensions)
 }
 
-/// Ermittelt die Syntax-Highlighting-Sprache für eine Datei.
+/// Ermittelt die Syntax-Highlighting-Sprache für einen Dateinamen; leer,
+/// wenn keine bekannt ist.
 pub fn get_syntax_for_file(filename: &str) -> &'static str {
     let lower = filename.to_lowercase();
-    
+
     // Spezialfälle für Dateien ohne Endung oder mit speziellem Namen
-    if lower == "dockerfile" {
-        return "dockerfile";
-    }
-    if lower == "makefile" {
-        return "makefile";
-    }
-    if lower.starts_with(".env") {
-        return "dotenv";
+    match lower.as_str() {
+        "dockerfile" => return "dockerfile",
+        "makefile" => return "makefile",
+        ".gitignore" => return "gitignore",
+        name if name.starts_with(".env") => return "dotenv",
+        _ => {}
     }
-    if lower == ".gitignore" {
-        return "gitignore";
-    }
-    
-    // Nach Dateiendung suchen
-    let syntax_map = get_syntax_map();
-    
-    if let Some(dot_pos) = lower.rfind('.') {
-        let ext = &lower[dot_pos..];
-        if let Some(syntax) = syntax_map.get(ext) {
-            return syntax;
-        }
+
+    match lower.rfind('.') {
+        Some(dot_pos) => syntax_for_extension(&lower[dot_pos..]),
+        None => "",
     }
-    
-    ""
+}
+
+/// Wie [`get_syntax_for_file`], für den Dateinamen eines Pfads.
+pub fn syntax_for_path(path: &Path) -> &'static str {
+    path.file_name().map_or("", |name| get_syntax_for_file(&name.to_string_lossy()))
+}
+
+/// Sprache für eine Dateiendung, mit oder ohne führenden Punkt (`rs`, `.rs`).
+pub fn syntax_for_extension(extension: &str) -> &'static str {
+    let lower = extension.to_lowercase();
+    let key = if lower.starts_with('.') { lower } else { format!(".{}", lower) };
+    get_syntax_map().get(key.as_str()).copied().unwrap_or("")
+}
+
+/// Prüft, ob eine Sprache für Codeblöcke vorkommen kann (z.B. `yaml`, nicht `yml`).
+pub fn is_known_syntax(language: &str) -> bool {
+    let language = language.to_lowercase();
+    SPECIAL_SYNTAXES.contains(&language.as_str()) || get_syntax_map().values().any(|syntax| *syntax == language)
+}
+
+/// Endungen der Dateinamen, für die keine Syntax bekannt ist (sortiert,
+/// ohne Duplikate). Dateien ohne Endung erscheinen mit ihrem Namen.
+pub fn unknown_syntax_extensions<'a>(filenames: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut unknown: Vec<String> = filenames
+        .into_iter()
+        .filter(|name| get_syntax_for_file(name).is_empty())
+        .map(|name| {
+            let lower = name.to_lowercase();
+            match lower.rfind('.') {
+                Some(dot_pos) if dot_pos > 0 => lower[dot_pos..].to_string(),
+                _ => lower,
+            }
+        })
+        .collect();
+    unknown.sort();
+    unknown.dedup();
+    unknown
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_known_syntax() {
+        assert!(is_known_syntax("yaml"));
+        assert!(is_known_syntax("JSON"));
+        assert!(is_known_syntax("dockerfile"));
+        assert!(!is_known_syntax("yml"));
+    }
+
+    #[test]
+    fn test_unknown_syntax_extensions() {
+        let names = ["main.rs", "rules.XYZ", "a.xyz", "LICENSE", "b.proto2"];
+        assert_eq!(unknown_syntax_extensions(names), vec![".proto2", ".xyz", "license"]);
+    }
+
     #[test]
     fn test_find_project_type() {
         assert!(find_project_type("python").is_some());
@@ -286,11 +382,58 @@ mod tests {
         assert!(find_project_type("unknown").is_none());
     }
 
+    #[test]
+    fn test_collect_extensions_with_aliases() -> anyhow::Result<()> {
+        let names = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let mut aliases = TypeAliases::new();
+        aliases.insert("backend".to_string(), names(&["python", "config"]));
+        aliases.insert("fullstack".to_string(), names(&["backend", "vue"]));
+
+        let extensions = collect_extensions(&names(&["Fullstack"]), &aliases)?;
+        assert!(extensions.contains(".py") && extensions.contains(".vue") && extensions.contains(".toml"));
+
+        aliases.insert("vue".to_string(), names(&["fullstack"]));
+        assert!(matches!(
+            collect_extensions(&names(&["fullstack"]), &aliases),
+            Err(Code2MdError::Config(message)) if message == "Zyklischer Typ-Alias: fullstack → vue → fullstack"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_default_excludes() -> anyhow::Result<()> {
+        let mut extra = ExcludeCategories::new();
+        extra.insert("ide".to_string(), vec![".fleet".to_string()]);
+        extra.insert("generated".to_string(), vec!["*.pb.go".to_string()]);
+
+        let patterns = default_excludes(&[], &extra)?;
+        assert!(["node_modules", ".idea", ".fleet", "*.pb.go", ".code2md"].iter().all(|p| patterns.contains(&p.to_string())));
+
+        let patterns = default_excludes(&["IDE".to_string(), "generated".to_string()], &extra)?;
+        assert!(!patterns.iter().any(|p| p == ".idea" || p == ".fleet" || p == "*.pb.go"));
+        assert!(patterns.contains(&"target".to_string()));
+
+        assert!(default_excludes(&["unbekannt".to_string()], &extra).is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_get_syntax_for_file() {
         assert_eq!(get_syntax_for_file("main.py"), "python");
         assert_eq!(get_syntax_for_file("app.tsx"), "tsx");
         assert_eq!(get_syntax_for_file("Dockerfile"), "dockerfile");
         assert_eq!(get_syntax_for_file(".gitignore"), "gitignore");
+        assert_eq!(get_syntax_for_file("README"), "");
+    }
+
+    #[test]
+    fn test_syntax_for_path_and_extension() {
+        assert_eq!(syntax_for_path(Path::new("src/lib.RS")), "rust");
+        assert_eq!(syntax_for_path(Path::new("docker/Dockerfile")), "dockerfile");
+        assert_eq!(syntax_for_path(Path::new("")), "");
+        assert_eq!(syntax_for_extension("py"), "python");
+        assert_eq!(syntax_for_extension(".YAML"), syntax_for_extension("yaml"));
+        assert_eq!(syntax_for_extension("unbekannt"), "");
+        assert!(std::ptr::eq(get_syntax_map(), get_syntax_map()));
     }
 }
@@ -0,0 +1,305 @@
+//! Hochladen der erzeugten Dateien per HTTP PUT oder in S3-kompatible
+//! Speicher (`--upload`).
+//!
+//! Zugangsdaten kommen aus der Umgebung: für `s3://bucket/pfad/` die
+//! üblichen `AWS_*`-Variablen (mit `AWS_ENDPOINT_URL` z.B. für MinIO), für
+//! `http(s)://` optional `CODE2MD_UPLOAD_TOKEN` bzw. Benutzer und Passwort.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+
+use crate::error::{Code2MdError, Result};
+use crate::http::status_error;
+use crate::permalink::encode_path;
+
+/// Bearer-Token für HTTP-Ziele.
+pub const TOKEN_ENV: &str = "CODE2MD_UPLOAD_TOKEN";
+/// Benutzer und Passwort (Basic-Auth) für HTTP-Ziele.
+pub const USER_ENV: &str = "CODE2MD_UPLOAD_USER";
+pub const PASSWORD_ENV: &str = "CODE2MD_UPLOAD_PASSWORD";
+
+/// Zugangsdaten für S3-kompatible Speicher.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S3Credentials {
+    pub access_key: String,
+    pub secret_key: String,
+    pub session_token: Option<String>,
+    pub region: String,
+    /// Eigener Endpunkt (Pfad-Adressierung); sonst AWS mit virtuellem Host
+    pub endpoint: Option<String>,
+}
+
+impl S3Credentials {
+    /// Liest die Zugangsdaten aus den `AWS_*`-Umgebungsvariablen.
+    pub fn from_env() -> Result<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let required = |name: &str| {
+            var(name).ok_or_else(|| Code2MdError::Config(format!("--upload s3:// benötigt die Umgebungsvariable {}.", name)))
+        };
+        Ok(Self {
+            access_key: required("AWS_ACCESS_KEY_ID")?,
+            secret_key: required("AWS_SECRET_ACCESS_KEY")?,
+            session_token: var("AWS_SESSION_TOKEN"),
+            region: var("AWS_REGION")
+                .or_else(|| var("AWS_DEFAULT_REGION"))
+                .unwrap_or_else(|| "us-east-1".to_string()),
+            endpoint: var("AWS_ENDPOINT_URL_S3").or_else(|| var("AWS_ENDPOINT_URL")),
+        })
+    }
+
+    /// URL eines Objekts.
+    fn object_url(&self, bucket: &str, key: &str) -> String {
+        match &self.endpoint {
+            Some(endpoint) => format!("{}/{}/{}", endpoint.trim_end_matches('/'), bucket, encode_path(key)),
+            None => format!("https://{}.s3.{}.amazonaws.com/{}", bucket, self.region, encode_path(key)),
+        }
+    }
+}
+
+/// Lädt die Dateien hoch und gibt die Ziel-URLs zurück. Endet das Ziel auf
+/// `/` oder sind es mehrere Dateien, wird der Dateiname angehängt.
+pub fn upload(files: &[PathBuf], target: &str) -> Result<Vec<String>> {
+    let s3 = match target.strip_prefix("s3://") {
+        Some(_) => Some(S3Credentials::from_env()?),
+        None if target.starts_with("http://") || target.starts_with("https://") => None,
+        None => {
+            return Err(Code2MdError::Config(format!(
+                "Ungültiges Ziel für --upload: '{}' (erwartet s3://, http:// oder https://).",
+                target
+            )))
+        }
+    };
+
+    let mut urls = Vec::with_capacity(files.len());
+    for path in files {
+        let location = destination(target, path, files.len() > 1);
+        let body = std::fs::read(path).map_err(|e| Code2MdError::io(path, e))?;
+        let url = match (&s3, location.strip_prefix("s3://")) {
+            (Some(credentials), Some(object)) => {
+                let (bucket, key) = object.split_once('/').unwrap_or((object, ""));
+                if key.is_empty() {
+                    return Err(Code2MdError::Config(format!("--upload {} enthält keinen Objektschlüssel.", target)));
+                }
+                let url = credentials.object_url(bucket, key);
+                let mut request = ureq::put(&url).set("Content-Type", content_type(path));
+                for (name, value) in sign_s3(credentials, &url, &body, Utc::now()) {
+                    request = request.set(&name, &value);
+                }
+                request.send_bytes(&body).map_err(status_error)?;
+                url
+            }
+            _ => {
+                let mut request = ureq::put(&location).set("Content-Type", content_type(path));
+                if let Some(auth) = http_authorization() {
+                    request = request.set("Authorization", &auth);
+                }
+                request.send_bytes(&body).map_err(status_error)?;
+                location
+            }
+        };
+        urls.push(url);
+    }
+    Ok(urls)
+}
+
+/// Zieladresse einer Datei.
+fn destination(target: &str, path: &Path, several: bool) -> String {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if target.ends_with('/') {
+        format!("{}{}", target, name)
+    } else if several {
+        format!("{}/{}", target, name)
+    } else {
+        target.to_string()
+    }
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("md") => "text/markdown; charset=utf-8",
+        Some("xml") => "application/xml",
+        Some("json") => "application/json",
+        Some("jsonl") => "application/jsonl",
+        Some("zip") => "application/zip",
+        Some("gz") => "application/gzip",
+        Some("zst") => "application/zstd",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Authorization-Header für HTTP-Ziele, falls Zugangsdaten gesetzt sind.
+fn http_authorization() -> Option<String> {
+    use base64::Engine;
+
+    if let Ok(token) = std::env::var(TOKEN_ENV) {
+        return Some(format!("Bearer {}", token));
+    }
+    let user = std::env::var(USER_ENV).ok()?;
+    let password = std::env::var(PASSWORD_ENV).unwrap_or_default();
+    Some(format!(
+        "Basic {}",
+        base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", user, password))
+    ))
+}
+
+/// Header eines mit AWS Signature Version 4 signierten PUT-Requests.
+fn sign_s3(credentials: &S3Credentials, url: &str, body: &[u8], now: DateTime<Utc>) -> Vec<(String, String)> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let payload_hash = format!("{:x}", Sha256::digest(body));
+
+    let mut headers = vec![
+        ("host".to_string(), host.to_string()),
+        ("x-amz-content-sha256".to_string(), payload_hash.clone()),
+        ("x-amz-date".to_string(), now.format("%Y%m%dT%H%M%SZ").to_string()),
+    ];
+    if let Some(token) = &credentials.session_token {
+        headers.push(("x-amz-security-token".to_string(), token.clone()));
+    }
+    let authorization = authorization(credentials, "s3", "PUT", &format!("/{}", path), &headers, &payload_hash, now);
+
+    headers.remove(0);
+    headers.push(("Authorization".to_string(), authorization));
+    headers
+}
+
+/// `Authorization`-Header nach AWS Signature Version 4 für einen Request
+/// ohne Query-String. `headers` sind alle signierten Header, klein
+/// geschrieben und sortiert, darunter `host` und `x-amz-date`; `path` ist
+/// bereits URL-kodiert.
+fn authorization(
+    credentials: &S3Credentials,
+    service: &str,
+    method: &str,
+    path: &str,
+    headers: &[(String, String)],
+    payload_hash: &str,
+    now: DateTime<Utc>,
+) -> String {
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let signed_headers = headers.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>().join(";");
+    let canonical_headers: String = headers.iter().map(|(name, value)| format!("{}:{}\n", name, value)).collect();
+    let canonical_request = format!(
+        "{}\n{}\n\n{}\n{}\n{}",
+        method, path, canonical_headers, signed_headers, payload_hash
+    );
+
+    let scope = format!("{}/{}/{}/aws4_request", date, credentials.region, service);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{:x}",
+        amz_date,
+        scope,
+        Sha256::digest(canonical_request.as_bytes())
+    );
+    let key = signing_key(&credentials.secret_key, &date, &credentials.region, service);
+    let signature = hex(&hmac_sha256(&key, string_to_sign.as_bytes()));
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        credentials.access_key, scope, signed_headers, signature
+    )
+}
+
+/// Schlüssel für die Signatur eines Tages, einer Region und eines Dienstes.
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac_sha256(format!("AWS4{}", secret).as_bytes(), date.as_bytes());
+    let key = hmac_sha256(&key, region.as_bytes());
+    let key = hmac_sha256(&key, service.as_bytes());
+    hmac_sha256(&key, b"aws4_request")
+}
+
+/// HMAC-SHA256 nach RFC 2104.
+fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    const BLOCK_SIZE: usize = 64;
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(message);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_destination() {
+        let path = Path::new("/tmp/app_code.md");
+        assert_eq!(destination("s3://bucket/exports/", path, false), "s3://bucket/exports/app_code.md");
+        assert_eq!(destination("https://host/nightly.md", path, false), "https://host/nightly.md");
+        assert_eq!(destination("https://host/nightly", path, true), "https://host/nightly/app_code.md");
+    }
+
+    #[test]
+    fn test_sigv4() -> anyhow::Result<()> {
+        // Testvektoren aus RFC 4231 und der AWS-Dokumentation zur Signaturberechnung
+        assert_eq!(
+            hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        let key = signing_key("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "20120215", "us-east-1", "iam");
+        assert_eq!(hex(&key), "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d");
+
+        let credentials = S3Credentials {
+            access_key: "AKIDEXAMPLE".to_string(),
+            secret_key: "secret".to_string(),
+            session_token: None,
+            region: "eu-central-1".to_string(),
+            endpoint: Some("http://localhost:9000".to_string()),
+        };
+        let url = credentials.object_url("exports", "nightly/app code.md");
+        assert_eq!(url, "http://localhost:9000/exports/nightly/app%20code.md");
+
+        let now = DateTime::parse_from_rfc3339("2026-01-02T03:04:05Z")?.with_timezone(&Utc);
+        let headers = sign_s3(&credentials, &url, b"", now);
+        assert_eq!(headers[1], ("x-amz-date".to_string(), "20260102T030405Z".to_string()));
+        assert!(headers[2].1.starts_with(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20260102/eu-central-1/s3/aws4_request, \
+             SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature="
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_sigv4_get_vanilla() -> anyhow::Result<()> {
+        // Testsuite der AWS-Dokumentation, Fall `get-vanilla`
+        let credentials = S3Credentials {
+            access_key: "AKIDEXAMPLE".to_string(),
+            secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token: None,
+            region: "us-east-1".to_string(),
+            endpoint: None,
+        };
+        let headers = vec![
+            ("host".to_string(), "example.amazonaws.com".to_string()),
+            ("x-amz-date".to_string(), "20150830T123600Z".to_string()),
+        ];
+        let now = DateTime::parse_from_rfc3339("2015-08-30T12:36:00Z")?.with_timezone(&Utc);
+        let empty_hash = format!("{:x}", Sha256::digest(b""));
+        assert_eq!(
+            authorization(&credentials, "service", "GET", "/", &headers, &empty_hash, now),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+//! XML-Dokumentformat für die Eingabe in Sprachmodelle.
+//!
+//! Erzeugt die von Anthropic empfohlene Struktur
+//! `<documents><document index="1"><source>…</source><document_contents>…`.
+
+use crate::collector::CollectedFile;
+use crate::events::ExportEvent;
+use crate::markdown::{file_blocks, render_blocks_plain, MarkdownConfig};
+use crate::paths;
+
+/// Generiert das vollständige XML-Dokument.
+///
+/// Dateiinhalte werden bewusst nicht maskiert, da Sprachmodelle den
+/// unveränderten Quelltext besser lesen; nur Pfade werden maskiert.
+pub fn generate_xml(files: &[CollectedFile], config: &MarkdownConfig) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    lines.push("<documents>".to_string());
+
+    let mut index = 1;
+    for file in files {
+        if let Some(rel_str) = paths::relative_to(&file.path, &config.base_path) {
+            let blocks = file_blocks(file, &rel_str, config);
+
+            lines.push(format!("<document index=\"{}\">", index));
+            lines.push(format!("<source>{}</source>", escape_xml(&rel_str)));
+            lines.push("<document_contents>".to_string());
+            lines.push(render_blocks_plain(blocks));
+            lines.push("</document_contents>".to_string());
+            lines.push("</document>".to_string());
+            config.events.emit(ExportEvent::SectionWritten {
+                path: rel_str,
+                index,
+                total: files.len(),
+            });
+            index += 1;
+        }
+    }
+
+    lines.push("</documents>".to_string());
+    lines.push(String::new());
+    config.transform.line_ending.join(&lines)
+}
+
+/// Maskiert XML-Sonderzeichen.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_xml() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let base = dir.path().to_path_buf();
+        std::fs::write(base.join("main.py"), "if a < b:\n    pass\n")?;
+
+        let config = MarkdownConfig {
+            base_path: base.clone(),
+            ..Default::default()
+        };
+        let xml = generate_xml(&[CollectedFile::from_path(base.join("main.py"))?], &config);
+
+        assert_eq!(
+            xml,
+            "<documents>\n<document index=\"1\">\n<source>main.py</source>\n\
+             <document_contents>\nif a < b:\n    pass\n</document_contents>\n\
+             </document>\n</documents>\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(escape_xml("a<b>&\"c\""), "a&lt;b&gt;&amp;&quot;c&quot;");
+    }
+}
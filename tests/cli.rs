@@ -0,0 +1,126 @@
+//! Tests der Kommandozeile über das gebaute Programm.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn code2md(args: &[&str], dir: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_code2md"))
+        .args(args)
+        .current_dir(dir)
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("code2md startet")
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hooks_need_opt_in() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    std::fs::write(dir.path().join("main.rs"), "fn main() {}\n")?;
+    std::fs::write(dir.path().join("code2md.toml"), "[hooks]\npre_export = [\"touch hook.txt\"]\n")?;
+    let args = [".", "--type", "rust", "--output", "out.md", "--no-cache"];
+
+    let output = code2md(&args, dir.path());
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(!dir.path().join("hook.txt").exists());
+
+    let output = code2md(&[&args[..], &["--allow-hooks"]].concat(), dir.path());
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(dir.path().join("hook.txt").exists());
+    Ok(())
+}
+
+#[test]
+fn test_run_cache_keeps_manifest() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    std::fs::write(dir.path().join("main.rs"), "fn main() {}\n")?;
+    let args = [".", "--type", "rust", "--output", "out.md", "--manifest"];
+    let manifest = dir.path().join("out.md.manifest.json");
+
+    let first = code2md(&args, dir.path());
+    assert!(first.status.success(), "{}", String::from_utf8_lossy(&first.stderr));
+    assert!(manifest.exists());
+
+    // Zweiter Lauf trifft den Run-Cache, erzeugt das Manifest aber neu
+    std::fs::remove_file(&manifest)?;
+    let second = code2md(&args, dir.path());
+    assert!(second.status.success(), "{}", String::from_utf8_lossy(&second.stderr));
+    assert!(String::from_utf8_lossy(&second.stdout).contains("Ausgabe ist aktuell"));
+    assert!(manifest.exists());
+    Ok(())
+}
+
+#[test]
+fn test_check_ignores_run_cache() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    std::fs::write(dir.path().join("main.rs"), "fn main() {}\n")?;
+    let args = [".", "--type", "rust", "--output", "out.md"];
+
+    // Der Export mit Zeitstempel füllt den Run-Cache, ist aber nicht reproduzierbar
+    let export = code2md(&args, dir.path());
+    assert!(export.status.success(), "{}", String::from_utf8_lossy(&export.stderr));
+    let check = code2md(&[&args[..], &["--check"]].concat(), dir.path());
+    assert_eq!(check.status.code(), Some(6), "{}", String::from_utf8_lossy(&check.stdout));
+
+    let export = code2md(&[&args[..], &["--reproducible"]].concat(), dir.path());
+    assert!(export.status.success(), "{}", String::from_utf8_lossy(&export.stderr));
+    let check = code2md(&[&args[..], &["--check"]].concat(), dir.path());
+    assert_eq!(check.status.code(), Some(0), "{}", String::from_utf8_lossy(&check.stdout));
+    Ok(())
+}
+
+#[test]
+fn test_run_cache_follows_git_head() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .current_dir(dir.path())
+            .env("GIT_AUTHOR_NAME", "test")
+            .env("GIT_AUTHOR_EMAIL", "test@example.com")
+            .env("GIT_COMMITTER_NAME", "test")
+            .env("GIT_COMMITTER_EMAIL", "test@example.com")
+            .output()
+    };
+    if git(&["init", "-q"]).is_err() {
+        // Ohne git lassen sich keine Permalinks erzeugen
+        return Ok(());
+    }
+    std::fs::write(dir.path().join("main.rs"), "fn main() {}\n")?;
+    git(&["add", "main.rs"])?;
+    git(&["commit", "-q", "-m", "eins"])?;
+    let args = [".", "--type", "rust", "--output", "out.md", "--repo-url", "https://github.com/o/r"];
+
+    let first = code2md(&args, dir.path());
+    assert!(first.status.success(), "{}", String::from_utf8_lossy(&first.stderr));
+    git(&["commit", "-q", "--allow-empty", "-m", "zwei"])?;
+    let head = String::from_utf8(git(&["rev-parse", "HEAD"])?.stdout)?;
+
+    // Neuer Commit ändert die Permalinks, der Run-Cache darf nicht greifen
+    let second = code2md(&args, dir.path());
+    assert!(second.status.success(), "{}", String::from_utf8_lossy(&second.stderr));
+    assert!(!String::from_utf8_lossy(&second.stdout).contains("Ausgabe ist aktuell"));
+    assert!(std::fs::read_to_string(dir.path().join("out.md"))?.contains(head.trim()));
+    Ok(())
+}
+
+#[test]
+fn test_import_links_follow_final_selection() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    std::fs::write(dir.path().join("a.py"), "import zz\n")?;
+    std::fs::write(dir.path().join("zz.py"), "x = 1\n".repeat(2000))?;
+    let args = [
+        ".", "--type", "python", "--output", "out.md", "--import-links", "--max-output-size", "3KB", "--on-oversize",
+        "truncate",
+    ];
+
+    // Exit-Code 5: Export mit Warnung über die weggelassene Datei
+    let output = code2md(&args, dir.path());
+    assert_eq!(output.status.code(), Some(5), "{}", String::from_utf8_lossy(&output.stderr));
+    let document = std::fs::read_to_string(dir.path().join("out.md"))?;
+    assert!(document.contains("### `a.py`"));
+    assert!(!document.contains("### `zz.py`"));
+    // Kein Link auf den weggelassenen Abschnitt
+    assert!(!document.contains("Imports:"));
+    Ok(())
+}